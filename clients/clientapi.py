@@ -36,6 +36,7 @@ from pyotp import TOTP, random_base32
 import base64
 import traceback
 import time
+from datetime import datetime
 import httpx
 import asyncio
 import io
@@ -499,7 +500,8 @@ async def api_return_episodes(user_id: int, cnx=Depends(get_database_connection)
 
 
 @app.get("/api/data/podcast_episodes")
-async def api_podcast_episodes(cnx=Depends(get_database_connection), api_key: str = Depends(get_api_key_from_header), user_id: int = Query(...), podcast_id: int = Query(...)):
+async def api_podcast_episodes(cnx=Depends(get_database_connection), api_key: str = Depends(get_api_key_from_header), user_id: int = Query(...), podcast_id: int = Query(...),
+                               offset: int = Query(0, ge=0), limit: Optional[int] = Query(None, ge=1, le=500)):
     is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
     if not is_valid_key:
         raise HTTPException(status_code=403,
@@ -512,7 +514,7 @@ async def api_podcast_episodes(cnx=Depends(get_database_connection), api_key: st
 
     # Allow the action if the API key belongs to the user, or it's the web API key
     if key_id == user_id or is_web_key:
-        episodes = database_functions.functions.return_podcast_episodes(database_type, cnx, user_id, podcast_id)
+        episodes = database_functions.functions.return_podcast_episodes(database_type, cnx, user_id, podcast_id, offset, limit)
         if episodes is None:
             episodes = []  # Return an empty list instead of raising an exception
         return {"episodes": episodes}
@@ -947,6 +949,60 @@ async def api_record_listen_duration(data: RecordListenDurationData, cnx=Depends
         raise HTTPException(status_code=403, detail="You can only record your own listen duration")
 
 
+class EpisodePlayedData(BaseModel):
+    episode_id: int
+    user_id: int
+    played: bool
+
+
+@app.post("/api/data/mark_episode_played")
+async def api_mark_episode_played(data: EpisodePlayedData, cnx=Depends(get_database_connection),
+                                  api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    is_web_key = api_key == base_webkey.web_key
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    if key_id == data.user_id or is_web_key:
+        listen_duration = database_functions.functions.set_episode_played(cnx, data.episode_id, data.user_id, data.played)
+        if listen_duration is None:
+            raise HTTPException(status_code=404, detail="Episode not found")
+        return {"listen_duration": listen_duration}
+    else:
+        raise HTTPException(status_code=403, detail="You can only mark your own episodes")
+
+
+class CatchUpData(BaseModel):
+    user_id: int
+    podcast_id: int
+    older_than_episode_id: Optional[int] = None
+    keep_latest: Optional[int] = Field(None, ge=0)
+
+
+@app.post("/api/data/catch_up_podcast")
+async def api_catch_up_podcast(data: CatchUpData, cnx=Depends(get_database_connection),
+                               api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    is_web_key = api_key == base_webkey.web_key
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    if key_id == data.user_id or is_web_key:
+        episodes = database_functions.functions.catch_up_podcast(database_type, cnx, data.user_id, data.podcast_id,
+                                                                 data.older_than_episode_id, data.keep_latest)
+        if episodes is None:
+            raise HTTPException(status_code=404, detail="Episode not found in this podcast")
+        return {"episodes": episodes}
+    else:
+        raise HTTPException(status_code=403, detail="You can only mark your own episodes")
+
+
 
 @app.get("/api/data/refresh_pods")
 async def api_refresh_pods(background_tasks: BackgroundTasks, is_admin: bool = Depends(check_if_admin)):
@@ -985,6 +1041,30 @@ async def api_get_stats(user_id: int, cnx=Depends(get_database_connection),
                             detail="You can only get stats for your own account.")
 
 
+@app.get("/api/data/listening_activity/{user_id}")
+async def api_get_listening_activity(user_id: int, start: Optional[datetime] = Query(None),
+                                     end: Optional[datetime] = Query(None), cnx=Depends(get_database_connection),
+                                     api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    # Allow the action if the API key belongs to the user or it's the web API key
+    if key_id == user_id or is_web_key:
+        activity = database_functions.functions.get_listening_activity(database_type, cnx, user_id, start, end)
+        days = database_functions.functions.get_listening_days(cnx, user_id, start, end)
+        return {"activity": activity, "days": days}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only get stats for your own account.")
+
+
 @app.get("/api/data/get_user_episode_count")
 async def api_get_user_episode_count(user_id: int, cnx=Depends(get_database_connection),
                                      api_key: str = Depends(get_api_key_from_header)):
@@ -1138,9 +1218,35 @@ async def api_return_pods(user_id: int, cnx=Depends(get_database_connection),
                             detail="You can only return pods for yourself!")
 
 
+@app.get("/api/data/podcast_details/{user_id}/{podcast_id}")
+async def api_get_podcast_details(user_id: int, podcast_id: int, cnx=Depends(get_database_connection),
+                                  api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    if key_id != user_id and not is_web_key:
+        raise HTTPException(status_code=403,
+                            detail="You can only view your own podcasts!")
+
+    podcast = database_functions.functions.get_podcast_details(database_type, cnx, user_id, podcast_id)
+    if podcast is None:
+        raise HTTPException(status_code=404, detail="Podcast not found")
+    return {"podcast": podcast}
+
+
 @app.get("/api/data/user_history/{user_id}")
 async def api_user_history(user_id: int, cnx=Depends(get_database_connection),
-                           api_key: str = Depends(get_api_key_from_header)):
+                           api_key: str = Depends(get_api_key_from_header),
+                           offset: int = Query(0, ge=0), limit: Optional[int] = Query(None, ge=1, le=500),
+                           podcast_id: Optional[int] = Query(None), start: Optional[datetime] = Query(None),
+                           end: Optional[datetime] = Query(None)):
     is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
     if not is_valid_key:
         raise HTTPException(status_code=403,
@@ -1153,7 +1259,7 @@ async def api_user_history(user_id: int, cnx=Depends(get_database_connection),
 
     # Allow the action if the API key belongs to the user or it's the web API key
     if key_id == user_id or is_web_key:
-        history = database_functions.functions.user_history(cnx, user_id)
+        history = database_functions.functions.user_history(cnx, user_id, offset, limit, podcast_id, start, end)
         return {"data": history}
     else:
         raise HTTPException(status_code=403,
@@ -1412,6 +1518,50 @@ async def api_set_theme(user_id: int = Body(...), new_theme: str = Body(...), cn
                             detail="You can only set your own theme!")
 
 
+@app.get("/api/data/get_language/{user_id}")
+async def api_get_language(user_id: int, cnx=Depends(get_database_connection),
+                           api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    # Allow the action if the API key belongs to the user, or it's the web API key
+    if key_id == user_id or is_web_key:
+        language = database_functions.functions.get_language(cnx, user_id)
+        return {"language": language}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only get your own language!")
+
+
+@app.put("/api/data/user/set_language")
+async def api_set_language(user_id: int = Body(...), new_language: str = Body(...), cnx=Depends(get_database_connection),
+                           api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    # Allow the action if the API key belongs to the user or it's the web API key
+    if key_id == user_id or is_web_key:
+        database_functions.functions.set_language(cnx, user_id, new_language)
+        return {"message": "Language updated successfully"}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only set your own language!")
+
+
 @app.get("/api/data/user/check_downloaded")
 async def api_check_downloaded(user_id: int, title: str, url: str, cnx=Depends(get_database_connection),
                                api_key: str = Depends(get_api_key_from_header)):
@@ -2052,6 +2202,55 @@ async def api_remove_episode_from_history(data: EpisodeToRemove, cnx=Depends(get
                             detail="You can only remove your own history!")
 
 
+class HistoryEpisodeToRemove(BaseModel):
+    episode_id: int
+    user_id: int
+
+
+@app.post("/api/data/remove_history_episode")
+async def api_remove_history_episode(data: HistoryEpisodeToRemove, cnx=Depends(get_database_connection),
+                                     api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    is_web_key = api_key == base_webkey.web_key
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    if key_id == data.user_id or is_web_key:
+        success = database_functions.functions.remove_episode_history_by_id(cnx, data.episode_id, data.user_id)
+        return {"success": success}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only remove your own history!")
+
+
+class ClearHistoryData(BaseModel):
+    user_id: int
+
+
+@app.post("/api/data/clear_history")
+async def api_clear_history(data: ClearHistoryData, cnx=Depends(get_database_connection),
+                            api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    is_web_key = api_key == base_webkey.web_key
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    if key_id == data.user_id or is_web_key:
+        removed = database_functions.functions.clear_history(cnx, data.user_id)
+        if removed is None:
+            raise HTTPException(status_code=500, detail="Failed to clear history")
+        return {"removed": removed}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only clear your own history!")
+
+
 # Model for request data
 class TimeZoneInfo(BaseModel):
     user_id: int
@@ -2201,6 +2400,8 @@ async def delete_selected_podcasts(data: SelectedPodcastsDelete, cnx=Depends(get
 class SearchPodcastData(BaseModel):
     search_term: str
     user_id: int
+    offset: int = Field(0, ge=0)
+    limit: Optional[int] = Field(None, ge=1, le=500)
 
 
 @app.post("/api/data/search_data")
@@ -2208,7 +2409,7 @@ async def search_data(data: SearchPodcastData, cnx=Depends(get_database_connecti
                       api_key: str = Depends(get_api_key_from_header)):
     is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
     if is_valid_key:
-        result = database_functions.functions.search_data(database_type, cnx, data.search_term, data.user_id)
+        result = database_functions.functions.search_data(database_type, cnx, data.search_term, data.user_id, data.offset, data.limit)
         return {"data": result}
     else:
         raise HTTPException(status_code=403,
@@ -2274,6 +2475,540 @@ async def remove_queued_pod(data: QueueRmData, cnx=Depends(get_database_connecti
                             detail="You can only remove episodes for your own queue!")
 
 
+class QueueReorderData(BaseModel):
+    user_id: int
+    # The whole queue, first to last
+    episode_ids: List[int]
+
+
+@app.post("/api/data/reorder_queue")
+async def api_reorder_queue(data: QueueReorderData, cnx=Depends(get_database_connection),
+                            api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    # Allow the action if the API key belongs to the user or it's the web API key
+    if key_id == data.user_id or is_web_key:
+        reordered = database_functions.functions.reorder_queue(database_type, cnx, data.user_id, data.episode_ids)
+        if not reordered:
+            raise HTTPException(status_code=409, detail="The queue changed since it was loaded")
+        return {"data": True}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only reorder your own queue!")
+
+
+@app.get("/api/data/podcast_settings/{user_id}/{podcast_id}")
+async def api_get_podcast_settings(user_id: int, podcast_id: int, cnx=Depends(get_database_connection),
+                                   api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    # Allow the action if the API key belongs to the user or it's the web API key
+    if key_id == user_id or is_web_key:
+        settings = database_functions.functions.get_podcast_settings(database_type, cnx, user_id, podcast_id)
+        if settings is None:
+            raise HTTPException(status_code=404, detail="Podcast not found")
+        return {"settings": settings}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only view settings for your own podcasts!")
+
+
+@app.get("/api/data/episode_podcast_settings/{user_id}/{episode_id}")
+async def api_get_episode_podcast_settings(user_id: int, episode_id: int, cnx=Depends(get_database_connection),
+                                           api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    # Allow the action if the API key belongs to the user or it's the web API key
+    if key_id == user_id or is_web_key:
+        settings = database_functions.functions.get_episode_podcast_settings(database_type, cnx, user_id, episode_id)
+        if settings is None:
+            raise HTTPException(status_code=404, detail="Episode not found")
+        return {"settings": settings}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only view settings for your own podcasts!")
+
+
+class PodcastSettingsData(BaseModel):
+    user_id: int
+    podcast_id: int
+    PlaybackSpeed: float = 1.0
+    StartSkip: int = 0
+    EndSkip: int = 0
+    EpisodeSortOrder: str = "newest"
+    AutoDownload: bool = False
+    AutoQueue: bool = False
+    NotifyNewEpisodes: bool = False
+
+
+@app.post("/api/data/podcast_settings")
+async def api_update_podcast_settings(data: PodcastSettingsData, cnx=Depends(get_database_connection),
+                                      api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    if key_id != data.user_id and not is_web_key:
+        raise HTTPException(status_code=403,
+                            detail="You can only change settings for your own podcasts!")
+
+    if not 0.5 <= data.PlaybackSpeed <= 3.0:
+        raise HTTPException(status_code=400, detail="Playback speed must be between 0.5 and 3.0")
+    if data.StartSkip < 0 or data.EndSkip < 0:
+        raise HTTPException(status_code=400, detail="Skip times cannot be negative")
+    if data.EpisodeSortOrder not in ("newest", "oldest"):
+        raise HTTPException(status_code=400, detail="Episode sort order must be 'newest' or 'oldest'")
+
+    settings = data.dict(exclude={"user_id", "podcast_id"})
+    success = database_functions.functions.update_podcast_settings(database_type, cnx, data.user_id, data.podcast_id, settings)
+    if not success:
+        raise HTTPException(status_code=404, detail="Podcast not found")
+    return {"success": True}
+
+
+@app.get("/api/data/notification_episodes/{user_id}")
+async def api_get_notification_episodes(user_id: int, cnx=Depends(get_database_connection),
+                                        api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    # Allow the action if the API key belongs to the user or it's the web API key
+    if key_id == user_id or is_web_key:
+        episodes = database_functions.functions.get_notification_episodes(database_type, cnx, user_id)
+        return {"episodes": episodes}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only check notifications for your own podcasts!")
+
+
+@app.get("/api/data/folders/{user_id}")
+async def api_get_folders(user_id: int, cnx=Depends(get_database_connection),
+                          api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    # Allow the action if the API key belongs to the user or it's the web API key
+    if key_id == user_id or is_web_key:
+        folders = database_functions.functions.get_folders(database_type, cnx, user_id)
+        return {"folders": folders}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only view your own folders!")
+
+
+class CreateFolderData(BaseModel):
+    user_id: int
+    folder_name: str
+
+
+@app.post("/api/data/create_folder")
+async def api_create_folder(data: CreateFolderData, cnx=Depends(get_database_connection),
+                            api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    if key_id != data.user_id and not is_web_key:
+        raise HTTPException(status_code=403,
+                            detail="You can only create folders for yourself!")
+
+    folder_name = data.folder_name.strip()
+    if not folder_name or len(folder_name) > 255:
+        raise HTTPException(status_code=400, detail="Folder names must be between 1 and 255 characters")
+
+    folder_id = database_functions.functions.create_folder(database_type, cnx, data.user_id, folder_name)
+    return {"folder_id": folder_id, "folder_name": folder_name}
+
+
+class DeleteFolderData(BaseModel):
+    user_id: int
+    folder_id: int
+
+
+@app.post("/api/data/delete_folder")
+async def api_delete_folder(data: DeleteFolderData, cnx=Depends(get_database_connection),
+                            api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    if key_id != data.user_id and not is_web_key:
+        raise HTTPException(status_code=403,
+                            detail="You can only delete your own folders!")
+
+    if not database_functions.functions.delete_folder(database_type, cnx, data.user_id, data.folder_id):
+        raise HTTPException(status_code=404, detail="Folder not found")
+    return {"success": True}
+
+
+class PodcastFoldersData(BaseModel):
+    user_id: int
+    podcast_id: int
+    folder_ids: List[int]
+
+
+@app.post("/api/data/set_podcast_folders")
+async def api_set_podcast_folders(data: PodcastFoldersData, cnx=Depends(get_database_connection),
+                                  api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    if key_id != data.user_id and not is_web_key:
+        raise HTTPException(status_code=403,
+                            detail="You can only organize your own podcasts!")
+
+    if not database_functions.functions.set_podcast_folders(database_type, cnx, data.user_id, data.podcast_id, data.folder_ids):
+        raise HTTPException(status_code=404, detail="Podcast not found")
+    return {"success": True}
+
+
+def check_playlist_fields(name, rules):
+    name = name.strip()
+    if not name or len(name) > 255:
+        raise HTTPException(status_code=400, detail="Playlist names must be between 1 and 255 characters")
+    if rules is not None:
+        try:
+            json.loads(rules)
+        except ValueError:
+            raise HTTPException(status_code=400, detail="Smart playlist rules must be valid JSON")
+    return name
+
+
+@app.get("/api/data/playlists/{user_id}")
+async def api_get_playlists(user_id: int, cnx=Depends(get_database_connection),
+                            api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    # Allow the action if the API key belongs to the user or it's the web API key
+    if key_id == user_id or is_web_key:
+        playlists = database_functions.functions.get_playlists(database_type, cnx, user_id)
+        return {"playlists": playlists}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only view your own playlists!")
+
+
+@app.get("/api/data/playlist_episodes/{user_id}/{playlist_id}")
+async def api_get_playlist_episodes(user_id: int, playlist_id: int, cnx=Depends(get_database_connection),
+                                    api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    if key_id != user_id and not is_web_key:
+        raise HTTPException(status_code=403,
+                            detail="You can only view your own playlists!")
+
+    episodes = database_functions.functions.get_playlist_episodes(database_type, cnx, user_id, playlist_id)
+    if episodes is None:
+        raise HTTPException(status_code=404, detail="Playlist not found")
+    return {"episodes": episodes}
+
+
+class CreatePlaylistData(BaseModel):
+    user_id: int
+    name: str
+    # JSON rules for a smart playlist, left out for a manual one
+    rules: Optional[str] = None
+
+
+@app.post("/api/data/create_playlist")
+async def api_create_playlist(data: CreatePlaylistData, cnx=Depends(get_database_connection),
+                              api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    if key_id != data.user_id and not is_web_key:
+        raise HTTPException(status_code=403,
+                            detail="You can only create playlists for yourself!")
+
+    name = check_playlist_fields(data.name, data.rules)
+    playlist_id = database_functions.functions.create_playlist(database_type, cnx, data.user_id, name, data.rules)
+    return {"playlist_id": playlist_id, "name": name}
+
+
+class UpdatePlaylistData(BaseModel):
+    user_id: int
+    playlist_id: int
+    name: str
+    rules: Optional[str] = None
+
+
+@app.post("/api/data/update_playlist")
+async def api_update_playlist(data: UpdatePlaylistData, cnx=Depends(get_database_connection),
+                              api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    if key_id != data.user_id and not is_web_key:
+        raise HTTPException(status_code=403,
+                            detail="You can only edit your own playlists!")
+
+    name = check_playlist_fields(data.name, data.rules)
+    if not database_functions.functions.update_playlist(database_type, cnx, data.user_id, data.playlist_id, name, data.rules):
+        raise HTTPException(status_code=404, detail="Playlist not found")
+    return {"success": True}
+
+
+class DeletePlaylistData(BaseModel):
+    user_id: int
+    playlist_id: int
+
+
+@app.post("/api/data/delete_playlist")
+async def api_delete_playlist(data: DeletePlaylistData, cnx=Depends(get_database_connection),
+                              api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    if key_id != data.user_id and not is_web_key:
+        raise HTTPException(status_code=403,
+                            detail="You can only delete your own playlists!")
+
+    if not database_functions.functions.delete_playlist(database_type, cnx, data.user_id, data.playlist_id):
+        raise HTTPException(status_code=404, detail="Playlist not found")
+    return {"success": True}
+
+
+class PlaylistEpisodesData(BaseModel):
+    user_id: int
+    playlist_id: int
+    episode_ids: List[int]
+
+
+@app.post("/api/data/set_playlist_episodes")
+async def api_set_playlist_episodes(data: PlaylistEpisodesData, cnx=Depends(get_database_connection),
+                                    api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    if key_id != data.user_id and not is_web_key:
+        raise HTTPException(status_code=403,
+                            detail="You can only edit your own playlists!")
+
+    episode_ids = database_functions.functions.set_playlist_episodes(database_type, cnx, data.user_id, data.playlist_id, data.episode_ids)
+    if episode_ids is None:
+        raise HTTPException(status_code=404, detail="Playlist not found")
+    return {"episode_ids": episode_ids}
+
+
+@app.post("/api/data/add_playlist_episodes")
+async def api_add_playlist_episodes(data: PlaylistEpisodesData, cnx=Depends(get_database_connection),
+                                    api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    if key_id != data.user_id and not is_web_key:
+        raise HTTPException(status_code=403,
+                            detail="You can only edit your own playlists!")
+
+    episode_ids = database_functions.functions.add_playlist_episodes(database_type, cnx, data.user_id, data.playlist_id, data.episode_ids)
+    if episode_ids is None:
+        raise HTTPException(status_code=404, detail="Playlist not found")
+    return {"episode_ids": episode_ids}
+
+
+def check_goal_owner(cnx, api_key, user_id):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    if key_id != user_id and not is_web_key:
+        raise HTTPException(status_code=403,
+                            detail="You can only manage your own listening goals!")
+
+    # The shared guest account keeps no goals or streaks
+    if user_id == 1:
+        raise HTTPException(status_code=403, detail="Listening goals are not available for guest accounts")
+
+
+@app.get("/api/data/listening_goals/{user_id}")
+async def api_get_listening_goals(user_id: int, cnx=Depends(get_database_connection),
+                                  api_key: str = Depends(get_api_key_from_header)):
+    check_goal_owner(cnx, api_key, user_id)
+    goals = database_functions.functions.get_listening_goals(database_type, cnx, user_id)
+    streak = database_functions.functions.get_listening_streak(cnx, user_id)
+    return {"goals": goals, "streak": streak}
+
+
+class CreateListeningGoalData(BaseModel):
+    user_id: int
+    # "minutes" or "episodes"
+    goal_type: str
+    target: int
+    # "day", "week" or "month"
+    period: str
+
+
+@app.post("/api/data/create_listening_goal")
+async def api_create_listening_goal(data: CreateListeningGoalData, cnx=Depends(get_database_connection),
+                                    api_key: str = Depends(get_api_key_from_header)):
+    check_goal_owner(cnx, api_key, data.user_id)
+    if data.goal_type not in database_functions.functions.GOAL_TYPES:
+        raise HTTPException(status_code=400, detail="Goal type must be minutes or episodes")
+    if data.period not in database_functions.functions.GOAL_PERIODS:
+        raise HTTPException(status_code=400, detail="Goal period must be day, week or month")
+    if data.target < 1:
+        raise HTTPException(status_code=400, detail="Goal target must be at least 1")
+
+    goal_id = database_functions.functions.add_listening_goal(database_type, cnx, data.user_id, data.goal_type, data.target, data.period)
+    return {"goal_id": goal_id}
+
+
+class DeleteListeningGoalData(BaseModel):
+    user_id: int
+    goal_id: int
+
+
+@app.post("/api/data/delete_listening_goal")
+async def api_delete_listening_goal(data: DeleteListeningGoalData, cnx=Depends(get_database_connection),
+                                    api_key: str = Depends(get_api_key_from_header)):
+    check_goal_owner(cnx, api_key, data.user_id)
+    if not database_functions.functions.delete_listening_goal(database_type, cnx, data.user_id, data.goal_id):
+        raise HTTPException(status_code=404, detail="Goal not found")
+    return {"success": True}
+
+
+@app.get("/api/data/year_review/{user_id}")
+async def api_get_year_review(user_id: int, year: int = Query(...), cnx=Depends(get_database_connection),
+                              api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, api_key)
+
+    if key_id != user_id and not is_web_key:
+        raise HTTPException(status_code=403,
+                            detail="You can only view your own year in review!")
+
+    if year < 1970 or year > 9998:
+        raise HTTPException(status_code=400, detail="Year is out of range")
+    return database_functions.functions.get_year_review(database_type, cnx, user_id, year)
+
+
 # class QueuedEpisodesData(BaseModel):
 #     user_id: int
 
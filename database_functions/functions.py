@@ -7,6 +7,7 @@ import sys
 import os
 import requests
 import datetime
+import pytz
 import time
 import appdirs
 import base64
@@ -14,12 +15,14 @@ import subprocess
 import psycopg2
 from psycopg2.extras import RealDictCursor
 from requests.exceptions import RequestException
+from html import escape
 
 # # Get the application root directory from the environment variable
 # app_root = os.environ.get('APP_ROOT')
 sys.path.append('/pinepods/')
 # Import the functions directly from app_functions.py located in the database_functions directory
 from database_functions.app_functions import sync_subscription_change, get_podcast_values
+from database_functions.streaks import listening_streak, longest_listening_run
 
 def get_web_key(cnx):
     cursor = cnx.cursor()
@@ -53,8 +56,8 @@ def add_podcast(cnx, podcast_values, user_id):
 
     # insert the podcast into the database
     add_podcast = ("INSERT INTO Podcasts "
-                   "(PodcastName, ArtworkURL, Author, Categories, Description, EpisodeCount, FeedURL, WebsiteURL, Explicit, UserID) "
-                   "VALUES (%s, %s, %s, %s, %s, %s, %s, %s, %s, %s)")
+                   "(PodcastName, ArtworkURL, Author, Categories, Description, EpisodeCount, FeedURL, WebsiteURL, Explicit, UserID, DateAdded) "
+                   "VALUES (%s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s)")
     cursor.execute(add_podcast, (
         podcast_values['pod_title'], 
         podcast_values['pod_artwork'], 
@@ -65,7 +68,8 @@ def add_podcast(cnx, podcast_values, user_id):
         podcast_values['pod_feed_url'], 
         podcast_values['pod_website'],
         podcast_values['pod_explicit'], 
-        user_id
+        user_id,
+        datetime.datetime.utcnow()
     ))
 
     # get the ID of the newly-inserted podcast
@@ -178,6 +182,8 @@ def add_episodes(cnx, podcast_id, feed_url, artwork_url):
 
 
 
+    new_episode_ids = []
+
     for entry in episode_dump.entries:
         # Check necessary fields are present
         if not all(hasattr(entry, attr) for attr in ["title", "summary", "enclosures"]):
@@ -232,9 +238,15 @@ def add_episodes(cnx, podcast_id, feed_url, artwork_url):
 
         if cursor.rowcount > 0:
             print(f"Added episode '{parsed_title}'")
+            cursor.execute("SELECT EpisodeID FROM Episodes WHERE PodcastID = %s AND EpisodeTitle = %s", (podcast_id, parsed_title))
+            row = cursor.fetchone()
+            if row:
+                new_episode_ids.append(row[0])
 
     cnx.commit()
 
+    return new_episode_ids
+
 
 def remove_podcast(cnx, podcast_name, podcast_url, user_id):
     cursor = cnx.cursor()
@@ -266,6 +278,22 @@ def remove_podcast(cnx, podcast_name, podcast_url, user_id):
         delete_queue = "DELETE FROM EpisodeQueue WHERE EpisodeID IN (SELECT EpisodeID FROM Episodes WHERE PodcastID = %s)"
         cursor.execute(delete_queue, (podcast_id,))
 
+        # Delete per-podcast settings
+        delete_settings = "DELETE FROM PodcastSettings WHERE PodcastID = %s"
+        cursor.execute(delete_settings, (podcast_id,))
+
+        # Delete the play log
+        delete_plays = "DELETE FROM UserEpisodePlays WHERE EpisodeID IN (SELECT EpisodeID FROM Episodes WHERE PodcastID = %s)"
+        cursor.execute(delete_plays, (podcast_id,))
+
+        # Take the podcast out of any folders
+        delete_folder_members = "DELETE FROM PodcastFolderMembers WHERE PodcastID = %s"
+        cursor.execute(delete_folder_members, (podcast_id,))
+
+        # Take the podcast's episodes out of any playlists
+        delete_playlist_episodes = "DELETE FROM PlaylistEpisodes WHERE EpisodeID IN (SELECT EpisodeID FROM Episodes WHERE PodcastID = %s)"
+        cursor.execute(delete_playlist_episodes, (podcast_id,))
+
         # Delete episodes associated with the podcast
         delete_episodes = "DELETE FROM Episodes WHERE PodcastID = %s"
         cursor.execute(delete_episodes, (podcast_id,))
@@ -316,6 +344,22 @@ def remove_podcast_id(cnx, podcast_id, user_id):
         delete_queue = "DELETE FROM EpisodeQueue WHERE EpisodeID IN (SELECT EpisodeID FROM Episodes WHERE PodcastID = %s)"
         cursor.execute(delete_queue, (podcast_id,))
 
+        # Delete per-podcast settings
+        delete_settings = "DELETE FROM PodcastSettings WHERE PodcastID = %s"
+        cursor.execute(delete_settings, (podcast_id,))
+
+        # Delete the play log
+        delete_plays = "DELETE FROM UserEpisodePlays WHERE EpisodeID IN (SELECT EpisodeID FROM Episodes WHERE PodcastID = %s)"
+        cursor.execute(delete_plays, (podcast_id,))
+
+        # Take the podcast out of any folders
+        delete_folder_members = "DELETE FROM PodcastFolderMembers WHERE PodcastID = %s"
+        cursor.execute(delete_folder_members, (podcast_id,))
+
+        # Take the podcast's episodes out of any playlists
+        delete_playlist_episodes = "DELETE FROM PlaylistEpisodes WHERE EpisodeID IN (SELECT EpisodeID FROM Episodes WHERE PodcastID = %s)"
+        cursor.execute(delete_playlist_episodes, (podcast_id,))
+
         # Delete episodes associated with the podcast
         delete_episodes = "DELETE FROM Episodes WHERE PodcastID = %s"
         cursor.execute(delete_episodes, (podcast_id,))
@@ -350,7 +394,7 @@ def return_episodes(database_type, cnx, user_id):
 
     query = (f"SELECT Podcasts.PodcastName, Episodes.EpisodeTitle, Episodes.EpisodePubDate, "
              f"Episodes.EpisodeDescription, Episodes.EpisodeArtwork, Episodes.EpisodeURL, Episodes.EpisodeDuration, "
-             f"UserEpisodeHistory.ListenDuration, Episodes.EpisodeID "
+             f"UserEpisodeHistory.ListenDuration, Episodes.EpisodeID, Episodes.PodcastID "
              f"FROM Episodes "
              f"INNER JOIN Podcasts ON Episodes.PodcastID = Podcasts.PodcastID "
              f"LEFT JOIN UserEpisodeHistory ON Episodes.EpisodeID = UserEpisodeHistory.EpisodeID AND UserEpisodeHistory.UserID = %s "
@@ -369,7 +413,14 @@ def return_episodes(database_type, cnx, user_id):
     return rows
 
 
-def return_podcast_episodes(database_type, cnx, user_id, podcast_id):
+def paginate_query(query, params, offset, limit):
+    # Pages are only requested by clients that render long lists incrementally
+    if limit is None:
+        return query, params
+    return query + " LIMIT %s OFFSET %s", params + (limit, offset or 0)
+
+
+def return_podcast_episodes(database_type, cnx, user_id, podcast_id, offset=None, limit=None):
     if database_type == "postgresql":
         cursor = cnx.cursor(cursor_factory=RealDictCursor)
     else:  # Assuming MariaDB/MySQL if not PostgreSQL
@@ -379,15 +430,19 @@ def return_podcast_episodes(database_type, cnx, user_id, podcast_id):
         "SELECT Podcasts.PodcastID, Podcasts.PodcastName, Episodes.EpisodeID, "
         "Episodes.EpisodeTitle, Episodes.EpisodePubDate, Episodes.EpisodeDescription, "
         "Episodes.EpisodeArtwork, Episodes.EpisodeURL, Episodes.EpisodeDuration, "
-        "UserEpisodeHistory.ListenDuration, CAST(Episodes.EpisodeID AS CHAR) AS guid "
+        "UserEpisodeHistory.ListenDuration, CAST(Episodes.EpisodeID AS CHAR) AS guid, "
+        "CASE WHEN EXISTS (SELECT 1 FROM SavedEpisodes WHERE SavedEpisodes.EpisodeID = Episodes.EpisodeID AND SavedEpisodes.UserID = %s) THEN 1 ELSE 0 END AS Saved, "
+        "CASE WHEN EXISTS (SELECT 1 FROM DownloadedEpisodes WHERE DownloadedEpisodes.EpisodeID = Episodes.EpisodeID AND DownloadedEpisodes.UserID = %s) THEN 1 ELSE 0 END AS Downloaded "
         "FROM Episodes "
         "INNER JOIN Podcasts ON Episodes.PodcastID = Podcasts.PodcastID "
         "LEFT JOIN UserEpisodeHistory ON Episodes.EpisodeID = UserEpisodeHistory.EpisodeID AND UserEpisodeHistory.UserID = %s "
         "WHERE Podcasts.PodcastID = %s AND Podcasts.UserID = %s "
-        "ORDER BY Episodes.EpisodePubDate DESC"
+        "ORDER BY Episodes.EpisodePubDate DESC, Episodes.EpisodeID DESC"
     )
+    params = (user_id, user_id, user_id, podcast_id, user_id)
+    query, params = paginate_query(query, params, offset, limit)
 
-    cursor.execute(query, (user_id, podcast_id, user_id))
+    cursor.execute(query, params)
     rows = cursor.fetchall()
     cursor.close()
 
@@ -416,6 +471,168 @@ def get_podcast_id(database_type, cnx, user_id, podcast_feed, podcast_name):
     return row['PodcastID']  # Assuming the column name is 'PodcastID'
 
 
+PODCAST_SETTINGS_DEFAULTS = {
+    "PlaybackSpeed": 1.0,
+    "StartSkip": 0,
+    "EndSkip": 0,
+    "EpisodeSortOrder": "newest",
+    "AutoDownload": False,
+    "AutoQueue": False,
+    "NotifyNewEpisodes": False,
+}
+
+
+def format_podcast_settings(podcast_id, row):
+    settings = dict(PODCAST_SETTINGS_DEFAULTS)
+    if row:
+        for key in PODCAST_SETTINGS_DEFAULTS:
+            if row.get(key) is not None:
+                settings[key] = row[key]
+    settings["PodcastID"] = podcast_id
+    settings["PlaybackSpeed"] = float(settings["PlaybackSpeed"])
+    for key in ("AutoDownload", "AutoQueue", "NotifyNewEpisodes"):
+        settings[key] = bool(settings[key])
+    return settings
+
+
+def get_podcast_settings(database_type, cnx, user_id, podcast_id):
+    if database_type == "postgresql":
+        cursor = cnx.cursor(cursor_factory=RealDictCursor)
+    else:  # Assuming MariaDB/MySQL if not PostgreSQL
+        cursor = cnx.cursor(dictionary=True)
+
+    # Podcasts without a settings row fall back to the defaults
+    query = ("SELECT Podcasts.PodcastID, PodcastSettings.PlaybackSpeed, PodcastSettings.StartSkip, "
+             "PodcastSettings.EndSkip, PodcastSettings.EpisodeSortOrder, PodcastSettings.AutoDownload, "
+             "PodcastSettings.AutoQueue, PodcastSettings.NotifyNewEpisodes "
+             "FROM Podcasts "
+             "LEFT JOIN PodcastSettings ON PodcastSettings.PodcastID = Podcasts.PodcastID "
+             "AND PodcastSettings.UserID = Podcasts.UserID "
+             "WHERE Podcasts.PodcastID = %s AND Podcasts.UserID = %s")
+
+    cursor.execute(query, (podcast_id, user_id))
+    row = cursor.fetchone()
+    cursor.close()
+
+    if not row:
+        return None
+
+    return format_podcast_settings(row["PodcastID"], row)
+
+
+def get_episode_podcast_settings(database_type, cnx, user_id, episode_id):
+    if database_type == "postgresql":
+        cursor = cnx.cursor(cursor_factory=RealDictCursor)
+    else:  # Assuming MariaDB/MySQL if not PostgreSQL
+        cursor = cnx.cursor(dictionary=True)
+
+    query = ("SELECT Episodes.PodcastID FROM Episodes "
+             "JOIN Podcasts ON Episodes.PodcastID = Podcasts.PodcastID "
+             "WHERE Episodes.EpisodeID = %s AND Podcasts.UserID = %s")
+    cursor.execute(query, (episode_id, user_id))
+    row = cursor.fetchone()
+    cursor.close()
+
+    if not row:
+        return None
+
+    return get_podcast_settings(database_type, cnx, user_id, row["PodcastID"])
+
+
+def update_podcast_settings(database_type, cnx, user_id, podcast_id, settings):
+    if get_podcast_settings(database_type, cnx, user_id, podcast_id) is None:
+        return False
+
+    cursor = cnx.cursor()
+    values = (settings["PlaybackSpeed"], settings["StartSkip"], settings["EndSkip"],
+              settings["EpisodeSortOrder"], settings["AutoDownload"], settings["AutoQueue"],
+              settings["NotifyNewEpisodes"])
+
+    try:
+        cursor.execute("SELECT PodcastSettingID FROM PodcastSettings WHERE PodcastID = %s AND UserID = %s",
+                       (podcast_id, user_id))
+        if cursor.fetchone():
+            query = ("UPDATE PodcastSettings SET PlaybackSpeed = %s, StartSkip = %s, EndSkip = %s, "
+                     "EpisodeSortOrder = %s, AutoDownload = %s, AutoQueue = %s, NotifyNewEpisodes = %s "
+                     "WHERE PodcastID = %s AND UserID = %s")
+            cursor.execute(query, values + (podcast_id, user_id))
+        else:
+            query = ("INSERT INTO PodcastSettings "
+                     "(PlaybackSpeed, StartSkip, EndSkip, EpisodeSortOrder, AutoDownload, AutoQueue, "
+                     "NotifyNewEpisodes, PodcastID, UserID) "
+                     "VALUES (%s, %s, %s, %s, %s, %s, %s, %s, %s)")
+            cursor.execute(query, values + (podcast_id, user_id))
+        cnx.commit()
+    except Exception as e:
+        print("Error updating podcast settings:", e)
+        cnx.rollback()
+        return False
+    finally:
+        cursor.close()
+
+    return True
+
+
+def get_notification_episodes(database_type, cnx, user_id):
+    if database_type == "postgresql":
+        cursor = cnx.cursor(cursor_factory=RealDictCursor)
+    else:  # Assuming MariaDB/MySQL if not PostgreSQL
+        cursor = cnx.cursor(dictionary=True)
+
+    # Newest episode of every podcast the user asked to be notified about
+    query = ("SELECT Podcasts.PodcastID, Podcasts.PodcastName, Episodes.EpisodeID, Episodes.EpisodeTitle, "
+             "Episodes.EpisodePubDate, Episodes.EpisodeArtwork "
+             "FROM Podcasts "
+             "INNER JOIN PodcastSettings ON PodcastSettings.PodcastID = Podcasts.PodcastID "
+             "AND PodcastSettings.UserID = Podcasts.UserID "
+             "INNER JOIN Episodes ON Episodes.PodcastID = Podcasts.PodcastID "
+             "WHERE Podcasts.UserID = %s AND PodcastSettings.NotifyNewEpisodes = TRUE "
+             "AND Episodes.EpisodePubDate = (SELECT MAX(Latest.EpisodePubDate) FROM Episodes Latest "
+             "WHERE Latest.PodcastID = Podcasts.PodcastID) "
+             "ORDER BY Episodes.EpisodePubDate DESC, Episodes.EpisodeID DESC")
+
+    cursor.execute(query, (user_id,))
+    rows = cursor.fetchall()
+    cursor.close()
+
+    # Episodes published at the same moment would otherwise notify twice
+    newest = {}
+    for row in rows:
+        newest.setdefault(row["PodcastID"], row)
+    return list(newest.values())
+
+
+def apply_new_episode_settings(cnx, podcast_id, episode_ids):
+    cursor = cnx.cursor()
+    cursor.execute("SELECT UserID, AutoDownload, AutoQueue FROM PodcastSettings WHERE PodcastID = %s",
+                   (podcast_id,))
+    row = cursor.fetchone()
+
+    if not row:
+        cursor.close()
+        return
+
+    user_id, auto_download, auto_queue = row
+
+    if auto_queue:
+        cursor.execute("SELECT MAX(QueuePosition) FROM EpisodeQueue WHERE UserID = %s", (user_id,))
+        max_pos = cursor.fetchone()[0] or 0
+        for episode_id in episode_ids:
+            max_pos += 1
+            cursor.execute("INSERT INTO EpisodeQueue (UserID, EpisodeID, QueuePosition) VALUES (%s, %s, %s)",
+                           (user_id, episode_id, max_pos))
+        cnx.commit()
+
+    cursor.close()
+
+    if auto_download:
+        for episode_id in episode_ids:
+            try:
+                download_podcast(cnx, episode_id, user_id)
+            except Exception as e:
+                print(f"Error auto-downloading episode {episode_id}: {e}")
+
+
 def delete_episode(cnx, episode_id, user_id):
     cursor = cnx.cursor()
 
@@ -490,10 +707,20 @@ def return_pods(database_type, cnx, user_id):
     else:  # Assuming MariaDB/MySQL if not PostgreSQL
         cursor = cnx.cursor(dictionary=True)
 
+    # LatestEpisodeDate, TotalListenTime and DateAdded let the client sort by recent activity,
+    # listening and when each show was subscribed to
     query = (
-        "SELECT PodcastID, PodcastName, ArtworkURL, Description, EpisodeCount, WebsiteURL, FeedURL, Author, Categories, Explicit "
+        "SELECT Podcasts.PodcastID, Podcasts.PodcastName, Podcasts.ArtworkURL, Podcasts.Description, "
+        "Podcasts.EpisodeCount, Podcasts.WebsiteURL, Podcasts.FeedURL, Podcasts.Author, Podcasts.Categories, Podcasts.Explicit, "
+        "Podcasts.DateAdded, "
+        "MAX(Episodes.EpisodePubDate) AS LatestEpisodeDate, "
+        "COALESCE(SUM(UserEpisodeHistory.ListenDuration), 0) AS TotalListenTime "
         "FROM Podcasts "
-        "WHERE UserID = %s")
+        "LEFT JOIN Episodes ON Episodes.PodcastID = Podcasts.PodcastID "
+        "LEFT JOIN UserEpisodeHistory ON UserEpisodeHistory.EpisodeID = Episodes.EpisodeID "
+        "AND UserEpisodeHistory.UserID = Podcasts.UserID "
+        "WHERE Podcasts.UserID = %s "
+        "GROUP BY Podcasts.PodcastID")
 
     cursor.execute(query, (user_id,))
     rows = cursor.fetchall()
@@ -504,8 +731,49 @@ def return_pods(database_type, cnx, user_id):
     if not rows:
         return None
 
+    # SUM comes back as a Decimal, which would serialize as a float
+    for row in rows:
+        row["TotalListenTime"] = int(row.get("TotalListenTime") or 0)
+
     return rows
 
+
+def get_podcast_details(database_type, cnx, user_id, podcast_id):
+    # One subscribed podcast with the user's own listening of it, or None when it isn't theirs
+    if database_type == "postgresql":
+        cursor = cnx.cursor(cursor_factory=RealDictCursor)
+    else:  # Assuming MariaDB/MySQL if not PostgreSQL
+        cursor = cnx.cursor(dictionary=True)
+
+    cursor.execute("SELECT PodcastID, PodcastName, ArtworkURL, Description, EpisodeCount, WebsiteURL, FeedURL, "
+                   "Author, Categories, Explicit FROM Podcasts WHERE PodcastID = %s AND UserID = %s",
+                   (podcast_id, user_id))
+    podcast = cursor.fetchone()
+    if podcast is None:
+        cursor.close()
+        return None
+
+    # Finished matches the client: less than a minute, or five percent, left
+    cursor.execute("SELECT COUNT(*) AS EpisodesStarted, "
+                   "COALESCE(SUM(CASE WHEN Episodes.EpisodeDuration > 0 AND UserEpisodeHistory.ListenDuration >= "
+                   "Episodes.EpisodeDuration - GREATEST(60, Episodes.EpisodeDuration / 20) THEN 1 ELSE 0 END), 0) AS EpisodesCompleted, "
+                   "COALESCE(SUM(LEAST(UserEpisodeHistory.ListenDuration, Episodes.EpisodeDuration)), 0) AS TimeListened, "
+                   "MAX(UserEpisodeHistory.ListenDate) AS LastPlayed "
+                   "FROM UserEpisodeHistory "
+                   "JOIN Episodes ON UserEpisodeHistory.EpisodeID = Episodes.EpisodeID "
+                   "WHERE UserEpisodeHistory.UserID = %s AND Episodes.PodcastID = %s AND UserEpisodeHistory.ListenDuration > 0",
+                   (user_id, podcast_id))
+    stats = cursor.fetchone()
+    cursor.close()
+
+    podcast["EpisodesStarted"] = int(stats["EpisodesStarted"] or 0)
+    podcast["EpisodesCompleted"] = int(stats["EpisodesCompleted"] or 0)
+    podcast["TimeListened"] = int(stats["TimeListened"] or 0)
+    # Seconds precision, the format the client parses dates in
+    last_played = stats["LastPlayed"]
+    podcast["LastPlayed"] = last_played.strftime("%Y-%m-%dT%H:%M:%S") if last_played else None
+    return podcast
+
 def check_self_service(cnx):
     cursor = cnx.cursor()
     query = "SELECT SelfServiceUser FROM AppSettings"
@@ -533,7 +801,9 @@ def refresh_pods(cnx):
 
     for (podcast_id, feed_url, artwork_url) in result_set:
         print(f'Running for :{podcast_id}')
-        add_episodes(cnx, podcast_id, feed_url, artwork_url)
+        new_episode_ids = add_episodes(cnx, podcast_id, feed_url, artwork_url)
+        if new_episode_ids:
+            apply_new_episode_settings(cnx, podcast_id, new_episode_ids)
 
     cursor.close()
     # cnx.close()
@@ -655,7 +925,7 @@ def record_podcast_history(cnx, episode_id, user_id, episode_pos):
                             "WHERE UserEpisodeHistoryID = %s")
         progress_id = result[0]
         new_listen_duration = round(episode_pos)
-        now = datetime.now()
+        now = datetime.utcnow()
         values = (new_listen_duration, now, progress_id)
         cursor.execute(update_history, values)
     else:
@@ -664,10 +934,15 @@ def record_podcast_history(cnx, episode_id, user_id, episode_pos):
                         "(EpisodeID, UserID, ListenDuration, ListenDate) "
                         "VALUES (%s, %s, %s, %s)")
         new_listen_duration = round(episode_pos)
-        now = datetime.now()
+        now = datetime.utcnow()
         values = (episode_id, user_id, new_listen_duration, now)
         cursor.execute(add_history, values)
 
+    # Every start of playback is logged, so replays can be counted
+    if user_id != 1:
+        cursor.execute("INSERT INTO UserEpisodePlays (UserID, EpisodeID, PlayDate) VALUES (%s, %s, %s)",
+                       (user_id, episode_id, now))
+
     cnx.commit()
 
     cursor.close()
@@ -728,18 +1003,35 @@ def get_user_details_id(cnx, user_id):
         return None
 
 
-def user_history(cnx, user_id):
+def user_history(cnx, user_id, offset=None, limit=None, podcast_id=None, start=None, end=None):
     cursor = cnx.cursor()
     query = ("SELECT Episodes.EpisodeID, UserEpisodeHistory.ListenDate, UserEpisodeHistory.ListenDuration, "
              "Episodes.EpisodeTitle, Episodes.EpisodeDescription, Episodes.EpisodeArtwork, "
-             "Episodes.EpisodeURL, Episodes.EpisodeDuration, Podcasts.PodcastName, Episodes.EpisodePubDate "
+             "Episodes.EpisodeURL, Episodes.EpisodeDuration, Podcasts.PodcastName, Podcasts.PodcastID, "
+             "Episodes.EpisodePubDate "
              "FROM UserEpisodeHistory "
              "JOIN Episodes ON UserEpisodeHistory.EpisodeID = Episodes.EpisodeID "
              "JOIN Podcasts ON Episodes.PodcastID = Podcasts.PodcastID "
-             "WHERE UserEpisodeHistory.UserID = %s "
-             "ORDER BY UserEpisodeHistory.ListenDate")
+             "WHERE UserEpisodeHistory.UserID = %s ")
+    params = (user_id,)
+    # Filters narrow the same ordering, so paging through a filtered history works like the full one
+    if podcast_id is not None:
+        query += "AND Episodes.PodcastID = %s "
+        params += (podcast_id,)
+    if start is not None:
+        query += "AND UserEpisodeHistory.ListenDate >= %s "
+        params += (start,)
+    if end is not None:
+        query += "AND UserEpisodeHistory.ListenDate < %s "
+        params += (end,)
+    # Paged history starts from the most recent listen so the first page is the useful one
+    if limit is None:
+        query += "ORDER BY UserEpisodeHistory.ListenDate"
+    else:
+        query += "ORDER BY UserEpisodeHistory.ListenDate DESC, UserEpisodeHistory.EpisodeID DESC"
+    query, params = paginate_query(query, params, offset, limit)
 
-    cursor.execute(query, (user_id,))
+    cursor.execute(query, params)
     # results = cursor.fetchall()
     results = [dict(zip([column[0] for column in cursor.description], row)) for row in cursor.fetchall()]
 
@@ -1077,7 +1369,7 @@ def record_listen_duration(cnx, episode_id, user_id, listen_duration):
         logging.info(f"Skipped updating listen duration for user {user_id} and episode {episode_id} due to invalid duration: {listen_duration}")
         return
     
-    listen_date = datetime.datetime.now()
+    listen_date = datetime.datetime.utcnow()
     cursor = cnx.cursor()
 
     try:
@@ -1105,6 +1397,95 @@ def record_listen_duration(cnx, episode_id, user_id, listen_duration):
         cursor.close()
     # cnx.close()
 
+def set_episode_played(cnx, episode_id, user_id, played):
+    # Played episodes are recorded as listened to the end, unplayed ones as not started
+    listen_date = datetime.datetime.utcnow()
+    cursor = cnx.cursor()
+
+    try:
+        cursor.execute(
+            "SELECT Episodes.EpisodeDuration FROM Episodes "
+            "INNER JOIN Podcasts ON Episodes.PodcastID = Podcasts.PodcastID "
+            "WHERE Episodes.EpisodeID = %s AND Podcasts.UserID = %s",
+            (episode_id, user_id))
+        episode = cursor.fetchone()
+        if episode is None:
+            return None
+        listen_duration = episode[0] if played else 0
+
+        cursor.execute("SELECT ListenDuration FROM UserEpisodeHistory WHERE UserID=%s AND EpisodeID=%s", (user_id, episode_id))
+        if cursor.fetchone():
+            cursor.execute("UPDATE UserEpisodeHistory SET ListenDuration=%s, ListenDate=%s WHERE UserID=%s AND EpisodeID=%s",
+                           (listen_duration, listen_date, user_id, episode_id))
+        elif played:
+            cursor.execute("INSERT INTO UserEpisodeHistory (UserID, EpisodeID, ListenDate, ListenDuration) VALUES (%s, %s, %s, %s)",
+                           (user_id, episode_id, listen_date, listen_duration))
+
+        cnx.commit()
+        return listen_duration
+    except Exception as e:
+        logging.error(f"Failed to mark episode {episode_id} for user {user_id}: {e}")
+        cnx.rollback()
+        return None
+    finally:
+        cursor.close()
+
+
+def catch_up_podcast(database_type, cnx, user_id, podcast_id, older_than_episode_id=None, keep_latest=None):
+    # Marks a podcast's back catalog as played. older_than_episode_id limits it to episodes published
+    # before that one, keep_latest leaves the newest unfinished episodes alone. Returns what was marked,
+    # or None when the cutoff episode doesn't belong to the podcast.
+    if database_type == "postgresql":
+        cursor = cnx.cursor(cursor_factory=RealDictCursor)
+    else:  # Assuming MariaDB/MySQL if not PostgreSQL
+        cursor = cnx.cursor(dictionary=True)
+
+    try:
+        cursor.execute(
+            "SELECT Episodes.EpisodeID, Episodes.EpisodeDuration, Episodes.EpisodePubDate, "
+            "UserEpisodeHistory.ListenDuration "
+            "FROM Episodes "
+            "INNER JOIN Podcasts ON Episodes.PodcastID = Podcasts.PodcastID "
+            "LEFT JOIN UserEpisodeHistory ON Episodes.EpisodeID = UserEpisodeHistory.EpisodeID AND UserEpisodeHistory.UserID = %s "
+            "WHERE Podcasts.PodcastID = %s AND Podcasts.UserID = %s "
+            "ORDER BY Episodes.EpisodePubDate DESC, Episodes.EpisodeID DESC",
+            (user_id, podcast_id, user_id))
+        episodes = cursor.fetchall()
+
+        if older_than_episode_id is not None:
+            cutoff = next((episode for episode in episodes if episode["EpisodeID"] == older_than_episode_id), None)
+            if cutoff is None:
+                return None
+            episodes = [episode for episode in episodes if episode["EpisodePubDate"] < cutoff["EpisodePubDate"]]
+
+        # Finished matches the client: less than a minute, or five percent, left. Episodes without a
+        # known duration can't be marked finished, so they are left alone.
+        unfinished = [episode for episode in episodes
+                      if episode["EpisodeDuration"] and
+                      (episode["ListenDuration"] is None or
+                       episode["ListenDuration"] < episode["EpisodeDuration"] - max(60, episode["EpisodeDuration"] // 20))]
+        if keep_latest is not None:
+            unfinished = unfinished[keep_latest:]
+
+        listen_date = datetime.datetime.utcnow()
+        for episode in unfinished:
+            if episode["ListenDuration"] is None:
+                cursor.execute("INSERT INTO UserEpisodeHistory (UserID, EpisodeID, ListenDate, ListenDuration) VALUES (%s, %s, %s, %s)",
+                               (user_id, episode["EpisodeID"], listen_date, episode["EpisodeDuration"]))
+            else:
+                cursor.execute("UPDATE UserEpisodeHistory SET ListenDuration=%s, ListenDate=%s WHERE UserID=%s AND EpisodeID=%s",
+                               (episode["EpisodeDuration"], listen_date, user_id, episode["EpisodeID"]))
+        cnx.commit()
+
+        return [{"EpisodeID": episode["EpisodeID"], "ListenDuration": episode["EpisodeDuration"]} for episode in unfinished]
+    except Exception as e:
+        logging.error(f"Failed to catch up podcast {podcast_id} for user {user_id}: {e}")
+        cnx.rollback()
+        raise
+    finally:
+        cursor.close()
+
+
 def get_local_episode_times(cnx, user_id):
     cursor = cnx.cursor()
     # Correct SQL query to fetch all listen durations along with necessary URLs for the given user
@@ -1238,6 +1619,37 @@ def set_theme(cnx, user_id, theme):
             # cnx.close()
 
 
+def get_language(cnx, user_id):
+    cursor = None
+    try:
+        cursor = cnx.cursor()
+
+        # None until the user picks a language, so the device's choice is kept
+        query = "SELECT Language FROM UserSettings WHERE UserID = %s"
+        cursor.execute(query, (user_id,))
+        result = cursor.fetchone()
+
+        return result[0] if result else None
+
+    finally:
+        if cursor:
+            cursor.close()
+
+
+def set_language(cnx, user_id, language):
+    cursor = None
+    try:
+        cursor = cnx.cursor()
+
+        query = "UPDATE UserSettings SET Language = %s WHERE UserID = %s"
+        cursor.execute(query, (language, user_id))
+        cnx.commit()
+
+    finally:
+        if cursor:
+            cursor.close()
+
+
 def get_user_info(database_type, cnx):
     if database_type == "postgresql":
         cursor = cnx.cursor(cursor_factory=RealDictCursor)
@@ -1426,6 +1838,35 @@ def delete_user(cnx, user_id):
     except:
         pass
 
+    # Delete user from UserEpisodePlays table
+    try:
+        cursor.execute("DELETE FROM UserEpisodePlays WHERE UserID = %s", (user_id,))
+    except:
+        pass
+
+    # Delete the user's playlists, emptying them first since they reference the user's episodes
+    try:
+        cursor.execute("DELETE FROM PlaylistEpisodes WHERE PlaylistID IN "
+                       "(SELECT PlaylistID FROM Playlists WHERE UserID = %s)", (user_id,))
+        cursor.execute("DELETE FROM Playlists WHERE UserID = %s", (user_id,))
+    except:
+        pass
+
+    # Delete the user's folders, emptying them first
+    try:
+        cursor.execute("DELETE FROM PodcastFolderMembers WHERE FolderID IN "
+                       "(SELECT FolderID FROM PodcastFolders WHERE UserID = %s)", (user_id,))
+        cursor.execute("DELETE FROM PodcastFolders WHERE UserID = %s", (user_id,))
+    except:
+        pass
+
+    # Delete user from PodcastSettings table before the podcasts it references
+    try:
+        query = "DELETE FROM PodcastSettings WHERE UserID = %s"
+        cursor.execute(query, (user_id,))
+    except:
+        pass
+
     # Delete user from Podcasts table
     try:
         query = "DELETE FROM Podcasts WHERE UserID = %s"
@@ -1447,6 +1888,13 @@ def delete_user(cnx, user_id):
     except:
         pass
 
+    # Delete user from ListeningGoals and UserListenDays tables
+    try:
+        cursor.execute("DELETE FROM ListeningGoals WHERE UserID = %s", (user_id,))
+        cursor.execute("DELETE FROM UserListenDays WHERE UserID = %s", (user_id,))
+    except:
+        pass
+
     # Delete user from Users table
     query = "DELETE FROM Users WHERE UserID = %s"
     cursor.execute(query, (user_id,))
@@ -1690,6 +2138,65 @@ def get_stats(cnx, user_id):
     return stats
 
 
+def get_listening_activity(database_type, cnx, user_id, start=None, end=None):
+    if database_type == "postgresql":
+        cursor = cnx.cursor(cursor_factory=RealDictCursor)
+    else:  # Assuming MariaDB/MySQL if not PostgreSQL
+        cursor = cnx.cursor(dictionary=True)
+
+    # One row per listened episode. The client buckets them by day, hour, podcast and
+    # category in the user's own timezone.
+    query = ("SELECT UserEpisodeHistory.ListenDate, UserEpisodeHistory.ListenDuration, Episodes.EpisodeID, "
+             "Episodes.EpisodeDuration, Podcasts.PodcastID, Podcasts.PodcastName, Podcasts.Categories "
+             "FROM UserEpisodeHistory "
+             "JOIN Episodes ON UserEpisodeHistory.EpisodeID = Episodes.EpisodeID "
+             "JOIN Podcasts ON Episodes.PodcastID = Podcasts.PodcastID "
+             "WHERE UserEpisodeHistory.UserID = %s AND UserEpisodeHistory.ListenDuration > 0")
+    params = [user_id]
+    if start is not None:
+        query += " AND UserEpisodeHistory.ListenDate >= %s"
+        params.append(start)
+    if end is not None:
+        query += " AND UserEpisodeHistory.ListenDate < %s"
+        params.append(end)
+    query += " ORDER BY UserEpisodeHistory.ListenDate"
+
+    cursor.execute(query, tuple(params))
+    rows = cursor.fetchall()
+    cursor.close()
+
+    return rows
+
+
+def get_listening_days(cnx, user_id, start=None, end=None):
+    # Minutes listened on each of the user's local days, counted as the listening happens. History
+    # only keeps an episode's running total and latest play, so time per day comes from here.
+    # The UTC bounds are turned into the local days they fall on.
+    tz = user_timezone(cnx, user_id)
+
+    def local_day(moment):
+        if moment.tzinfo is None:
+            moment = pytz.utc.localize(moment)
+        return moment.astimezone(tz).date()
+
+    query = "SELECT ListenDay, Minutes FROM UserListenDays WHERE UserID = %s AND Minutes > 0"
+    params = [user_id]
+    if start is not None:
+        query += " AND ListenDay >= %s"
+        params.append(local_day(start))
+    if end is not None:
+        query += " AND ListenDay < %s"
+        params.append(local_day(end))
+    query += " ORDER BY ListenDay"
+
+    cursor = cnx.cursor()
+    cursor.execute(query, tuple(params))
+    days = [{"ListenDay": str(row[0]), "Minutes": row[1]} for row in cursor.fetchall()]
+    cursor.close()
+
+    return days
+
+
 def saved_episode_list(database_type, cnx, user_id):
     if database_type == "postgresql":
         cursor = cnx.cursor(cursor_factory=RealDictCursor)
@@ -1813,6 +2320,16 @@ def increment_listen_time(cnx, user_id):
     query = ("UPDATE UserStats SET TimeListened = TimeListened + 1 "
              "WHERE UserID = %s")
     cursor.execute(query, (user_id,))
+
+    # Each call is one minute of listening. Counting it against the user's local day drives
+    # streaks and time goals. The guest account keeps no goals.
+    if user_id != 1:
+        listen_day = user_local_now(cnx, user_id).date()
+        cursor.execute("UPDATE UserListenDays SET Minutes = Minutes + 1 WHERE UserID = %s AND ListenDay = %s",
+                       (user_id, listen_day))
+        if cursor.rowcount == 0:
+            cursor.execute("INSERT INTO UserListenDays (UserID, ListenDay, Minutes) VALUES (%s, %s, 1)",
+                           (user_id, listen_day))
     cnx.commit()
 
     cursor.close()
@@ -2288,6 +2805,37 @@ def remove_episode_history(database_type, cnx, url, title, user_id):
         return False
 
 
+def remove_episode_history_by_id(cnx, episode_id, user_id):
+    cursor = cnx.cursor()
+
+    try:
+        cursor.execute("DELETE FROM UserEpisodeHistory WHERE UserID = %s AND EpisodeID = %s", (user_id, episode_id))
+        cnx.commit()
+        return True
+    except Exception as e:
+        print("Error removing episode from history:", e)
+        cnx.rollback()
+        return False
+    finally:
+        cursor.close()
+
+
+def clear_history(cnx, user_id):
+    cursor = cnx.cursor()
+
+    try:
+        cursor.execute("DELETE FROM UserEpisodeHistory WHERE UserID = %s", (user_id,))
+        removed = cursor.rowcount
+        cnx.commit()
+        return removed
+    except Exception as e:
+        print("Error clearing history:", e)
+        cnx.rollback()
+        return None
+    finally:
+        cursor.close()
+
+
 def setup_timezone_info(database_type, cnx, user_id, timezone, hour_pref, date_format):
     if database_type == "postgresql":
         cursor = cnx.cursor(cursor_factory=RealDictCursor)
@@ -2427,7 +2975,7 @@ def delete_selected_podcasts(cnx, delete_list, user_id):
 import time
 
 
-def search_data(database_type, cnx, search_term, user_id):
+def search_data(database_type, cnx, search_term, user_id, offset=None, limit=None):
     if database_type == "postgresql":
         cursor = cnx.cursor(cursor_factory=RealDictCursor)
     else:  # Assuming MariaDB/MySQL if not PostgreSQL
@@ -2438,12 +2986,14 @@ def search_data(database_type, cnx, search_term, user_id):
     INNER JOIN Episodes ON Podcasts.PodcastID = Episodes.PodcastID 
     WHERE Podcasts.UserID = %s AND 
     Episodes.EpisodeTitle LIKE %s
+    ORDER BY Episodes.EpisodePubDate DESC, Episodes.EpisodeID DESC
     """
     search_term = '%' + search_term + '%'
+    query, params = paginate_query(query, (user_id, search_term), offset, limit)
 
     try:
         start = time.time()
-        cursor.execute(query, (user_id, search_term))
+        cursor.execute(query, params)
         result = cursor.fetchall()
         end = time.time()
         print(f"Query executed in {end - start} seconds.")
@@ -2571,11 +3121,13 @@ def get_queued_episodes(database_type, cnx, user_id):
         Episodes.EpisodeDuration, 
         EpisodeQueue.QueueDate,
         UserEpisodeHistory.ListenDuration,
-        Episodes.EpisodeID
+        Episodes.EpisodeID,
+        PodcastSettings.PlaybackSpeed
     FROM EpisodeQueue 
     INNER JOIN Episodes ON EpisodeQueue.EpisodeID = Episodes.EpisodeID 
     INNER JOIN Podcasts ON Episodes.PodcastID = Podcasts.PodcastID 
     LEFT JOIN UserEpisodeHistory ON EpisodeQueue.EpisodeID = UserEpisodeHistory.EpisodeID AND EpisodeQueue.UserID = UserEpisodeHistory.UserID
+    LEFT JOIN PodcastSettings ON PodcastSettings.PodcastID = Episodes.PodcastID AND PodcastSettings.UserID = EpisodeQueue.UserID
     WHERE EpisodeQueue.UserID = %s 
     ORDER BY EpisodeQueue.QueuePosition ASC
     """
@@ -2584,8 +3136,39 @@ def get_queued_episodes(database_type, cnx, user_id):
 
     cursor.close()
 
+    # Podcasts without a settings row play at the default speed
+    for episode in queued_episodes:
+        speed = episode["PlaybackSpeed"]
+        episode["PlaybackSpeed"] = float(speed) if speed is not None else PODCAST_SETTINGS_DEFAULTS["PlaybackSpeed"]
+
     return queued_episodes
 
+
+def reorder_queue(database_type, cnx, user_id, episode_ids):
+    # Rewrites every QueuePosition in one transaction. episode_ids must be exactly the user's
+    # current queue, so a stale client can't drop or duplicate entries; returns False otherwise.
+    if database_type == "postgresql":
+        cursor = cnx.cursor(cursor_factory=RealDictCursor)
+    else:  # Assuming MariaDB/MySQL if not PostgreSQL
+        cursor = cnx.cursor(dictionary=True)
+
+    try:
+        cursor.execute("SELECT EpisodeID FROM EpisodeQueue WHERE UserID = %s", (user_id,))
+        queued = {row["EpisodeID"] for row in cursor.fetchall()}
+        if len(episode_ids) != len(queued) or set(episode_ids) != queued:
+            return False
+
+        cursor.executemany("UPDATE EpisodeQueue SET QueuePosition = %s WHERE UserID = %s AND EpisodeID = %s",
+                           [(position, user_id, episode_id) for position, episode_id in enumerate(episode_ids, start=1)])
+        cnx.commit()
+        return True
+    except Exception as e:
+        logging.error(f"Failed to reorder queue for user {user_id}: {e}")
+        cnx.rollback()
+        raise
+    finally:
+        cursor.close()
+
 def check_episode_exists(cnx, user_id, episode_title, episode_url):
     cursor = cnx.cursor()
     query = """
@@ -2977,6 +3560,405 @@ def queue_bump(database_type, cnx, ep_url, title, user_id):
     return {"detail": f"{title} moved to the front of the queue."}
 
 
+def get_folders(database_type, cnx, user_id):
+    if database_type == "postgresql":
+        cursor = cnx.cursor(cursor_factory=RealDictCursor)
+    else:  # Assuming MariaDB/MySQL if not PostgreSQL
+        cursor = cnx.cursor(dictionary=True)
+
+    cursor.execute("SELECT FolderID, FolderName FROM PodcastFolders WHERE UserID = %s ORDER BY FolderName",
+                   (user_id,))
+    folders = [{"FolderID": row["FolderID"], "FolderName": row["FolderName"], "PodcastIDs": []}
+               for row in cursor.fetchall()]
+
+    cursor.execute("SELECT PodcastFolderMembers.FolderID, PodcastFolderMembers.PodcastID "
+                   "FROM PodcastFolderMembers "
+                   "JOIN PodcastFolders ON PodcastFolders.FolderID = PodcastFolderMembers.FolderID "
+                   "WHERE PodcastFolders.UserID = %s", (user_id,))
+    folders_by_id = {folder["FolderID"]: folder for folder in folders}
+    for row in cursor.fetchall():
+        if row["FolderID"] in folders_by_id:
+            folders_by_id[row["FolderID"]]["PodcastIDs"].append(row["PodcastID"])
+
+    cursor.close()
+    return folders
+
+
+def create_folder(database_type, cnx, user_id, folder_name):
+    cursor = cnx.cursor()
+
+    # Folder names are unique per user, so creating an existing one just returns it
+    cursor.execute("SELECT FolderID FROM PodcastFolders WHERE UserID = %s AND FolderName = %s",
+                   (user_id, folder_name))
+    existing = cursor.fetchone()
+    if existing:
+        cursor.close()
+        return existing[0]
+
+    cursor.execute("INSERT INTO PodcastFolders (UserID, FolderName) VALUES (%s, %s)", (user_id, folder_name))
+    cnx.commit()
+    cursor.execute("SELECT FolderID FROM PodcastFolders WHERE UserID = %s AND FolderName = %s",
+                   (user_id, folder_name))
+    folder_id = cursor.fetchone()[0]
+    cursor.close()
+    return folder_id
+
+
+def delete_folder(database_type, cnx, user_id, folder_id):
+    cursor = cnx.cursor()
+    cursor.execute("SELECT FolderID FROM PodcastFolders WHERE FolderID = %s AND UserID = %s", (folder_id, user_id))
+    if not cursor.fetchone():
+        cursor.close()
+        return False
+
+    cursor.execute("DELETE FROM PodcastFolderMembers WHERE FolderID = %s", (folder_id,))
+    cursor.execute("DELETE FROM PodcastFolders WHERE FolderID = %s", (folder_id,))
+    cnx.commit()
+    cursor.close()
+    return True
+
+
+def set_podcast_folders(database_type, cnx, user_id, podcast_id, folder_ids):
+    cursor = cnx.cursor()
+
+    cursor.execute("SELECT PodcastID FROM Podcasts WHERE PodcastID = %s AND UserID = %s", (podcast_id, user_id))
+    if not cursor.fetchone():
+        cursor.close()
+        return False
+
+    # Only folders owned by the same user can be assigned
+    cursor.execute("SELECT FolderID FROM PodcastFolders WHERE UserID = %s", (user_id,))
+    owned = {row[0] for row in cursor.fetchall()}
+
+    cursor.execute("DELETE FROM PodcastFolderMembers WHERE PodcastID = %s "
+                   "AND FolderID IN (SELECT FolderID FROM PodcastFolders WHERE UserID = %s)",
+                   (podcast_id, user_id))
+    for folder_id in set(folder_ids) & owned:
+        cursor.execute("INSERT INTO PodcastFolderMembers (FolderID, PodcastID) VALUES (%s, %s)",
+                       (folder_id, podcast_id))
+    cnx.commit()
+    cursor.close()
+    return True
+
+
+def get_playlists(database_type, cnx, user_id):
+    if database_type == "postgresql":
+        cursor = cnx.cursor(cursor_factory=RealDictCursor)
+    else:  # Assuming MariaDB/MySQL if not PostgreSQL
+        cursor = cnx.cursor(dictionary=True)
+
+    cursor.execute("SELECT PlaylistID, Name, Rules FROM Playlists WHERE UserID = %s ORDER BY Name", (user_id,))
+    playlists = [{"PlaylistID": row["PlaylistID"], "Name": row["Name"], "Rules": row["Rules"], "EpisodeIDs": []}
+                 for row in cursor.fetchall()]
+
+    cursor.execute("SELECT PlaylistEpisodes.PlaylistID, PlaylistEpisodes.EpisodeID "
+                   "FROM PlaylistEpisodes "
+                   "JOIN Playlists ON Playlists.PlaylistID = PlaylistEpisodes.PlaylistID "
+                   "WHERE Playlists.UserID = %s "
+                   "ORDER BY PlaylistEpisodes.Position", (user_id,))
+    playlists_by_id = {playlist["PlaylistID"]: playlist for playlist in playlists}
+    for row in cursor.fetchall():
+        if row["PlaylistID"] in playlists_by_id:
+            playlists_by_id[row["PlaylistID"]]["EpisodeIDs"].append(row["EpisodeID"])
+
+    cursor.close()
+    return playlists
+
+
+def owns_playlist(cnx, user_id, playlist_id):
+    cursor = cnx.cursor()
+    cursor.execute("SELECT PlaylistID FROM Playlists WHERE PlaylistID = %s AND UserID = %s", (playlist_id, user_id))
+    owned = cursor.fetchone() is not None
+    cursor.close()
+    return owned
+
+
+def create_playlist(database_type, cnx, user_id, name, rules):
+    # rules is the JSON for a smart playlist, None for a manual one
+    cursor = cnx.cursor()
+    cursor.execute("INSERT INTO Playlists (UserID, Name, Rules) VALUES (%s, %s, %s)", (user_id, name, rules))
+    cnx.commit()
+    cursor.execute("SELECT MAX(PlaylistID) FROM Playlists WHERE UserID = %s AND Name = %s", (user_id, name))
+    playlist_id = cursor.fetchone()[0]
+    cursor.close()
+    return playlist_id
+
+
+def update_playlist(database_type, cnx, user_id, playlist_id, name, rules):
+    if not owns_playlist(cnx, user_id, playlist_id):
+        return False
+
+    cursor = cnx.cursor()
+    cursor.execute("UPDATE Playlists SET Name = %s, Rules = %s WHERE PlaylistID = %s", (name, rules, playlist_id))
+    cnx.commit()
+    cursor.close()
+    return True
+
+
+def delete_playlist(database_type, cnx, user_id, playlist_id):
+    if not owns_playlist(cnx, user_id, playlist_id):
+        return False
+
+    cursor = cnx.cursor()
+    cursor.execute("DELETE FROM PlaylistEpisodes WHERE PlaylistID = %s", (playlist_id,))
+    cursor.execute("DELETE FROM Playlists WHERE PlaylistID = %s", (playlist_id,))
+    cnx.commit()
+    cursor.close()
+    return True
+
+
+def set_playlist_episodes(database_type, cnx, user_id, playlist_id, episode_ids):
+    # Replaces the playlist's contents and order in one transaction. Episodes that aren't from the
+    # user's podcasts are dropped and repeats keep their first position. Returns the saved ids,
+    # or None when the playlist isn't the user's.
+    if not owns_playlist(cnx, user_id, playlist_id):
+        return None
+
+    cursor = cnx.cursor()
+    try:
+        owned = set()
+        if episode_ids:
+            placeholders = ", ".join(["%s"] * len(episode_ids))
+            cursor.execute("SELECT Episodes.EpisodeID FROM Episodes "
+                           "JOIN Podcasts ON Episodes.PodcastID = Podcasts.PodcastID "
+                           f"WHERE Podcasts.UserID = %s AND Episodes.EpisodeID IN ({placeholders})",
+                           (user_id, *episode_ids))
+            owned = {row[0] for row in cursor.fetchall()}
+
+        saved = []
+        for episode_id in episode_ids:
+            if episode_id in owned and episode_id not in saved:
+                saved.append(episode_id)
+
+        cursor.execute("DELETE FROM PlaylistEpisodes WHERE PlaylistID = %s", (playlist_id,))
+        if saved:
+            cursor.executemany("INSERT INTO PlaylistEpisodes (PlaylistID, EpisodeID, Position) VALUES (%s, %s, %s)",
+                               [(playlist_id, episode_id, position) for position, episode_id in enumerate(saved, start=1)])
+        cnx.commit()
+        return saved
+    except Exception as e:
+        logging.error(f"Failed to update playlist {playlist_id} for user {user_id}: {e}")
+        cnx.rollback()
+        raise
+    finally:
+        cursor.close()
+
+
+def add_playlist_episodes(database_type, cnx, user_id, playlist_id, episode_ids):
+    # Appends to the end of the playlist, skipping episodes that are already in it
+    playlist = next((playlist for playlist in get_playlists(database_type, cnx, user_id)
+                     if playlist["PlaylistID"] == playlist_id), None)
+    if playlist is None:
+        return None
+    return set_playlist_episodes(database_type, cnx, user_id, playlist_id, playlist["EpisodeIDs"] + list(episode_ids))
+
+
+def get_playlist_episodes(database_type, cnx, user_id, playlist_id):
+    if not owns_playlist(cnx, user_id, playlist_id):
+        return None
+
+    if database_type == "postgresql":
+        cursor = cnx.cursor(cursor_factory=RealDictCursor)
+    else:  # Assuming MariaDB/MySQL if not PostgreSQL
+        cursor = cnx.cursor(dictionary=True)
+
+    query = ("SELECT Podcasts.PodcastName, Episodes.EpisodeTitle, Episodes.EpisodePubDate, "
+             "Episodes.EpisodeDescription, Episodes.EpisodeArtwork, Episodes.EpisodeURL, Episodes.EpisodeDuration, "
+             "UserEpisodeHistory.ListenDuration, Episodes.EpisodeID, Episodes.PodcastID "
+             "FROM PlaylistEpisodes "
+             "INNER JOIN Episodes ON PlaylistEpisodes.EpisodeID = Episodes.EpisodeID "
+             "INNER JOIN Podcasts ON Episodes.PodcastID = Podcasts.PodcastID "
+             "LEFT JOIN UserEpisodeHistory ON Episodes.EpisodeID = UserEpisodeHistory.EpisodeID AND UserEpisodeHistory.UserID = %s "
+             "WHERE PlaylistEpisodes.PlaylistID = %s "
+             "ORDER BY PlaylistEpisodes.Position")
+    cursor.execute(query, (user_id, playlist_id))
+    rows = cursor.fetchall()
+    cursor.close()
+    return rows
+
+
+GOAL_TYPES = ("minutes", "episodes")
+GOAL_PERIODS = ("day", "week", "month")
+
+
+def user_timezone(cnx, user_id):
+    cursor = cnx.cursor()
+    cursor.execute("SELECT TimeZone FROM Users WHERE UserID = %s", (user_id,))
+    row = cursor.fetchone()
+    cursor.close()
+    try:
+        return pytz.timezone(row[0]) if row and row[0] else pytz.utc
+    except pytz.UnknownTimeZoneError:
+        return pytz.utc
+
+
+def user_local_now(cnx, user_id):
+    return datetime.datetime.now(user_timezone(cnx, user_id))
+
+
+def goal_period_start(local_now, period):
+    today = local_now.date()
+    if period == "week":
+        return today - datetime.timedelta(days=today.weekday())
+    if period == "month":
+        return today.replace(day=1)
+    return today
+
+
+def get_listening_goals(database_type, cnx, user_id):
+    if database_type == "postgresql":
+        cursor = cnx.cursor(cursor_factory=RealDictCursor)
+    else:  # Assuming MariaDB/MySQL if not PostgreSQL
+        cursor = cnx.cursor(dictionary=True)
+
+    local_now = user_local_now(cnx, user_id)
+    cursor.execute("SELECT GoalID, GoalType, Target, Period FROM ListeningGoals WHERE UserID = %s ORDER BY GoalID",
+                   (user_id,))
+    goals = cursor.fetchall()
+
+    for goal in goals:
+        start = goal_period_start(local_now, goal["Period"])
+        if goal["GoalType"] == "minutes":
+            cursor.execute("SELECT COALESCE(SUM(Minutes), 0) AS Progress FROM UserListenDays "
+                           "WHERE UserID = %s AND ListenDay >= %s", (user_id, start))
+        else:
+            # Finished means less than a minute, or five percent, left. History is stored in UTC.
+            start_utc = local_now.tzinfo.localize(datetime.datetime.combine(start, datetime.time())) \
+                .astimezone(pytz.utc).replace(tzinfo=None)
+            cursor.execute("SELECT COUNT(*) AS Progress FROM UserEpisodeHistory "
+                           "JOIN Episodes ON UserEpisodeHistory.EpisodeID = Episodes.EpisodeID "
+                           "WHERE UserEpisodeHistory.UserID = %s AND UserEpisodeHistory.ListenDate >= %s "
+                           "AND Episodes.EpisodeDuration > 0 AND UserEpisodeHistory.ListenDuration >= "
+                           "Episodes.EpisodeDuration - GREATEST(60, Episodes.EpisodeDuration / 20)",
+                           (user_id, start_utc))
+        goal["Progress"] = int(cursor.fetchone()["Progress"])
+        goal["PeriodStart"] = start.isoformat()
+
+    cursor.close()
+    return goals
+
+
+def get_listening_streak(cnx, user_id):
+    # Consecutive local days with any listening
+    local_now = user_local_now(cnx, user_id)
+    today = local_now.date()
+    cursor = cnx.cursor()
+    cursor.execute("SELECT ListenDay FROM UserListenDays WHERE UserID = %s AND Minutes > 0 ORDER BY ListenDay DESC",
+                   (user_id,))
+    days = [row[0] if isinstance(row[0], datetime.date) else datetime.date.fromisoformat(str(row[0]))
+            for row in cursor.fetchall()]
+    cursor.close()
+
+    return listening_streak(days, today)
+
+
+def add_listening_goal(database_type, cnx, user_id, goal_type, target, period):
+    cursor = cnx.cursor()
+    cursor.execute("INSERT INTO ListeningGoals (UserID, GoalType, Target, Period) VALUES (%s, %s, %s, %s)",
+                   (user_id, goal_type, target, period))
+    cnx.commit()
+    cursor.execute("SELECT MAX(GoalID) FROM ListeningGoals WHERE UserID = %s", (user_id,))
+    goal_id = cursor.fetchone()[0]
+    cursor.close()
+    return goal_id
+
+
+def delete_listening_goal(database_type, cnx, user_id, goal_id):
+    cursor = cnx.cursor()
+    cursor.execute("DELETE FROM ListeningGoals WHERE GoalID = %s AND UserID = %s", (goal_id, user_id))
+    deleted = cursor.rowcount > 0
+    cnx.commit()
+    cursor.close()
+    return deleted
+
+
+YEAR_REVIEW_TOP = 5
+
+
+def get_year_review(database_type, cnx, user_id, year):
+    # Everything for one calendar year in the user's timezone. History keeps one row per
+    # episode, so listening counts towards the year the episode was last played in.
+    if database_type == "postgresql":
+        cursor = cnx.cursor(cursor_factory=RealDictCursor)
+    else:  # Assuming MariaDB/MySQL if not PostgreSQL
+        cursor = cnx.cursor(dictionary=True)
+
+    tz = user_local_now(cnx, user_id).tzinfo
+
+    def to_utc(day):
+        return tz.localize(datetime.datetime.combine(day, datetime.time())).astimezone(pytz.utc).replace(tzinfo=None)
+
+    first_day = datetime.date(year, 1, 1)
+    next_year = datetime.date(year + 1, 1, 1)
+    start, end = to_utc(first_day), to_utc(next_year)
+
+    cursor.execute("SELECT UserEpisodeHistory.ListenDate, UserEpisodeHistory.ListenDuration, "
+                   "Episodes.EpisodeDuration, Podcasts.PodcastID, Podcasts.PodcastName, Podcasts.ArtworkURL "
+                   "FROM UserEpisodeHistory "
+                   "JOIN Episodes ON UserEpisodeHistory.EpisodeID = Episodes.EpisodeID "
+                   "JOIN Podcasts ON Episodes.PodcastID = Podcasts.PodcastID "
+                   "WHERE UserEpisodeHistory.UserID = %s AND UserEpisodeHistory.ListenDuration > 0 "
+                   "AND UserEpisodeHistory.ListenDate >= %s AND UserEpisodeHistory.ListenDate < %s",
+                   (user_id, start, end))
+    history = cursor.fetchall()
+
+    total_seconds = 0
+    finished = 0
+    by_month = [0] * 12
+    podcasts = {}
+    for row in history:
+        seconds = min(row["ListenDuration"], row["EpisodeDuration"]) if row["EpisodeDuration"] else row["ListenDuration"]
+        total_seconds += seconds
+        if row["EpisodeDuration"] and row["ListenDuration"] >= row["EpisodeDuration"] - max(60, row["EpisodeDuration"] // 20):
+            finished += 1
+        local_date = pytz.utc.localize(row["ListenDate"]).astimezone(tz)
+        by_month[local_date.month - 1] += seconds
+        podcast = podcasts.setdefault(row["PodcastID"], {"PodcastID": row["PodcastID"], "PodcastName": row["PodcastName"],
+                                                         "ArtworkURL": row["ArtworkURL"], "Seconds": 0, "Episodes": 0})
+        podcast["Seconds"] += seconds
+        podcast["Episodes"] += 1
+    top_podcasts = sorted(podcasts.values(), key=lambda podcast: podcast["Seconds"], reverse=True)[:YEAR_REVIEW_TOP]
+
+    cursor.execute("SELECT ListenDay FROM UserListenDays WHERE UserID = %s AND Minutes > 0 "
+                   "AND ListenDay >= %s AND ListenDay < %s ORDER BY ListenDay DESC",
+                   (user_id, first_day, next_year))
+    days = [row["ListenDay"] if isinstance(row["ListenDay"], datetime.date) else datetime.date.fromisoformat(str(row["ListenDay"]))
+            for row in cursor.fetchall()]
+
+    cursor.execute("SELECT Episodes.EpisodeID, Episodes.EpisodeTitle, Episodes.EpisodeArtwork, Podcasts.PodcastName, "
+                   "COUNT(*) AS Plays "
+                   "FROM UserEpisodePlays "
+                   "JOIN Episodes ON UserEpisodePlays.EpisodeID = Episodes.EpisodeID "
+                   "JOIN Podcasts ON Episodes.PodcastID = Podcasts.PodcastID "
+                   "WHERE UserEpisodePlays.UserID = %s AND UserEpisodePlays.PlayDate >= %s AND UserEpisodePlays.PlayDate < %s "
+                   "GROUP BY Episodes.EpisodeID, Episodes.EpisodeTitle, Episodes.EpisodeArtwork, Podcasts.PodcastName "
+                   "HAVING COUNT(*) > 1 "
+                   "ORDER BY Plays DESC, Episodes.EpisodeID LIMIT 1",
+                   (user_id, start, end))
+    most_replayed = cursor.fetchone()
+    if most_replayed:
+        most_replayed["Plays"] = int(most_replayed["Plays"])
+
+    cursor.execute("SELECT PodcastID, PodcastName, ArtworkURL, DateAdded FROM Podcasts "
+                   "WHERE UserID = %s AND DateAdded >= %s AND DateAdded < %s ORDER BY DateAdded",
+                   (user_id, start, end))
+    new_subscriptions = cursor.fetchall()
+
+    cursor.close()
+    return {
+        "year": year,
+        "total_seconds": total_seconds,
+        "episodes_listened": len(history),
+        "episodes_finished": finished,
+        "podcasts_listened": len(podcasts),
+        "top_podcasts": top_podcasts,
+        "by_month": by_month,
+        "longest_streak": longest_listening_run(days),
+        "most_replayed": most_replayed,
+        "new_subscriptions": new_subscriptions,
+    }
+
+
 def backup_user(database_type, cnx, user_id):
     if database_type == "postgresql":
         cursor = cnx.cursor(cursor_factory=RealDictCursor)
@@ -2985,16 +3967,38 @@ def backup_user(database_type, cnx, user_id):
 
     # Fetch podcasts for the user
     cursor.execute(
-        "SELECT PodcastName, FeedURL FROM Podcasts WHERE UserID = %s", (user_id,)
+        "SELECT PodcastID, PodcastName, FeedURL FROM Podcasts WHERE UserID = %s", (user_id,)
     )
     podcasts = cursor.fetchall()
     cursor.close()
 
+    folders = get_folders(database_type, cnx, user_id)
+
+    def podcast_outline(podcast, indent):
+        name = escape(podcast["PodcastName"] or "", quote=True)
+        feed_url = escape(podcast["FeedURL"] or "", quote=True)
+        return f'{indent}<outline text="{name}" title="{name}" type="rss" xmlUrl="{feed_url}" />\n'
+
     # Construct the OPML content
     opml_content = '<?xml version="1.0" encoding="UTF-8"?>\n<opml version="2.0">\n  <head>\n    <title>Podcast Subscriptions</title>\n  </head>\n  <body>\n'
 
+    # Folders become nested outline groups, podcasts outside any folder stay at the top level
+    podcasts_by_id = {podcast["PodcastID"]: podcast for podcast in podcasts}
+    foldered_ids = set()
+    for folder in folders:
+        members = [podcasts_by_id[podcast_id] for podcast_id in folder["PodcastIDs"] if podcast_id in podcasts_by_id]
+        if not members:
+            continue
+        folder_name = escape(folder["FolderName"], quote=True)
+        opml_content += f'    <outline text="{folder_name}" title="{folder_name}">\n'
+        for podcast in members:
+            opml_content += podcast_outline(podcast, "      ")
+            foldered_ids.add(podcast["PodcastID"])
+        opml_content += '    </outline>\n'
+
     for podcast in podcasts:
-        opml_content += f'    <outline text="{podcast["PodcastName"]}" title="{podcast["PodcastName"]}" type="rss" xmlUrl="{podcast["FeedURL"]}" />\n'
+        if podcast["PodcastID"] not in foldered_ids:
+            opml_content += podcast_outline(podcast, "    ")
 
     opml_content += '  </body>\n</opml>'
 
@@ -0,0 +1,32 @@
+import datetime
+
+
+# Listening streaks, worked out from the local days a user listened on. Kept apart from the
+# database code so the day arithmetic can be tested on its own.
+
+def listening_streak(days, today):
+    # Days are newest first. A streak that ended yesterday is still current until today is
+    # over, which is when it is at risk.
+    listened_today = bool(days) and days[0] == today
+    current = 0
+    if days and days[0] >= today - datetime.timedelta(days=1):
+        expected = days[0]
+        for day in days:
+            if day != expected:
+                break
+            current += 1
+            expected = day - datetime.timedelta(days=1)
+
+    return {"current": current, "longest": longest_listening_run(days), "listened_today": listened_today}
+
+
+def longest_listening_run(days):
+    # Days are newest first
+    longest = 0
+    run = 0
+    previous = None
+    for day in days:
+        run = run + 1 if previous is not None and previous - day == datetime.timedelta(days=1) else 1
+        longest = max(longest, run)
+        previous = day
+    return longest
@@ -0,0 +1,51 @@
+import datetime
+import unittest
+
+from database_functions.streaks import listening_streak, longest_listening_run
+
+
+def days_ago(today, *offsets):
+    return [today - datetime.timedelta(days=offset) for offset in offsets]
+
+
+class ListeningStreakTest(unittest.TestCase):
+    today = datetime.date(2024, 3, 1)
+
+    def test_no_listening(self):
+        self.assertEqual(listening_streak([], self.today), {"current": 0, "longest": 0, "listened_today": False})
+
+    def test_streak_including_today(self):
+        streak = listening_streak(days_ago(self.today, 0, 1, 2), self.today)
+        self.assertEqual(streak, {"current": 3, "longest": 3, "listened_today": True})
+
+    def test_streak_ending_yesterday_is_current_but_at_risk(self):
+        streak = listening_streak(days_ago(self.today, 1, 2), self.today)
+        self.assertEqual(streak, {"current": 2, "longest": 2, "listened_today": False})
+
+    def test_streak_ending_earlier_is_broken(self):
+        streak = listening_streak(days_ago(self.today, 2, 3, 4), self.today)
+        self.assertEqual(streak, {"current": 0, "longest": 3, "listened_today": False})
+
+    def test_gap_ends_the_current_streak(self):
+        streak = listening_streak(days_ago(self.today, 0, 1, 3, 4, 5, 6), self.today)
+        self.assertEqual(streak, {"current": 2, "longest": 4, "listened_today": True})
+
+    def test_streak_across_month_end(self):
+        # 2024 is a leap year, so the day before March 1st is February 29th
+        days = [datetime.date(2024, 3, 1), datetime.date(2024, 2, 29), datetime.date(2024, 2, 28)]
+        self.assertEqual(listening_streak(days, self.today)["current"], 3)
+
+
+class LongestListeningRunTest(unittest.TestCase):
+    def test_single_day(self):
+        self.assertEqual(longest_listening_run([datetime.date(2024, 1, 1)]), 1)
+
+    def test_longest_of_several_runs(self):
+        days = [datetime.date(2024, 1, 10), datetime.date(2024, 1, 9),
+                datetime.date(2024, 1, 5), datetime.date(2024, 1, 4), datetime.date(2024, 1, 3),
+                datetime.date(2024, 1, 1)]
+        self.assertEqual(longest_listening_run(days), 3)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -213,6 +213,7 @@ try:
                         WebsiteURL TEXT,
                         Explicit TINYINT(1),
                         UserID INT,
+                        DateAdded DATETIME NULL DEFAULT CURRENT_TIMESTAMP,
                         FOREIGN KEY (UserID) REFERENCES Users(UserID)
                     )""")
 
@@ -243,9 +244,21 @@ try:
                         UserSettingID INT AUTO_INCREMENT PRIMARY KEY,
                         UserID INT UNIQUE,
                         Theme VARCHAR(255) DEFAULT 'nordic',
+                        Language VARCHAR(16) DEFAULT NULL,
                         FOREIGN KEY (UserID) REFERENCES Users(UserID)
                     )""")
 
+    def add_column_if_not_exists(cursor, table_name, column_name, column_definition):
+        cursor.execute(f"SELECT COUNT(1) FROM INFORMATION_SCHEMA.COLUMNS WHERE table_schema = DATABASE() AND table_name = '{table_name}' AND column_name = '{column_name}'")
+        if cursor.fetchone()[0] == 0:
+            cursor.execute(f"ALTER TABLE {table_name} ADD COLUMN {column_name} {column_definition}")
+
+    # Installs that predate the column
+    add_column_if_not_exists(cursor, "UserSettings", "Language", "VARCHAR(16) DEFAULT NULL")
+    # Podcasts added before the column existed keep a NULL date rather than all sharing today's
+    add_column_if_not_exists(cursor, "Podcasts", "DateAdded", "DATETIME NULL")
+    cursor.execute("ALTER TABLE Podcasts MODIFY DateAdded DATETIME NULL DEFAULT CURRENT_TIMESTAMP")
+
     cursor.execute("""INSERT IGNORE INTO UserSettings (UserID, Theme) VALUES ('1', 'nordic')""")
     cursor.execute("""INSERT IGNORE INTO UserSettings (UserID, Theme) VALUES ('2', 'nordic')""")
 
@@ -292,6 +305,89 @@ try:
                     FOREIGN KEY (EpisodeID) REFERENCES Episodes(EpisodeID)
                     )""")
 
+    # Create the PodcastSettings table
+    cursor.execute("""CREATE TABLE IF NOT EXISTS PodcastSettings (
+                    PodcastSettingID INT AUTO_INCREMENT PRIMARY KEY,
+                    PodcastID INT,
+                    UserID INT,
+                    PlaybackSpeed FLOAT NOT NULL DEFAULT 1.0,
+                    StartSkip INT NOT NULL DEFAULT 0,
+                    EndSkip INT NOT NULL DEFAULT 0,
+                    EpisodeSortOrder VARCHAR(16) NOT NULL DEFAULT 'newest',
+                    AutoDownload TINYINT(1) NOT NULL DEFAULT 0,
+                    AutoQueue TINYINT(1) NOT NULL DEFAULT 0,
+                    NotifyNewEpisodes TINYINT(1) NOT NULL DEFAULT 0,
+                    UNIQUE (PodcastID, UserID),
+                    FOREIGN KEY (PodcastID) REFERENCES Podcasts(PodcastID),
+                    FOREIGN KEY (UserID) REFERENCES Users(UserID)
+                    )""")
+
+    # Create the PodcastFolders table
+    cursor.execute("""CREATE TABLE IF NOT EXISTS PodcastFolders (
+                    FolderID INT AUTO_INCREMENT PRIMARY KEY,
+                    UserID INT,
+                    FolderName VARCHAR(255) NOT NULL,
+                    UNIQUE (UserID, FolderName),
+                    FOREIGN KEY (UserID) REFERENCES Users(UserID)
+                    )""")
+
+    # Create the PodcastFolderMembers table
+    cursor.execute("""CREATE TABLE IF NOT EXISTS PodcastFolderMembers (
+                    FolderID INT,
+                    PodcastID INT,
+                    PRIMARY KEY (FolderID, PodcastID),
+                    FOREIGN KEY (FolderID) REFERENCES PodcastFolders(FolderID),
+                    FOREIGN KEY (PodcastID) REFERENCES Podcasts(PodcastID)
+                    )""")
+
+    # Create the Playlists table. Rules holds the JSON for smart playlists and is NULL for manual ones
+    cursor.execute("""CREATE TABLE IF NOT EXISTS Playlists (
+                    PlaylistID INT AUTO_INCREMENT PRIMARY KEY,
+                    UserID INT,
+                    Name VARCHAR(255) NOT NULL,
+                    Rules TEXT,
+                    FOREIGN KEY (UserID) REFERENCES Users(UserID)
+                    )""")
+
+    # Create the PlaylistEpisodes table
+    cursor.execute("""CREATE TABLE IF NOT EXISTS PlaylistEpisodes (
+                    PlaylistID INT,
+                    EpisodeID INT,
+                    Position INT NOT NULL,
+                    PRIMARY KEY (PlaylistID, EpisodeID),
+                    FOREIGN KEY (PlaylistID) REFERENCES Playlists(PlaylistID),
+                    FOREIGN KEY (EpisodeID) REFERENCES Episodes(EpisodeID)
+                    )""")
+
+    # Create the ListeningGoals table
+    cursor.execute("""CREATE TABLE IF NOT EXISTS ListeningGoals (
+                    GoalID INT AUTO_INCREMENT PRIMARY KEY,
+                    UserID INT,
+                    GoalType VARCHAR(20) NOT NULL,
+                    Target INT NOT NULL,
+                    Period VARCHAR(10) NOT NULL,
+                    FOREIGN KEY (UserID) REFERENCES Users(UserID)
+                    )""")
+
+    # Create the UserListenDays table
+    cursor.execute("""CREATE TABLE IF NOT EXISTS UserListenDays (
+                    UserID INT,
+                    ListenDay DATE,
+                    Minutes INT DEFAULT 0,
+                    PRIMARY KEY (UserID, ListenDay),
+                    FOREIGN KEY (UserID) REFERENCES Users(UserID)
+                    )""")
+
+    # Create the UserEpisodePlays table
+    cursor.execute("""CREATE TABLE IF NOT EXISTS UserEpisodePlays (
+                    PlayID INT AUTO_INCREMENT PRIMARY KEY,
+                    UserID INT,
+                    EpisodeID INT,
+                    PlayDate TIMESTAMP,
+                    FOREIGN KEY (UserID) REFERENCES Users(UserID),
+                    FOREIGN KEY (EpisodeID) REFERENCES Episodes(EpisodeID)
+                    )""")
+
     # Create the Sessions table
     cursor.execute("""CREATE TABLE IF NOT EXISTS Sessions (
                     SessionID INT AUTO_INCREMENT PRIMARY KEY,
@@ -199,6 +199,7 @@ cursor.execute("""CREATE TABLE IF NOT EXISTS Podcasts (
                     FeedURL TEXT,
                     WebsiteURL TEXT,
                     UserID INT,
+                    DateAdded TIMESTAMP NULL DEFAULT CURRENT_TIMESTAMP,
                     FOREIGN KEY (UserID) REFERENCES Users(UserID)
                 )""")
 
@@ -234,9 +235,19 @@ cursor.execute("""CREATE TABLE IF NOT EXISTS UserSettings (
                     UserSettingID SERIAL PRIMARY KEY,
                     UserID INT UNIQUE,
                     Theme VARCHAR(255) DEFAULT 'nordic',
+                    Language VARCHAR(16) DEFAULT NULL,
                     FOREIGN KEY (UserID) REFERENCES Users(UserID)
                 )""")
 
+def add_column_if_not_exists(cursor, table_name, column_name, column_definition):
+    cursor.execute(f"ALTER TABLE {table_name} ADD COLUMN IF NOT EXISTS {column_name} {column_definition}")
+
+# Installs that predate the column
+add_column_if_not_exists(cursor, "UserSettings", "Language", "VARCHAR(16) DEFAULT NULL")
+# Podcasts added before the column existed keep a NULL date rather than all sharing today's
+add_column_if_not_exists(cursor, "Podcasts", "DateAdded", "TIMESTAMP NULL")
+cursor.execute("ALTER TABLE Podcasts ALTER COLUMN DateAdded SET DEFAULT CURRENT_TIMESTAMP")
+
 cursor.execute("""INSERT INTO UserSettings (UserID, Theme) VALUES ('1', 'nordic') ON CONFLICT (UserID) DO NOTHING""")
 cursor.execute("""INSERT INTO UserSettings (UserID, Theme) VALUES ('2', 'nordic') ON CONFLICT (UserID) DO NOTHING""")
 
@@ -283,6 +294,89 @@ cursor.execute("""CREATE TABLE IF NOT EXISTS EpisodeQueue (
                   FOREIGN KEY (EpisodeID) REFERENCES Episodes(EpisodeID)
                 )""")
 
+# Create the PodcastSettings table
+cursor.execute("""CREATE TABLE IF NOT EXISTS PodcastSettings (
+                  PodcastSettingID SERIAL PRIMARY KEY,
+                  PodcastID INT,
+                  UserID INT,
+                  PlaybackSpeed REAL NOT NULL DEFAULT 1.0,
+                  StartSkip INT NOT NULL DEFAULT 0,
+                  EndSkip INT NOT NULL DEFAULT 0,
+                  EpisodeSortOrder VARCHAR(16) NOT NULL DEFAULT 'newest',
+                  AutoDownload BOOLEAN NOT NULL DEFAULT FALSE,
+                  AutoQueue BOOLEAN NOT NULL DEFAULT FALSE,
+                  NotifyNewEpisodes BOOLEAN NOT NULL DEFAULT FALSE,
+                  UNIQUE (PodcastID, UserID),
+                  FOREIGN KEY (PodcastID) REFERENCES Podcasts(PodcastID),
+                  FOREIGN KEY (UserID) REFERENCES Users(UserID)
+                )""")
+
+# Create the PodcastFolders table
+cursor.execute("""CREATE TABLE IF NOT EXISTS PodcastFolders (
+                  FolderID SERIAL PRIMARY KEY,
+                  UserID INT,
+                  FolderName VARCHAR(255) NOT NULL,
+                  UNIQUE (UserID, FolderName),
+                  FOREIGN KEY (UserID) REFERENCES Users(UserID)
+                )""")
+
+# Create the PodcastFolderMembers table
+cursor.execute("""CREATE TABLE IF NOT EXISTS PodcastFolderMembers (
+                  FolderID INT,
+                  PodcastID INT,
+                  PRIMARY KEY (FolderID, PodcastID),
+                  FOREIGN KEY (FolderID) REFERENCES PodcastFolders(FolderID),
+                  FOREIGN KEY (PodcastID) REFERENCES Podcasts(PodcastID)
+                )""")
+
+# Create the Playlists table. Rules holds the JSON for smart playlists and is NULL for manual ones
+cursor.execute("""CREATE TABLE IF NOT EXISTS Playlists (
+                  PlaylistID SERIAL PRIMARY KEY,
+                  UserID INT,
+                  Name VARCHAR(255) NOT NULL,
+                  Rules TEXT,
+                  FOREIGN KEY (UserID) REFERENCES Users(UserID)
+                )""")
+
+# Create the PlaylistEpisodes table
+cursor.execute("""CREATE TABLE IF NOT EXISTS PlaylistEpisodes (
+                  PlaylistID INT,
+                  EpisodeID INT,
+                  Position INT NOT NULL,
+                  PRIMARY KEY (PlaylistID, EpisodeID),
+                  FOREIGN KEY (PlaylistID) REFERENCES Playlists(PlaylistID),
+                  FOREIGN KEY (EpisodeID) REFERENCES Episodes(EpisodeID)
+                )""")
+
+# Create the ListeningGoals table
+cursor.execute("""CREATE TABLE IF NOT EXISTS ListeningGoals (
+                  GoalID SERIAL PRIMARY KEY,
+                  UserID INT,
+                  GoalType VARCHAR(20) NOT NULL,
+                  Target INT NOT NULL,
+                  Period VARCHAR(10) NOT NULL,
+                  FOREIGN KEY (UserID) REFERENCES Users(UserID)
+                )""")
+
+# Create the UserListenDays table
+cursor.execute("""CREATE TABLE IF NOT EXISTS UserListenDays (
+                  UserID INT,
+                  ListenDay DATE,
+                  Minutes INT DEFAULT 0,
+                  PRIMARY KEY (UserID, ListenDay),
+                  FOREIGN KEY (UserID) REFERENCES Users(UserID)
+                )""")
+
+# Create the UserEpisodePlays table
+cursor.execute("""CREATE TABLE IF NOT EXISTS UserEpisodePlays (
+                  PlayID SERIAL PRIMARY KEY,
+                  UserID INT,
+                  EpisodeID INT,
+                  PlayDate TIMESTAMP,
+                  FOREIGN KEY (UserID) REFERENCES Users(UserID),
+                  FOREIGN KEY (EpisodeID) REFERENCES Episodes(EpisodeID)
+                )""")
+
 # Create the Sessions table
 cursor.execute("""CREATE TABLE IF NOT EXISTS Sessions (
                   SessionID SERIAL PRIMARY KEY,
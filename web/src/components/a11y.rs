@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::{window, HtmlElement, KeyboardEvent};
+use yew::prelude::*;
+use yewdux::prelude::*;
+use crate::components::context::{AppState, UIState};
+
+// Shared keyboard and screen reader helpers. Rows rendered by `episode_item` carry a
+// `data-list-row` attribute so arrow keys can move between them on any page, and menus
+// use `role="menuitem"` children so focus can be walked and trapped inside them.
+
+pub const LIST_ROW_SELECTOR: &str = "[data-list-row]";
+pub const MENU_ITEM_SELECTOR: &str = "[role='menuitem']";
+
+fn focusable_elements(root: &web_sys::Element, selector: &str) -> Vec<HtmlElement> {
+    let mut elements = Vec::new();
+    if let Ok(nodes) = root.query_selector_all(selector) {
+        for i in 0..nodes.length() {
+            if let Some(element) = nodes.item(i).and_then(|node| node.dyn_into::<HtmlElement>().ok()) {
+                elements.push(element);
+            }
+        }
+    }
+    elements
+}
+
+fn active_index(elements: &[HtmlElement]) -> Option<usize> {
+    let active = window()?.document()?.active_element()?;
+    elements.iter().position(|element| {
+        let element: &web_sys::Element = element.as_ref();
+        element == &active || element.contains(Some(&active))
+    })
+}
+
+// Enter or Space on a non-button element behaves like a click
+pub fn activate_on_key(e: KeyboardEvent) {
+    if e.key() == "Enter" || e.key() == " " {
+        e.prevent_default();
+        e.stop_propagation();
+        if let Some(element) = e.current_target().and_then(|target| target.dyn_into::<HtmlElement>().ok()) {
+            element.click();
+        }
+    }
+}
+
+// Arrow key navigation between episode rows. Home/End jump to the ends of the list.
+pub fn on_list_keydown(e: KeyboardEvent) {
+    let Some(document) = window().and_then(|w| w.document()) else {
+        return;
+    };
+    let Some(root) = document.document_element() else {
+        return;
+    };
+    let rows = focusable_elements(&root, LIST_ROW_SELECTOR);
+    if rows.is_empty() {
+        return;
+    }
+    let current = active_index(&rows);
+    let next = match e.key().as_str() {
+        "ArrowDown" => current.map(|i| (i + 1).min(rows.len() - 1)).unwrap_or(0),
+        "ArrowUp" => current.map(|i| i.saturating_sub(1)).unwrap_or(0),
+        "Home" => 0,
+        "End" => rows.len() - 1,
+        _ => return,
+    };
+    e.prevent_default();
+    let _ = rows[next].focus();
+}
+
+pub fn focus_first_menu_item(container: &NodeRef) {
+    if let Some(menu) = container.cast::<web_sys::Element>() {
+        if let Some(first) = focusable_elements(&menu, MENU_ITEM_SELECTOR).first() {
+            let _ = first.focus();
+        }
+    }
+}
+
+// Keyboard handling for an open menu: arrows move, Tab is trapped inside, Escape closes
+pub fn on_menu_keydown(e: &KeyboardEvent, container: &NodeRef, on_close: &Callback<()>) {
+    let Some(menu) = container.cast::<web_sys::Element>() else {
+        return;
+    };
+    let items = focusable_elements(&menu, MENU_ITEM_SELECTOR);
+    if items.is_empty() {
+        return;
+    }
+    let current = active_index(&items).unwrap_or(0);
+    let last = items.len() - 1;
+    let next = match e.key().as_str() {
+        "ArrowDown" => if current == last { 0 } else { current + 1 },
+        "ArrowUp" => if current == 0 { last } else { current - 1 },
+        "Home" => 0,
+        "End" => last,
+        "Tab" if e.shift_key() => if current == 0 { last } else { current - 1 },
+        "Tab" => if current == last { 0 } else { current + 1 },
+        "Escape" => {
+            e.prevent_default();
+            e.stop_propagation();
+            on_close.emit(());
+            return;
+        }
+        _ => return,
+    };
+    e.prevent_default();
+    e.stop_propagation();
+    let _ = items[next].focus();
+}
+
+// Used by global shortcuts so they never steal keys from form controls or menus
+pub fn focus_is_in_control() -> bool {
+    let Some(active) = window().and_then(|w| w.document()).and_then(|d| d.active_element()) else {
+        return false;
+    };
+    let tag = active.tag_name().to_lowercase();
+    let role = active.get_attribute("role").unwrap_or_default();
+    matches!(tag.as_str(), "input" | "textarea" | "select" | "button" | "a")
+        || matches!(role.as_str(), "menuitem" | "button" | "link" | "slider" | "checkbox")
+        || active.has_attribute("contenteditable")
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct AccessibilityPreferences {
+    pub reduce_motion: bool,
+    pub font_scale: u16,
+}
+
+impl Default for AccessibilityPreferences {
+    fn default() -> Self {
+        let prefers_reduced_motion = window()
+            .and_then(|w| w.match_media("(prefers-reduced-motion: reduce)").ok().flatten())
+            .map(|query| query.matches())
+            .unwrap_or(false);
+        AccessibilityPreferences {
+            reduce_motion: prefers_reduced_motion,
+            font_scale: 100,
+        }
+    }
+}
+
+pub const FONT_SCALES: [u16; 5] = [90, 100, 112, 125, 150];
+
+pub fn load_accessibility_preferences() -> AccessibilityPreferences {
+    window()
+        .and_then(|w| w.local_storage().ok().flatten())
+        .and_then(|storage| storage.get_item("accessibility_preferences").ok().flatten())
+        .and_then(|stored| serde_json::from_str(&stored).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_accessibility_preferences(preferences: &AccessibilityPreferences) {
+    if let Some(storage) = window().and_then(|w| w.local_storage().ok().flatten()) {
+        if let Ok(serialized) = serde_json::to_string(preferences) {
+            let _ = storage.set_item("accessibility_preferences", &serialized);
+        }
+    }
+    apply_accessibility_preferences(preferences);
+}
+
+// Font scaling works through the root font size since the Tailwind sizes are rem based
+pub fn apply_accessibility_preferences(preferences: &AccessibilityPreferences) {
+    if let Some(root) = window().and_then(|w| w.document()).and_then(|d| d.document_element()) {
+        let class_list = root.class_list();
+        if preferences.reduce_motion {
+            let _ = class_list.add_1("reduce-motion");
+        } else {
+            let _ = class_list.remove_1("reduce-motion");
+        }
+        if let Ok(root) = root.dyn_into::<HtmlElement>() {
+            let _ = root.style().set_property("font-size", &format!("{}%", preferences.font_scale));
+        }
+    }
+}
+
+// Screen reader announcements for the snackbar messages from both stores
+#[function_component(LiveAnnouncer)]
+pub fn live_announcer() -> Html {
+    let (state, _dispatch) = use_store::<AppState>();
+    let (ui_state, _ui_dispatch) = use_store::<UIState>();
+    let info = ui_state.info_message.clone().or(state.info_message.clone()).unwrap_or_default();
+    let error = ui_state.error_message.clone().or(state.error_message.clone()).unwrap_or_default();
+
+    html! {
+        <>
+            <div class="sr-only" role="status" aria-live="polite" aria-atomic="true">{ info }</div>
+            <div class="sr-only" role="alert" aria-live="assertive" aria-atomic="true">{ error }</div>
+        </>
+    }
+}
@@ -1,7 +1,11 @@
 use yew::prelude::*;
 use yew_router::prelude::Link;
 use yewdux::{use_store};
-use crate::components::context::AppState;
+use crate::components::context::{AppState, UIState};
+use crate::components::profiles::{load_profiles, active_profile_id, switch_profile};
+use crate::components::i18n::{t, t_args};
+use crate::components::folders::DrawerFolders;
+use crate::components::playlists::use_playlists;
 use super::routes::Route;
 
 #[allow(non_camel_case_types)]
@@ -11,7 +15,11 @@ pub fn app_drawer() -> Html {
     // let (state, _dispatch) = use_store::<AppState>();
 
     let is_drawer_open = use_state(|| false);
+    let show_profiles = use_state(|| false);
     let (state, _dispatch) = use_store::<AppState>();
+    let (_ui_state, ui_dispatch) = use_store::<UIState>();
+    // Loaded here since the drawer is on every page and episode menus offer the manual playlists
+    use_playlists();
     let username = state.user_details.as_ref().map_or("Guest".to_string(), |ud| ud.Username.clone().unwrap());
     let toggle_drawer = {
         let is_drawer_open = is_drawer_open.clone();
@@ -28,6 +36,20 @@ pub fn app_drawer() -> Html {
         }
     };
 
+    let toggle_profiles = {
+        let show_profiles = show_profiles.clone();
+        Callback::from(move |_: MouseEvent| {
+            show_profiles.set(!*show_profiles);
+        })
+    };
+
+    // Every saved account other than the one currently signed in
+    let active_id = active_profile_id();
+    let other_profiles: Vec<_> = load_profiles()
+        .into_iter()
+        .filter(|profile| Some(&profile.id) != active_id.as_ref())
+        .collect();
+
     html! {
         <div class="relative min-h-screen">
             // Drawer
@@ -54,12 +76,69 @@ pub fn app_drawer() -> Html {
                             </Link<Route>>
                         </div>
 
+                            // Saved server profiles
+                            <div class="flex items-center space-x-3">
+                                <div onclick={toggle_profiles} class="drawer-text flex items-center space-x-3 cursor-pointer">
+                                    <span class="material-icons icon-space">{"switch_account"}</span>
+                                    <span class="text-lg">{ t("drawer.switch_account") }</span>
+                                    <span class="material-icons">{ if *show_profiles { "expand_less" } else { "expand_more" } }</span>
+                                </div>
+                            </div>
+                            {
+                                if *show_profiles {
+                                    html! {
+                                        <div class="ml-6 space-y-3">
+                                            {
+                                                for other_profiles.iter().map(|profile| {
+                                                    let on_switch = {
+                                                        let profile = profile.clone();
+                                                        let state = state.clone();
+                                                        let dispatch = _dispatch.clone();
+                                                        let ui_dispatch = ui_dispatch.clone();
+                                                        let toggle_drawer = toggle_drawer.clone();
+                                                        Callback::from(move |e: MouseEvent| {
+                                                            switch_profile(profile.clone(), &state, dispatch.clone(), ui_dispatch.clone());
+                                                            toggle_drawer(e);
+                                                        })
+                                                    };
+                                                    html! {
+                                                        <div onclick={on_switch} class="drawer-text flex items-center space-x-3 cursor-pointer">
+                                                            <img
+                                                                src={profile.gravatar_url.clone().unwrap_or_else(|| "/static/assets/favicon.png".to_string())}
+                                                                style="width: 20px; height: 20px;"
+                                                                class="rounded-full"
+                                                                alt={t_args("profiles.avatar", &[("name", profile.display_name.as_str())])}
+                                                            />
+                                                            <span class="text-md">{ &profile.display_name }</span>
+                                                        </div>
+                                                    }
+                                                })
+                                            }
+                                            <div onclick={toggle_drawer.clone()} class="drawer-text flex items-center space-x-3 cursor-pointer">
+                                                <Link<Route> to={Route::ChangeServer}>
+                                                    <span class="material-icons icon-space">{"person_add"}</span>
+                                                    <span class="text-md">{ t("drawer.add_account") }</span>
+                                                </Link<Route>>
+                                            </div>
+                                            <div onclick={toggle_drawer.clone()} class="drawer-text flex items-center space-x-3 cursor-pointer">
+                                                <Link<Route> to={Route::Settings}>
+                                                    <span class="material-icons icon-space">{"manage_accounts"}</span>
+                                                    <span class="text-md">{ t("drawer.manage_accounts") }</span>
+                                                </Link<Route>>
+                                            </div>
+                                        </div>
+                                    }
+                                } else {
+                                    html! {}
+                                }
+                            }
+
                             // Other Links
                             <div class="m-0 p-0 flex items-center space-x-3">
                                 <div onclick={toggle_drawer.clone()} class="drawer-text flex items-center space-x-3 cursor-pointer">
                                     <Link<Route> to={Route::Home}>
                                         <span class="material-standard-color material-icons icon-space">{"home"}</span>
-                                        <span class="text-lg">{"Home"}</span>
+                                        <span class="text-lg">{ t("drawer.home") }</span>
                                     </Link<Route>>
                                 </div>
                             </div>
@@ -67,7 +146,7 @@ pub fn app_drawer() -> Html {
                                 <div onclick={toggle_drawer.clone()} class="drawer-text flex items-center space-x-3 cursor-pointer">
                                     <Link<Route> to={Route::Search}>
                                         <span class="material-icons icon-space">{"search"}</span>
-                                        <span class="text-lg">{"Search Podcasts"}</span>
+                                        <span class="text-lg">{ t("drawer.search") }</span>
                                     </Link<Route>>
                                 </div>
                             </div>
@@ -75,7 +154,7 @@ pub fn app_drawer() -> Html {
                                 <div onclick={toggle_drawer.clone()} class="drawer-text flex items-center space-x-3 cursor-pointer">
                                     <Link<Route> to={Route::Queue}>
                                     <span class="material-icons icon-space">{"queue"}</span>
-                                    <span class="text-lg">{"Queue"}</span>
+                                    <span class="text-lg">{ t("drawer.queue") }</span>
                                     </Link<Route>>
                                 </div>
                             </div>
@@ -83,7 +162,15 @@ pub fn app_drawer() -> Html {
                                 <div onclick={toggle_drawer.clone()} class="drawer-text flex items-center space-x-3 cursor-pointer">
                                     <Link<Route> to={Route::Saved}>
                                         <span class="material-icons icon-space">{"star"}</span>
-                                        <span class="text-lg">{"Saved"}</span>
+                                        <span class="text-lg">{ t("drawer.saved") }</span>
+                                    </Link<Route>>
+                                </div>
+                            </div>
+                            <div class="flex items-center space-x-3">
+                                <div onclick={toggle_drawer.clone()} class="drawer-text flex items-center space-x-3 cursor-pointer">
+                                    <Link<Route> to={Route::Playlists}>
+                                        <span class="material-icons icon-space">{"queue_music"}</span>
+                                        <span class="text-lg">{ t("drawer.playlists") }</span>
                                     </Link<Route>>
                                 </div>
                             </div>
@@ -91,7 +178,7 @@ pub fn app_drawer() -> Html {
                                 <div onclick={toggle_drawer.clone()} class="drawer-text flex items-center space-x-3 cursor-pointer">
                                     <Link<Route> to={Route::PodHistory}>
                                         <span class="material-icons icon-space">{"history"}</span>
-                                        <span class="text-lg">{"History"}</span>
+                                        <span class="text-lg">{ t("drawer.history") }</span>
                                     </Link<Route>>
                                 </div>
                             </div>
@@ -99,7 +186,7 @@ pub fn app_drawer() -> Html {
                             <div onclick={toggle_drawer.clone()} class="drawer-text flex items-center space-x-3 cursor-pointer">
                                 <Link<Route> to={Route::Downloads}>
                                     <span class="material-icons icon-space">{"download"}</span>
-                                    <span class="text-lg">{"Downloads"}</span>
+                                    <span class="text-lg">{ t("drawer.downloads") }</span>
                                 </Link<Route>>
                             </div>
                         </div>
@@ -107,15 +194,16 @@ pub fn app_drawer() -> Html {
                                 <div onclick={toggle_drawer.clone()} class="drawer-text flex items-center space-x-3 cursor-pointer">
                                     <Link<Route> to={Route::Podcasts}>
                                         <span class="material-icons icon-space">{"podcasts"}</span>
-                                        <span class="text-lg">{"Podcasts"}</span>
+                                        <span class="text-lg">{ t("drawer.podcasts") }</span>
                                     </Link<Route>>
                                 </div>
                             </div>
+                            <DrawerFolders on_navigate={Callback::from(toggle_drawer.clone())} />
                             <div class="flex items-center space-x-3">
                                 <div onclick={toggle_drawer.clone()} class="drawer-text flex items-center space-x-3 cursor-pointer">
                                     <Link<Route> to={Route::Settings}>
                                         <span class="material-icons icon-space">{"settings"}</span>
-                                        <span class="text-lg">{"Settings"}</span>
+                                        <span class="text-lg">{ t("drawer.settings") }</span>
                                     </Link<Route>>
                                 </div>
                             </div>
@@ -126,7 +214,7 @@ pub fn app_drawer() -> Html {
                     <div onclick={toggle_drawer.clone()} class="drawer-text flex items-center space-x-3 cursor-pointer">
                         <Link<Route> to={Route::LogOut}>
                             <span class="material-icons icon-space">{"logout"}</span>
-                            <span class="text-lg">{"Sign Out"}</span>
+                            <span class="text-lg">{ t("drawer.sign_out") }</span>
                         </Link<Route>>
                     </div>
                 </div>
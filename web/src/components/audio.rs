@@ -1,9 +1,10 @@
 use gloo_timers::callback::Interval;
 use yew::{Callback, function_component, Html, html};
 use yew::prelude::*;
+use crate::components::i18n::{t, t_args};
 use yew_router::history::{BrowserHistory, History};
 use yewdux::prelude::*;
-use crate::components::context::{AppState, UIState};
+use crate::components::context::{AppState, UIState, EpisodeCache, EpisodeCacheMsg, EpisodeList};
 use web_sys::{window, HtmlAudioElement, HtmlInputElement};
 use std::string::String;
 use wasm_bindgen::closure::Closure;
@@ -11,8 +12,9 @@ use wasm_bindgen_futures::spawn_local;
 use wasm_bindgen::JsCast;
 use web_sys::HtmlElement;
 use std::rc::Rc;
-use crate::requests::pod_req::{call_add_history, HistoryAddRequest, call_record_listen_duration, RecordListenDurationRequest, call_increment_listen_time, call_increment_played, call_get_queued_episodes, call_remove_queued_episode, QueuePodcastRequest, call_queue_episode, call_check_episode_in_db};
+use crate::requests::pod_req::{call_add_history, HistoryAddRequest, call_record_listen_duration, RecordListenDurationRequest, call_increment_listen_time, call_increment_played, call_get_queued_episodes, call_remove_queued_episode, QueuePodcastRequest, call_queue_episode, call_check_episode_in_db, call_get_episode_podcast_settings};
 use futures_util::stream::StreamExt;
+use crate::components::a11y;
 
 
 #[derive(Properties, PartialEq, Debug, Clone)]
@@ -105,7 +107,8 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
             let keydown_handler = {
                 let audio_info = audio_dispatch_effect.clone();
                 Closure::wrap(Box::new(move |event: KeyboardEvent| {
-                    if event.key() == " " {
+                    // Leave the spacebar alone while a control has focus so it can still be activated or typed into
+                    if event.key() == " " && !a11y::focus_is_in_control() {
                         // Prevent the default behavior of the spacebar key
                         event.prevent_default();
                         // Toggle `audio_playing` here
@@ -146,6 +149,12 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
                         0.0
                     };
 
+                    // Honour the podcast's outro skip by jumping to the end, which fires the usual ended handling
+                    let end_skip = audio_dispatch.get().playback_settings.as_ref().map_or(0, |settings| settings.EndSkip);
+                    if end_skip > 0 && duration > 0.0 && !audio_element.ended() && time_in_seconds >= duration - end_skip as f64 {
+                        audio_element.set_current_time(duration);
+                    }
+
                     audio_dispatch.reduce_mut(move |state_clone| {
                         // Update the global state with the current time
                         state_clone.current_time_seconds = time_in_seconds;
@@ -190,6 +199,8 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
                                 user_id: user_id.unwrap().clone(),
                                 listen_duration,
                             };
+                            // Progress bars on every page read from the shared episode cache
+                            Dispatch::<EpisodeCache>::global().apply(EpisodeCacheMsg::SetProgress(episode_id.unwrap(), listen_duration as i32));
     
                             // Perform the API call to record the listen duration
                             match call_record_listen_duration(&server_name.clone().unwrap(), &api_key.clone().unwrap().unwrap(), request_data).await {
@@ -271,10 +282,18 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
                     let audio_state = audio_state.clone();
                     // Closure::wrap(Box::new(move |_| {
                     wasm_bindgen_futures::spawn_local(async move {
+                        // This closure outlives the episode it was set up for, so read what just finished from the store
+                        let ui_state = Dispatch::<UIState>::global().get();
+                        let current_episode_id = ui_state.currently_playing.as_ref().map(|props| props.episode_id).or(current_episode_id);
+                        // Inside a playlist the next episode comes from the playlist rather than the queue
+                        let playlist = ui_state
+                            .playing_playlist
+                            .clone()
+                            .filter(|playlist| current_episode_id.map_or(false, |id| playlist.episode_ids.contains(&id)));
                         let queued_episodes_result = call_get_queued_episodes(&server_name.clone().unwrap(), &api_key.clone().unwrap(), &user_id.clone().unwrap()).await;
                         match queued_episodes_result {
                             Ok(episodes) => {
-                                if let Some(current_episode) = episodes.iter().find(|ep| ep.EpisodeID == current_episode_id.unwrap()) {
+                                if let Some(current_episode) = episodes.iter().find(|ep| Some(ep.EpisodeID) == current_episode_id) {
                                     let current_queue_position = current_episode.QueuePosition.unwrap_or_default();
                                     // Remove the currently playing episode from the queue
                                     let request = QueuePodcastRequest {
@@ -284,13 +303,16 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
                                     let remove_result = call_remove_queued_episode(&server_name.clone().unwrap(), &api_key.clone().unwrap(), &request).await;
                                     match remove_result {
                                         Ok(_) => {
+                                            Dispatch::<EpisodeCache>::global().apply(EpisodeCacheMsg::SetMembership(EpisodeList::Queue, request.episode_id, false));
                                             // web_sys::console::log_1(&"Successfully removed episode from queue".into());
                                         },
                                         Err(_e) => {
                                             // web_sys::console::log_1(&format!("Failed to remove episode from queue: {:?}", e).into());
                                         }
                                     }
-                                    if let Some(next_episode) = episodes.iter().find(|ep| ep.QueuePosition == Some(current_queue_position + 1)) {
+                                    if playlist.is_some() {
+                                        // Continued below
+                                    } else if let Some(next_episode) = episodes.iter().find(|ep| ep.QueuePosition == Some(current_queue_position + 1)) {
                                         on_play_click(
                                             next_episode.EpisodeURL.clone(),
                                             next_episode.EpisodeTitle.clone(),
@@ -316,6 +338,36 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
                                 // web_sys::console::log_1(&format!("Failed to fetch queued episodes: {:?}", e).into());
                             }
                         }
+                        if let (Some(playlist), Some(finished_id)) = (playlist, current_episode_id) {
+                            let next_episode = playlist
+                                .next_after(finished_id)
+                                .and_then(|next_id| Dispatch::<EpisodeCache>::global().get().episodes.get(&next_id).cloned());
+                            match next_episode {
+                                Some(next_episode) => {
+                                    on_play_click(
+                                        next_episode.url.clone(),
+                                        next_episode.title.clone(),
+                                        next_episode.artwork.clone(),
+                                        next_episode.duration,
+                                        next_episode.episode_id,
+                                        next_episode.listen_duration,
+                                        api_key.clone().unwrap().unwrap(),
+                                        user_id.unwrap(),
+                                        server_name.clone().unwrap(),
+                                        audio_dispatch.clone(),
+                                        audio_state.clone(),
+                                        None,
+                                    ).emit(MouseEvent::new("click").unwrap());
+                                },
+                                None => {
+                                    // End of the playlist
+                                    audio_dispatch.reduce_mut(|state| {
+                                        state.audio_playing = Some(false);
+                                        state.playing_playlist = None;
+                                    });
+                                },
+                            }
+                        }
                     });
                     // }) as Box<dyn FnMut()>);
     
@@ -371,6 +423,19 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
     };
 
 
+    // Rewind
+    let skip_back = {
+        let audio_dispatch = _audio_dispatch.clone();
+        let audio_state = audio_state.clone();
+        Callback::from(move |_| {
+            if let Some(audio_element) = audio_state.audio_element.as_ref() {
+                let new_time = (audio_element.current_time() - 15.0).max(0.0);
+                audio_element.set_current_time(new_time);
+                audio_dispatch.reduce_mut(|state| state.update_current_time(new_time));
+            }
+        })
+    };
+
 // Skip forward
     let skip_forward = {
         // let dispatch = _dispatch.clone();
@@ -430,40 +495,46 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
             )
         };
         
+        let is_playing = audio_state.audio_playing.unwrap_or(false);
+        let play_label = if is_playing { t("player.pause") } else { t("player.play") };
+        let seek_value_text = t_args("player.position", &[("current", audio_state.current_time_formatted.as_str()), ("total", formatted_duration.as_str())]);
         let audio_bar_class = classes!("audio-player", "border", "border-solid", "border-color", "fixed", "bottom-0", "z-50", "w-full", if audio_state.is_expanded { "expanded" } else { "" });
         html! {
-            <div class={audio_bar_class} ref={container_ref.clone()}>
+            <div class={audio_bar_class} ref={container_ref.clone()} role="region" aria-label={t_args("player.region", &[("title", audio_props.title.as_str())])}>
                 <div class="top-section">
-                    <button onclick={title_click.clone()} class="retract-button">
-                        <span class="material-icons">{"expand_more"}</span>
+                    <button onclick={title_click.clone()} class="retract-button" aria-label={t("player.collapse")}>
+                        <span class="material-icons" aria-hidden="true">{"expand_more"}</span>
                     </button>
-                    <img onclick={title_click.clone()} src={audio_props.artwork_url.clone()} />
+                    <img onclick={title_click.clone()} src={audio_props.artwork_url.clone()} alt={t_args("a11y.cover_for", &[("title", audio_props.title.as_str())])} />
                     <div class="title" onclick={title_click.clone()}>{ &audio_props.title }
                     </div>
                     <div class="scrub-bar">
-                        <span>{audio_state.current_time_formatted.clone()}</span>
+                        <span aria-hidden="true">{audio_state.current_time_formatted.clone()}</span>
                         <input type="range"
                             class="flex-grow h-1 cursor-pointer"
                             min="0.0"
                             max={audio_props.duration_sec.to_string().clone()}
+                            step="5"
                             value={audio_state.current_time_seconds.to_string()}
                             oninput={update_time.clone()}
                             style={progress_style}
+                            aria-label={t("player.seek")}
+                            aria-valuetext={seek_value_text.clone()}
                         />
-                        <span>{formatted_duration.clone()}</span>
+                        <span aria-hidden="true">{formatted_duration.clone()}</span>
                     </div>
 
                     <div class="button-container flex items-center justify-center">
-                        <button class="rewind-button item-container-button border-solid border selector-button font-bold py-2 px-4 rounded-full w-10 h-10 flex items-center justify-center">
-                            <span class="material-icons">{"fast_rewind"}</span>
+                        <button onclick={skip_back.clone()} aria-label={t("player.rewind")} class="rewind-button item-container-button border-solid border selector-button font-bold py-2 px-4 rounded-full w-10 h-10 flex items-center justify-center">
+                            <span class="material-icons" aria-hidden="true">{"fast_rewind"}</span>
                         </button>
-                        <button onclick={toggle_playback.clone()} class="item-container-button border-solid border selector-button font-bold py-2 px-4 rounded-full w-10 h-10 flex items-center justify-center">
-                            <span class="material-icons">
-                                { if audio_state.audio_playing.unwrap_or(false) { "pause" } else { "play_arrow" } }
+                        <button onclick={toggle_playback.clone()} aria-label={play_label.clone()} class="item-container-button border-solid border selector-button font-bold py-2 px-4 rounded-full w-10 h-10 flex items-center justify-center">
+                            <span class="material-icons" aria-hidden="true">
+                                { if is_playing { "pause" } else { "play_arrow" } }
                             </span>
                         </button>
-                        <button onclick={skip_forward.clone()} class="skip-button item-container-button border-solid border selector-button font-bold py-2 px-4 rounded-full w-10 h-10 flex items-center justify-center">
-                            <span class="material-icons">{"fast_forward"}</span>
+                        <button onclick={skip_forward.clone()} aria-label={t("player.skip_forward")} class="skip-button item-container-button border-solid border selector-button font-bold py-2 px-4 rounded-full w-10 h-10 flex items-center justify-center">
+                            <span class="material-icons" aria-hidden="true">{"fast_forward"}</span>
                         </button>
                     </div>
                     <div class="button-container flex items-center justify-center">
@@ -474,13 +545,13 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
                                     on_shownotes_click.emit(e.clone());
                                     title_click_emit.emit(e);
                                 })} class="item-container-button audio-full-button border-solid border selector-button font-bold py-2 px-4 rounded-full flex items-center justify-center">
-                                    { "Shownotes" }
+                                    { t("player.shownotes") }
                                 </button>
                             }
                         } else {
                             html! {
                                 <button disabled=true class="item-container-button audio-full-button border-solid border selector-button font-bold py-2 px-4 rounded-full flex items-center justify-center opacity-50 cursor-not-allowed">
-                                    { "Shownotes (Unavailable)" }
+                                    { t("player.shownotes_unavailable") }
                                 </button>
                             }
                         }
@@ -490,30 +561,33 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
                 </div>
                 <div class="line-content">
                 <div class="left-group">
-                    <img class={artwork_class} src={audio_props.artwork_url.clone()} />
-                    <div class="title" onclick={title_click.clone()}>
+                    <img class={artwork_class} src={audio_props.artwork_url.clone()} alt={t_args("a11y.cover_for", &[("title", audio_props.title.as_str())])} />
+                    <div class="title" role="button" tabindex="0" aria-label={t("player.expand")} onclick={title_click.clone()} onkeydown={Callback::from(a11y::activate_on_key)}>
                         <span>{ &audio_props.title }</span>
                     </div>
                 </div>
                 <div class="right-group">
-                    <button onclick={toggle_playback} class="item-container-button border-solid border selector-button font-bold py-2 px-4 rounded-full w-10 h-10 flex items-center justify-center">
-                        <span class="material-icons">
-                            { if audio_state.audio_playing.unwrap_or(false) { "pause" } else { "play_arrow" } }
+                    <button onclick={toggle_playback} aria-label={play_label.clone()} class="item-container-button border-solid border selector-button font-bold py-2 px-4 rounded-full w-10 h-10 flex items-center justify-center">
+                        <span class="material-icons" aria-hidden="true">
+                            { if is_playing { "pause" } else { "play_arrow" } }
                         </span>
                     </button>
-                    <button onclick={skip_forward} class="item-container-button border-solid border selector-button font-bold py-2 px-4 rounded-full w-10 h-10 flex items-center justify-center">
-                        <span class="material-icons">{"fast_forward"}</span>
+                    <button onclick={skip_forward} aria-label={t("player.skip_forward")} class="item-container-button border-solid border selector-button font-bold py-2 px-4 rounded-full w-10 h-10 flex items-center justify-center">
+                        <span class="material-icons" aria-hidden="true">{"fast_forward"}</span>
                     </button>
                     <div class="flex-grow flex items-center sm:block hidden">
                         <div class="flex items-center flex-nowrap">
-                            <span class="time-display px-2">{audio_state.current_time_formatted.clone()}</span>
+                            <span class="time-display px-2" aria-hidden="true">{audio_state.current_time_formatted.clone()}</span>
                             <input type="range"
                                 class="flex-grow h-1 cursor-pointer"
                                 min="0.0"
                                 max={audio_props.duration_sec.to_string().clone()}
+                                step="5"
                                 value={audio_state.current_time_seconds.to_string()}
-                                oninput={update_time.clone()} />
-                            <span class="time-display px-2">{formatted_duration}</span>
+                                oninput={update_time.clone()}
+                                aria-label={t("player.seek")}
+                                aria-valuetext={seek_value_text} />
+                            <span class="time-display px-2" aria-hidden="true">{formatted_duration}</span>
                         </div>
                     </div>
                 </div>
@@ -561,7 +635,13 @@ pub fn on_play_click(
         let episode_title_for_closure = episode_title_for_closure.clone();
         let episode_artwork_for_closure = episode_artwork_for_closure.clone();
         let episode_duration_for_closure = episode_duration_for_closure.clone();
-        let listen_duration_for_closure = listen_duration_for_closure.clone();
+        // Resume from the latest progress the player recorded, even if this page fetched earlier
+        let listen_duration_for_closure = Dispatch::<EpisodeCache>::global()
+            .get()
+            .episodes
+            .get(&episode_id_for_closure)
+            .and_then(|episode| episode.listen_duration)
+            .or(listen_duration_for_closure);
         let episode_id_for_closure = episode_id_for_closure.clone();
         let api_key = api_key.clone();
         let user_id = user_id.clone();
@@ -637,6 +717,7 @@ pub fn on_play_click(
                 );
                 match add_queue_future.await {
                     Ok(_) => {
+                        Dispatch::<EpisodeCache>::global().apply(EpisodeCacheMsg::SetMembership(EpisodeList::Queue, episode_id, true));
                         // web_sys::console::log_1(&"Successfully Added Episode to Queue".into());
                     },
                     Err(_e) => {
@@ -675,23 +756,41 @@ pub fn on_play_click(
             src
         };
 
-        audio_dispatch.reduce_mut(move |audio_state| {
-            audio_state.audio_playing = Some(true);
-            audio_state.currently_playing = Some(AudioPlayerProps {
-                src: src.clone(),
-                title: episode_title_for_wasm.clone(),
-                artwork_url: episode_artwork_for_wasm.clone(),
-                duration: episode_duration_for_wasm.clone().to_string(),
-                episode_id: episode_id_for_wasm.clone(),
-                duration_sec: formatted_duration,
-                start_pos_sec: listen_duration_for_closure.unwrap_or(0) as f64, 
+        let settings_server_name = server_name.clone();
+        let settings_api_key = Some(api_key.clone());
+        spawn_local(async move {
+            // Start at the podcast's own speed and intro skip instead of correcting them once playback is under way.
+            // Episodes that aren't in the library have no settings and play with the defaults.
+            let settings = call_get_episode_podcast_settings(&settings_server_name, &settings_api_key, user_id, episode_id).await.ok();
+            let playback_rate = settings.as_ref().map_or(1.0, |settings| settings.PlaybackSpeed);
+            let start_skip = settings.as_ref().map_or(0, |settings| settings.StartSkip);
+            let start_pos = listen_duration_for_closure.unwrap_or(0).max(start_skip) as f64;
+            audio_dispatch.reduce_mut(move |audio_state| {
+                audio_state.audio_playing = Some(true);
+                audio_state.currently_playing = Some(AudioPlayerProps {
+                    src: src.clone(),
+                    title: episode_title_for_wasm.clone(),
+                    artwork_url: episode_artwork_for_wasm.clone(),
+                    duration: episode_duration_for_wasm.clone().to_string(),
+                    episode_id: episode_id_for_wasm.clone(),
+                    duration_sec: formatted_duration,
+                    start_pos_sec: start_pos, 
+                });
+                audio_state.set_audio_source(src.to_string());
+                audio_state.playback_settings = settings;
+                // Playing something from outside the playlist ends playlist playback
+                if audio_state.playing_playlist.as_ref().map_or(false, |playlist| !playlist.episode_ids.contains(&episode_id_for_wasm)) {
+                    audio_state.playing_playlist = None;
+                }
+                if let Some(audio) = &audio_state.audio_element {
+                    // Loading a new source resets the rate to the default one, so set both
+                    audio.set_default_playback_rate(playback_rate);
+                    audio.set_playback_rate(playback_rate);
+                    audio.set_current_time(start_pos);
+                    let _ = audio.play();
+                }
+                audio_state.audio_playing = Some(true);
             });
-            audio_state.set_audio_source(src.to_string());
-            if let Some(audio) = &audio_state.audio_element {
-                audio.set_current_time(listen_duration_for_closure.unwrap_or(0) as f64);
-                let _ = audio.play();
-            }
-            audio_state.audio_playing = Some(true);
         });
     })
 }
\ No newline at end of file
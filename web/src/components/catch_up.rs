@@ -0,0 +1,200 @@
+use yew::prelude::*;
+use yew::functional::hook;
+use yewdux::prelude::*;
+use web_sys::HtmlInputElement;
+use wasm_bindgen_futures::spawn_local;
+use crate::components::context::{AppState, UIState, EpisodeCache, EpisodeCacheMsg};
+use crate::components::i18n::{t, t_plural};
+use crate::requests::pod_req::{call_catch_up_podcast, CatchUpRequest};
+
+// How many unplayed episodes "keep the latest" leaves by default
+pub const DEFAULT_KEEP_LATEST: usize = 3;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CatchUp {
+    All,
+    // Everything published before this episode
+    OlderThan(i32),
+    // Everything but the newest unfinished episodes
+    KeepLatest(usize),
+}
+
+// Marks the episodes on the server, then in the episode cache so open lists update. Returns how many changed.
+pub async fn catch_up(server_name: &str, api_key: &Option<String>, user_id: i32, podcast_id: i32, mode: CatchUp) -> Result<usize, anyhow::Error> {
+    let request = CatchUpRequest {
+        user_id,
+        podcast_id,
+        older_than_episode_id: match mode {
+            CatchUp::OlderThan(episode_id) => Some(episode_id),
+            _ => None,
+        },
+        keep_latest: match mode {
+            CatchUp::KeepLatest(keep) => Some(keep),
+            _ => None,
+        },
+    };
+    let marked = call_catch_up_podcast(server_name, api_key, &request).await?;
+    let count = marked.len();
+    Dispatch::<EpisodeCache>::global().apply(EpisodeCacheMsg::SetPlayed(
+        marked.into_iter().map(|episode| (episode.EpisodeID, episode.ListenDuration)).collect(),
+        true,
+    ));
+    Ok(count)
+}
+
+fn confirm(message: &str) -> bool {
+    web_sys::window()
+        .and_then(|window| window.confirm_with_message(message).ok())
+        .unwrap_or(false)
+}
+
+// Runs a catch-up for the signed in user and reports the result in the snackbar
+#[hook]
+pub fn use_catch_up(podcast_id: i32) -> Callback<(CatchUp, Callback<()>)> {
+    let auth_details = use_selector(|state: &AppState| state.auth_details.clone());
+    let user_id = *use_selector(|state: &AppState| state.user_details.as_ref().map(|ud| ud.UserID));
+    let server_name = auth_details.as_ref().as_ref().map(|ud| ud.server_name.clone());
+    let api_key = auth_details.as_ref().as_ref().and_then(|ud| ud.api_key.clone());
+
+    Callback::from(move |(mode, on_done): (CatchUp, Callback<()>)| {
+        let (Some(server_name), Some(user_id)) = (server_name.clone(), user_id) else {
+            return;
+        };
+        let api_key = api_key.clone();
+        let ui_dispatch = Dispatch::<UIState>::global();
+        spawn_local(async move {
+            match catch_up(&server_name, &api_key, user_id, podcast_id, mode).await {
+                Ok(count) => ui_dispatch.reduce_mut(|ui_state| ui_state.info_message = Some(t_plural("catch_up.done", count as i64))),
+                Err(e) => ui_dispatch.reduce_mut(|ui_state| ui_state.error_message = Some(format!("{}: {}", t("catch_up.error"), e))),
+            }
+            on_done.emit(());
+        });
+    })
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct CatchUpPanelProps {
+    pub podcast_id: i32,
+    // Shown right after subscribing, worded as an offer rather than a tool
+    #[prop_or_default]
+    pub prompt: bool,
+    // Fired once an action finishes or the prompt is dismissed
+    #[prop_or_default]
+    pub on_done: Callback<()>,
+}
+
+#[function_component(CatchUpPanel)]
+pub fn catch_up_panel(props: &CatchUpPanelProps) -> Html {
+    let run = use_catch_up(props.podcast_id);
+    let keep_latest = use_state(|| DEFAULT_KEEP_LATEST);
+    let running = use_state(|| false);
+
+    let finish = {
+        let running = running.clone();
+        let on_done = props.on_done.clone();
+        Callback::from(move |_: ()| {
+            running.set(false);
+            on_done.emit(());
+        })
+    };
+
+    let on_mark_all = {
+        let run = run.clone();
+        let running = running.clone();
+        let finish = finish.clone();
+        let prompt = props.prompt;
+        Callback::from(move |_: MouseEvent| {
+            // Accepting the subscription prompt is confirmation enough
+            if !prompt && !confirm(&t("catch_up.confirm_all")) {
+                return;
+            }
+            running.set(true);
+            run.emit((CatchUp::All, finish.clone()));
+        })
+    };
+
+    let on_keep_latest = {
+        let run = run.clone();
+        let running = running.clone();
+        let finish = finish.clone();
+        let keep_latest = keep_latest.clone();
+        Callback::from(move |_: MouseEvent| {
+            running.set(true);
+            run.emit((CatchUp::KeepLatest(*keep_latest), finish.clone()));
+        })
+    };
+
+    let on_keep_input = {
+        let keep_latest = keep_latest.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(value) = input.value().parse::<usize>() {
+                keep_latest.set(value);
+            }
+        })
+    };
+
+    let on_dismiss = {
+        let on_done = props.on_done.clone();
+        Callback::from(move |_: MouseEvent| on_done.emit(()))
+    };
+
+    html! {
+        <div class="item-container border-solid border p-4 mb-4 rounded-lg shadow-md" role="region" aria-label={t("catch_up.title")}>
+            <p class="item_container-text text-lg font-bold">{ t(if props.prompt { "catch_up.prompt_title" } else { "catch_up.title" }) }</p>
+            <p class="item_container-text mb-4">{ t(if props.prompt { "catch_up.prompt_body" } else { "catch_up.body" }) }</p>
+            <div class="flex flex-wrap items-center gap-2">
+                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_mark_all} disabled={*running}>
+                    <span class="material-icons icon-space">{"done_all"}</span>
+                    <span>{ t("catch_up.mark_all") }</span>
+                </button>
+                <label class="item_container-text flex items-center gap-2">
+                    <span>{ t("catch_up.keep_latest_label") }</span>
+                    <input
+                        type="number"
+                        min="0"
+                        class="search-bar-input border text-sm rounded-lg p-2 w-20"
+                        value={keep_latest.to_string()}
+                        oninput={on_keep_input}
+                    />
+                </label>
+                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_keep_latest} disabled={*running}>
+                    <span class="material-icons icon-space">{"playlist_add_check"}</span>
+                    <span>{ t_plural("catch_up.keep_latest", *keep_latest as i64) }</span>
+                </button>
+                if props.prompt {
+                    <button class="link hover:underline cursor-pointer" onclick={on_dismiss} disabled={*running}>{ t("catch_up.not_now") }</button>
+                }
+            </div>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct MarkOlderButtonProps {
+    pub podcast_id: i32,
+    pub episode_id: i32,
+}
+
+// Row action on the podcast page that marks everything published before this episode
+#[function_component(MarkOlderButton)]
+pub fn mark_older_button(props: &MarkOlderButtonProps) -> Html {
+    let run = use_catch_up(props.podcast_id);
+    let episode_id = props.episode_id;
+    let onclick = Callback::from(move |_: MouseEvent| {
+        if confirm(&t("catch_up.confirm_older")) {
+            run.emit((CatchUp::OlderThan(episode_id), Callback::noop()));
+        }
+    });
+
+    html! {
+        <button
+            class="item-container-button border-solid border selector-button font-bold py-2 px-4 rounded-full flex items-center justify-center md:w-16 md:h-16 w-10 h-10"
+            title={t("catch_up.mark_older")}
+            aria-label={t("catch_up.mark_older")}
+            {onclick}
+        >
+            <span class="material-icons md:text-4xl text-2xl" aria-hidden="true">{"done_all"}</span>
+        </button>
+    }
+}
@@ -6,8 +6,8 @@ use yewdux::prelude::*; // or wherever your Dispatch type is defined
 use crate::components::context::{AppState};
 use yew_router::history::{BrowserHistory, History};
 use crate::components::podcast_layout::ClickedFeedURL;
-use crate::requests::search_pods::{call_get_podcast_episodes, call_parse_podcast_url};
-use crate::requests::pod_req::{call_get_podcast_id, call_check_podcast};
+use crate::requests::search_pods::{call_get_podcast_episodes_page, call_parse_podcast_url};
+use crate::requests::pod_req::{call_get_podcast_id, call_check_podcast, EPISODE_PAGE_SIZE};
 
 pub fn create_on_title_click(
     dispatch: Dispatch<AppState>,
@@ -63,7 +63,7 @@ pub fn create_on_title_click(
                             Ok(podcast_id) => {
                                 web_sys::console::log_1(&format!("Podcast IDs: {:?}", podcast_id).into());
 
-                                match call_get_podcast_episodes(&server_clone, &api_clone, &user_id, &podcast_id).await {
+                                match call_get_podcast_episodes_page(&server_clone, &api_clone, &user_id, &podcast_id, 0, EPISODE_PAGE_SIZE).await {
                                     Ok(podcast_feed_results) => {
                                         dispatch.reduce_mut(move |state| {
                                             state.podcast_added = Some(true);
@@ -71,7 +71,8 @@ pub fn create_on_title_click(
                                             state.clicked_podcast_info = Some(podcast_values);
                                         });
                                         dispatch.reduce_mut(|state| state.is_loading = Some(false));
-                                        history.push("/episode_layout"); // Navigate to episode_layout
+                                        // Subscribed podcasts have their own page, addressed by id
+                                        history.push(format!("/podcast/{}", podcast_id));
                                     },
                                     Err(e) => {
                                         web_sys::console::log_1(&format!("Error fetching episodes: {:?}", e).into());
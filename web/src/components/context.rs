@@ -1,6 +1,6 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
 use crate::requests::login_requests::AddUserRequest;
@@ -9,13 +9,15 @@ use crate::requests::login_requests::LoginServerRequest;
 use crate::requests::login_requests::{GetApiDetails, TimeZoneInfo};
 use crate::components::audio::AudioPlayerProps;
 use crate::requests::setting_reqs::{AddSettingsUserRequest, EditSettingsUserRequest};
-use crate::requests::search_pods::{PodcastFeedResult, PodcastSearchResult, SearchResponse};
-use crate::requests::pod_req::{Episode, RecentEps, Podcast, PodcastResponse, QueuedEpisodesResponse, SavedEpisodesResponse, HistoryDataResponse, EpisodeDownloadResponse, EpisodeMetadataResponse};
+use crate::requests::search_pods::{PodcastFeedResult, SearchEpisode};
+use crate::requests::search_providers::SearchOutcome;
+use crate::requests::pod_req::{Episode, Podcast, PodcastResponse, EpisodeMetadataResponse};
+use crate::requests::pod_req::{QueuedEpisode, SavedEpisode, HistoryEpisode, EpisodeDownload, PodcastSettings, Folder, Playlist};
 use yewdux::prelude::*;
+use yew::functional::hook;
 use web_sys::HtmlAudioElement;
-use serde_json::{json, from_str};
-use web_sys::window;
 use crate::components::podcast_layout::ClickedFeedURL;
+use crate::components::gen_funcs::{marked_listen_state, ListenState};
 use crate::requests::stat_reqs::UserStats;
 
 #[allow(dead_code)]
@@ -24,8 +26,6 @@ pub enum AppStateMsg {
     ExpandEpisode(String),
     CollapseEpisode(String),
     SetLoading(bool),
-    UpdateSelectedEpisodesForDeletion(i32), // Add this line
-    DeleteSelectedEpisodes, // Add this line
 }
 
 impl Reducer<AppState> for AppStateMsg {
@@ -43,14 +43,6 @@ impl Reducer<AppState> for AppStateMsg {
             AppStateMsg::SetLoading(is_loading) => {
                 state_mut.is_loading = Option::from(is_loading);
             },
-            AppStateMsg::UpdateSelectedEpisodesForDeletion(episode_id) => { // Add this block
-                state_mut.selected_episodes_for_deletion.insert(episode_id);
-            },
-            AppStateMsg::DeleteSelectedEpisodes => { // Add this block
-                // Here you can delete the selected episodes from your state
-                // For now, let's just clear the selected episodes
-                state_mut.selected_episodes_for_deletion.clear();
-            },
         }
 
         state
@@ -72,14 +64,8 @@ pub struct AppState {
     pub server_details: Option<GetApiDetails>,
     pub error_message: Option<String>,
     pub info_message: Option<String>,
-    pub search_results: Option<PodcastSearchResult>,
+    pub search_results: Option<SearchOutcome>,
     pub podcast_feed_results: Option<PodcastFeedResult>,
-    pub server_feed_results: Option<RecentEps>,
-    pub queued_episodes: Option<QueuedEpisodesResponse>,
-    pub saved_episodes: Option<SavedEpisodesResponse>,
-    pub episode_history: Option<HistoryDataResponse>,
-    pub downloaded_episodes: Option<EpisodeDownloadResponse>,
-    pub search_episodes: Option<SearchResponse>,
     pub episodes: Option<Episode>,
     pub clicked_podcast_info: Option<ClickedFeedURL>,
     pub pods: Option<Podcast>,
@@ -95,8 +81,6 @@ pub struct AppState {
     pub time_zone_setup: Option<TimeZoneInfo>,
     pub add_settings_user_reqeust: Option<AddSettingsUserRequest>,
     pub edit_settings_user_reqeust: Option<EditSettingsUserRequest>,
-    #[serde(default)]
-    pub selected_episodes_for_deletion: HashSet<i32>,
     pub reload_occured: Option<bool>,
     pub user_tz: Option<String>,
     pub hour_preference: Option<i16>,
@@ -109,11 +93,710 @@ pub struct UserStatsStore {
     pub stats: Option<UserStats>,
 }
 
+// Normalized caches of every episode and podcast the client has seen, keyed by id.
+// Pages feed what they fetch into these stores and mutations (save, queue, download,
+// listen progress) update the single entry, so every page showing that episode picks
+// up the change without refetching and without touching `AppState`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct CachedEpisode {
+    pub episode_id: i32,
+    pub podcast_id: Option<i32>,
+    pub podcast_name: String,
+    pub title: String,
+    pub description: String,
+    pub pub_date: String,
+    pub artwork: String,
+    pub url: String,
+    pub duration: i32,
+    pub listen_duration: Option<i32>,
+    // None means the client has not yet seen the list that would tell us
+    pub saved: Option<bool>,
+    pub queued: Option<bool>,
+    pub downloaded: Option<bool>,
+    // Set when the user marks the episode played or unplayed, until playback moves it again
+    pub played: Option<bool>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EpisodeList {
+    Queue,
+    Saved,
+    Downloads,
+}
+
+impl CachedEpisode {
+    fn flag_mut(&mut self, list: EpisodeList) -> &mut Option<bool> {
+        match list {
+            EpisodeList::Queue => &mut self.queued,
+            EpisodeList::Saved => &mut self.saved,
+            EpisodeList::Downloads => &mut self.downloaded,
+        }
+    }
+
+    pub fn listen_state(&self) -> ListenState {
+        marked_listen_state(self.played, self.listen_duration, self.duration)
+    }
+
+    pub fn flag(&self, list: EpisodeList) -> Option<bool> {
+        match list {
+            EpisodeList::Queue => self.queued,
+            EpisodeList::Saved => self.saved,
+            EpisodeList::Downloads => self.downloaded,
+        }
+    }
+
+    // Fresh metadata wins, but flags we already know survive a fetch that doesn't carry them
+    fn merge(&mut self, incoming: CachedEpisode) {
+        let (saved, queued, downloaded) = (self.saved, self.queued, self.downloaded);
+        let podcast_id = self.podcast_id;
+        let (listen_duration, played) = (self.listen_duration, self.played);
+        *self = incoming;
+        // A mark only stands while the server still reports the progress it was made with
+        if self.listen_duration.is_none() || self.listen_duration == listen_duration {
+            self.played = self.played.or(played);
+        }
+        self.saved = self.saved.or(saved);
+        self.queued = self.queued.or(queued);
+        self.downloaded = self.downloaded.or(downloaded);
+        self.podcast_id = self.podcast_id.or(podcast_id);
+        self.listen_duration = self.listen_duration.or(listen_duration);
+    }
+}
+
+// Shared by the unit tests that need a library of episodes. Tests set anything else on the result.
+#[cfg(test)]
+impl CachedEpisode {
+    pub fn for_test(episode_id: i32, podcast_id: Option<i32>, podcast_name: &str, pub_date: &str, duration: i32) -> Self {
+        CachedEpisode {
+            episode_id,
+            podcast_id,
+            podcast_name: podcast_name.to_string(),
+            title: format!("Episode {}", episode_id),
+            pub_date: pub_date.to_string(),
+            duration,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&Episode> for CachedEpisode {
+    fn from(episode: &Episode) -> Self {
+        CachedEpisode {
+            episode_id: episode.EpisodeID,
+            podcast_id: episode.PodcastID,
+            podcast_name: episode.PodcastName.clone(),
+            title: episode.EpisodeTitle.clone(),
+            description: episode.EpisodeDescription.clone(),
+            pub_date: episode.EpisodePubDate.clone(),
+            artwork: episode.EpisodeArtwork.clone(),
+            url: episode.EpisodeURL.clone(),
+            duration: episode.EpisodeDuration,
+            listen_duration: episode.ListenDuration,
+            ..CachedEpisode::default()
+        }
+    }
+}
+
+impl From<&QueuedEpisode> for CachedEpisode {
+    fn from(episode: &QueuedEpisode) -> Self {
+        CachedEpisode {
+            episode_id: episode.EpisodeID,
+            podcast_name: episode.PodcastName.clone(),
+            title: episode.EpisodeTitle.clone(),
+            description: episode.EpisodeDescription.clone(),
+            pub_date: episode.EpisodePubDate.clone(),
+            artwork: episode.EpisodeArtwork.clone(),
+            url: episode.EpisodeURL.clone(),
+            duration: episode.EpisodeDuration,
+            listen_duration: episode.ListenDuration,
+            queued: Some(true),
+            ..CachedEpisode::default()
+        }
+    }
+}
+
+impl From<&SavedEpisode> for CachedEpisode {
+    fn from(episode: &SavedEpisode) -> Self {
+        CachedEpisode {
+            episode_id: episode.EpisodeID,
+            podcast_name: episode.PodcastName.clone(),
+            title: episode.EpisodeTitle.clone(),
+            description: episode.EpisodeDescription.clone(),
+            pub_date: episode.EpisodePubDate.clone(),
+            artwork: episode.EpisodeArtwork.clone(),
+            url: episode.EpisodeURL.clone(),
+            duration: episode.EpisodeDuration,
+            listen_duration: episode.ListenDuration,
+            saved: Some(true),
+            ..CachedEpisode::default()
+        }
+    }
+}
+
+impl From<&HistoryEpisode> for CachedEpisode {
+    fn from(episode: &HistoryEpisode) -> Self {
+        CachedEpisode {
+            episode_id: episode.EpisodeID,
+            podcast_id: episode.PodcastID,
+            podcast_name: episode.PodcastName.clone(),
+            title: episode.EpisodeTitle.clone(),
+            description: episode.EpisodeDescription.clone(),
+            pub_date: episode.EpisodePubDate.clone(),
+            artwork: episode.EpisodeArtwork.clone(),
+            url: episode.EpisodeURL.clone(),
+            duration: episode.EpisodeDuration,
+            listen_duration: episode.ListenDuration,
+            ..CachedEpisode::default()
+        }
+    }
+}
+
+impl From<&EpisodeDownload> for CachedEpisode {
+    fn from(episode: &EpisodeDownload) -> Self {
+        CachedEpisode {
+            episode_id: episode.EpisodeID,
+            podcast_id: Some(episode.PodcastID),
+            podcast_name: episode.PodcastName.clone(),
+            title: episode.EpisodeTitle.clone(),
+            description: episode.EpisodeDescription.clone(),
+            pub_date: episode.EpisodePubDate.clone(),
+            artwork: episode.EpisodeArtwork.clone(),
+            url: episode.EpisodeURL.clone(),
+            duration: episode.EpisodeDuration,
+            listen_duration: episode.ListenDuration,
+            downloaded: Some(true),
+            ..CachedEpisode::default()
+        }
+    }
+}
+
+impl From<&SearchEpisode> for CachedEpisode {
+    fn from(episode: &SearchEpisode) -> Self {
+        CachedEpisode {
+            episode_id: episode.EpisodeID,
+            podcast_id: Some(episode.PodcastID),
+            podcast_name: episode.PodcastName.clone(),
+            title: episode.EpisodeTitle.clone(),
+            description: episode.EpisodeDescription.clone(),
+            pub_date: episode.EpisodePubDate.clone(),
+            artwork: episode.EpisodeArtwork.clone(),
+            url: episode.EpisodeURL.clone(),
+            duration: episode.EpisodeDuration,
+            listen_duration: episode.ListenDuration,
+            ..CachedEpisode::default()
+        }
+    }
+}
+
+// Library search results come from the client side index rather than the server
+impl From<&CachedEpisode> for SearchEpisode {
+    fn from(episode: &CachedEpisode) -> Self {
+        SearchEpisode {
+            PodcastID: episode.podcast_id.unwrap_or_default(),
+            PodcastName: episode.podcast_name.clone(),
+            ArtworkURL: episode.artwork.clone(),
+            Author: String::new(),
+            Categories: String::new(),
+            Description: String::new(),
+            EpisodeCount: 0,
+            FeedURL: String::new(),
+            WebsiteURL: String::new(),
+            Explicit: 0,
+            UserID: 0,
+            EpisodeID: episode.episode_id,
+            EpisodeTitle: episode.title.clone(),
+            EpisodeDescription: episode.description.clone(),
+            EpisodeURL: episode.url.clone(),
+            EpisodeArtwork: episode.artwork.clone(),
+            EpisodePubDate: episode.pub_date.clone(),
+            EpisodeDuration: episode.duration,
+            ListenDuration: episode.listen_duration,
+        }
+    }
+}
+
+// Lets pages built from the cache alone, like playlists, reuse the shared episode rows
+impl From<&CachedEpisode> for Episode {
+    fn from(episode: &CachedEpisode) -> Self {
+        Episode {
+            PodcastName: episode.podcast_name.clone(),
+            EpisodeTitle: episode.title.clone(),
+            EpisodePubDate: episode.pub_date.clone(),
+            EpisodeDescription: episode.description.clone(),
+            EpisodeArtwork: episode.artwork.clone(),
+            EpisodeURL: episode.url.clone(),
+            EpisodeDuration: episode.duration,
+            ListenDuration: episode.listen_duration,
+            EpisodeID: episode.episode_id,
+            PodcastID: episode.podcast_id,
+        }
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Store, Debug)]
+pub struct EpisodeCache {
+    // Entries are behind an Rc so copying the map on write stays cheap
+    pub episodes: HashMap<i32, Rc<CachedEpisode>>,
+}
+
+impl EpisodeCache {
+    // Ids known to have left a list since the page last fetched it
+    pub fn removed_from(&self, list: EpisodeList) -> HashSet<i32> {
+        self.episodes
+            .values()
+            .filter(|episode| episode.flag(list) == Some(false))
+            .map(|episode| episode.episode_id)
+            .collect()
+    }
+
+    fn set_progress(&mut self, episode_id: i32, listen_duration: i32) {
+        let entry = self.episodes.entry(episode_id).or_insert_with(|| Rc::new(CachedEpisode {
+            episode_id,
+            ..CachedEpisode::default()
+        }));
+        let episode = Rc::make_mut(entry);
+        episode.listen_duration = Some(listen_duration);
+        episode.played = None;
+    }
+
+    fn upsert(&mut self, incoming: CachedEpisode) {
+        match self.episodes.get_mut(&incoming.episode_id) {
+            Some(existing) => Rc::make_mut(existing).merge(incoming),
+            None => {
+                self.episodes.insert(incoming.episode_id, Rc::new(incoming));
+            }
+        }
+    }
+}
+
+pub enum EpisodeCacheMsg {
+    // Episodes fetched by any page, without list membership information
+    Ingest(Vec<CachedEpisode>),
+    // The full contents of a list as returned by the server
+    SyncList(EpisodeList, Vec<CachedEpisode>),
+    SetMembership(EpisodeList, i32, bool),
+    SetProgress(i32, i32),
+    // Progress for many episodes at once, as after catching up a podcast
+    SetProgressBatch(Vec<(i32, i32)>),
+    // Episodes marked played or unplayed, with the progress the server recorded for each
+    SetPlayed(Vec<(i32, i32)>, bool),
+}
+
+impl Reducer<EpisodeCache> for EpisodeCacheMsg {
+    fn apply(self, mut state: Rc<EpisodeCache>) -> Rc<EpisodeCache> {
+        let cache = Rc::make_mut(&mut state);
+
+        match self {
+            EpisodeCacheMsg::Ingest(episodes) => {
+                for episode in episodes {
+                    cache.upsert(episode);
+                }
+            },
+            EpisodeCacheMsg::SyncList(list, episodes) => {
+                let listed: HashSet<i32> = episodes.iter().map(|episode| episode.episode_id).collect();
+                for (id, episode) in cache.episodes.iter_mut() {
+                    if episode.flag(list) == Some(true) && !listed.contains(id) {
+                        *Rc::make_mut(episode).flag_mut(list) = Some(false);
+                    }
+                }
+                for episode in episodes {
+                    cache.upsert(episode);
+                }
+            },
+            EpisodeCacheMsg::SetMembership(list, episode_id, member) => {
+                let entry = cache.episodes.entry(episode_id).or_insert_with(|| Rc::new(CachedEpisode {
+                    episode_id,
+                    ..CachedEpisode::default()
+                }));
+                *Rc::make_mut(entry).flag_mut(list) = Some(member);
+            },
+            EpisodeCacheMsg::SetProgress(episode_id, listen_duration) => {
+                cache.set_progress(episode_id, listen_duration);
+            },
+            EpisodeCacheMsg::SetProgressBatch(progress) => {
+                for (episode_id, listen_duration) in progress {
+                    cache.set_progress(episode_id, listen_duration);
+                }
+            },
+            EpisodeCacheMsg::SetPlayed(episodes, played) => {
+                for (episode_id, listen_duration) in episodes {
+                    cache.set_progress(episode_id, listen_duration);
+                    if let Some(entry) = cache.episodes.get_mut(&episode_id) {
+                        Rc::make_mut(entry).played = Some(played);
+                    }
+                }
+            },
+        }
+
+        state
+    }
+}
+
+// What each list page shows, in the order the server returned it. The rows carry what only
+// the list knows (queue position, listen date, download location); flags and progress are
+// read from `EpisodeCache`, which also drops episodes that left a list on another page.
+#[derive(Default, Clone, PartialEq, Store, Debug)]
+pub struct EpisodeLists {
+    pub recent: Option<Rc<Vec<Episode>>>,
+    pub queue: Option<Rc<Vec<QueuedEpisode>>>,
+    pub saved: Option<Rc<Vec<SavedEpisode>>>,
+    pub history: Option<Rc<Vec<HistoryEpisode>>>,
+    pub downloads: Option<Rc<Vec<EpisodeDownload>>>,
+    pub search: Option<Rc<Vec<SearchEpisode>>>,
+}
+
+pub enum EpisodeListsMsg {
+    Recent(Vec<Episode>),
+    Queue(Vec<QueuedEpisode>),
+    Saved(Vec<SavedEpisode>),
+    // The first page of history, replacing whatever was loaded before
+    History(Vec<HistoryEpisode>),
+    MoreHistory(Vec<HistoryEpisode>),
+    RemoveFromHistory(i32),
+    Downloads(Vec<EpisodeDownload>),
+    Search(Vec<SearchEpisode>),
+}
+
+impl Reducer<EpisodeLists> for EpisodeListsMsg {
+    fn apply(self, mut state: Rc<EpisodeLists>) -> Rc<EpisodeLists> {
+        let lists = Rc::make_mut(&mut state);
+
+        match self {
+            EpisodeListsMsg::Recent(episodes) => lists.recent = Some(Rc::new(episodes)),
+            EpisodeListsMsg::Queue(episodes) => lists.queue = Some(Rc::new(episodes)),
+            EpisodeListsMsg::Saved(episodes) => lists.saved = Some(Rc::new(episodes)),
+            EpisodeListsMsg::History(episodes) => lists.history = Some(Rc::new(episodes)),
+            EpisodeListsMsg::MoreHistory(episodes) => {
+                if let Some(history) = lists.history.as_mut() {
+                    Rc::make_mut(history).extend(episodes);
+                }
+            },
+            EpisodeListsMsg::RemoveFromHistory(episode_id) => {
+                if let Some(history) = lists.history.as_mut() {
+                    Rc::make_mut(history).retain(|episode| episode.EpisodeID != episode_id);
+                }
+            },
+            EpisodeListsMsg::Downloads(episodes) => lists.downloads = Some(Rc::new(episodes)),
+            EpisodeListsMsg::Search(episodes) => lists.search = Some(Rc::new(episodes)),
+        }
+
+        state
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Store, Debug)]
+pub struct PodcastCache {
+    pub podcasts: HashMap<i32, Rc<Podcast>>,
+}
+
+pub enum PodcastCacheMsg {
+    // Replaces the cache with the user's current subscriptions
+    Sync(Vec<Podcast>),
+    Remove(i32),
+}
+
+impl Reducer<PodcastCache> for PodcastCacheMsg {
+    fn apply(self, mut state: Rc<PodcastCache>) -> Rc<PodcastCache> {
+        let cache = Rc::make_mut(&mut state);
+
+        match self {
+            PodcastCacheMsg::Sync(podcasts) => {
+                cache.podcasts = podcasts
+                    .into_iter()
+                    .map(|podcast| (podcast.PodcastID, Rc::new(podcast)))
+                    .collect();
+            },
+            PodcastCacheMsg::Remove(podcast_id) => {
+                cache.podcasts.remove(&podcast_id);
+            },
+        }
+
+        state
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Store, Debug)]
+pub struct FolderState {
+    // None until the user's folders have been fetched
+    pub folders: Option<Vec<Folder>>,
+    // Shared so the drawer, the Podcasts page and Home all filter by the same folder
+    pub active_folder: Option<i32>,
+}
+
+impl FolderState {
+    pub fn podcast_ids(&self, folder_id: i32) -> Option<HashSet<i32>> {
+        self.folders
+            .as_ref()?
+            .iter()
+            .find(|folder| folder.FolderID == folder_id)
+            .map(|folder| folder.PodcastIDs.iter().copied().collect())
+    }
+
+    // Podcasts in the active folder, or None when nothing is filtered
+    pub fn active_podcast_ids(&self) -> Option<HashSet<i32>> {
+        self.active_folder.and_then(|folder_id| self.podcast_ids(folder_id))
+    }
+
+    pub fn folders_for(&self, podcast_id: i32) -> Vec<i32> {
+        self.folders
+            .iter()
+            .flatten()
+            .filter(|folder| folder.PodcastIDs.contains(&podcast_id))
+            .map(|folder| folder.FolderID)
+            .collect()
+    }
+}
+
+pub enum FolderMsg {
+    Loaded(Vec<Folder>),
+    Created(Folder),
+    Deleted(i32),
+    SetActive(Option<i32>),
+    // Replaces every folder membership of one podcast
+    AssignPodcast(i32, Vec<i32>),
+}
+
+impl Reducer<FolderState> for FolderMsg {
+    fn apply(self, mut state: Rc<FolderState>) -> Rc<FolderState> {
+        let folder_state = Rc::make_mut(&mut state);
+
+        match self {
+            FolderMsg::Loaded(folders) => {
+                if folder_state.active_folder.map_or(false, |id| !folders.iter().any(|folder| folder.FolderID == id)) {
+                    folder_state.active_folder = None;
+                }
+                folder_state.folders = Some(folders);
+            },
+            FolderMsg::Created(folder) => {
+                let folders = folder_state.folders.get_or_insert_with(Vec::new);
+                if !folders.iter().any(|existing| existing.FolderID == folder.FolderID) {
+                    folders.push(folder);
+                    folders.sort_by_key(|folder| folder.FolderName.to_lowercase());
+                }
+            },
+            FolderMsg::Deleted(folder_id) => {
+                if let Some(folders) = folder_state.folders.as_mut() {
+                    folders.retain(|folder| folder.FolderID != folder_id);
+                }
+                if folder_state.active_folder == Some(folder_id) {
+                    folder_state.active_folder = None;
+                }
+            },
+            FolderMsg::SetActive(folder_id) => {
+                folder_state.active_folder = folder_id;
+            },
+            FolderMsg::AssignPodcast(podcast_id, folder_ids) => {
+                for folder in folder_state.folders.iter_mut().flatten() {
+                    folder.PodcastIDs.retain(|id| *id != podcast_id);
+                    if folder_ids.contains(&folder.FolderID) {
+                        folder.PodcastIDs.push(podcast_id);
+                    }
+                }
+            },
+        }
+
+        state
+    }
+}
+
+// Episodes picked for a bulk action on the current page
+#[derive(Default, Clone, PartialEq, Store, Debug)]
+pub struct SelectionState {
+    pub active: bool,
+    pub selected: HashSet<i32>,
+    // The last episode clicked without shift, where a shift-click range starts
+    pub anchor: Option<i32>,
+    // Ids in the order the page lists them, so ranges and "select all" match what is on screen
+    pub order: Vec<i32>,
+}
+
+pub enum SelectionMsg {
+    Start,
+    Exit,
+    SetOrder(Vec<i32>),
+    // Shift-click selects everything between the anchor and this episode
+    Toggle { episode_id: i32, extend: bool },
+    SelectAll,
+    Clear,
+}
+
+impl Reducer<SelectionState> for SelectionMsg {
+    fn apply(self, mut state: Rc<SelectionState>) -> Rc<SelectionState> {
+        let selection = Rc::make_mut(&mut state);
+
+        match self {
+            SelectionMsg::Start => {
+                selection.active = true;
+            },
+            SelectionMsg::Exit => {
+                let order = std::mem::take(&mut selection.order);
+                *selection = SelectionState { order, ..SelectionState::default() };
+            },
+            SelectionMsg::SetOrder(order) => {
+                // Episodes that left the page can't be acted on from it
+                selection.selected.retain(|id| order.contains(id));
+                selection.order = order;
+            },
+            SelectionMsg::Toggle { episode_id, extend } => {
+                let position = |id: i32| selection.order.iter().position(|listed| *listed == id);
+                match (extend, selection.anchor.and_then(position), position(episode_id)) {
+                    (true, Some(from), Some(to)) => {
+                        let range = if from <= to { from..=to } else { to..=from };
+                        selection.selected.extend(selection.order[range].iter().copied());
+                    },
+                    _ => {
+                        if !selection.selected.remove(&episode_id) {
+                            selection.selected.insert(episode_id);
+                        }
+                        selection.anchor = Some(episode_id);
+                    },
+                }
+            },
+            SelectionMsg::SelectAll => {
+                selection.selected = selection.order.iter().copied().collect();
+            },
+            SelectionMsg::Clear => {
+                selection.selected.clear();
+                selection.anchor = None;
+            },
+        }
+
+        state
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Store, Debug)]
+pub struct PlaylistState {
+    // None until the user's playlists have been fetched
+    pub playlists: Option<Vec<Playlist>>,
+}
+
+impl PlaylistState {
+    pub fn get(&self, playlist_id: i32) -> Option<&Playlist> {
+        self.playlists.as_ref()?.iter().find(|playlist| playlist.PlaylistID == playlist_id)
+    }
+
+    // Episodes can only be added by hand to playlists without rules
+    pub fn manual(&self) -> impl Iterator<Item = &Playlist> {
+        self.playlists.iter().flatten().filter(|playlist| playlist.Rules.is_none())
+    }
+}
+
+pub enum PlaylistMsg {
+    Loaded(Vec<Playlist>),
+    // Adds a new playlist or replaces the one with the same id
+    Saved(Playlist),
+    Deleted(i32),
+    SetEpisodes(i32, Vec<i32>),
+}
+
+impl Reducer<PlaylistState> for PlaylistMsg {
+    fn apply(self, mut state: Rc<PlaylistState>) -> Rc<PlaylistState> {
+        let playlist_state = Rc::make_mut(&mut state);
+
+        match self {
+            PlaylistMsg::Loaded(playlists) => {
+                playlist_state.playlists = Some(playlists);
+            },
+            PlaylistMsg::Saved(playlist) => {
+                let playlists = playlist_state.playlists.get_or_insert_with(Vec::new);
+                match playlists.iter_mut().find(|existing| existing.PlaylistID == playlist.PlaylistID) {
+                    Some(existing) => *existing = playlist,
+                    None => playlists.push(playlist),
+                }
+                playlists.sort_by_key(|playlist| playlist.Name.to_lowercase());
+            },
+            PlaylistMsg::Deleted(playlist_id) => {
+                if let Some(playlists) = playlist_state.playlists.as_mut() {
+                    playlists.retain(|playlist| playlist.PlaylistID != playlist_id);
+                }
+            },
+            PlaylistMsg::SetEpisodes(playlist_id, episode_ids) => {
+                if let Some(playlist) = playlist_state.playlists.iter_mut().flatten().find(|playlist| playlist.PlaylistID == playlist_id) {
+                    playlist.EpisodeIDs = episode_ids;
+                }
+            },
+        }
+
+        state
+    }
+}
+
+// Entities belong to one account, so they are dropped whenever the account changes
+pub fn clear_entity_caches() {
+    Dispatch::<EpisodeCache>::global().set(EpisodeCache::default());
+    Dispatch::<PodcastCache>::global().set(PodcastCache::default());
+    Dispatch::<EpisodeLists>::global().set(EpisodeLists::default());
+    Dispatch::<FolderState>::global().set(FolderState::default());
+    Dispatch::<SelectionState>::global().set(SelectionState::default());
+    Dispatch::<PlaylistState>::global().set(PlaylistState::default());
+}
+
+// Components select just their own entry so a change to one episode only re-renders its rows
+#[hook]
+pub fn use_cached_episode(episode_id: i32) -> Rc<Option<Rc<CachedEpisode>>> {
+    use_selector_with_deps(
+        |cache: &EpisodeCache, episode_id: &i32| cache.episodes.get(episode_id).cloned(),
+        episode_id,
+    )
+}
+
+#[hook]
+pub fn use_removed_from(list: EpisodeList) -> Rc<HashSet<i32>> {
+    use_selector_with_deps(|cache: &EpisodeCache, list: &EpisodeList| cache.removed_from(*list), list)
+}
+
+// The part of `AppState` list pages render from, so rows don't re-render on unrelated changes
+#[derive(Clone, PartialEq, Debug)]
+pub struct ListPageState {
+    pub auth_details: Option<LoginServerRequest>,
+    pub user_details: Option<GetUserDetails>,
+    pub expanded_descriptions: HashSet<String>,
+    pub user_tz: Option<String>,
+    pub hour_preference: Option<i16>,
+    pub date_format: Option<String>,
+    pub reload_occured: Option<bool>,
+}
+
+#[hook]
+pub fn use_list_page_state() -> Rc<ListPageState> {
+    use_selector(|state: &AppState| ListPageState {
+        auth_details: state.auth_details.clone(),
+        user_details: state.user_details.clone(),
+        expanded_descriptions: state.expanded_descriptions.clone(),
+        user_tz: state.user_tz.clone(),
+        hour_preference: state.hour_preference,
+        date_format: state.date_format.clone(),
+        reload_occured: state.reload_occured,
+    })
+}
+
+// Bumped whenever the interface language changes so the router re-renders every page
+#[derive(Default, Clone, PartialEq, Store, Debug)]
+pub struct LocaleState {
+    pub locale: Option<String>,
+}
+
 #[derive(Default, Deserialize, Clone, PartialEq, Store, Debug)]
 pub struct SettingsState {
     pub active_tab: Option<String>,
 }
 
+#[derive(Clone, PartialEq, Debug)]
+pub struct PlaylistPlayback {
+    pub playlist_id: i32,
+    // Snapshot of the playlist's order when playback started
+    pub episode_ids: Vec<i32>,
+}
+
+impl PlaylistPlayback {
+    pub fn next_after(&self, episode_id: i32) -> Option<i32> {
+        let position = self.episode_ids.iter().position(|id| *id == episode_id)?;
+        self.episode_ids.get(position + 1).copied()
+    }
+}
+
 #[derive(Default, Clone, PartialEq, Store, Debug)]
 pub struct UIState {
     pub audio_playing: Option<bool>,
@@ -127,6 +810,10 @@ pub struct UIState {
     pub info_message: Option<String>,
     pub is_expanded: bool,
     pub episode_in_db: Option<bool>,
+    // Settings of the podcast the playing episode belongs to, once they have loaded
+    pub playback_settings: Option<PodcastSettings>,
+    // Set while playing through a playlist, so the player continues with its next episode instead of the queue
+    pub playing_playlist: Option<PlaylistPlayback>,
     // pub start_pos_sec: f64,
 }
 
@@ -178,36 +865,8 @@ impl UIState {
 
 
 impl AppState {
-
-    pub fn deserialize(serialized_state: &str) -> Result<Self, serde_json::Error> {
-        from_str(serialized_state)
-    }
-
     pub fn store_app_state(&self) {
-        if let Some(window) = window() {
-            if let Some(local_storage) = window.local_storage().unwrap() {
-                let user_key = "userState";
-                let user_state = json!({ "user_details": self.user_details }).to_string();
-                let auth_key = "userAuthState";
-                let auth_state = json!({"auth_details": self.auth_details}).to_string();
-                let server_key = "serverState";
-                let server_state = json!({"server_details":self.server_details}).to_string();
-                let _ = local_storage.set_item(user_key, &user_state);
-                let _ = local_storage.set_item(auth_key, &auth_state);
-                let _ = local_storage.set_item(server_key, &server_state);
-            }
-        }
+        crate::components::persistence::save_persisted_state(self);
+        crate::components::profiles::upsert_profile(self);
     }
-
-    // pub fn load_app_state(key: &str) -> Option<AppState> {
-    //     if let Some(window) = window() {
-    //         if let Some(local_storage) = window.local_storage().unwrap() {
-    //             if let Ok(Some(serialized_state)) = local_storage.get_item(key) {
-    //                 return AppState::deserialize(&serialized_state).ok();
-    //             }
-    //         }
-    //     }
-    //     None
-    // }
-
 }
\ No newline at end of file
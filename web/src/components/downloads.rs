@@ -1,10 +1,14 @@
 use yew::{function_component, Html, html};
 use yew::prelude::*;
 use super::app_drawer::App_drawer;
+use std::rc::Rc;
 use super::gen_components::{UseScrollToTop, Search_nav, empty_message, episode_item, on_shownotes_click};
-use crate::requests::pod_req::{self, EpisodeDownloadResponse, DownloadEpisodeRequest, call_remove_downloaded_episode};
+use super::virtual_list::VirtualList;
+use yew::virtual_dom::Key;
+use super::selection::BulkActionBar;
+use crate::requests::pod_req;
 use yewdux::prelude::*;
-use crate::components::context::{AppState, UIState};
+use crate::components::context::{AppState, UIState, CachedEpisode, EpisodeCache, EpisodeCacheMsg, EpisodeList, EpisodeLists, EpisodeListsMsg, use_list_page_state, use_removed_from};
 use yew_router::history::BrowserHistory;
 use crate::components::audio::AudioPlayer;
 use crate::components::gen_funcs::{sanitize_html_with_blank_target, truncate_description, parse_date, format_datetime, match_date_format};
@@ -15,12 +19,15 @@ use crate::components::episodes_layout::UIStateMsg;
 use wasm_bindgen::closure::Closure;
 use web_sys::window;
 use wasm_bindgen::JsCast;
-use std::borrow::Borrow;
 use crate::requests::login_requests::use_check_authentication;
+use crate::components::i18n::t;
 
 #[function_component(Downloads)]
 pub fn downloads() -> Html {
-    let (state, dispatch) = use_store::<AppState>();
+    let state = use_list_page_state();
+    let dispatch = Dispatch::<AppState>::global();
+    let downloads = use_selector(|lists: &EpisodeLists| lists.downloads.clone());
+    let removed = use_removed_from(EpisodeList::Downloads);
     let effect_dispatch = dispatch.clone();
     let history = BrowserHistory::new();
 
@@ -57,14 +64,9 @@ pub fn downloads() -> Html {
     });
 
     let error = use_state(|| None);
-    let (post_state, _post_dispatch) = use_store::<AppState>();
     let (audio_state, audio_dispatch) = use_store::<UIState>();
     let error_message = audio_state.error_message.clone();
     let info_message = audio_state.info_message.clone();
-    let page_state = use_state(|| PageState::Normal);
-    let api_key = post_state.auth_details.as_ref().map(|ud| ud.api_key.clone());
-    let user_id = post_state.user_details.as_ref().map(|ud| ud.UserID.clone());
-    let server_name = post_state.auth_details.as_ref().map(|ud| ud.server_name.clone());
     let loading = use_state(|| true);
 
     {
@@ -94,11 +96,9 @@ pub fn downloads() -> Html {
     {
         // let episodes = episodes.clone();
         let error = error.clone();
-        let api_key = post_state.auth_details.as_ref().map(|ud| ud.api_key.clone());
-        let user_id = post_state.user_details.as_ref().map(|ud| ud.UserID.clone());
-        let server_name = post_state.auth_details.as_ref().map(|ud| ud.server_name.clone());
-
-        let effect_dispatch = dispatch.clone();
+        let api_key = state.auth_details.as_ref().map(|ud| ud.api_key.clone());
+        let user_id = state.user_details.as_ref().map(|ud| ud.UserID.clone());
+        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
 
         // fetch_episodes(api_key.flatten(), user_id, server_name, dispatch, error, pod_req::call_get_recent_eps);
 
@@ -107,14 +107,14 @@ pub fn downloads() -> Html {
             move |_| {
                 let error_clone = error.clone();
                 if let (Some(api_key), Some(user_id), Some(server_name)) = (api_key.clone(), user_id.clone(), server_name.clone()) {
-                    let dispatch = effect_dispatch.clone();
-    
                     wasm_bindgen_futures::spawn_local(async move {
                         match pod_req::call_get_episode_downloads(&server_name, &api_key, &user_id).await {
                             Ok(fetched_episodes) => {
-                                dispatch.reduce_mut(move |state| {
-                                    state.downloaded_episodes = Some(EpisodeDownloadResponse { episodes: fetched_episodes });
-                                });
+                                Dispatch::<EpisodeCache>::global().apply(EpisodeCacheMsg::SyncList(
+                                    EpisodeList::Downloads,
+                                    fetched_episodes.iter().map(CachedEpisode::from).collect(),
+                                ));
+                                Dispatch::<EpisodeLists>::global().apply(EpisodeListsMsg::Downloads(fetched_episodes));
                                 loading_ep.set(false);
                                 // web_sys::console::log_1(&format!("State after update: {:?}", state).into()); // Log state after update
                             },
@@ -130,92 +130,12 @@ pub fn downloads() -> Html {
         );
     }
 
-    // Define the state of the application
-    #[derive(Clone, PartialEq)]
-    enum PageState {
-        Delete,
-        Normal,
-    }
-
-    // Define the function to Enter Delete Mode
-    let delete_mode_enable = {
-        let page_state = page_state.clone();
-        Callback::from(move |_: MouseEvent| {
-            page_state.set(PageState::Delete);
-        })
-    };
-
-    // Define the function to Exit Delete Mode
-    let delete_mode_disable = {
-        let page_state = page_state.clone();
-        Callback::from(move |_: MouseEvent| {
-            page_state.set(PageState::Normal);
-        })
-    };
-
-    let on_checkbox_change = {
-        let dispatch = dispatch.clone();
-        Callback::from(move |episode_id: i32| {
-            dispatch.reduce_mut(move |state| {
-                // Update the state of the selected episodes for deletion
-                state.selected_episodes_for_deletion.insert(episode_id);
-            });
-        })
-    };
-
-    let delete_selected_episodes = {
-        let dispatch = dispatch.clone();
-        let page_state = page_state.clone();
-        let server_name = server_name.clone();
-        let api_key = api_key.clone();
-        let user_id = user_id.clone(); // Make sure this is cloned from a state or props where it's guaranteed to exist.
-    
-        Callback::from(move |_: MouseEvent| {
-            // Clone values for use inside the async block
-            let dispatch_cloned = dispatch.clone();
-            let page_state_cloned = page_state.clone();
-            let server_name_cloned = server_name.clone().unwrap(); // Assuming you've ensured these are present
-            let api_key_cloned = api_key.clone().unwrap();
-            let user_id_cloned = user_id.unwrap();
-    
-            dispatch.reduce_mut(move |state| {
-                let selected_episodes = state.selected_episodes_for_deletion.clone();
-                // Clear the selected episodes for deletion right away to prevent re-deletion in case of re-render
-                state.selected_episodes_for_deletion.clear();
-    
-                for &episode_id in &selected_episodes {
-                    let request = DownloadEpisodeRequest {
-                        episode_id,
-                        user_id: user_id_cloned,
-                    };
-                    let server_name_cloned = server_name_cloned.clone();
-                    let api_key_cloned = api_key_cloned.clone();
-                    let future = async move {
-                        match call_remove_downloaded_episode(&server_name_cloned, &api_key_cloned, &request).await {
-                            Ok(success_message) => Some((success_message, episode_id)),
-                            Err(_) => None,
-                        }
-                    };
-    
-                    let dispatch_for_future = dispatch_cloned.clone();
-                    wasm_bindgen_futures::spawn_local(async move {
-                        if let Some((success_message, episode_id)) = future.await {
-                            dispatch_for_future.reduce_mut(|state| {
-                                if let Some(downloaded_episodes) = &mut state.downloaded_episodes {
-                                    downloaded_episodes.episodes.retain(|ep| ep.EpisodeID != episode_id);
-                                }
-                                state.info_message = Some(success_message);
-                            });
-                        }
-                    });
-                }
-    
-                page_state_cloned.set(PageState::Normal); // Return to normal state after operations
-            });
-        })
-    };
-    
-    let is_delete_mode = **page_state.borrow() == PageState::Delete; // Add this line
+    // Ids still in the list, in the order it is shown, for selection ranges and "select all"
+    let download_ids: Vec<i32> = downloads
+        .as_ref()
+        .as_ref()
+        .map(|episodes| episodes.iter().map(|episode| episode.EpisodeID).filter(|id| !removed.contains(id)).collect())
+        .unwrap_or_default();
 
     html! {
         <>
@@ -240,54 +160,43 @@ pub fn downloads() -> Html {
                         html! {
                             <div>
                                 <h1 class="text-2xl item_container-text font-bold text-center mb-6">{"Downloaded Episodes"}</h1>
-                                <div class="flex justify-between">
-                                    {
-                                        if **page_state.borrow() == PageState::Normal {
-                                            html! {
-                                                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center"
-                                                    onclick={delete_mode_enable.clone()}>
-                                                    <span class="material-icons icon-space">{"check_box"}</span>
-                                                    <span class="text-lg">{"Select Multiple"}</span>
-                                                </button>
-                                            }
-                                        } else {
-                                            html! {
-                                                <>
-                                                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center"
-                                                    onclick={delete_mode_disable.clone()}>
-                                                    <span class="material-icons icon-space">{"cancel"}</span>
-                                                    <span class="text-lg">{"Cancel"}</span>
-                                                </button>
-                                                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center"
-                                                    onclick={delete_selected_episodes.clone()}>
-                                                    <span class="material-icons icon-space">{"delete"}</span>
-                                                    <span class="text-lg">{"Delete"}</span>
-                                                </button>
-                                                </>
-                                            }
-                                        }
-                                    }
-                                </div>
+                                <BulkActionBar episode_ids={download_ids} />
                             </div>
                         }
                     }
                     
                     {
-                    if let Some(download_eps) = state.downloaded_episodes.clone() {
+                    // Episodes removed from this list on any page since the fetch drop out immediately
+                    if let Some(download_eps) = downloads.as_ref().as_ref().map(|episodes| {
+                        episodes.iter().filter(|episode| !removed.contains(&episode.EpisodeID)).cloned().collect::<Vec<_>>()
+                    }) {
                         let int_download_eps = download_eps.clone();
-                            let api_key = post_state.auth_details.as_ref().map(|ud| ud.api_key.clone());
-                            let user_id = post_state.user_details.as_ref().map(|ud| ud.UserID.clone());
-                            let server_name = post_state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+                            let api_key = state.auth_details.as_ref().map(|ud| ud.api_key.clone());
+                            let user_id = state.user_details.as_ref().map(|ud| ud.UserID.clone());
+                            let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
                             let history_clone = history.clone();
 
-                            if int_download_eps.episodes.is_empty() {
+                            if int_download_eps.is_empty() {
                                 // Render "No Recent Episodes Found" if episodes list is empty
                                 empty_message(
-                                    "No Downloaded Episodes Found",
-                                    "This is where episode downloads will appear. To download an episode you can open the context menu on an episode and select Download Episode. It will then download the the server and show up here!"
+                                    &t("empty.downloads.title"),
+                                    &t("empty.downloads.body")
                                 )
                             } else {
-                                int_download_eps.episodes.into_iter().map(|episode| {
+                                let episodes = Rc::new(int_download_eps);
+                                let item_count = episodes.len();
+                                let item_keys: Vec<Key> = episodes.iter().map(|episode| Key::from(episode.EpisodeID)).collect();
+                                let render_item = {
+                                    let state = state.clone();
+                                    let dispatch = dispatch.clone();
+                                    let audio_state = audio_state.clone();
+                                    let audio_dispatch = audio_dispatch.clone();
+                                    let api_key = api_key.clone();
+                                    let user_id = user_id.clone();
+                                    let server_name = server_name.clone();
+                                    let history_clone = history_clone.clone();
+                                    Callback::from(move |index: usize| {
+                                    let episode = episodes[index].clone();
 
                                 let id_string = &episode.EpisodeID.to_string();
         
@@ -364,8 +273,6 @@ pub fn downloads() -> Html {
                                 let datetime = parse_date(&episode.EpisodePubDate, &state.user_tz);
                                 let format_release = format!("{}", format_datetime(&datetime, &state.hour_preference, date_format));
     
-                                let on_checkbox_change_cloned = on_checkbox_change.clone();
-
                                 let item = episode_item(
                                     Box::new(episode),
                                     description.clone(),
@@ -377,19 +284,19 @@ pub fn downloads() -> Html {
                                     episode_duration_clone,
                                     episode_listened_clone,
                                     "downloads",
-                                    on_checkbox_change_cloned, // Add this line
-                                    is_delete_mode, // Add this line
                                 );
 
                                 item
-                            }).collect::<Html>()
+                            })
+                                };
+                                html! { <VirtualList item_count={item_count} render_item={render_item} item_keys={item_keys} /> }
                             }
                         
 
                         } else {
                             empty_message(
-                                "No Episode Downloads Found",
-                                "This is where episode downloads will appear. To download an episode you can open the context menu on an episode and select Download Episode. It will then download the the server and show up here!"
+                                &t("empty.downloads.title"),
+                                &t("empty.downloads.body")
                             )
                         }
                     }
@@ -4,7 +4,7 @@ use super::app_drawer::App_drawer;
 use super::gen_components::{Search_nav, empty_message, UseScrollToTop};
 use crate::requests::pod_req;
 use yewdux::prelude::*;
-use crate::components::context::{AppState, UIState};
+use crate::components::context::{AppState, UIState, EpisodeCache, EpisodeCacheMsg, EpisodeList};
 use crate::components::audio::AudioPlayer;
 use crate::components::gen_funcs::{sanitize_html_with_blank_target, format_datetime, format_time, match_date_format, parse_date};
 use crate::requests::pod_req::{EpisodeRequest, EpisodeMetadataResponse, QueuePodcastRequest, call_queue_episode, SavePodcastRequest, call_save_episode, DownloadEpisodeRequest, call_download_episode};
@@ -15,6 +15,7 @@ use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
 use web_sys::window;
 use crate::requests::login_requests::use_check_authentication;
+use crate::components::i18n::t;
 
 #[function_component(Episode)]
 pub fn epsiode() -> Html {
@@ -191,6 +192,7 @@ pub fn epsiode() -> Html {
                                 // queue_post.reduce_mut(|state| state.info_message = Option::from(format!("Episode added to Queue!")));
                                 match call_queue_episode(&server_name.unwrap(), &api_key.flatten(), &request).await {
                                     Ok(success_message) => {
+                                        Dispatch::<EpisodeCache>::global().apply(EpisodeCacheMsg::SetMembership(EpisodeList::Queue, request.episode_id, true));
                                         queue_post.reduce_mut(|state| state.info_message = Option::from(format!("{}", success_message)));
                                     },
                                     Err(e) => {
@@ -225,6 +227,7 @@ pub fn epsiode() -> Html {
                                 // post_state.reduce_mut(|state| state.info_message = Option::from(format!("Episode saved successfully")));
                                 match call_save_episode(&server_name.unwrap(), &api_key.flatten(), &request).await {
                                     Ok(success_message) => {
+                                        Dispatch::<EpisodeCache>::global().apply(EpisodeCacheMsg::SetMembership(EpisodeList::Saved, request.episode_id, true));
                                         post_state.reduce_mut(|state| state.info_message = Option::from(format!("{}", success_message)));
                                     },
                                     Err(e) => {
@@ -259,6 +262,7 @@ pub fn epsiode() -> Html {
                                 // post_state.reduce_mut(|state| state.info_message = Option::from(format!("Episode now downloading!")));
                                 match call_download_episode(&server_name.unwrap(), &api_key.flatten(), &request).await {
                                     Ok(success_message) => {
+                                        Dispatch::<EpisodeCache>::global().apply(EpisodeCacheMsg::SetMembership(EpisodeList::Downloads, request.episode_id, true));
                                         post_state.reduce_mut(|state| state.info_message = Option::from(format!("{}", success_message)));
                                     },
                                     Err(e) => {
@@ -322,8 +326,8 @@ pub fn epsiode() -> Html {
 
                 } else {
                     empty_message(
-                        "Unable to display episode",
-                        "Something seems to have gone wrong. A straightup server disconnect maybe? Did you browse here directly? That's not how this app works. It needs the context to browse around. I honestly don't have anything else for you as this shouldn't happen. This is embarrasing."
+                        &t("empty.episode.title"),
+                        &t("empty.episode.body")
                     )
                 }
             }
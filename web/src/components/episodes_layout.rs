@@ -2,22 +2,32 @@ use std::rc::Rc;
 use js_sys::encode_uri_component;
 use yew::{Callback, function_component, Html, html, TargetCast, use_effect, use_effect_with, use_node_ref};
 use yew::prelude::*;
-use web_sys::{Event, MouseEvent, window};
+use web_sys::{Event, HtmlInputElement, HtmlSelectElement, MouseEvent, window};
 use yew_router::history::{BrowserHistory, History};
 use yewdux::prelude::*;
-use crate::components::context::{AppState, UIState};
+use crate::components::context::{AppState, UIState, EpisodeCache, EpisodeList};
 use crate::components::audio::{AudioPlayer, on_play_click};
 use super::gen_components::{UseScrollToTop, Search_nav, EpisodeTrait};
 use super::app_drawer::App_drawer;
-use crate::requests::pod_req::{call_add_podcast, PodcastValues, call_check_podcast, call_remove_podcasts_name, RemovePodcastValuesName};
+use crate::requests::pod_req::{call_add_podcast, PodcastValues, call_check_podcast, call_remove_podcasts_name, RemovePodcastValuesName, call_get_podcast_id, call_get_podcast_settings, PodcastSettings, PodcastDetails, EPISODE_PAGE_SIZE};
+use super::podcast_settings::PodcastSettingsPanel;
+use super::settings::Tab;
+use super::folders::FolderPicker;
+use super::catch_up::{CatchUpPanel, MarkOlderButton};
+use super::virtual_list::VirtualList;
+use yew::virtual_dom::Key;
+use super::selection::{BulkActionBar, SelectCheckbox};
+use crate::components::i18n::{t, t_args};
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
 use yew::Properties;
 use super::gen_components::ContextButton;
-use super::gen_funcs::{parse_date, format_datetime, match_date_format};
+use super::gen_funcs::{parse_date, format_datetime, format_duration, match_date_format};
 use crate::components::gen_funcs::format_time;
 use crate::requests::login_requests::use_check_authentication;
-use crate::components::gen_funcs::{sanitize_html_with_blank_target, truncate_description, convert_time_to_seconds};
+use crate::components::gen_funcs::{sanitize_html_with_blank_target, truncate_description, convert_time_to_seconds, marked_listen_state, ListenState};
+use crate::requests::search_pods::{call_get_podcast_episodes_page, Episode as FeedEpisode};
+use serde::{Deserialize, Serialize};
 
 fn add_icon() -> Html {
     html! {
@@ -50,6 +60,176 @@ fn pause_icon() -> Html {
     }
 }
 
+// Feed items carry RFC 2822 publish dates, while episodes from the database use ISO 8601
+fn episode_timestamp(pub_date: Option<&str>) -> i64 {
+    let Some(pub_date) = pub_date else {
+        return 0;
+    };
+    chrono::DateTime::parse_from_rfc2822(pub_date)
+        .map(|date| date.timestamp())
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(pub_date, "%Y-%m-%dT%H:%M:%S").map(|date| date.and_utc().timestamp()))
+        .unwrap_or(0)
+}
+
+fn episode_seconds(episode: &FeedEpisode) -> u32 {
+    episode.duration.as_deref().and_then(|duration| convert_time_to_seconds(duration).ok()).unwrap_or(0)
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+enum EpisodeFilter {
+    #[default]
+    All,
+    Unplayed,
+    InProgress,
+    Played,
+    Downloaded,
+    Saved,
+}
+
+impl EpisodeFilter {
+    const ALL: [EpisodeFilter; 6] = [
+        EpisodeFilter::All,
+        EpisodeFilter::Unplayed,
+        EpisodeFilter::InProgress,
+        EpisodeFilter::Played,
+        EpisodeFilter::Downloaded,
+        EpisodeFilter::Saved,
+    ];
+
+    fn value(&self) -> &'static str {
+        match self {
+            EpisodeFilter::All => "all",
+            EpisodeFilter::Unplayed => "unplayed",
+            EpisodeFilter::InProgress => "in_progress",
+            EpisodeFilter::Played => "played",
+            EpisodeFilter::Downloaded => "downloaded",
+            EpisodeFilter::Saved => "saved",
+        }
+    }
+
+    fn from_value(value: &str) -> Self {
+        EpisodeFilter::ALL.into_iter().find(|filter| filter.value() == value).unwrap_or_default()
+    }
+
+    fn label(&self) -> String {
+        t(&format!("episode_filters.filter.{}", self.value()))
+    }
+
+    fn is_all(&self) -> bool {
+        *self == EpisodeFilter::All
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+enum EpisodeSortField {
+    #[default]
+    Date,
+    Duration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+enum SortDirection {
+    Asc,
+    Desc,
+}
+
+// Tabs on the page of a subscribed podcast. Other podcasts only show their episodes.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+enum PodcastTab {
+    About,
+    #[default]
+    Episodes,
+    Stats,
+    Settings,
+}
+
+impl PodcastTab {
+    const ALL: [PodcastTab; 4] = [PodcastTab::About, PodcastTab::Episodes, PodcastTab::Stats, PodcastTab::Settings];
+
+    fn label(&self) -> String {
+        let key = match self {
+            PodcastTab::About => "about",
+            PodcastTab::Episodes => "episodes",
+            PodcastTab::Stats => "stats",
+            PodcastTab::Settings => "settings",
+        };
+        t(&format!("podcast_page.tab.{}", key))
+    }
+
+    fn is_episodes(&self) -> bool {
+        *self == PodcastTab::Episodes
+    }
+}
+
+// Kept in the query string so going back from an episode restores the list as it was left
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+struct EpisodeListQuery {
+    #[serde(default, skip_serializing_if = "PodcastTab::is_episodes")]
+    tab: PodcastTab,
+    #[serde(default, skip_serializing_if = "EpisodeFilter::is_all")]
+    filter: EpisodeFilter,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sort: Option<EpisodeSortField>,
+    // Unset follows the sort order chosen in the podcast's settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    order: Option<SortDirection>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    q: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    hide_played: bool,
+}
+
+impl EpisodeListQuery {
+    // Feed episodes only know their own details, database ones are overlaid with what the cache has seen since
+    fn matches(&self, episode: &FeedEpisode, cache: &EpisodeCache) -> bool {
+        let cached = episode.episode_id.and_then(|id| cache.episodes.get(&id));
+        let listened = cached.and_then(|cached| cached.listen_duration).or(episode.listen_duration);
+        let state = marked_listen_state(cached.and_then(|cached| cached.played), listened, episode_seconds(episode) as i32);
+        let saved = cached.and_then(|cached| cached.flag(EpisodeList::Saved)).unwrap_or(episode.saved);
+        let downloaded = cached.and_then(|cached| cached.flag(EpisodeList::Downloads)).unwrap_or(episode.downloaded);
+
+        let matches_filter = match self.filter {
+            EpisodeFilter::All => true,
+            EpisodeFilter::Unplayed => state == ListenState::Unplayed,
+            EpisodeFilter::InProgress => state == ListenState::InProgress,
+            EpisodeFilter::Played => state == ListenState::Played,
+            EpisodeFilter::Downloaded => downloaded,
+            EpisodeFilter::Saved => saved,
+        };
+        let needle = self.q.trim().to_lowercase();
+        let matches_text = needle.is_empty()
+            || episode.title.as_deref().unwrap_or_default().to_lowercase().contains(&needle)
+            || episode.description.as_deref().unwrap_or_default().to_lowercase().contains(&needle);
+
+        matches_filter && matches_text && !(self.hide_played && state == ListenState::Played)
+    }
+
+    // Indices into `episodes` of the visible rows, in display order
+    fn apply(&self, episodes: &[FeedEpisode], cache: &EpisodeCache, oldest_first: bool) -> Vec<usize> {
+        let mut visible: Vec<usize> = (0..episodes.len()).filter(|&index| self.matches(&episodes[index], cache)).collect();
+        match self.sort.unwrap_or_default() {
+            EpisodeSortField::Date => visible.sort_by_key(|&index| episode_timestamp(episodes[index].pub_date.as_deref())),
+            EpisodeSortField::Duration => visible.sort_by_key(|&index| episode_seconds(&episodes[index])),
+        }
+        let ascending = match self.order {
+            Some(order) => order == SortDirection::Asc,
+            None => oldest_first,
+        };
+        if !ascending {
+            visible.reverse();
+        }
+        visible
+    }
+
+    fn is_filtered(&self) -> bool {
+        !self.filter.is_all() || !self.q.trim().is_empty() || self.hide_played
+    }
+}
+
 #[derive(Properties, PartialEq)]
 pub struct Props {
     pub html: String,
@@ -111,8 +291,15 @@ impl Reducer<UIState> for UIStateMsg {
 
 
 
+#[derive(Properties, PartialEq)]
+pub struct EpisodeLayoutProps {
+    // Set on a subscribed podcast's own page, which adds the about, stats and settings tabs
+    #[prop_or_default]
+    pub details: Option<PodcastDetails>,
+}
+
 #[function_component(EpisodeLayout)]
-pub fn episode_layout() -> Html {
+pub fn episode_layout(props: &EpisodeLayoutProps) -> Html {
     let is_added = use_state(|| false);
     let (state, _dispatch) = use_store::<UIState>();
     let (search_state, _search_dispatch) = use_store::<AppState>();
@@ -183,6 +370,241 @@ pub fn episode_layout() -> Html {
         );
     }
 
+    // Subscribed podcasts have settings that shape how their episodes are listed and played
+    let podcast_id = use_state(|| None::<i32>);
+    let podcast_settings = use_state(|| None::<PodcastSettings>);
+    let show_settings = use_state(|| false);
+    {
+        let podcast_id = podcast_id.clone();
+        let podcast_settings = podcast_settings.clone();
+        let podcast = clicked_podcast_info.clone();
+        let user_id = effect_user_id.clone();
+        let api_key = effect_api_key.clone().flatten();
+        let server_name = server_name.clone();
+
+        use_effect_with(*is_added, move |is_added| {
+            if *is_added {
+                if let (Some(podcast), Some(server_name)) = (podcast, server_name) {
+                    wasm_bindgen_futures::spawn_local(async move {
+                        if let Ok(id) = call_get_podcast_id(&server_name, &api_key, &user_id, &podcast.podcast_url, &podcast.podcast_title).await {
+                            podcast_id.set(Some(id));
+                            if let Ok(settings) = call_get_podcast_settings(&server_name, &api_key, user_id, id).await {
+                                podcast_settings.set(Some(settings));
+                            }
+                        }
+                    });
+                }
+            } else {
+                podcast_id.set(None);
+                podcast_settings.set(None);
+            }
+            || ()
+        });
+    }
+
+    let toggle_settings = {
+        let show_settings = show_settings.clone();
+        Callback::from(move |_: MouseEvent| show_settings.set(!*show_settings))
+    };
+
+    // Catch-up tools, also offered once right after subscribing
+    let show_catch_up = use_state(|| false);
+    let offer_catch_up = use_state(|| false);
+    let on_catch_up_done = {
+        let show_catch_up = show_catch_up.clone();
+        let offer_catch_up = offer_catch_up.clone();
+        Callback::from(move |_: ()| {
+            show_catch_up.set(false);
+            offer_catch_up.set(false);
+        })
+    };
+
+    let on_settings_change = {
+        let podcast_settings = podcast_settings.clone();
+        Callback::from(move |settings: PodcastSettings| podcast_settings.set(Some(settings)))
+    };
+
+    let oldest_first = podcast_settings.as_ref().map_or(false, |settings| settings.oldest_first());
+
+    let (episode_cache, _episode_cache_dispatch) = use_store::<EpisodeCache>();
+    let list_query = use_state(|| BrowserHistory::new().location().query::<EpisodeListQuery>().unwrap_or_default());
+    let update_list_query = {
+        let list_query = list_query.clone();
+        Callback::from(move |updated: EpisodeListQuery| {
+            let history = BrowserHistory::new();
+            let path = history.location().path().to_string();
+            let _ = history.replace_with_query(path, &updated);
+            list_query.set(updated);
+        })
+    };
+
+    let on_list_search = {
+        let list_query = list_query.clone();
+        let update_list_query = update_list_query.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                update_list_query.emit(EpisodeListQuery { q: input.value(), ..(*list_query).clone() });
+            }
+        })
+    };
+
+    let on_list_filter = {
+        let list_query = list_query.clone();
+        let update_list_query = update_list_query.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                update_list_query.emit(EpisodeListQuery { filter: EpisodeFilter::from_value(&select.value()), ..(*list_query).clone() });
+            }
+        })
+    };
+
+    let on_list_sort = {
+        let list_query = list_query.clone();
+        let update_list_query = update_list_query.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                let (sort, order) = match select.value().as_str() {
+                    "date_asc" => (EpisodeSortField::Date, SortDirection::Asc),
+                    "duration_asc" => (EpisodeSortField::Duration, SortDirection::Asc),
+                    "duration_desc" => (EpisodeSortField::Duration, SortDirection::Desc),
+                    _ => (EpisodeSortField::Date, SortDirection::Desc),
+                };
+                update_list_query.emit(EpisodeListQuery { sort: Some(sort), order: Some(order), ..(*list_query).clone() });
+            }
+        })
+    };
+
+    let on_hide_played = {
+        let list_query = list_query.clone();
+        let update_list_query = update_list_query.clone();
+        Callback::from(move |_: Event| {
+            update_list_query.emit(EpisodeListQuery { hide_played: !list_query.hide_played, ..(*list_query).clone() });
+        })
+    };
+
+    let on_clear_filters = {
+        let list_query = list_query.clone();
+        let update_list_query = update_list_query.clone();
+        Callback::from(move |_: MouseEvent| update_list_query.emit(EpisodeListQuery { tab: list_query.tab, ..EpisodeListQuery::default() }))
+    };
+
+    let on_tab_click = |tab: PodcastTab| {
+        let list_query = list_query.clone();
+        let update_list_query = update_list_query.clone();
+        Callback::from(move |_: MouseEvent| update_list_query.emit(EpisodeListQuery { tab, ..(*list_query).clone() }))
+    };
+    let has_tabs = props.details.is_some();
+    let tab = if has_tabs { list_query.tab } else { PodcastTab::Episodes };
+    let settings_open = if has_tabs { tab == PodcastTab::Settings } else { *show_settings };
+    // The catch-up panel sits above the episode list, so opening it from another tab goes there first
+    let toggle_catch_up = {
+        let show_catch_up = show_catch_up.clone();
+        let list_query = list_query.clone();
+        let update_list_query = update_list_query.clone();
+        Callback::from(move |_: MouseEvent| {
+            if tab == PodcastTab::Episodes {
+                show_catch_up.set(!*show_catch_up);
+            } else {
+                show_catch_up.set(true);
+                update_list_query.emit(EpisodeListQuery { tab: PodcastTab::Episodes, ..(*list_query).clone() });
+            }
+        })
+    };
+
+    let copy_feed_url = {
+        let dispatch = _dispatch.clone();
+        let feed_url = clicked_podcast_info.as_ref().map(|podcast| podcast.podcast_url.clone()).unwrap_or_default();
+        Callback::from(move |_: MouseEvent| {
+            let dispatch = dispatch.clone();
+            let clipboard = window().unwrap().navigator().clipboard();
+            let promise = clipboard.write_text(&feed_url);
+            wasm_bindgen_futures::spawn_local(async move {
+                match wasm_bindgen_futures::JsFuture::from(promise).await {
+                    Ok(_) => dispatch.reduce_mut(|state| state.info_message = Some(t("podcast_page.feed_url_copied"))),
+                    Err(_) => dispatch.reduce_mut(|state| state.error_message = Some(t("podcast_page.copy_failed"))),
+                }
+            });
+        })
+    };
+
+    let sort_value = {
+        let ascending = list_query.order.map_or(oldest_first, |order| order == SortDirection::Asc);
+        match (list_query.sort.unwrap_or_default(), ascending) {
+            (EpisodeSortField::Date, true) => "date_asc",
+            (EpisodeSortField::Date, false) => "date_desc",
+            (EpisodeSortField::Duration, true) => "duration_asc",
+            (EpisodeSortField::Duration, false) => "duration_desc",
+        }
+    };
+
+    // Subscribed podcasts load their episodes a page at a time. A short page means everything is
+    // loaded; `episodes_exhausted` covers a last page that happened to be exactly full.
+    let loading_more = use_state(|| false);
+    let episodes_exhausted = use_state(|| false);
+    {
+        let episodes_exhausted = episodes_exhausted.clone();
+        use_effect_with(clicked_podcast_info.as_ref().map(|podcast| podcast.podcast_url.clone()), move |_| {
+            episodes_exhausted.set(false);
+            || ()
+        });
+    }
+    let loaded_episodes = podcast_feed_results.as_ref().map_or(0, |results| results.episodes.len());
+    let has_more = podcast_added && !*episodes_exhausted && loaded_episodes > 0 && loaded_episodes % EPISODE_PAGE_SIZE == 0;
+
+    let load_more = {
+        let loading_more = loading_more.clone();
+        let episodes_exhausted = episodes_exhausted.clone();
+        let search_dispatch = _search_dispatch.clone();
+        let server_name = server_name.clone();
+        let api_key = effect_api_key.clone().flatten();
+        let user_id = effect_user_id;
+        let podcast_id = *podcast_id;
+        Callback::from(move |_: ()| {
+            let (Some(podcast_id), Some(server_name)) = (podcast_id, server_name.clone()) else {
+                return;
+            };
+            if !has_more || *loading_more {
+                return;
+            }
+            loading_more.set(true);
+            let loading_more = loading_more.clone();
+            let episodes_exhausted = episodes_exhausted.clone();
+            let search_dispatch = search_dispatch.clone();
+            let api_key = api_key.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match call_get_podcast_episodes_page(&server_name, &api_key, &user_id, &podcast_id, loaded_episodes, EPISODE_PAGE_SIZE).await {
+                    Ok(page) => {
+                        if page.episodes.is_empty() {
+                            episodes_exhausted.set(true);
+                        }
+                        search_dispatch.reduce_mut(move |state| {
+                            if let Some(results) = state.podcast_feed_results.as_mut() {
+                                results.episodes.extend(page.episodes);
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        web_sys::console::log_1(&format!("Error fetching more episodes: {:?}", e).into());
+                        episodes_exhausted.set(true);
+                    }
+                }
+                loading_more.set(false);
+            });
+        })
+    };
+
+    // Filters and any order other than newest first are only right once every episode is loaded
+    {
+        let load_more = load_more.clone();
+        let needs_all = list_query.is_filtered() || sort_value != "date_desc";
+        use_effect_with((needs_all, has_more, *loading_more, loaded_episodes, podcast_id.is_some()), move |(needs_all, has_more, loading_more, _, _)| {
+            if *needs_all && *has_more && !*loading_more {
+                load_more.emit(());
+            }
+            || ()
+        });
+    }
+
     // Function to handle link clicks
     let handle_click = Callback::from(move |event: MouseEvent| {
         if let Some(target) = event.target_dyn_into::<web_sys::HtmlElement>() {
@@ -265,6 +687,7 @@ pub fn episode_layout() -> Html {
         let user_id_clone = user_id.clone();
 
         let is_added = is_added.clone();
+        let offer_catch_up = offer_catch_up.clone();
 
         if *is_added == true{
             Callback::from(move |_: MouseEvent| { 
@@ -330,6 +753,7 @@ pub fn episode_layout() -> Html {
                 let api_key_call = api_key_clone.clone();
                 let server_name_call = server_name_clone.clone();
                 let user_id_call = user_id_clone.clone();
+                let offer_catch_up = offer_catch_up.clone();
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let dispatch_wasm = call_dispatch.clone();
@@ -343,6 +767,7 @@ pub fn episode_layout() -> Html {
                             if success {
                                 dispatch_wasm.reduce_mut(|state| state.info_message = Option::from("Podcast successfully added".to_string()));
                                 is_added_inner.set(true);
+                                offer_catch_up.set(true);
                             } else {
                                 dispatch_wasm.reduce_mut(|state| state.error_message = Option::from("Failed to add podcast".to_string()));
                             }
@@ -356,6 +781,11 @@ pub fn episode_layout() -> Html {
         }
     };
 
+    let about_categories: Vec<String> = clicked_podcast_info.as_ref()
+        .and_then(|podcast| podcast.podcast_categories.as_ref())
+        .map(|categories| categories.values().cloned().collect())
+        .unwrap_or_default();
+
     let button_content = if *is_added {
         trash_icon()
     } else {
@@ -368,20 +798,20 @@ pub fn episode_layout() -> Html {
         <div class="main-container">
             <Search_nav />
             <UseScrollToTop />
-            <h1 class="page_header text-2xl font-bold my-4 text-center">{ "Podcast Episode Results" }</h1>
+            <h1 class="page_header text-2xl font-bold my-4 text-center">{ t("feed.title") }</h1>
         {
             if let Some(podcast_info) = clicked_podcast_info {
                 html! {
                     <div class="item-header">
-                        <img src={podcast_info.podcast_artwork.clone()} alt={format!("Cover for {}", &podcast_info.podcast_title)} class="item-header-cover"/>
+                        <img src={podcast_info.podcast_artwork.clone()} alt={t_args("a11y.cover_for", &[("title", podcast_info.podcast_title.as_str())])} class="item-header-cover"/>
                         <div class="item-header-info">
                             <h2 class="item-header-title">{ &podcast_info.podcast_title }</h2>
 
                             <p class="item-header-description">{ &podcast_info.podcast_description }</p>
                             <div class="item-header-info">
-                                <p class="header-text">{ format!("Episode Count: {}", &podcast_info.podcast_episode_count) }</p>
-                                <p class="header-text">{ format!("Authors: {}", &podcast_info.podcast_author) }</p>
-                                <p class="header-text">{ format!("Explicit: {}", if podcast_info.podcast_explicit { "Yes" } else { "No" }) }</p>
+                                <p class="header-text">{ t_args("feed.episode_count", &[("count", podcast_info.podcast_episode_count.to_string().as_str())]) }</p>
+                                <p class="header-text">{ t_args("feed.authors", &[("authors", podcast_info.podcast_author.as_str())]) }</p>
+                                <p class="header-text">{ t_args("feed.explicit", &[("value", t(if podcast_info.podcast_explicit { "podcast_page.yes" } else { "podcast_page.no" }).as_str())]) }</p>
 
                                 <div>
                                     {
@@ -407,6 +837,19 @@ pub fn episode_layout() -> Html {
                         <button onclick={toggle_podcast} class={format!("item-container-button selector-button hover:bg-blue-700 text-white font-bold py-2 px-4 rounded {}", button_class)}>
                             { button_content }
                         </button>
+                        if let Some(id) = *podcast_id {
+                            if !has_tabs {
+                                <button onclick={toggle_settings} aria-label={t("podcast_settings.open")} aria-expanded={show_settings.to_string()} class="item-container-button selector-button font-bold py-2 px-4 rounded mt-2">
+                                    <span class="material-icons">{"settings"}</span>
+                                </button>
+                            }
+                            <button onclick={toggle_catch_up} aria-label={t("catch_up.open")} title={t("catch_up.open")} aria-expanded={(tab == PodcastTab::Episodes && *show_catch_up).to_string()} class="item-container-button selector-button font-bold py-2 px-4 rounded mt-2">
+                                <span class="material-icons">{"done_all"}</span>
+                            </button>
+                            <div class="mt-2">
+                                <FolderPicker podcast_id={id} />
+                            </div>
+                        }
                         </div>
                     </div>
                 }
@@ -414,175 +857,336 @@ pub fn episode_layout() -> Html {
                 html! {}
             }
         }
+        if has_tabs {
+            <div class="item_container-text tabs flex flex-wrap text-sm font-medium text-center border-b border-gray-200 mb-4">
+                { for PodcastTab::ALL.iter().map(|option| html! {
+                    <Tab is_active={tab == *option} class="me-2" label={option.label()} onclick={on_tab_click(*option)} />
+                }) }
+            </div>
+        }
+        if let (PodcastTab::About, Some(details)) = (tab, props.details.as_ref()) {
+            <div class="item_container-text px-2 mb-4">
+                <div class="mb-4">
+                    <SafeHtml html={sanitize_html_with_blank_target(details.Description.as_deref().unwrap_or_default())} />
+                </div>
+                <dl class="podcast-about">
+                    <dt class="font-semibold">{ t("podcast_page.author") }</dt>
+                    <dd>{ details.Author.clone().filter(|author| !author.is_empty()).unwrap_or_else(|| t("podcast_page.unknown")) }</dd>
+                    <dt class="font-semibold">{ t("podcast_page.website") }</dt>
+                    <dd>
+                        if let Some(website) = details.WebsiteURL.clone().filter(|website| !website.is_empty()) {
+                            <a class="link hover:underline" href={website.clone()} target="_blank" rel="noopener noreferrer">{ website }</a>
+                        } else {
+                            { t("podcast_page.unknown") }
+                        }
+                    </dd>
+                    <dt class="font-semibold">{ t("podcast_page.categories") }</dt>
+                    <dd>
+                        { for about_categories.iter().map(|category| html! {
+                            <span class="category-box">{ category }</span>
+                        }) }
+                    </dd>
+                    <dt class="font-semibold">{ t("podcast_page.explicit") }</dt>
+                    <dd>{ if details.Explicit { t("podcast_page.yes") } else { t("podcast_page.no") } }</dd>
+                    <dt class="font-semibold">{ t("podcast_page.feed_url") }</dt>
+                    <dd class="flex items-center gap-2">
+                        <span class="break-all">{ &details.FeedURL }</span>
+                        <button class="item-container-button selector-button rounded p-1" onclick={copy_feed_url} aria-label={t("podcast_page.copy_feed_url")} title={t("podcast_page.copy_feed_url")}>
+                            <span class="material-icons" aria-hidden="true">{"content_copy"}</span>
+                        </button>
+                    </dd>
+                </dl>
+            </div>
+        }
+        if let (PodcastTab::Stats, Some(details)) = (tab, props.details.as_ref()) {
+            <div class="item_container-text px-2 mb-4">
+                <dl class="podcast-about">
+                    <dt class="font-semibold">{ t("podcast_page.time_listened") }</dt>
+                    <dd>{ format_duration(details.TimeListened) }</dd>
+                    <dt class="font-semibold">{ t("podcast_page.episodes_completed") }</dt>
+                    <dd>{ t_args("podcast_page.completed_of_started", &[
+                        ("completed", details.EpisodesCompleted.to_string().as_str()),
+                        ("started", details.EpisodesStarted.to_string().as_str()),
+                    ]) }</dd>
+                    <dt class="font-semibold">{ t("podcast_page.last_played") }</dt>
+                    <dd>{
+                        match details.LastPlayed.as_deref() {
+                            Some(last_played) => format_datetime(
+                                &parse_date(last_played, &search_state.user_tz),
+                                &search_state.hour_preference,
+                                match_date_format(search_state.date_format.as_deref()),
+                            ),
+                            None => t("podcast_page.never_played"),
+                        }
+                    }</dd>
+                </dl>
+            </div>
+        }
+        if let (true, Some(podcast_id)) = (settings_open, *podcast_id) {
+            <PodcastSettingsPanel podcast_id={podcast_id} on_change={on_settings_change} />
+        }
+        if tab == PodcastTab::Episodes {
+        if let (true, Some(podcast_id)) = (*offer_catch_up || *show_catch_up, *podcast_id) {
+            <CatchUpPanel podcast_id={podcast_id} prompt={*offer_catch_up} on_done={on_catch_up_done} />
+        }
         {
                 if let Some(results) = podcast_feed_results {
-                    html! {
-                        <div>
-                            { for results.episodes.iter().map(|episode| {
-                                let dispatch = _dispatch.clone();
-                                let search_dispatch = _search_dispatch.clone();
-                                let search_state_clone = search_state.clone(); // Clone search_state
-
-                                // Clone the variables outside the closure
-                                let episode_url_clone = episode.enclosure_url.clone().unwrap_or_default();
-                                let episode_title_clone = episode.title.clone().unwrap_or_default();
-                                let episode_artwork_clone = episode.artwork.clone().unwrap_or_default();
-                                // let episode_duration_clone = episode.duration.clone().unwrap_or_default();
-                                let episode_duration_clone = episode.duration.clone().unwrap_or_default();
-                                let episode_duration_in_seconds = match convert_time_to_seconds(&episode_duration_clone) {
-                                    Ok(seconds) => seconds as i32,
-                                    Err(e) => {
-                                        eprintln!("Failed to convert time to seconds: {}", e);
-                                        0
+                    let results = Rc::new(results);
+                    let episodes = Rc::new(list_query.apply(&results.episodes, &episode_cache, oldest_first));
+                    let episode_ids: Vec<i32> = episodes.iter().filter_map(|&index| results.episodes[index].episode_id).collect();
+                    let filter_bar = html! {
+                        <div class="flex flex-wrap items-center gap-2 mb-4">
+                            <input
+                                type="search"
+                                class="search-bar-input border text-sm rounded-lg p-2.5 flex-grow"
+                                placeholder={t("episode_filters.search_placeholder")}
+                                aria-label={t("episode_filters.search_placeholder")}
+                                value={list_query.q.clone()}
+                                oninput={on_list_search}
+                            />
+                            // Listening state, downloads and saves are only tracked for subscribed podcasts
+                            if podcast_added {
+                                <select aria-label={t("episode_filters.filter_label")} onchange={on_list_filter} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                                    { for EpisodeFilter::ALL.iter().map(|filter| html! {
+                                        <option value={filter.value()} selected={list_query.filter == *filter}>{ filter.label() }</option>
+                                    }) }
+                                </select>
+                            }
+                            <select aria-label={t("episode_filters.sort_label")} onchange={on_list_sort} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                                { for ["date_desc", "date_asc", "duration_desc", "duration_asc"].iter().map(|value| html! {
+                                    <option value={*value} selected={sort_value == *value}>{ t(&format!("episode_filters.sort.{}", value)) }</option>
+                                }) }
+                            </select>
+                            if podcast_added {
+                                <label class="flex items-center space-x-2 item_container-text">
+                                    <input type="checkbox" class="form-checkbox h-5 w-5" checked={list_query.hide_played} onchange={on_hide_played} />
+                                    <span>{ t("episode_filters.hide_played") }</span>
+                                </label>
+                            }
+                        </div>
+                    };
+                    // Episodes of podcasts that aren't subscribed have no id yet
+                    let item_keys: Vec<Key> = episodes.iter().map(|index| {
+                        let episode = &results.episodes[*index];
+                        match episode.episode_id {
+                            Some(episode_id) => Key::from(episode_id),
+                            None => Key::from(episode.guid.clone().or_else(|| episode.enclosure_url.clone()).unwrap_or_else(|| index.to_string())),
+                        }
+                    }).collect();
+                    let render_item = {
+                        let results = results.clone();
+                        let episodes = episodes.clone();
+                        let _dispatch = _dispatch.clone();
+                        let _search_dispatch = _search_dispatch.clone();
+                        let search_state = search_state.clone();
+                        let server_name = server_name.clone();
+                        let user_id = user_id.clone();
+                        let api_key = api_key.clone();
+                        let state = state.clone();
+                        let catch_up_podcast_id = *podcast_id;
+                        Callback::from(move |index: usize| {
+                            let episode = &results.episodes[episodes[index]];
+                            let dispatch = _dispatch.clone();
+                            let search_dispatch = _search_dispatch.clone();
+                            let search_state_clone = search_state.clone(); // Clone search_state
+
+                            // Clone the variables outside the closure
+                            let episode_url_clone = episode.enclosure_url.clone().unwrap_or_default();
+                            let episode_title_clone = episode.title.clone().unwrap_or_default();
+                            let episode_artwork_clone = episode.artwork.clone().unwrap_or_default();
+                            // let episode_duration_clone = episode.duration.clone().unwrap_or_default();
+                            let episode_duration_clone = episode.duration.clone().unwrap_or_default();
+                            let episode_duration_in_seconds = match convert_time_to_seconds(&episode_duration_clone) {
+                                Ok(seconds) => seconds as i32,
+                                Err(e) => {
+                                    web_sys::console::log_1(&format!("Failed to convert time to seconds: {}", e).into());
+                                    0
+                                }
+                            };
+                            let episode_id_clone = episode.episode_id.unwrap_or(0);
+                            let server_name_play = server_name.clone();
+                            let user_id_play = user_id.clone();
+                            let api_key_play = api_key.clone();
+
+                            // Feeds without guids fall back to the enclosure url to track the expanded description
+                            let episode_guid = episode.guid.clone().unwrap_or_else(|| episode_url_clone.clone());
+                            let is_expanded = search_state.expanded_descriptions.contains(&episode_guid);
+
+
+                            let sanitized_description = sanitize_html_with_blank_target(&episode.description.clone().unwrap_or_default());
+
+                            let (description, _is_truncated) = if is_expanded {
+                                (sanitized_description, false)
+                            } else {
+                                truncate_description(sanitized_description, 300)
+                            };
+
+                            let search_state_toggle = search_state_clone.clone();
+                            let toggle_expanded = {
+                                let search_dispatch_clone = search_dispatch.clone();
+                                let episode_guid = episode_guid.clone();
+                                Callback::from(move |_: MouseEvent| {
+                                    let guid_clone = episode_guid.clone();
+                                    let search_dispatch_call = search_dispatch_clone.clone();
+
+                                    if search_state_toggle.expanded_descriptions.contains(&guid_clone) {
+                                        search_dispatch_call.apply(AppStateMsg::CollapseEpisode(guid_clone));
+                                    } else {
+                                        search_dispatch_call.apply(AppStateMsg::ExpandEpisode(guid_clone));
                                     }
-                                };
-                                let episode_id_clone = episode.episode_id.unwrap_or(0);
-                                let server_name_play = server_name.clone();
-                                let user_id_play = user_id.clone();
-                                let api_key_play = api_key.clone();
-
-                                let is_expanded = search_state.expanded_descriptions.contains(
-                                    &episode.guid.clone().unwrap()
-                                );
-                                
-
-                                let sanitized_description = sanitize_html_with_blank_target(&episode.description.clone().unwrap_or_default());
-
-                                let (description, _is_truncated) = if is_expanded {
-                                    (sanitized_description, false)
-                                } else {
-                                    truncate_description(sanitized_description, 300)
-                                };
-
-                                let search_state_toggle = search_state_clone.clone();
-                                let toggle_expanded = {
-                                    let search_dispatch_clone = search_dispatch.clone();
-                                    let episode_guid = episode.guid.clone().unwrap();
-                                    Callback::from(move |_: MouseEvent| {
-                                        let guid_clone = episode_guid.clone();
-                                        let search_dispatch_call = search_dispatch_clone.clone();
-
-                                        if search_state_toggle.expanded_descriptions.contains(&guid_clone) {
-                                            search_dispatch_call.apply(AppStateMsg::CollapseEpisode(guid_clone));
-                                        } else {
-                                            search_dispatch_call.apply(AppStateMsg::ExpandEpisode(guid_clone));
-                                        }
 
-                                    })
-                                };
-
-
-                                let state = state.clone();
-                                let on_play_click = on_play_click(
-                                    episode_url_clone.clone(),
-                                    episode_title_clone.clone(),
-                                    episode_artwork_clone.clone(),
-                                    episode_duration_in_seconds,
-                                    episode_id_clone.clone(),
-                                    Some(0),
-                                    api_key_play.unwrap().unwrap(),
-                                    user_id_play.unwrap(),
-                                    server_name_play.unwrap(),
-                                    dispatch.clone(),
-                                    state.clone(),
-                                    None,
-                                );
-
-                                let description_class = if is_expanded {
-                                    "desc-expanded".to_string()
-                                } else {
-                                    "desc-collapsed".to_string()
-                                };
-
-                                let date_format = match_date_format(search_state_clone.date_format.as_deref());
-                                let datetime = parse_date(&episode.pub_date.clone().unwrap_or_default(), &search_state_clone.user_tz);
-                                let format_release = format!("{}", format_datetime(&datetime, &search_state_clone.hour_preference, date_format));
-                                let boxed_episode = Box::new(episode.clone()) as Box<dyn EpisodeTrait>;
-                                let duration = episode.duration.clone().unwrap().parse::<f64>().unwrap_or(0.0);
-                                let formatted_duration = format_time(duration);
-                                html! {
-                                    <div class="item-container flex items-center mb-4 shadow-md rounded-lg">
-                                        <img src={episode.artwork.clone().unwrap_or_default()} alt={format!("Cover for {}", &episode.title.clone().unwrap_or_default())} class="object-cover align-top-cover w-full item-container img"/>
-                                        <div class="flex flex-col p-4 space-y-2 flex-grow md:w-7/12">
-                                            <p class="item_container-text text-xl font-semibold">{ &episode.title.clone().unwrap_or_default() }</p>
-                                            // <p class="text-gray-600">{ &episode.description.clone().unwrap_or_default() }</p>
-                                            {
-                                                html! {
-                                                    <div class="item-container-text hidden md:block">
-                                                        <div class={format!("item_container-text episode-description-container {}", description_class)}>
-                                                            <SafeHtml html={description} />
-                                                        </div>
-                                                        <a class="link hover:underline cursor-pointer mt-4" onclick={toggle_expanded}>
-                                                            { if is_expanded { "See Less" } else { "See More" } }
-                                                        </a>
+                                })
+                            };
+
+
+                            let state = state.clone();
+                            let on_play_click = on_play_click(
+                                episode_url_clone.clone(),
+                                episode_title_clone.clone(),
+                                episode_artwork_clone.clone(),
+                                episode_duration_in_seconds,
+                                episode_id_clone.clone(),
+                                Some(0),
+                                api_key_play.unwrap().unwrap(),
+                                user_id_play.unwrap(),
+                                server_name_play.unwrap(),
+                                dispatch.clone(),
+                                state.clone(),
+                                None,
+                            );
+
+                            let description_class = if is_expanded {
+                                "desc-expanded".to_string()
+                            } else {
+                                "desc-collapsed".to_string()
+                            };
+
+                            let date_format = match_date_format(search_state_clone.date_format.as_deref());
+                            let datetime = parse_date(&episode.pub_date.clone().unwrap_or_default(), &search_state_clone.user_tz);
+                            let format_release = format!("{}", format_datetime(&datetime, &search_state_clone.hour_preference, date_format));
+                            let boxed_episode = Box::new(episode.clone()) as Box<dyn EpisodeTrait>;
+                            let duration = episode_duration_clone.parse::<f64>().unwrap_or(0.0);
+                            let formatted_duration = format_time(duration);
+                            html! {
+                                <div class="item-container flex items-center mb-4 shadow-md rounded-lg">
+                                    if podcast_added {
+                                        <SelectCheckbox episode_id={episode_id_clone} title={episode_title_clone.clone()} />
+                                    }
+                                    <img src={episode.artwork.clone().unwrap_or_default()} alt={t_args("a11y.cover_for", &[("title", episode_title_clone.as_str())])} class="object-cover align-top-cover w-full item-container img"/>
+                                    <div class="flex flex-col p-4 space-y-2 flex-grow md:w-7/12">
+                                        <p class="item_container-text text-xl font-semibold">{ &episode.title.clone().unwrap_or_default() }</p>
+                                        // <p class="text-gray-600">{ &episode.description.clone().unwrap_or_default() }</p>
+                                        {
+                                            html! {
+                                                <div class="item-container-text hidden md:block">
+                                                    <div class={format!("item_container-text episode-description-container {}", description_class)}>
+                                                        <SafeHtml html={description} />
                                                     </div>
-                                                }
-                                            }
-                                            <span class="episode-time-badge inline-flex items-center px-2.5 py-0.5 rounded me-2">
-                                                <svg class="time-icon w-2.5 h-2.5 me-1.5" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" fill="currentColor" viewBox="0 0 20 20">
-                                                    <path d="M10 0a10 10 0 1 0 10 10A10.011 10.011 0 0 0 10 0Zm3.982 13.982a1 1 0 0 1-1.414 0l-3.274-3.274A1.012 1.012 0 0 1 9 10V6a1 1 0 0 1 2 0v3.586l2.982 2.982a1 1 0 0 1 0 1.414Z"/>
-                                                </svg>
-                                                { format_release }
-                                            </span>
-                                            {
-                                                // if formatted_listen_duration.is_some() {
-                                                //     html! {
-                                                //         <div class="flex items-center space-x-2">
-                                                //             <span class="item_container-text">{ formatted_listen_duration.clone() }</span>
-                                                //             <div class="progress-bar-container">
-                                                //                 <div class="progress-bar" style={ format!("width: {}%;", listen_duration_percentage) }></div>
-                                                //             </div>
-                                                //             <span class="item_container-text">{ formatted_duration }</span>
-                                                //         </div>
-                                                //     }
-                                                    
-                                                // } else {
-                                                    html! {
-                                                        <span class="item_container-text">{ format!("{}", formatted_duration) }</span>
-                                                    }
-                                                // }
+                                                    <a class="link hover:underline cursor-pointer mt-4" onclick={toggle_expanded}>
+                                                        { if is_expanded { t("episode.see_less") } else { t("episode.see_more") } }
+                                                    </a>
+                                                </div>
                                             }
-                                        </div>
-                                        <div class="flex flex-col items-center h-full w-2/12 px-2 space-y-4 md:space-y-8"> // More space on medium and larger screens
-                                            <button
-                                                class="item-container-button border-solid border selector-button font-bold py-2 px-4 rounded-full flex items-center justify-center md:w-16 md:h-16 w-10 h-10"
-                                                onclick={on_play_click}
-                                            >
-                                                <span class="material-bonus-color material-icons large-material-icons md:text-6xl text-4xl">{"play_arrow"}</span>
-                                            </button>
-                                            {
-                                                if podcast_added {
-                                                    let page_type = "episode_layout".to_string();
-
-                                                    let context_button = html! {
+                                        }
+                                        <span class="episode-time-badge inline-flex items-center px-2.5 py-0.5 rounded me-2">
+                                            <svg class="time-icon w-2.5 h-2.5 me-1.5" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" fill="currentColor" viewBox="0 0 20 20">
+                                                <path d="M10 0a10 10 0 1 0 10 10A10.011 10.011 0 0 0 10 0Zm3.982 13.982a1 1 0 0 1-1.414 0l-3.274-3.274A1.012 1.012 0 0 1 9 10V6a1 1 0 0 1 2 0v3.586l2.982 2.982a1 1 0 0 1 0 1.414Z"/>
+                                            </svg>
+                                            { format_release }
+                                        </span>
+                                        {
+                                            // if formatted_listen_duration.is_some() {
+                                            //     html! {
+                                            //         <div class="flex items-center space-x-2">
+                                            //             <span class="item_container-text">{ formatted_listen_duration.clone() }</span>
+                                            //             <div class="progress-bar-container">
+                                            //                 <div class="progress-bar" style={ format!("width: {}%;", listen_duration_percentage) }></div>
+                                            //             </div>
+                                            //             <span class="item_container-text">{ formatted_duration }</span>
+                                            //         </div>
+                                            //     }
+
+                                            // } else {
+                                                html! {
+                                                    <span class="item_container-text">{ format!("{}", formatted_duration) }</span>
+                                                }
+                                            // }
+                                        }
+                                    </div>
+                                    <div class="flex flex-col items-center h-full w-2/12 px-2 space-y-4 md:space-y-8"> // More space on medium and larger screens
+                                        <button
+                                            class="item-container-button border-solid border selector-button font-bold py-2 px-4 rounded-full flex items-center justify-center md:w-16 md:h-16 w-10 h-10"
+                                            onclick={on_play_click}
+                                        >
+                                            <span class="material-bonus-color material-icons large-material-icons md:text-6xl text-4xl">{"play_arrow"}</span>
+                                        </button>
+                                        {
+                                            if podcast_added {
+                                                let page_type = "episode_layout".to_string();
+
+                                                let context_button = html! {
+                                                    <>
                                                         <ContextButton episode={boxed_episode} page_type={page_type.clone()} />
-                                                    };
+                                                        if let (Some(podcast_id), Some(episode_id)) = (catch_up_podcast_id, episode.episode_id) {
+                                                            <MarkOlderButton podcast_id={podcast_id} episode_id={episode_id} />
+                                                        }
+                                                    </>
+                                                };
 
 
-                                                    context_button
+                                                context_button
 
-                                                } else {
-                                                    html! {}
-                                                }
+                                            } else {
+                                                html! {}
                                             }
-                                        </div>
+                                        }
+                                    </div>
 
 
-                                    </div>
-                                }
-                            })}
+                                </div>
+                            }
+                        })
+                    };
+                    if episodes.is_empty() && list_query.is_filtered() && !has_more {
+                        html! {
+                            <>
+                                { filter_bar }
+                                <div class="empty-episodes-container">
+                                    <h1 class="page-subtitles">{ t("episode_filters.no_matches.title") }</h1>
+                                    <p class="page-paragraphs">{ t("episode_filters.no_matches.body") }</p>
+                                    <button class="settings-button mt-4" onclick={on_clear_filters}>{ t("episode_filters.clear") }</button>
+                                </div>
+                            </>
+                        }
+                    } else {
+                    html! {
+                        <div>
+                            { filter_bar }
+                            // Only episodes stored on the server can be queued, saved or marked in bulk
+                            if podcast_added {
+                                <BulkActionBar episode_ids={episode_ids} />
+                            }
+                            <VirtualList
+                                item_count={episodes.len()}
+                                render_item={render_item}
+                                item_keys={item_keys}
+                                on_end_reached={has_more.then(|| load_more.clone())}
+                                loading_more={*loading_more}
+                            />
                         </div>
                     }
+                    }
                 } else {
                     html! {
                         <div class="empty-episodes-container" id="episode-container">
                             <img src="static/assets/favicon.png" alt="Logo" class="logo"/>
-                            <h1 class="page-subtitles">{ "No Episodes Found" }</h1>
-                            <p class="page-paragraphs">{"This podcast strangely doesn't have any episodes. Try a more mainstream one maybe?"}</p>
+                            <h1 class="page-subtitles">{ t("empty.feed.title") }</h1>
+                            <p class="page-paragraphs">{ t("empty.feed.body") }</p>
                         </div>
                     }
                 }
             }
+        }
         <App_drawer />
         // Conditional rendering for the error banner
         {
@@ -0,0 +1,343 @@
+use std::rc::Rc;
+use yew::prelude::*;
+use yew::functional::hook;
+use yewdux::prelude::*;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use wasm_bindgen_futures::spawn_local;
+use yew_router::history::{BrowserHistory, History};
+use crate::components::context::{AppState, UIState, FolderState, FolderMsg};
+use crate::components::click_events::create_on_title_click;
+use crate::components::i18n::{t, t_args};
+use crate::requests::pod_req::{call_create_folder, call_delete_folder, call_get_folders, call_set_podcast_folders};
+
+// Folders are fetched once per account and then kept in sync by the components that change them
+#[hook]
+pub fn use_folders() -> Rc<FolderState> {
+    let (state, _dispatch) = use_store::<AppState>();
+    let (folder_state, folder_dispatch) = use_store::<FolderState>();
+    let api_key = state.auth_details.as_ref().and_then(|ud| ud.api_key.clone());
+    let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+    let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+    let loaded = folder_state.folders.is_some();
+
+    use_effect_with((server_name, user_id, loaded), move |(server_name, user_id, loaded)| {
+        if let (Some(server_name), Some(user_id), false) = (server_name.clone(), *user_id, *loaded) {
+            spawn_local(async move {
+                if let Ok(folders) = call_get_folders(&server_name, &api_key, user_id).await {
+                    folder_dispatch.apply(FolderMsg::Loaded(folders));
+                }
+            });
+        }
+        || ()
+    });
+
+    folder_state
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct FolderPickerProps {
+    pub podcast_id: i32,
+}
+
+// Lets the user file one podcast under any number of folders, creating new ones inline
+#[function_component(FolderPicker)]
+pub fn folder_picker(props: &FolderPickerProps) -> Html {
+    let (state, _dispatch) = use_store::<AppState>();
+    let (_ui_state, ui_dispatch) = use_store::<UIState>();
+    let folder_dispatch = Dispatch::<FolderState>::global();
+    let folder_state = use_folders();
+    let is_open = use_state(|| false);
+    let new_folder_name = use_state(String::new);
+
+    let api_key = state.auth_details.as_ref().and_then(|ud| ud.api_key.clone());
+    let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+    let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+    let podcast_id = props.podcast_id;
+    let assigned = folder_state.folders_for(podcast_id);
+
+    let toggle_open = {
+        let is_open = is_open.clone();
+        Callback::from(move |_: MouseEvent| is_open.set(!*is_open))
+    };
+
+    let assign = {
+        let ui_dispatch = ui_dispatch.clone();
+        let folder_dispatch = folder_dispatch.clone();
+        let api_key = api_key.clone();
+        let server_name = server_name.clone();
+        Callback::from(move |folder_ids: Vec<i32>| {
+            let (Some(server_name), Some(user_id)) = (server_name.clone(), user_id) else {
+                return;
+            };
+            let api_key = api_key.clone();
+            let ui_dispatch = ui_dispatch.clone();
+            let folder_dispatch = folder_dispatch.clone();
+            spawn_local(async move {
+                match call_set_podcast_folders(&server_name, &api_key, user_id, podcast_id, &folder_ids).await {
+                    Ok(_) => folder_dispatch.apply(FolderMsg::AssignPodcast(podcast_id, folder_ids)),
+                    Err(e) => ui_dispatch.reduce_mut(|ui_state| ui_state.error_message = Some(format!("{}: {}", t("folders.save_error"), e))),
+                }
+            });
+        })
+    };
+
+    let on_name_input = {
+        let new_folder_name = new_folder_name.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                new_folder_name.set(input.value());
+            }
+        })
+    };
+
+    let on_create = {
+        let new_folder_name = new_folder_name.clone();
+        let assigned = assigned.clone();
+        let assign = assign.clone();
+        let folder_dispatch = folder_dispatch.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let name = new_folder_name.trim().to_string();
+            let (false, Some(server_name), Some(user_id)) = (name.is_empty(), server_name.clone(), user_id) else {
+                return;
+            };
+            new_folder_name.set(String::new());
+            let api_key = api_key.clone();
+            let assigned = assigned.clone();
+            let assign = assign.clone();
+            let ui_dispatch = ui_dispatch.clone();
+            let folder_dispatch = folder_dispatch.clone();
+            spawn_local(async move {
+                match call_create_folder(&server_name, &api_key, user_id, &name).await {
+                    Ok(folder) => {
+                        let mut folder_ids = assigned;
+                        folder_ids.push(folder.FolderID);
+                        folder_dispatch.apply(FolderMsg::Created(folder));
+                        assign.emit(folder_ids);
+                    }
+                    Err(e) => ui_dispatch.reduce_mut(|ui_state| ui_state.error_message = Some(format!("{}: {}", t("folders.save_error"), e))),
+                }
+            });
+        })
+    };
+
+    let folders = folder_state.folders.clone().unwrap_or_default();
+
+    html! {
+        <div class="relative inline-block">
+            <button onclick={toggle_open} aria-label={t("folders.assign")} aria-expanded={is_open.to_string()} class="item-container-button selector-button font-bold py-2 px-4 rounded">
+                <span class="material-icons" aria-hidden="true">{"folder"}</span>
+            </button>
+            if *is_open {
+                <div class="absolute right-0 z-10 mt-2 w-64 p-3 rounded-lg shadow-lg modal-container">
+                    <p class="item_container-text font-semibold mb-2">{ t("folders.assign") }</p>
+                    if folders.is_empty() {
+                        <p class="item_container-text text-sm mb-2">{ t("folders.none") }</p>
+                    }
+                    { for folders.iter().map(|folder| {
+                        let folder_id = folder.FolderID;
+                        let checked = assigned.contains(&folder_id);
+                        let on_toggle = {
+                            let assigned = assigned.clone();
+                            let assign = assign.clone();
+                            Callback::from(move |_: Event| {
+                                let mut folder_ids = assigned.clone();
+                                if checked {
+                                    folder_ids.retain(|id| *id != folder_id);
+                                } else {
+                                    folder_ids.push(folder_id);
+                                }
+                                assign.emit(folder_ids);
+                            })
+                        };
+                        html! {
+                            <label class="flex items-center space-x-2 item_container-text py-1">
+                                <input type="checkbox" class="form-checkbox h-4 w-4" checked={checked} onchange={on_toggle} />
+                                <span>{ &folder.FolderName }</span>
+                            </label>
+                        }
+                    }) }
+                    <form onsubmit={on_create} class="flex items-center space-x-2 mt-2">
+                        <input type="text" value={(*new_folder_name).clone()} oninput={on_name_input} placeholder={t("folders.new_placeholder")} aria-label={t("folders.new_placeholder")} class="search-bar-input border text-sm rounded-lg p-2 flex-grow min-w-0" />
+                        <button type="submit" aria-label={t("folders.create")} class="selector-button rounded p-2">
+                            <span class="material-icons text-base" aria-hidden="true">{"create_new_folder"}</span>
+                        </button>
+                    </form>
+                </div>
+            }
+        </div>
+    }
+}
+
+// Select bound to the shared active folder, used above the Podcasts and Home lists
+#[function_component(FolderFilter)]
+pub fn folder_filter() -> Html {
+    let folder_state = use_folders();
+    let folder_dispatch = Dispatch::<FolderState>::global();
+    let folders = folder_state.folders.clone().unwrap_or_default();
+
+    let on_change = Callback::from(move |e: Event| {
+        if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+            folder_dispatch.apply(FolderMsg::SetActive(select.value().parse::<i32>().ok()));
+        }
+    });
+
+    if folders.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <select aria-label={t("folders.filter")} onchange={on_change} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+            <option value="" selected={folder_state.active_folder.is_none()}>{ t("folders.all") }</option>
+            { for folders.iter().map(|folder| html! {
+                <option value={folder.FolderID.to_string()} selected={folder_state.active_folder == Some(folder.FolderID)}>{ &folder.FolderName }</option>
+            }) }
+        </select>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct DrawerFoldersProps {
+    // Closes the drawer after navigating
+    pub on_navigate: Callback<MouseEvent>,
+}
+
+// Collapsible folder groups for the app drawer
+#[function_component(DrawerFolders)]
+pub fn drawer_folders(props: &DrawerFoldersProps) -> Html {
+    let (state, dispatch) = use_store::<AppState>();
+    let (_ui_state, ui_dispatch) = use_store::<UIState>();
+    let folder_state = use_folders();
+    let folder_dispatch = Dispatch::<FolderState>::global();
+    let show_folders = use_state(|| false);
+    let expanded = use_state(Vec::<i32>::new);
+
+    let folders = folder_state.folders.clone().unwrap_or_default();
+    if folders.is_empty() {
+        return html! {};
+    }
+
+    let api_key = state.auth_details.as_ref().map(|ud| ud.api_key.clone());
+    let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone()).unwrap_or_default();
+    let user_id = state.user_details.as_ref().map(|ud| ud.UserID).unwrap_or_default();
+    let podcasts = state.podcast_feed_return.as_ref().and_then(|podcasts| podcasts.pods.clone()).unwrap_or_default();
+    let history = BrowserHistory::new();
+
+    let toggle_folders = {
+        let show_folders = show_folders.clone();
+        Callback::from(move |_: MouseEvent| show_folders.set(!*show_folders))
+    };
+
+    html! {
+        <>
+            <div class="flex items-center space-x-3">
+                <div onclick={toggle_folders} class="drawer-text flex items-center space-x-3 cursor-pointer">
+                    <span class="material-icons icon-space">{"folder"}</span>
+                    <span class="text-lg">{ t("drawer.folders") }</span>
+                    <span class="material-icons">{ if *show_folders { "expand_less" } else { "expand_more" } }</span>
+                </div>
+            </div>
+            if *show_folders {
+                <div class="ml-6 space-y-3">
+                    { for folders.iter().map(|folder| {
+                        let folder_id = folder.FolderID;
+                        let is_expanded = expanded.contains(&folder_id);
+                        let toggle_expanded = {
+                            let expanded = expanded.clone();
+                            Callback::from(move |_: MouseEvent| {
+                                let mut updated = (*expanded).clone();
+                                if is_expanded {
+                                    updated.retain(|id| *id != folder_id);
+                                } else {
+                                    updated.push(folder_id);
+                                }
+                                expanded.set(updated);
+                            })
+                        };
+                        let open_folder = {
+                            let folder_dispatch = folder_dispatch.clone();
+                            let on_navigate = props.on_navigate.clone();
+                            let history = history.clone();
+                            Callback::from(move |e: MouseEvent| {
+                                folder_dispatch.apply(FolderMsg::SetActive(Some(folder_id)));
+                                history.push("/podcasts");
+                                on_navigate.emit(e);
+                            })
+                        };
+                        let on_delete = {
+                            let folder_dispatch = folder_dispatch.clone();
+                            let ui_dispatch = ui_dispatch.clone();
+                            let api_key = api_key.clone().flatten();
+                            let server_name = server_name.clone();
+                            let folder_name = folder.FolderName.clone();
+                            Callback::from(move |_: MouseEvent| {
+                                let confirmed = web_sys::window()
+                                    .and_then(|window| window.confirm_with_message(&t_args("folders.confirm_delete", &[("name", &folder_name)])).ok())
+                                    .unwrap_or(false);
+                                if !confirmed {
+                                    return;
+                                }
+                                let folder_dispatch = folder_dispatch.clone();
+                                let ui_dispatch = ui_dispatch.clone();
+                                let api_key = api_key.clone();
+                                let server_name = server_name.clone();
+                                spawn_local(async move {
+                                    match call_delete_folder(&server_name, &api_key, user_id, folder_id).await {
+                                        Ok(_) => folder_dispatch.apply(FolderMsg::Deleted(folder_id)),
+                                        Err(e) => ui_dispatch.reduce_mut(|ui_state| ui_state.error_message = Some(format!("{}: {}", t("folders.save_error"), e))),
+                                    }
+                                });
+                            })
+                        };
+                        let members: Vec<_> = podcasts.iter().filter(|podcast| folder.PodcastIDs.contains(&podcast.PodcastID)).collect();
+
+                        html! {
+                            <div class="space-y-2">
+                                <div class="drawer-text flex items-center space-x-2">
+                                    <span onclick={toggle_expanded} class="material-icons cursor-pointer" role="button" aria-expanded={is_expanded.to_string()} aria-label={folder.FolderName.clone()}>
+                                        { if is_expanded { "expand_less" } else { "expand_more" } }
+                                    </span>
+                                    <span onclick={open_folder} class="text-md cursor-pointer flex-grow">{ &folder.FolderName }</span>
+                                    <span onclick={on_delete} class="material-icons text-base cursor-pointer" role="button" aria-label={t("folders.delete")}>{"delete"}</span>
+                                </div>
+                                if is_expanded {
+                                    <div class="ml-8 space-y-2">
+                                        if members.is_empty() {
+                                            <p class="drawer-text text-sm">{ t("folders.empty") }</p>
+                                        }
+                                        { for members.into_iter().map(|podcast| {
+                                            let on_title_click = create_on_title_click(
+                                                dispatch.clone(),
+                                                server_name.clone(),
+                                                api_key.clone(),
+                                                &history,
+                                                podcast.PodcastName.clone(),
+                                                podcast.FeedURL.clone(),
+                                                podcast.Description.clone().unwrap_or_default(),
+                                                podcast.Author.clone().unwrap_or_default(),
+                                                podcast.ArtworkURL.clone().unwrap_or_default(),
+                                                podcast.Explicit,
+                                                podcast.EpisodeCount,
+                                                serde_json::from_str(&podcast.Categories).ok(),
+                                                podcast.WebsiteURL.clone().unwrap_or_default(),
+                                                user_id,
+                                            );
+                                            let on_navigate = props.on_navigate.clone();
+                                            let on_click = Callback::from(move |e: MouseEvent| {
+                                                on_title_click.emit(e.clone());
+                                                on_navigate.emit(e);
+                                            });
+                                            html! {
+                                                <p onclick={on_click} class="drawer-text text-sm cursor-pointer truncate">{ &podcast.PodcastName }</p>
+                                            }
+                                        }) }
+                                    </div>
+                                }
+                            </div>
+                        }
+                    }) }
+                </div>
+            }
+        </>
+    }
+}
@@ -1,20 +1,28 @@
-use std::rc::Rc;
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
-use wasm_bindgen::JsValue;
 use yew::prelude::*;
 use yew_router::history::{BrowserHistory, History};
-use crate::requests::search_pods::{call_get_podcast_info, test_connection};
-use web_sys::{console, window, HtmlInputElement, MouseEvent};
+use crate::requests::search_providers::{enabled_providers, provider_name, search_all, SearchOutcome, SearchProviderPreferences, PROVIDERS};
+use web_sys::{window, HtmlInputElement, MouseEvent};
 use yewdux::prelude::*;
-use crate::components::context::{AppState, UIState};
+use crate::components::context::{AppState, UIState, EpisodeCache, EpisodeCacheMsg, EpisodeList, EpisodeLists, EpisodeListsMsg, PlaylistState, use_cached_episode};
 use crate::components::episodes_layout::SafeHtml;
 use yew::Callback;
-use crate::requests::pod_req::{call_download_episode, call_queue_episode, call_save_episode, DownloadEpisodeRequest, Episode, EpisodeDownload, HistoryEpisode, QueuePodcastRequest, QueuedEpisode, SavePodcastRequest, SavedEpisode, call_remove_downloaded_episode, call_remove_queued_episode, call_remove_saved_episode};
+use crate::requests::pod_req::{call_download_episode, call_queue_episode, call_save_episode, DownloadEpisodeRequest, Episode, EpisodeDownload, HistoryEpisode, QueuePodcastRequest, QueuedEpisode, SavePodcastRequest, SavedEpisode, call_remove_downloaded_episode, call_remove_history_episode, call_remove_queued_episode, call_remove_saved_episode};
 use crate::requests::search_pods::SearchEpisode;
 use crate::requests::search_pods::Episode as SearchNewEpisode;
 use std::any::Any;
 use crate::components::gen_funcs::format_time;
+use crate::components::i18n::{t, t_args, t_plural};
+use crate::components::persistence::user_key;
+use crate::components::playlists::add_to_playlist;
+use crate::components::a11y;
+use crate::components::incremental_search::{clear_recent_searches, load_recent_searches, matching_recent, remember_search, suggestion_id, suggestion_key, use_debounced, use_in_flight, InFlight, Suggestion, SuggestionKey, SuggestionList, SEARCH_DEBOUNCE_MS};
+use crate::components::selection::SelectCheckbox;
+use crate::components::app_drawer::App_drawer;
+use crate::components::audio::AudioPlayer;
+use crate::components::episodes_layout::UIStateMsg;
+use crate::requests::login_requests::use_check_authentication;
 use wasm_bindgen::prelude::*;
 
 #[derive(Properties, PartialEq, Clone)]
@@ -27,9 +35,16 @@ pub fn use_scroll_to_top() -> Html {
     let history = BrowserHistory::new();
     use_effect_with((), move |_| {
         // Create a closure that will be called on history change
-        // Create a callback to scroll to the top of the page when the route changes
+        // Create a callback to scroll to the top of the page when the route changes.
+        // Query string updates, like list filters, keep the current scroll position.
+        let current_path = std::cell::RefCell::new(history.location().path().to_string());
+        let listener_history = history.clone();
         let callback = history.listen(move || {
-            web_sys::window().unwrap().scroll_to_with_x_and_y(0.0, 0.0);
+            let path = listener_history.location().path().to_string();
+            if *current_path.borrow() != path {
+                current_path.replace(path);
+                web_sys::window().unwrap().scroll_to_with_x_and_y(0.0, 0.0);
+            }
         });
 
         // Cleanup function: This will be executed when the component unmounts
@@ -81,78 +96,225 @@ pub fn error_message(props: &ErrorMessageProps) -> Html {
     }
 }
 
+// What a row in the discovery bar's suggestion list stands for
+#[derive(Clone, PartialEq)]
+enum DiscoverySuggestion {
+    Recent(String),
+    // Index into the podcasts of the preview search
+    Podcast(usize),
+}
+
+const DISCOVERY_SUGGESTIONS_ID: &str = "discovery-suggestions";
+const MIN_PREVIEW_QUERY_LEN: usize = 3;
+const MAX_PREVIEW_SUGGESTIONS: usize = 5;
+
 #[allow(non_camel_case_types)]
 #[function_component(Search_nav)]
 pub fn search_bar() -> Html {
     let history = BrowserHistory::new();
-    let dispatch = Dispatch::<AppState>::global();
-    let state: Rc<AppState> = dispatch.get();
     let podcast_value = use_state(|| "".to_string());
-    let search_index = use_state(|| "podcast_index".to_string()); // Default to "podcast_index"
-    let (_app_state, dispatch) = use_store::<AppState>();
+    let (app_state, dispatch) = use_store::<AppState>();
+    let view_key = user_key(&app_state);
+    let api_url = app_state.server_details.as_ref().and_then(|ud| ud.api_url.clone());
+    let provider_preferences = use_state(SearchProviderPreferences::default);
+    let recent = use_state(Vec::<String>::new);
+    let suggestions_open = use_state(|| false);
+    let highlighted = use_state(|| None::<usize>);
+    // Results for the query as typed so far, reused when it is submitted unchanged
+    let preview = use_state(|| None::<(String, SearchOutcome)>);
+    let preview_flight = use_in_flight();
+    // Not cancelled on unmount, a submitted search still opens its results
+    let submit_flight = use_memo((), |_| InFlight::default());
+
+    {
+        let provider_preferences = provider_preferences.clone();
+        let recent = recent.clone();
+        use_effect_with(view_key.clone(), move |view_key| {
+            if let Some(key) = view_key {
+                provider_preferences.set(SearchProviderPreferences::load(key));
+                recent.set(load_recent_searches(key, "discovery"));
+            }
+            || ()
+        });
+    }
+
+    let debounced_query = use_debounced(podcast_value.trim().to_string(), SEARCH_DEBOUNCE_MS);
+    {
+        let preview = preview.clone();
+        let preview_flight = preview_flight.clone();
+        use_effect_with((debounced_query, (*provider_preferences).clone(), api_url.clone()), move |(query, preferences, api_url)| {
+            if query.chars().count() < MIN_PREVIEW_QUERY_LEN {
+                preview_flight.cancel();
+                preview.set(None);
+            } else {
+                let providers = enabled_providers(preferences, api_url);
+                let (generation, signal) = preview_flight.start();
+                let query = query.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let outcome = search_all(&providers, &query, signal.as_ref()).await;
+                    if preview_flight.is_current(generation) {
+                        preview.set(Some((query, outcome)));
+                    }
+                });
+            }
+            || ()
+        });
+    }
 
-    let history_clone = history.clone();
-    let podcast_value_clone = podcast_value.clone();
-    let search_index_clone = search_index.clone();
     // State for toggling the dropdown in mobile view
     let mobile_dropdown_open = use_state(|| false);
-    let on_submit = {
-        Callback::from(move |_: ()| {
-            let api_url = state.server_details.as_ref().map(|ud| ud.api_url.clone());
-            let history = history_clone.clone();
-            let search_value = podcast_value_clone.clone();
-            let search_index = search_index_clone.clone();
-            let dispatch = dispatch.clone();
-            // Assuming `search_index` is a String
-            let search_index_test = search_index.clone(); // Example assignment
 
-            // Convert the Rust String to JsValue
-            let js_value = JsValue::from_str(&*search_index_test);
-
-            // Use the converted JsValue with `web_sys::console::log_1`
-            web_sys::console::log_1(&js_value);
-            // web_sys::console::log_1((*search_index).clone());
+    // Opens the results for a query. A podcast picked from the suggestions is moved to the top.
+    let run_search = {
+        let history = history.clone();
+        let provider_preferences = provider_preferences.clone();
+        let preview = preview.clone();
+        let recent = recent.clone();
+        let suggestions_open = suggestions_open.clone();
+        let highlighted = highlighted.clone();
+        let view_key = view_key.clone();
+        Callback::from(move |(query, picked): (String, Option<usize>)| {
+            let query = query.trim().to_string();
+            if query.is_empty() {
+                return;
+            }
+            if let Some(key) = &view_key {
+                recent.set(remember_search(key, "discovery", &query));
+            }
+            suggestions_open.set(false);
+            highlighted.set(None);
+            preview_flight.cancel();
+
+            let show_results = {
+                let dispatch = dispatch.clone();
+                let history = history.clone();
+                move |mut outcome: SearchOutcome| {
+                    if let Some(index) = picked.filter(|index| *index < outcome.podcasts.len()) {
+                        let podcast = outcome.podcasts.remove(index);
+                        outcome.podcasts.insert(0, podcast);
+                    }
+                    dispatch.reduce_mut(move |state| {
+                        state.search_results = Some(outcome);
+                        state.podcast_added = Some(false);
+                        state.is_loading = Some(false);
+                    });
+                    history.push("/pod_layout"); // Use the route path
+                }
+            };
 
-            wasm_bindgen_futures::spawn_local(async move {
-                dispatch.reduce_mut(|state| state.is_loading = Some(true));
-                let cloned_api_url = &api_url.clone();
-                match test_connection(&cloned_api_url.clone().unwrap()).await {
-                    Ok(_) => {
-                        let js_value = JsValue::from_str("running call");
-                        console::log_1(&js_value);
-                        match call_get_podcast_info(&search_value, &api_url.unwrap(), &search_index).await {
-                            Ok(search_results) => {
-                                let js_value = JsValue::from_str("pulled response");
-                                console::log_1(&js_value);
-                                dispatch.reduce_mut(move |state| {
-                                    state.search_results = Some(search_results);
-                                    state.podcast_added = Some(false);
-                                });
-                                dispatch.reduce_mut(|state| state.is_loading = Some(false));
-                                history.push("/pod_layout"); // Use the route path
-                            },
-                            Err(e) => {
-                                let js_value = JsValue::from_str(&format!("Error getting data connection: {}", e));
-                                console::log_1(&js_value);
-                                dispatch.reduce_mut(|state| state.is_loading = Some(false));
-                            }
+            match &*preview {
+                Some((previewed, outcome)) if *previewed == query => show_results(outcome.clone()),
+                _ => {
+                    let providers = enabled_providers(&provider_preferences, &api_url);
+                    let (generation, signal) = submit_flight.start();
+                    let submit_flight = submit_flight.clone();
+                    let dispatch = dispatch.clone();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        dispatch.reduce_mut(|state| state.is_loading = Some(true));
+                        let outcome = search_all(&providers, &query, signal.as_ref()).await;
+                        // A newer submit owns the loading state now
+                        if submit_flight.is_current(generation) {
+                            show_results(outcome);
                         }
-                    },
-                    Err(e) => {
-                        let error = JsValue::from_str(&format!("Error testing connection: {}", e));
-                        console::log_1(&error); // Log the error from test_connection
-                        dispatch.reduce_mut(|state| state.is_loading = Some(false));
-                    }
+                    });
                 }
-            });
+            }
         })
     };
 
+    let on_submit = {
+        let run_search = run_search.clone();
+        let podcast_value = podcast_value.clone();
+        Callback::from(move |_: ()| run_search.emit(((*podcast_value).clone(), None)))
+    };
+
     let on_input_change = {
         let podcast_value = podcast_value.clone();
+        let suggestions_open = suggestions_open.clone();
+        let highlighted = highlighted.clone();
         Callback::from(move |e: InputEvent| {
             let input: HtmlInputElement = e.target_unchecked_into();
             podcast_value.set(input.value());
+            suggestions_open.set(true);
+            highlighted.set(None);
+        })
+    };
+
+    // Recent searches matching the input, then the best podcasts found for it so far
+    let query = podcast_value.trim().to_string();
+    let mut entries: Vec<DiscoverySuggestion> = matching_recent(&recent, &query).into_iter()
+        .take(if query.is_empty() { usize::MAX } else { 3 })
+        .map(DiscoverySuggestion::Recent)
+        .collect();
+    if let Some((previewed, outcome)) = &*preview {
+        if *previewed == query {
+            entries.extend((0..outcome.podcasts.len().min(MAX_PREVIEW_SUGGESTIONS)).map(DiscoverySuggestion::Podcast));
+        }
+    }
+    let suggestions: Vec<Suggestion> = entries.iter().map(|entry| match entry {
+        DiscoverySuggestion::Recent(previous) => Suggestion { label: previous.clone(), icon: "history" },
+        DiscoverySuggestion::Podcast(index) => Suggestion {
+            label: (*preview).as_ref().map(|(_, outcome)| outcome.podcasts[*index].title.clone()).unwrap_or_default(),
+            icon: "podcasts",
+        },
+    }).collect();
+    let show_suggestions = *suggestions_open && !suggestions.is_empty();
+
+    let on_select = {
+        let run_search = run_search.clone();
+        let podcast_value = podcast_value.clone();
+        let entries = entries.clone();
+        Callback::from(move |index: usize| match entries.get(index) {
+            Some(DiscoverySuggestion::Recent(previous)) => {
+                podcast_value.set(previous.clone());
+                run_search.emit((previous.clone(), None));
+            }
+            Some(DiscoverySuggestion::Podcast(podcast)) => run_search.emit(((*podcast_value).clone(), Some(*podcast))),
+            None => {}
+        })
+    };
+
+    let on_clear_recent = (query.is_empty() && !recent.is_empty()).then(|| {
+        let recent = recent.clone();
+        let view_key = view_key.clone();
+        Callback::from(move |_: ()| {
+            if let Some(key) = &view_key {
+                clear_recent_searches(key, "discovery");
+            }
+            recent.set(Vec::new());
+        })
+    });
+
+    let on_input_keydown = {
+        let suggestions_open = suggestions_open.clone();
+        let highlighted = highlighted.clone();
+        let on_select = on_select.clone();
+        let count = suggestions.len();
+        Callback::from(move |e: KeyboardEvent| match suggestion_key(&e, *highlighted, count) {
+            Some(SuggestionKey::Move(index)) => {
+                suggestions_open.set(true);
+                highlighted.set(index);
+            }
+            Some(SuggestionKey::Choose(index)) => on_select.emit(index),
+            Some(SuggestionKey::Close) => {
+                suggestions_open.set(false);
+                highlighted.set(None);
+            }
+            None => {}
+        })
+    };
+
+    let on_input_focus = {
+        let suggestions_open = suggestions_open.clone();
+        Callback::from(move |_: FocusEvent| suggestions_open.set(true))
+    };
+
+    let on_input_blur = {
+        let suggestions_open = suggestions_open.clone();
+        let highlighted = highlighted.clone();
+        Callback::from(move |_: FocusEvent| {
+            suggestions_open.set(false);
+            highlighted.set(None);
         })
     };
 
@@ -163,14 +325,13 @@ pub fn search_bar() -> Html {
         })
     };
 
+    // On desktop the button submits the form, on mobile it opens the search panel instead
     let on_search_click = {
-        let on_submit = on_submit.clone();
         let mobile_dropdown_open = mobile_dropdown_open.clone();
-        Callback::from(move |_: MouseEvent| {
+        Callback::from(move |e: MouseEvent| {
             if web_sys::window().unwrap().inner_width().unwrap().as_f64().unwrap() < 768.0 {
+                e.prevent_default();
                 mobile_dropdown_open.set(!*mobile_dropdown_open);
-            } else {
-                on_submit.emit(());
             }
         })
     };
@@ -193,29 +354,23 @@ pub fn search_bar() -> Html {
         })
     };
 
-    let on_dropdown_select = {
-        let dropdown_open = dropdown_open.clone();
-        let search_index = search_index.clone();
-        move |category: &str| {
-            search_index.set(category.to_string());
-            dropdown_open.set(false);
-        }
-    };
-
-    let on_dropdown_select_itunes = {
-        let on_dropdown_select = on_dropdown_select.clone();
-        Callback::from(move |_| on_dropdown_select("itunes"))
-    };
-
-    let on_dropdown_select_podcast_index = {
-        let on_dropdown_select = on_dropdown_select.clone();
-        Callback::from(move |_| on_dropdown_select("podcast_index"))
+    // Several providers can be searched at once, so picking one toggles it and leaves the menu open
+    let on_provider_toggle = |provider: &'static str| {
+        let provider_preferences = provider_preferences.clone();
+        let view_key = view_key.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut updated = (*provider_preferences).clone();
+            updated.toggle(provider);
+            if let Some(key) = &view_key {
+                updated.save(key);
+            }
+            provider_preferences.set(updated);
+        })
     };
 
-    let search_index_display = match search_index.as_str() {
-        "podcast_index" => "Podcast Index",
-        "itunes" => "iTunes",
-        _ => "Unknown",
+    let search_index_display = match provider_preferences.enabled.as_slice() {
+        [only] => provider_name(only).to_string(),
+        enabled => t_plural("search_providers.sources", enabled.len() as i64),
     };
 
     html! {
@@ -226,6 +381,8 @@ pub fn search_bar() -> Html {
                 <button
                     id="dropdown-button"
                     onclick={toggle_dropdown}
+                    aria-haspopup="menu"
+                    aria-expanded={dropdown_open.to_string()}
                     class="dropdown-button hidden md:flex md:block flex-shrink-0 z-10 inline-flex items-center py-2.5 px-4 text-sm font-medium text-center border border-r-0 border-gray-300 dark:border-gray-700 rounded-l-lg focus:ring-4 focus:outline-none"
                     type="button"
                 >
@@ -240,10 +397,16 @@ pub fn search_bar() -> Html {
                     if *dropdown_open {
                         html! {
                             <div class="search-dropdown-content-class absolute z-10 divide-y rounded-lg shadow">
-                                <ul class="dropdown-container py-2 text-sm">
-                                    <li class="dropdown-option" onclick={on_dropdown_select_itunes.clone()}>{ "iTunes" }</li>
-                                    <li class="dropdown-option" onclick={on_dropdown_select_podcast_index.clone()}>{ "Podcast Index" }</li>
-                                    // Add more categories as needed
+                                <ul role="menu" aria-label={t("search_providers.menu_label")} class="dropdown-container py-2 text-sm">
+                                    { for PROVIDERS.iter().map(|(id, name)| {
+                                        let enabled = provider_preferences.is_enabled(id);
+                                        html! {
+                                            <li class="dropdown-option flex items-center" role="menuitemcheckbox" aria-checked={enabled.to_string()} tabindex="-1" onclick={on_provider_toggle(id)} onkeydown={Callback::from(a11y::activate_on_key)}>
+                                                <span class="material-icons icon-space" aria-hidden="true">{ if enabled { "check_box" } else { "check_box_outline_blank" } }</span>
+                                                { *name }
+                                            </li>
+                                        }
+                                    }) }
                                 </ul>
                             </div>
                         }
@@ -259,9 +422,23 @@ pub fn search_bar() -> Html {
                     id="search-dropdown"
                     class="search-input block p-2.5 w-full z-20 text-sm rounded-r-lg border hidden md:inline-flex"
                     placeholder="Search"
-                    required=true
+                    autocomplete="off"
+                    role="combobox"
+                    aria-autocomplete="list"
+                    aria-controls={DISCOVERY_SUGGESTIONS_ID}
+                    aria-expanded={show_suggestions.to_string()}
+                    aria-activedescendant={highlighted.filter(|_| show_suggestions).map(|index| suggestion_id(DISCOVERY_SUGGESTIONS_ID, index))}
+                    value={(*podcast_value).clone()}
                     oninput={on_input_change.clone()}
+                    onkeydown={on_input_keydown}
+                    onfocus={on_input_focus}
+                    onblur={on_input_blur}
                 />
+                if show_suggestions {
+                    <div class="hidden md:block">
+                        <SuggestionList id={DISCOVERY_SUGGESTIONS_ID} suggestions={suggestions} highlighted={*highlighted} on_select={on_select} on_clear={on_clear_recent} />
+                    </div>
+                }
             </div>
             // Search Button
             <button
@@ -279,24 +456,22 @@ pub fn search_bar() -> Html {
                 if *mobile_dropdown_open {
                     html! {
                         <div class="search-drop absolute top-full right-0 z-10 divide-y rounded-lg shadow p-4">
-                            // Outline buttons for podcast_index or itunes
-                            <div class="inline-flex rounded-md shadow-sm" role="group">
-                                <button
-                                    type="button"
-                                    class={format!("px-4 py-2 text-sm font-medium rounded-l-lg search-drop-button {}",
-                                        if *search_index == "podcast_index" { "active" } else { "" })}
-                                    onclick={on_dropdown_select_podcast_index}
-                                >
-                                    {"Podcast Index"}
-                                </button>
-                                <button
-                                    type="button"
-                                    class={format!("px-4 py-2 text-sm font-medium rounded-r-lg search-drop-button {}",
-                                        if *search_index == "itunes" { "active" } else { "" })}
-                                    onclick={on_dropdown_select_itunes}
-                                >
-                                    {"iTunes"}
-                                </button>
+                            // Outline buttons, one per provider, each switched on or off
+                            <div class="inline-flex flex-wrap rounded-md shadow-sm" role="group" aria-label={t("search_providers.menu_label")}>
+                                { for PROVIDERS.iter().enumerate().map(|(index, (id, name))| {
+                                    let rounding = if index == 0 { "rounded-l-lg" } else if index == PROVIDERS.len() - 1 { "rounded-r-lg" } else { "" };
+                                    let enabled = provider_preferences.is_enabled(id);
+                                    html! {
+                                        <button
+                                            type="button"
+                                            aria-pressed={enabled.to_string()}
+                                            class={format!("px-4 py-2 text-sm font-medium search-drop-button {} {}", rounding, if enabled { "active" } else { "" })}
+                                            onclick={on_provider_toggle(id)}
+                                        >
+                                            { *name }
+                                        </button>
+                                    }
+                                }) }
                             </div>
                             // Text field for search
                             <input
@@ -307,7 +482,7 @@ pub fn search_bar() -> Html {
                                 oninput={on_input_change.clone()}
                             />
                             // Search button
-                            <button class="search-btn no-margin border border-solid mt-4 font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline" onclick={on_submit_click.clone()}>
+                            <button type="button" class="search-btn no-margin border border-solid mt-4 font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline" onclick={on_submit_click.clone()}>
                                 {"Search"}
                             </button>
                         </div>
@@ -332,12 +507,17 @@ pub struct ContextButtonProps {
 #[function_component(ContextButton)]
 pub fn context_button(props: &ContextButtonProps) -> Html {
     let dropdown_open = use_state(|| false);
-    let (post_state, post_dispatch) = use_store::<AppState>();
+    // Select only what the menu needs so unrelated AppState changes don't re-render every row
+    let auth_details = use_selector(|state: &AppState| state.auth_details.clone());
+    let user_id = *use_selector(|state: &AppState| state.user_details.as_ref().map(|ud| ud.UserID));
     let (_audio_state, audio_dispatch) = use_store::<UIState>();
-    let api_key = post_state.auth_details.as_ref().map(|ud| ud.api_key.clone());
-    let user_id = post_state.user_details.as_ref().map(|ud| ud.UserID.clone());
-    let server_name = post_state.auth_details.as_ref().map(|ud| ud.server_name.clone());
-    let dropdown_ref = NodeRef::default();
+    let cache_dispatch = Dispatch::<EpisodeCache>::global();
+    let cached = use_cached_episode(props.episode.get_episode_id());
+    let manual_playlists = use_selector(|state: &PlaylistState| state.manual().cloned().collect::<Vec<_>>());
+    let api_key = auth_details.as_ref().as_ref().map(|ud| ud.api_key.clone());
+    let server_name = auth_details.as_ref().as_ref().map(|ud| ud.server_name.clone());
+    let dropdown_ref = use_node_ref();
+    let button_ref = use_node_ref();
     
     let toggle_dropdown = {
         let dropdown_open = dropdown_open.clone();
@@ -347,6 +527,48 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
         })
     };
 
+    // Closing from the keyboard hands focus back to the menu button
+    let close_menu = {
+        let dropdown_open = dropdown_open.clone();
+        let button_ref = button_ref.clone();
+        Callback::from(move |_: ()| {
+            dropdown_open.set(false);
+            if let Some(button) = button_ref.cast::<web_sys::HtmlElement>() {
+                let _ = button.focus();
+            }
+        })
+    };
+
+    let on_button_keydown = {
+        let dropdown_open = dropdown_open.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            if e.key() == "ArrowDown" {
+                e.prevent_default();
+                e.stop_propagation();
+                dropdown_open.set(true);
+            }
+        })
+    };
+
+    let on_menu_keydown = {
+        let dropdown_ref = dropdown_ref.clone();
+        let close_menu = close_menu.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            a11y::on_menu_keydown(&e, &dropdown_ref, &close_menu);
+        })
+    };
+
+    // Move focus into the menu as soon as it opens
+    {
+        let dropdown_ref = dropdown_ref.clone();
+        use_effect_with(*dropdown_open, move |open| {
+            if *open {
+                a11y::focus_first_menu_item(&dropdown_ref);
+            }
+            || ()
+        });
+    }
+
 
 
     {
@@ -391,10 +613,12 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
     let queue_api_key = api_key.clone();
     let queue_server_name = server_name.clone();
     let queue_post = audio_dispatch.clone();
+    let queue_cache = cache_dispatch.clone();
     // let server_name = server_name.clone();
     let on_add_to_queue = {
         let episode = props.episode.clone();
         Callback::from(move |_| {
+            let cache_dispatch = queue_cache.clone();
             let server_name_copy = queue_server_name.clone();
             let api_key_copy = queue_api_key.clone();
             let queue_post = queue_post.clone();
@@ -409,6 +633,7 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
                 // queue_post.reduce_mut(|state| state.info_message = Option::from(format!("Episode added to Queue!")));
                 match call_queue_episode(&server_name.unwrap(), &api_key.flatten(), &request).await {
                     Ok(success_message) => {
+                        cache_dispatch.apply(EpisodeCacheMsg::SetMembership(EpisodeList::Queue, request.episode_id, true));
                         queue_post.reduce_mut(|state| state.info_message = Option::from(format!("{}", success_message)));
                     },
                     Err(e) => {
@@ -425,13 +650,13 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
     let remove_queue_api_key = api_key.clone();
     let remove_queue_server_name = server_name.clone();
     let remove_queue_post = audio_dispatch.clone();
-    let dispatch_clone = post_dispatch.clone();
+    let remove_queue_cache = cache_dispatch.clone();
     // let server_name = server_name.clone();
     let on_remove_queued_episode = {
         let episode = props.episode.clone();
         let episode_id = props.episode.get_episode_id();
         Callback::from(move |_| {
-            let post_dispatch = dispatch_clone.clone();
+            let cache_dispatch = remove_queue_cache.clone();
             let server_name_copy = remove_queue_server_name.clone();
             let api_key_copy = remove_queue_api_key.clone();
             let queue_post = remove_queue_post.clone();
@@ -447,14 +672,9 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
                 match call_remove_queued_episode(&server_name.unwrap(), &api_key.flatten(), &request).await {
                     Ok(success_message) => {
                         // queue_post.reduce_mut(|state| state.info_message = Option::from(format!("{}", success_message)));
-                        post_dispatch.reduce_mut(|state| {
-                            // Here, you should remove the episode from the queued_episodes
-                            if let Some(ref mut queued_episodes) = state.queued_episodes {
-                                queued_episodes.episodes.retain(|ep| ep.get_episode_id() != episode_id);
-                            }
-                            // Optionally, you can update the info_message with success message
-                            state.info_message = Some(format!("{}", success_message).to_string());
-                        });
+                        // Every page listing the queue drops the episode through the shared cache
+                        cache_dispatch.apply(EpisodeCacheMsg::SetMembership(EpisodeList::Queue, episode_id, false));
+                        queue_post.reduce_mut(|state| state.info_message = Some(format!("{}", success_message)));
                     },
                     Err(e) => {
                         queue_post.reduce_mut(|state| state.error_message = Option::from(format!("{}", e)));
@@ -470,9 +690,11 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
     let saved_api_key = api_key.clone();
     let saved_server_name = server_name.clone();
     let save_post = audio_dispatch.clone();
+    let save_cache = cache_dispatch.clone();
     let on_save_episode = {
         let episode = props.episode.clone();
         Callback::from(move |_| {
+            let cache_dispatch = save_cache.clone();
             let server_name_copy = saved_server_name.clone();
             let api_key_copy = saved_api_key.clone();
             let post_state = save_post.clone();
@@ -487,6 +709,7 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
                 // post_state.reduce_mut(|state| state.info_message = Option::from(format!("Episode saved successfully")));
                 match call_save_episode(&server_name.unwrap(), &api_key.flatten(), &request).await {
                     Ok(success_message) => {
+                        cache_dispatch.apply(EpisodeCacheMsg::SetMembership(EpisodeList::Saved, request.episode_id, true));
                         post_state.reduce_mut(|state| state.info_message = Option::from(format!("{}", success_message)));
                     },
                     Err(e) => {
@@ -503,12 +726,12 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
     let remove_saved_api_key = api_key.clone();
     let remove_saved_server_name = server_name.clone();
     let remove_save_post = audio_dispatch.clone();
-    let dispatch_clone = post_dispatch.clone();
+    let remove_save_cache = cache_dispatch.clone();
     let on_remove_saved_episode = {
         let episode = props.episode.clone();
         let episode_id = props.episode.get_episode_id();
         Callback::from(move |_| {
-            let post_dispatch = dispatch_clone.clone();
+            let cache_dispatch = remove_save_cache.clone();
             let server_name_copy = remove_saved_server_name.clone();
             let api_key_copy = remove_saved_api_key.clone();
             let post_state = remove_save_post.clone();
@@ -522,14 +745,8 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
                 match call_remove_saved_episode(&server_name.unwrap(), &api_key.flatten(), &request).await {
                     Ok(success_message) => {
                         // queue_post.reduce_mut(|state| state.info_message = Option::from(format!("{}", success_message)));
-                        post_dispatch.reduce_mut(|state| {
-                            // Here, you should remove the episode from the saved_episodes
-                            if let Some(ref mut saved_episodes) = state.saved_episodes {
-                                saved_episodes.episodes.retain(|ep| ep.get_episode_id() != episode_id);
-                            }
-                            // Optionally, you can update the info_message with success message
-                            state.info_message = Some(format!("{}", success_message).to_string());
-                        });
+                        cache_dispatch.apply(EpisodeCacheMsg::SetMembership(EpisodeList::Saved, episode_id, false));
+                        post_state.reduce_mut(|state| state.info_message = Some(format!("{}", success_message)));
                     },
                     Err(e) => {
                         post_state.reduce_mut(|state| state.error_message = Option::from(format!("{}", e)));
@@ -545,9 +762,11 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
     let download_api_key = api_key.clone();
     let download_server_name = server_name.clone();
     let download_post = audio_dispatch.clone();
+    let download_cache = cache_dispatch.clone();
     let on_download_episode = {
         let episode = props.episode.clone();
         Callback::from(move |_| {
+            let cache_dispatch = download_cache.clone();
             let post_state = download_post.clone();
             let server_name_copy = download_server_name.clone();
             let api_key_copy = download_api_key.clone();
@@ -562,6 +781,7 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
                 // post_state.reduce_mut(|state| state.info_message = Option::from(format!("Episode now downloading!")));
                 match call_download_episode(&server_name.unwrap(), &api_key.flatten(), &request).await {
                     Ok(success_message) => {
+                        cache_dispatch.apply(EpisodeCacheMsg::SetMembership(EpisodeList::Downloads, request.episode_id, true));
                         post_state.reduce_mut(|state| state.info_message = Option::from(format!("{}", success_message)));
                     },
                     Err(e) => {
@@ -578,12 +798,12 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
     let remove_download_api_key = api_key.clone();
     let remove_download_server_name = server_name.clone();
     let remove_download_post = audio_dispatch.clone();
-    let dispatch_clone = post_dispatch.clone();
+    let remove_download_cache = cache_dispatch.clone();
     let on_remove_downloaded_episode = {
         let episode = props.episode.clone();
         let episode_id = props.episode.get_episode_id();
         Callback::from(move |_| {
-            let post_dispatch = dispatch_clone.clone();
+            let cache_dispatch = remove_download_cache.clone();
             let post_state = remove_download_post.clone();
             let server_name_copy = remove_download_server_name.clone();
             let api_key_copy = remove_download_api_key.clone();
@@ -599,14 +819,8 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
                 match call_remove_downloaded_episode(&server_name.unwrap(), &api_key.flatten(), &request).await {
                     Ok(success_message) => {
                         // queue_post.reduce_mut(|state| state.info_message = Option::from(format!("{}", success_message)));
-                        post_dispatch.reduce_mut(|state| {
-                            // Here, you should remove the episode from the downloaded_episodes
-                            if let Some(ref mut downloaded_episodes) = state.downloaded_episodes {
-                                downloaded_episodes.episodes.retain(|ep| ep.get_episode_id() != episode_id);
-                            }
-                            // Optionally, you can update the info_message with success message
-                            state.info_message = Some(format!("{}", success_message).to_string());
-                        });
+                        cache_dispatch.apply(EpisodeCacheMsg::SetMembership(EpisodeList::Downloads, episode_id, false));
+                        post_state.reduce_mut(|state| state.info_message = Some(format!("{}", success_message)));
                     },
                     Err(e) => {
                         post_state.reduce_mut(|state| state.error_message = Option::from(format!("{}", e)));
@@ -619,37 +833,89 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
         })
     };
 
-    let action_buttons = match props.page_type.as_str() {
-        "saved" => html! {
-            <>
-                <li class="dropdown-option" onclick={on_add_to_queue.clone()}>{ "Queue Episode" }</li>
-                <li class="dropdown-option" onclick={on_remove_saved_episode.clone()}>{ "Remove Saved Episode" }</li>
-                <li class="dropdown-option" onclick={on_download_episode.clone()}>{ "Download Episode" }</li>
-            </>
-        },
-        "queue" => html! {
-            <>
-                <li class="dropdown-option" onclick={on_save_episode.clone()}>{ "Save Episode" }</li>
-                <li class="dropdown-option" onclick={on_remove_queued_episode.clone()}>{ "Remove from Queue" }</li>
-                <li class="dropdown-option" onclick={on_download_episode.clone()}>{ "Download Episode" }</li>
-            </>
-        },
-        "downloads" => html! {
-            <>
-                <li class="dropdown-option" onclick={on_add_to_queue.clone()}>{ "Queue Episode" }</li>
-                <li class="dropdown-option" onclick={on_save_episode.clone()}>{ "Save Episode" }</li>
-                <li class="dropdown-option" onclick={on_remove_downloaded_episode.clone()}>{ "Remove Downloaded Episode" }</li>
-            </>
-        },
-        // Add more page types and their respective button sets as needed
-        _ => html! {
-            // Default set of buttons for other page types
-            <>
-                <li class="dropdown-option" onclick={on_add_to_queue.clone()}>{ "Queue Episode" }</li>
-                <li class="dropdown-option" onclick={on_save_episode.clone()}>{ "Save Episode" }</li>
-                <li class="dropdown-option" onclick={on_download_episode.clone()}>{ "Download Episode" }</li>
-            </>
-        },
+    // The cache knows whether the episode is queued/saved/downloaded no matter which page
+    // fetched it. Until it has seen the relevant list, fall back to the page we are on.
+    let page_type = props.page_type.as_str();
+    let in_list = |list: EpisodeList, list_page: &str| {
+        cached
+            .as_ref()
+            .as_ref()
+            .and_then(|episode| episode.flag(list))
+            .unwrap_or(page_type == list_page)
+    };
+    let is_queued = in_list(EpisodeList::Queue, "queue");
+    let is_saved = in_list(EpisodeList::Saved, "saved");
+    let is_downloaded = in_list(EpisodeList::Downloads, "downloads");
+
+    let episode_id = props.episode.get_episode_id();
+
+    // Forgetting a listen also drops its progress, so the episode shows as unplayed everywhere
+    let on_remove_from_history = {
+        let cache_dispatch = cache_dispatch.clone();
+        let audio_dispatch = audio_dispatch.clone();
+        let server_name = server_name.clone();
+        let api_key = api_key.clone().flatten();
+        Callback::from(move |_: MouseEvent| {
+            let (Some(server_name), Some(user_id)) = (server_name.clone(), user_id) else {
+                return;
+            };
+            let api_key = api_key.clone();
+            let cache_dispatch = cache_dispatch.clone();
+            let audio_dispatch = audio_dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match call_remove_history_episode(&server_name, &api_key, user_id, episode_id).await {
+                    Ok(_) => {
+                        cache_dispatch.apply(EpisodeCacheMsg::SetProgress(episode_id, 0));
+                        Dispatch::<EpisodeLists>::global().apply(EpisodeListsMsg::RemoveFromHistory(episode_id));
+                        audio_dispatch.reduce_mut(|state| state.info_message = Some(t("history.removed")));
+                    },
+                    Err(e) => audio_dispatch.reduce_mut(|state| state.error_message = Some(format!("{}", e))),
+                }
+            });
+        })
+    };
+
+    let playlist_buttons: Vec<Html> = manual_playlists
+        .iter()
+        .filter(|playlist| !playlist.EpisodeIDs.contains(&episode_id))
+        .map(|playlist| {
+            let playlist = playlist.clone();
+            let label = t_args("playlists.add_to", &[("name", playlist.Name.as_str())]);
+            let server_name = server_name.clone();
+            let api_key = api_key.clone().flatten();
+            let on_add = Callback::from(move |_: MouseEvent| {
+                if let (Some(server_name), Some(user_id)) = (server_name.clone(), user_id) {
+                    add_to_playlist(server_name, api_key.clone(), user_id, playlist.clone(), vec![episode_id]);
+                }
+            });
+            html! {
+                <li class="dropdown-option" role="menuitem" tabindex="-1" onclick={on_add} onkeydown={Callback::from(a11y::activate_on_key)}>{ label }</li>
+            }
+        })
+        .collect();
+
+    let action_buttons = html! {
+        <>
+            if is_queued {
+                <li class="dropdown-option" role="menuitem" tabindex="-1" onclick={on_remove_queued_episode.clone()} onkeydown={Callback::from(a11y::activate_on_key)}>{ t("episode.menu.unqueue") }</li>
+            } else {
+                <li class="dropdown-option" role="menuitem" tabindex="-1" onclick={on_add_to_queue.clone()} onkeydown={Callback::from(a11y::activate_on_key)}>{ t("episode.menu.queue") }</li>
+            }
+            if is_saved {
+                <li class="dropdown-option" role="menuitem" tabindex="-1" onclick={on_remove_saved_episode.clone()} onkeydown={Callback::from(a11y::activate_on_key)}>{ t("episode.menu.unsave") }</li>
+            } else {
+                <li class="dropdown-option" role="menuitem" tabindex="-1" onclick={on_save_episode.clone()} onkeydown={Callback::from(a11y::activate_on_key)}>{ t("episode.menu.save") }</li>
+            }
+            if is_downloaded {
+                <li class="dropdown-option" role="menuitem" tabindex="-1" onclick={on_remove_downloaded_episode.clone()} onkeydown={Callback::from(a11y::activate_on_key)}>{ t("episode.menu.remove_download") }</li>
+            } else {
+                <li class="dropdown-option" role="menuitem" tabindex="-1" onclick={on_download_episode.clone()} onkeydown={Callback::from(a11y::activate_on_key)}>{ t("episode.menu.download") }</li>
+            }
+            if page_type == "history" {
+                <li class="dropdown-option" role="menuitem" tabindex="-1" onclick={on_remove_from_history} onkeydown={Callback::from(a11y::activate_on_key)}>{ t("history.remove") }</li>
+            }
+            { for playlist_buttons }
+        </>
     };
 
     html! {
@@ -657,17 +923,22 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
         <div class="relative inline-block">
             <button
                 id="dropdown-button"
+                ref={button_ref.clone()}
                 onclick={toggle_dropdown.clone()}
+                onkeydown={on_button_keydown}
+                aria-label={t_args("episode.actions_for", &[("title", props.episode.get_episode_title().as_str())])}
+                aria-haspopup="menu"
+                aria-expanded={dropdown_open.to_string()}
                 class="item-container-button border-solid border selector-button font-bold py-2 px-4 rounded-full flex items-center justify-center md:w-16 md:h-16 w-10 h-10"
             >
-                <span class="material-icons large-material-icons md:text-6xl text-4xl">{"more_vert"}</span>
+                <span class="material-icons large-material-icons md:text-6xl text-4xl" aria-hidden="true">{"more_vert"}</span>
             </button>
             // Dropdown Content
             {
                 if *dropdown_open {
                     html! {
-                        <div ref={dropdown_ref.clone()} class="dropdown-content-class border border-solid absolute z-10 divide-y rounded-lg shadow w-48">
-                            <ul class="dropdown-container py-2 text-sm text-gray-700">
+                        <div ref={dropdown_ref.clone()} onkeydown={on_menu_keydown} class="dropdown-content-class border border-solid absolute z-10 divide-y rounded-lg shadow w-48">
+                            <ul role="menu" aria-label={t("episode.actions")} class="dropdown-container py-2 text-sm text-gray-700">
                                 { action_buttons }
                             </ul>
                         </div>
@@ -693,6 +964,64 @@ pub fn empty_message(header: &str, paragraph: &str) -> Html {
     }
 }
 
+// Session check and snackbar dismissal for pages built with page_frame
+#[hook]
+pub fn use_page_setup() {
+    let (session_state, session_dispatch) = use_store::<AppState>();
+    let ui_dispatch = Dispatch::<UIState>::global();
+
+    use_effect_with((), move |_| {
+        if !session_state.reload_occured.unwrap_or(false) {
+            let window = web_sys::window().expect("no global `window` exists");
+            let performance = window.performance().expect("should have performance");
+            if performance.navigation().type_() == 1 {
+                let session_storage = window.session_storage().unwrap().unwrap();
+                session_storage.set_item("isAuthenticated", "false").unwrap();
+            }
+            let current_route = window.location().href().unwrap_or_default();
+            use_check_authentication(session_dispatch.clone(), &current_route);
+            session_dispatch.reduce_mut(|state| state.reload_occured = Some(true));
+        }
+        || ()
+    });
+
+    use_effect(move || {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let closure = Closure::wrap(Box::new(move |_event: Event| {
+            ui_dispatch.apply(UIStateMsg::ClearErrorMessage);
+            ui_dispatch.apply(UIStateMsg::ClearInfoMessage);
+        }) as Box<dyn Fn(_)>);
+        document.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref()).unwrap();
+        move || {
+            document.remove_event_listener_with_callback("click", closure.as_ref().unchecked_ref()).unwrap();
+            closure.forget();
+        }
+    });
+}
+
+// Shared page chrome: player, snackbars and drawer around the page content
+pub fn page_frame(ui_state: &UIState, content: Html) -> Html {
+    html! {
+        <>
+        <div class="main-container">
+            <Search_nav />
+            <UseScrollToTop />
+            { content }
+            if let Some(audio_props) = &ui_state.currently_playing {
+                <AudioPlayer src={audio_props.src.clone()} title={audio_props.title.clone()} artwork_url={audio_props.artwork_url.clone()} duration={audio_props.duration.clone()} episode_id={audio_props.episode_id.clone()} duration_sec={audio_props.duration_sec.clone()} start_pos_sec={audio_props.start_pos_sec.clone()} />
+            }
+            if let Some(error) = ui_state.error_message.clone() {
+                <div class="error-snackbar">{ error }</div>
+            }
+            if let Some(info) = ui_state.info_message.clone() {
+                <div class="info-snackbar">{ info }</div>
+            }
+        </div>
+        <App_drawer />
+        </>
+    }
+}
+
 pub trait EpisodeTrait {
     fn get_episode_artwork(&self) -> String;
     fn get_episode_title(&self) -> String;
@@ -902,6 +1231,53 @@ pub fn on_shownotes_click(
 }
 
 
+#[derive(Properties, PartialEq, Clone)]
+pub struct EpisodeProgressProps {
+    pub episode_id: i32,
+    pub episode_duration: i32,
+    // What the page fetched, used until the player reports newer progress
+    pub listen_duration: Option<i32>,
+}
+
+#[function_component(EpisodeProgress)]
+pub fn episode_progress(props: &EpisodeProgressProps) -> Html {
+    let cached = use_cached_episode(props.episode_id);
+    let listen_duration = cached
+        .as_ref()
+        .as_ref()
+        .and_then(|episode| episode.listen_duration)
+        .or(props.listen_duration)
+        // Episodes marked unplayed have a zero duration and show like ones never started
+        .filter(|ld| *ld > 0);
+    let episode_duration = props.episode_duration;
+    let formatted_duration = format_time(episode_duration as f64);
+    let formatted_listen_duration = listen_duration.map(|ld| format_time(ld as f64));
+    // Calculate the percentage of the episode that has been listened to
+    let listen_duration_percentage = listen_duration.map_or(0.0, |ld| {
+        if episode_duration > 0 {
+            (ld as f64 / episode_duration as f64) * 100.0
+        } else {
+            0.0 // Avoid division by zero
+        }
+    });
+
+    if formatted_listen_duration.is_some() {
+        html! {
+            <div class="flex items-center space-x-2">
+                <span class="item_container-text">{ formatted_listen_duration.clone() }</span>
+                <div class="progress-bar-container">
+                    <div class="progress-bar" style={ format!("width: {}%;", listen_duration_percentage) }></div>
+                </div>
+                <span class="item_container-text">{ formatted_duration }</span>
+            </div>
+        }
+    } else {
+        html! {
+            <span class="item_container-text">{ format!("{}", formatted_duration) }</span>
+        }
+    }
+}
+
 pub fn episode_item(
     episode: Box<dyn EpisodeTrait>,
     description: String,
@@ -913,24 +1289,8 @@ pub fn episode_item(
     episode_duration: i32,
     listen_duration: Option<i32>,
     page_type: &str,
-    on_checkbox_change: Callback<i32>,
-    is_delete_mode: bool, // Add this line
 ) -> Html {
-    let span_duration = listen_duration.clone();
-    let span_episode = episode_duration.clone();
-    let formatted_duration = format_time(span_episode as f64);
-    let formatted_listen_duration = span_duration.map(|ld| format_time
-    (ld as f64));
     let episode_guid = episode.get_episode_id().to_string();
-    // Calculate the percentage of the episode that has been listened to
-    let listen_duration_percentage = listen_duration.map_or(0.0, |ld| {
-        if episode_duration > 0 {
-            (ld as f64 / episode_duration as f64) * 100.0
-        } else {
-            0.0 // Avoid division by zero
-        }
-    });
-    let checkbox_ep = episode.get_episode_id();
 
     #[wasm_bindgen]
     extern "C" {
@@ -942,26 +1302,26 @@ pub fn episode_item(
     } else {
         "desc-collapsed".to_string()
     };
+    let episode_title = episode.get_episode_title();
     html! {
         <div>
-            <div class="item-container border-solid border flex items-start mb-4 shadow-md rounded-lg h-full">
-                {if is_delete_mode {
-                    html! {
-                        <input type="checkbox" class="form-checkbox h-5 w-5 text-blue-600" 
-                            onchange={on_checkbox_change.reform(move |_| checkbox_ep)} /> // Modify this line
-                    }
-                } else {
-                    html! {}
-                }}
+            <div
+                class="item-container border-solid border flex items-start mb-4 shadow-md rounded-lg h-full"
+                data-list-row="true"
+                tabindex="0"
+                aria-label={episode_title.clone()}
+                onkeydown={Callback::from(a11y::on_list_keydown)}
+            >
+                <SelectCheckbox episode_id={episode.get_episode_id()} title={episode_title.clone()} />
                 <div class="flex flex-col w-auto object-cover pl-4">
                     <img 
                         src={episode.get_episode_artwork()} 
-                        alt={format!("Cover for {}", episode.get_episode_title())} 
+                        alt={t_args("a11y.cover_for", &[("title", episode.get_episode_title().as_str())])} 
                         class="object-cover align-top-cover w-full item-container img"
                     />
                 </div> 
                 <div class="flex flex-col p-4 space-y-2 flex-grow md:w-7/12">
-                    <p class="item_container-text text-xl font-semibold cursor-pointer" onclick={on_shownotes_click}>
+                    <p class="item_container-text text-xl font-semibold cursor-pointer" role="link" tabindex="0" onclick={on_shownotes_click} onkeydown={Callback::from(a11y::activate_on_key)}>
                         { episode.get_episode_title() }
                     </p>
                     <hr class="my-2 border-t hidden md:block"/>
@@ -971,8 +1331,8 @@ pub fn episode_item(
                                 <div class={format!("item_container-text episode-description-container {}", description_class)}>
                                     <SafeHtml html={description} />
                                 </div>
-                                <a class="link hover:underline cursor-pointer mt-4" onclick={toggle_expanded}>
-                                    { if is_expanded { "See Less" } else { "See More" } }
+                                <a class="link hover:underline cursor-pointer mt-4" role="button" tabindex="0" aria-expanded={is_expanded.to_string()} onclick={toggle_expanded} onkeydown={Callback::from(a11y::activate_on_key)}>
+                                    { if is_expanded { t("episode.see_less") } else { t("episode.see_more") } }
                                 </a>
                             </div>
                         }
@@ -983,31 +1343,15 @@ pub fn episode_item(
                         </svg>
                         { format_release }
                     </span>
-                    {
-                        if formatted_listen_duration.is_some() {
-                            html! {
-                                <div class="flex items-center space-x-2">
-                                    <span class="item_container-text">{ formatted_listen_duration.clone() }</span>
-                                    <div class="progress-bar-container">
-                                        <div class="progress-bar" style={ format!("width: {}%;", listen_duration_percentage) }></div>
-                                    </div>
-                                    <span class="item_container-text">{ formatted_duration }</span>
-                                </div>
-                            }
-                            
-                        } else {
-                            html! {
-                                <span class="item_container-text">{ format!("{}", formatted_duration) }</span>
-                            }
-                        }
-                    }
+                    <EpisodeProgress episode_id={episode.get_episode_id()} episode_duration={episode_duration} listen_duration={listen_duration} />
                 </div>
                 <div class="flex flex-col items-center h-full w-2/12 px-2 space-y-4 md:space-y-8" style="align-self: center;"> // Add align-self: center; here
                     <button
                         class="item-container-button border-solid border selector-button font-bold py-2 px-4 rounded-full flex items-center justify-center md:w-16 md:h-16 w-10 h-10"
                         onclick={on_play_click}
+                        aria-label={t_args("episode.play", &[("title", episode_title.as_str())])}
                     >
-                        <span class="material-bonus-color material-icons large-material-icons md:text-6xl text-4xl">{"play_arrow"}</span>
+                        <span class="material-bonus-color material-icons large-material-icons md:text-6xl text-4xl" aria-hidden="true">{"play_arrow"}</span>
                     </button>
                     <ContextButton episode={episode.clone()} page_type={page_type.to_string()} />
                 </div>
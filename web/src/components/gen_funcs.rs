@@ -9,8 +9,9 @@ use argon2::{
     },
     Argon2
 };
-use chrono::{DateTime, NaiveDateTime, Utc, TimeZone};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc, TimeZone, Timelike};
 use chrono_tz::Tz;
+use crate::components::i18n::{t, t_plural};
 
 
 pub fn format_date(date_str: &str) -> String {
@@ -19,7 +20,7 @@ pub fn format_date(date_str: &str) -> String {
     date.format("%m-%d-%Y").to_string()
 }
 
-#[derive(Clone)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum DateFormat {
     MDY,
     DMY,
@@ -53,8 +54,15 @@ pub fn parse_date(date_str: &str, user_tz: &Option<String>) -> DateTime<Tz> {
     datetime_utc.with_timezone(&tz)
 }
 
-pub fn format_datetime(datetime: &DateTime<Tz>, hour_preference: &Option<i16>, date_format: DateFormat) -> String {
-    let format_str = match date_format {
+// When a history entry was last listened to, in the given timezone. Postgres sends fractional seconds, which parse_date doesn't take.
+pub fn listened_at(listen_date: &str, tz: Tz) -> Option<DateTime<Tz>> {
+    let listen_date = listen_date.split('.').next().unwrap_or(listen_date);
+    let naive = NaiveDateTime::parse_from_str(listen_date, "%Y-%m-%dT%H:%M:%S").ok()?;
+    Some(Utc.from_utc_datetime(&naive).with_timezone(&tz))
+}
+
+fn date_pattern(date_format: DateFormat) -> &'static str {
+    match date_format {
         DateFormat::MDY => "%m-%d-%Y",
         DateFormat::DMY => "%d-%m-%Y",
         DateFormat::YMD => "%Y-%m-%d",
@@ -63,14 +71,68 @@ pub fn format_datetime(datetime: &DateTime<Tz>, hour_preference: &Option<i16>, d
         DateFormat::USA => "%m/%d/%Y",
         DateFormat::EUR => "%d.%m.%Y",
         DateFormat::JIS => "%Y-%m-%d",
-    };
+    }
+}
+
+// Just the date, in the user's preferred format
+pub fn format_day(date: &NaiveDate, date_format: DateFormat) -> String {
+    date.format(date_pattern(date_format)).to_string()
+}
+
+pub fn format_datetime(datetime: &DateTime<Tz>, hour_preference: &Option<i16>, date_format: DateFormat) -> String {
+    let format_str = date_pattern(date_format);
 
     match hour_preference {
-        Some(12) => datetime.format(&format!("{} %l:%M %p", format_str)).to_string(),
+        Some(12) => {
+            // chrono only knows English AM/PM markers so the localized one is appended manually
+            let meridiem = if datetime.hour() < 12 { t("datetime.am") } else { t("datetime.pm") };
+            format!("{} {}", datetime.format(&format!("{} %l:%M", format_str)), meridiem)
+        },
         _ => datetime.format(&format!("{} %H:%M", format_str)).to_string(),
     }
 }
 
+// Localized "3 days ago" style string relative to now
+pub fn format_relative_time(datetime: &DateTime<Tz>) -> String {
+    let elapsed = Utc::now().signed_duration_since(datetime.with_timezone(&Utc));
+    let minutes = elapsed.num_minutes();
+    let hours = elapsed.num_hours();
+    let days = elapsed.num_days();
+
+    if minutes < 1 {
+        t("relative.just_now")
+    } else if hours < 1 {
+        t_plural("relative.minutes_ago", minutes)
+    } else if days < 1 {
+        t_plural("relative.hours_ago", hours)
+    } else if days < 30 {
+        t_plural("relative.days_ago", days)
+    } else if days < 365 {
+        t_plural("relative.months_ago", days / 30)
+    } else {
+        t_plural("relative.years_ago", days / 365)
+    }
+}
+
+// Localized human readable duration, e.g. "1 hr 5 mins"
+pub fn format_duration(time_in_seconds: i64) -> String {
+    let hours = time_in_seconds / 3600;
+    let minutes = (time_in_seconds % 3600) / 60;
+    let seconds = time_in_seconds % 60;
+
+    if hours > 0 {
+        if minutes > 0 {
+            format!("{} {}", t_plural("duration.hours", hours), t_plural("duration.minutes", minutes))
+        } else {
+            t_plural("duration.hours", hours)
+        }
+    } else if minutes > 0 {
+        t_plural("duration.minutes", minutes)
+    } else {
+        t_plural("duration.seconds", seconds)
+    }
+}
+
 pub fn truncate_description(description: String, max_length: usize) -> (String, bool) {
     let is_truncated = description.len() > max_length;
 
@@ -171,25 +233,88 @@ pub fn validate_email(email: &str) -> Vec<ValidationError> {
 }
 
 
-pub fn parse_opml(opml_content: &str) -> Vec<(String, String)> {
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpmlPodcast {
+    pub title: String,
+    pub xml_url: String,
+    // Names of the <outline> groups the feed was nested under
+    pub folders: Vec<String>,
+}
+
+pub fn parse_opml(opml_content: &str) -> Vec<OpmlPodcast> {
     let parser = DomParser::new().unwrap();
     let doc = parser.parse_from_string(opml_content, SupportedType::TextXml)
         .unwrap()
         .dyn_into::<web_sys::Document>()
         .unwrap();
 
-    let mut podcasts = Vec::new();
-    let outlines = doc.query_selector_all("outline").unwrap();
+    let mut podcasts: Vec<OpmlPodcast> = Vec::new();
+    let outlines = doc.query_selector_all("outline[xmlUrl]").unwrap();
     for i in 0..outlines.length() {
         if let Some(outline) = outlines.item(i).and_then(|o| o.dyn_into::<web_sys::Element>().ok()) {
-            let title = outline.get_attribute("title").unwrap_or_default();
+            let label = |element: &web_sys::Element| {
+                element.get_attribute("title")
+                    .filter(|label| !label.trim().is_empty())
+                    .or_else(|| element.get_attribute("text"))
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string()
+            };
             let xml_url = outline.get_attribute("xmlUrl").unwrap_or_default();
-            podcasts.push((title, xml_url));
+            if xml_url.trim().is_empty() {
+                continue;
+            }
+            // Only the closest group counts, deeper nesting is flattened into it
+            let folder = outline.parent_element()
+                .filter(|parent| parent.tag_name() == "outline")
+                .map(|parent| label(&parent))
+                .filter(|name| !name.is_empty());
+
+            // A feed listed under several groups becomes one podcast in several folders
+            match podcasts.iter_mut().find(|podcast| podcast.xml_url == xml_url) {
+                Some(existing) => {
+                    if let Some(folder) = folder {
+                        if !existing.folders.contains(&folder) {
+                            existing.folders.push(folder);
+                        }
+                    }
+                }
+                None => podcasts.push(OpmlPodcast {
+                    title: label(&outline),
+                    xml_url,
+                    folders: folder.into_iter().collect(),
+                }),
+            }
         }
     }
     podcasts
 }
 
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ListenState {
+    Unplayed,
+    InProgress,
+    Played,
+}
+
+// An episode counts as played once less than a minute, or five percent of it, is left
+pub fn listen_state(listen_duration: Option<i32>, duration: i32) -> ListenState {
+    match listen_duration.unwrap_or(0) {
+        0 => ListenState::Unplayed,
+        listened if duration > 0 && duration - listened <= 60.max(duration / 20) => ListenState::Played,
+        _ => ListenState::InProgress,
+    }
+}
+
+// A played or unplayed mark from the user outranks what the progress says
+pub fn marked_listen_state(played: Option<bool>, listen_duration: Option<i32>, duration: i32) -> ListenState {
+    match played {
+        Some(true) => ListenState::Played,
+        Some(false) => ListenState::Unplayed,
+        None => listen_state(listen_duration, duration),
+    }
+}
+
 pub fn format_time(time_in_seconds: f64) -> String {
     let hours = (time_in_seconds / 3600.0).floor() as i32;
     let minutes = ((time_in_seconds % 3600.0) / 60.0).floor() as i32;
@@ -197,13 +322,6 @@ pub fn format_time(time_in_seconds: f64) -> String {
     format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
 }
 
-pub fn format_time_mins(time_in_minutes: i32) -> String {
-    let time_in_minutes = time_in_minutes as f64;
-    let hours = (time_in_minutes / 60.0).floor() as i32;
-    let minutes = (time_in_minutes % 60.0).floor() as i32;
-    format!("{:02}:{:02}", hours, minutes)
-}
-
 pub fn convert_time_to_seconds(time: &str) -> Result<u32, Box<dyn std::error::Error>> {
     let parts: Vec<&str> = time.split(':').collect();
 
@@ -225,4 +343,45 @@ pub fn convert_time_to_seconds(time: &str) -> Result<u32, Box<dyn std::error::Er
         }
         _ => Err("Invalid time format".into()),
     }
-}
\ No newline at end of file
+}
+// Saves text built in the browser, like an export, as a file download
+pub fn download_text(filename: &str, mime_type: &str, content: &str) -> Result<(), wasm_bindgen::JsValue> {
+    let array = js_sys::Array::new();
+    array.push(&wasm_bindgen::JsValue::from_str(content));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(&array, &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+    download_href(filename, &url)?;
+    web_sys::Url::revoke_object_url(&url)
+}
+
+// Saves whatever a link points at, such as a data URL from a canvas
+pub fn download_href(filename: &str, href: &str) -> Result<(), wasm_bindgen::JsValue> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| wasm_bindgen::JsValue::from_str("No document to download from"))?;
+    let anchor = document.create_element("a")?.dyn_into::<web_sys::HtmlAnchorElement>()?;
+    anchor.set_href(href);
+    anchor.set_download(filename);
+    anchor.click();
+    Ok(())
+}
+
+// Quotes a CSV field when it holds a separator, quote or line break
+pub fn csv_field(value: &str) -> String {
+    if value.contains(|c: char| matches!(c, ',' | '"' | '\n' | '\r')) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Escapes text for use inside XML attributes and elements
+pub fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
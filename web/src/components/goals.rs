@@ -0,0 +1,271 @@
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yewdux::prelude::*;
+use crate::components::context::{AppState, UIState};
+use crate::components::gen_funcs::format_duration;
+use crate::components::i18n::{t, t_args, t_plural};
+use crate::requests::stat_reqs::{
+    call_create_listening_goal, call_delete_listening_goal, call_get_listening_goals, ListeningGoal, ListeningGoalsResponse,
+};
+
+// Personal listening goals and the daily streak, shown on Home and the stats page.
+// Progress is worked out by the server so both places and every device agree.
+
+const GOAL_TYPES: [&str; 2] = ["minutes", "episodes"];
+const GOAL_PERIODS: [&str; 3] = ["day", "week", "month"];
+
+// The shared guest account keeps no goals
+pub fn goals_available(state: &AppState) -> bool {
+    state.user_details.as_ref().map_or(false, |ud| ud.UserID != 1)
+}
+
+fn goal_amount(goal_type: &str, amount: i32) -> String {
+    if goal_type == "minutes" {
+        format_duration(amount as i64 * 60)
+    } else {
+        t_plural("goals.episodes", amount as i64)
+    }
+}
+
+// e.g. "5 h per week" or "3 episodes per day"
+pub fn goal_label(goal: &ListeningGoal) -> String {
+    t_args(&format!("goals.per_{}", goal.Period), &[("amount", goal_amount(&goal.GoalType, goal.Target).as_str())])
+}
+
+fn goal_percent(goal: &ListeningGoal) -> i32 {
+    if goal.Target <= 0 {
+        100
+    } else {
+        (goal.Progress * 100 / goal.Target).min(100)
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct GoalRowProps {
+    goal: ListeningGoal,
+    // Set while editing, to offer removing the goal
+    #[prop_or_default]
+    on_delete: Option<Callback<i32>>,
+}
+
+#[function_component(GoalRow)]
+fn goal_row(props: &GoalRowProps) -> Html {
+    let goal = &props.goal;
+    let label = goal_label(goal);
+    let percent = goal_percent(goal);
+    let progress = if goal.Progress >= goal.Target {
+        t("goals.complete")
+    } else {
+        t_args("goals.progress", &[
+            ("done", goal_amount(&goal.GoalType, goal.Progress).as_str()),
+            ("target", goal_amount(&goal.GoalType, goal.Target).as_str()),
+        ])
+    };
+
+    html! {
+        <li class="mb-3">
+            <div class="flex items-center justify-between item_container-text mb-1">
+                <span class="font-semibold">{ label.clone() }</span>
+                <span class="flex items-center gap-2 text-sm">
+                    { progress }
+                    if let Some(on_delete) = &props.on_delete {
+                        <button
+                            class="item-container-button selector-button rounded p-1"
+                            onclick={on_delete.reform({ let goal_id = goal.GoalID; move |_: MouseEvent| goal_id })}
+                            aria-label={t_args("goals.delete", &[("goal", label.as_str())])}
+                        >
+                            <span class="material-icons" aria-hidden="true">{"delete"}</span>
+                        </button>
+                    }
+                </span>
+            </div>
+            <div class="goal-progress" role="progressbar" aria-label={label} aria-valuemin="0" aria-valuemax="100" aria-valuenow={percent.to_string()}>
+                <div class={classes!("goal-progress-fill", (percent >= 100).then_some("goal-progress-done"))} style={format!("width: {}%", percent)}></div>
+            </div>
+        </li>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct GoalEditorProps {
+    on_add: Callback<(String, i32, String)>,
+}
+
+// Adds a goal. Time goals are entered in minutes.
+#[function_component(GoalEditor)]
+fn goal_editor(props: &GoalEditorProps) -> Html {
+    let goal_type = use_state(|| GOAL_TYPES[0].to_string());
+    let period = use_state(|| GOAL_PERIODS[1].to_string());
+    let target = use_state(|| 300);
+
+    let on_select = |handle: UseStateHandle<String>| {
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                handle.set(select.value());
+            }
+        })
+    };
+    let on_target_input = {
+        let target = target.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(value) = e.target_dyn_into::<HtmlInputElement>().and_then(|input| input.value().parse::<i32>().ok()) {
+                target.set(value);
+            }
+        })
+    };
+    let on_add = {
+        let goal_type = goal_type.clone();
+        let period = period.clone();
+        let target = target.clone();
+        let on_add = props.on_add.clone();
+        Callback::from(move |_: MouseEvent| {
+            if *target > 0 {
+                on_add.emit(((*goal_type).clone(), *target, (*period).clone()));
+            }
+        })
+    };
+
+    html! {
+        <div class="flex flex-wrap items-end gap-4 mt-4">
+            <label class="flex flex-col item_container-text">
+                <span class="mb-1">{ t("goals.type") }</span>
+                <select class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline" onchange={on_select(goal_type.clone())}>
+                    { for GOAL_TYPES.iter().map(|option| html! {
+                        <option value={*option} selected={*option == goal_type.as_str()}>{ t(&format!("goals.type.{}", option)) }</option>
+                    }) }
+                </select>
+            </label>
+            <label class="flex flex-col item_container-text">
+                <span class="mb-1">{ t("goals.target") }</span>
+                <input type="number" min="1" class="search-bar-input border text-sm rounded-lg block w-28 p-2.5" value={target.to_string()} oninput={on_target_input} />
+            </label>
+            <label class="flex flex-col item_container-text">
+                <span class="mb-1">{ t("goals.period") }</span>
+                <select class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline" onchange={on_select(period.clone())}>
+                    { for GOAL_PERIODS.iter().map(|option| html! {
+                        <option value={*option} selected={*option == period.as_str()}>{ t(&format!("goals.period.{}", option)) }</option>
+                    }) }
+                </select>
+            </label>
+            <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_add}>
+                <span class="material-icons icon-space">{"add"}</span>
+                <span>{ t("goals.add") }</span>
+            </button>
+        </div>
+    }
+}
+
+// Goal progress bars and the current streak, with an editor behind a toggle
+#[function_component(GoalsPanel)]
+pub fn goals_panel() -> Html {
+    let (state, _dispatch) = use_store::<AppState>();
+    let (_ui_state, ui_dispatch) = use_store::<UIState>();
+    let goals = use_state(|| None::<ListeningGoalsResponse>);
+    let editing = use_state(|| false);
+    let reload = use_state(|| 0u32);
+
+    let api_key = state.auth_details.as_ref().and_then(|ud| ud.api_key.clone());
+    let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+    let user_id = state.user_details.as_ref().map(|ud| ud.UserID).filter(|_| goals_available(&state));
+
+    {
+        let goals = goals.clone();
+        let api_key = api_key.clone();
+        use_effect_with((server_name.clone(), user_id, *reload), move |(server_name, user_id, _)| {
+            if let (Some(server_name), Some(user_id)) = (server_name.clone(), *user_id) {
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(fetched) = call_get_listening_goals(&server_name, &api_key, user_id).await {
+                        goals.set(Some(fetched));
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_add = {
+        let reload = reload.clone();
+        let ui_dispatch = ui_dispatch.clone();
+        let api_key = api_key.clone();
+        let server_name = server_name.clone();
+        Callback::from(move |(goal_type, target, period): (String, i32, String)| {
+            let (Some(server_name), Some(user_id)) = (server_name.clone(), user_id) else {
+                return;
+            };
+            let reload = reload.clone();
+            let ui_dispatch = ui_dispatch.clone();
+            let api_key = api_key.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match call_create_listening_goal(&server_name, &api_key, user_id, &goal_type, target, &period).await {
+                    Ok(_) => reload.set(*reload + 1),
+                    Err(e) => ui_dispatch.reduce_mut(|state| state.error_message = Some(t_args("goals.save_failed", &[("error", e.to_string().as_str())]))),
+                }
+            });
+        })
+    };
+    let on_delete = {
+        let reload = reload.clone();
+        let ui_dispatch = ui_dispatch.clone();
+        Callback::from(move |goal_id: i32| {
+            let (Some(server_name), Some(user_id)) = (server_name.clone(), user_id) else {
+                return;
+            };
+            let reload = reload.clone();
+            let ui_dispatch = ui_dispatch.clone();
+            let api_key = api_key.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match call_delete_listening_goal(&server_name, &api_key, user_id, goal_id).await {
+                    Ok(()) => reload.set(*reload + 1),
+                    Err(e) => ui_dispatch.reduce_mut(|state| state.error_message = Some(t_args("goals.save_failed", &[("error", e.to_string().as_str())]))),
+                }
+            });
+        })
+    };
+    let toggle_editing = {
+        let editing = editing.clone();
+        Callback::from(move |_: MouseEvent| editing.set(!*editing))
+    };
+
+    if user_id.is_none() {
+        return html! {};
+    }
+    let Some(response) = (*goals).clone() else {
+        return html! { <p class="item_container-text text-sm px-2">{ t("goals.loading") }</p> };
+    };
+    let streak = &response.streak;
+
+    let streak_text = if streak.current > 0 {
+        t_plural("goals.streak_days", streak.current as i64)
+    } else {
+        t("goals.no_streak")
+    };
+
+    html! {
+        <div class="px-2">
+            <div class="flex flex-wrap items-center gap-2 item_container-text mb-3">
+                <span class="material-icons" aria-hidden="true">{"local_fire_department"}</span>
+                <span class="font-semibold">{ streak_text }</span>
+                <span class="text-sm">{ t_plural("goals.longest_streak", streak.longest as i64) }</span>
+                if streak.at_risk() {
+                    <span class="text-sm goal-streak-risk">{ t("goals.streak_at_risk") }</span>
+                }
+                <button class="download-button font-bold py-1 px-3 rounded inline-flex items-center ml-auto" onclick={toggle_editing} aria-expanded={editing.to_string()}>
+                    <span class="material-icons icon-space">{ if *editing { "done" } else { "edit" } }</span>
+                    <span>{ if *editing { t("goals.done_editing") } else { t("goals.edit") } }</span>
+                </button>
+            </div>
+            if response.goals.is_empty() {
+                <p class="item_container-text text-sm">{ t("goals.empty") }</p>
+            } else {
+                <ul>
+                    { for response.goals.iter().map(|goal| html! {
+                        <GoalRow key={goal.GoalID} goal={goal.clone()} on_delete={(*editing).then(|| on_delete.clone())} />
+                    }) }
+                </ul>
+            }
+            if *editing {
+                <GoalEditor on_add={on_add} />
+            }
+        </div>
+    }
+}
@@ -1,32 +1,132 @@
 use yew::{function_component, Html, html};
 use yew::prelude::*;
 use super::app_drawer::App_drawer;
+use std::rc::Rc;
 use super::gen_components::{UseScrollToTop, Search_nav, empty_message, episode_item, on_shownotes_click};
-use crate::requests::pod_req::{self, HistoryDataResponse};
+use super::virtual_list::VirtualList;
+use yew::virtual_dom::Key;
+use super::selection::BulkActionBar;
+use crate::requests::pod_req::{self, HistoryEpisode, HistoryFilter, EPISODE_PAGE_SIZE};
 use yewdux::prelude::*;
-use crate::components::context::{AppState, UIState};
+use crate::components::context::{AppState, UIState, CachedEpisode, EpisodeCache, EpisodeCacheMsg, EpisodeLists, EpisodeListsMsg, use_list_page_state, PodcastCache, PodcastCacheMsg};
 use yew_router::history::BrowserHistory;
 use crate::components::audio::AudioPlayer;
-use crate::components::gen_funcs::{sanitize_html_with_blank_target, truncate_description, parse_date, format_datetime, match_date_format};
+use crate::components::gen_funcs::{sanitize_html_with_blank_target, truncate_description, parse_date, format_day, format_datetime, match_date_format, download_text, csv_field, listened_at, DateFormat};
 use crate::components::audio::on_play_click;
-use crate::components::episodes_layout::AppStateMsg;
+use crate::components::episodes_layout::{AppStateMsg, UIStateMsg};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
 use crate::requests::login_requests::use_check_authentication;
+use crate::components::i18n::{t, t_plural};
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+
+fn user_timezone(user_tz: &Option<String>) -> Tz {
+    user_tz.as_ref().and_then(|tz| tz.parse().ok()).unwrap_or(chrono_tz::UTC)
+}
+
+// Start of the given local day (plus any days after it) as the UTC timestamp the server filters on
+fn day_start_utc(day: &str, tz: Tz, days_after: i64) -> Option<String> {
+    let date = NaiveDate::parse_from_str(day, "%Y-%m-%d").ok()? + Duration::days(days_after);
+    let start = tz.from_local_datetime(&date.and_hms_opt(0, 0, 0)?).earliest()?;
+    Some(start.with_timezone(&Utc).format("%Y-%m-%dT%H:%M:%S").to_string())
+}
+
+fn day_label(day: NaiveDate, today: NaiveDate, date_format: DateFormat) -> String {
+    if day == today {
+        t("history.today")
+    } else if Some(day) == today.pred_opt() {
+        t("history.yesterday")
+    } else {
+        format_day(&day, date_format)
+    }
+}
+
+// Rows of the export, with listen times as RFC 3339 in the user's timezone
+fn export_rows(episodes: &[HistoryEpisode], user_tz: &Option<String>) -> Vec<[String; 7]> {
+    episodes
+        .iter()
+        .map(|episode| {
+            [
+                episode.ListenDate.as_deref().and_then(|listen_date| listened_at(listen_date, user_timezone(user_tz))).map(|datetime| datetime.to_rfc3339()).unwrap_or_default(),
+                episode.PodcastName.clone(),
+                episode.EpisodeTitle.clone(),
+                episode.EpisodeID.to_string(),
+                episode.EpisodeDuration.to_string(),
+                episode.ListenDuration.unwrap_or(0).to_string(),
+                episode.EpisodeURL.clone(),
+            ]
+        })
+        .collect()
+}
+
+const EXPORT_COLUMNS: [&str; 7] = ["listened_at", "podcast", "episode", "episode_id", "duration_seconds", "listened_seconds", "url"];
+const NUMERIC_COLUMNS: [&str; 3] = ["episode_id", "duration_seconds", "listened_seconds"];
+
+fn history_csv(episodes: &[HistoryEpisode], user_tz: &Option<String>) -> String {
+    let mut csv = EXPORT_COLUMNS.join(",");
+    csv.push('\n');
+    for row in export_rows(episodes, user_tz) {
+        csv.push_str(&row.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn history_json(episodes: &[HistoryEpisode], user_tz: &Option<String>) -> String {
+    let rows: Vec<serde_json::Value> = export_rows(episodes, user_tz)
+        .into_iter()
+        .map(|row| {
+            let mut entry = serde_json::Map::new();
+            for (column, value) in EXPORT_COLUMNS.iter().zip(row) {
+                // Ids and durations stay numbers so the file loads cleanly elsewhere
+                let value = match value.parse::<i64>() {
+                    Ok(number) if NUMERIC_COLUMNS.contains(column) => serde_json::Value::from(number),
+                    _ => serde_json::Value::String(value),
+                };
+                entry.insert(column.to_string(), value);
+            }
+            serde_json::Value::Object(entry)
+        })
+        .collect();
+    serde_json::to_string_pretty(&rows).unwrap_or_default()
+}
 // use crate::components::gen_funcs::check_auth;
 
 #[function_component(PodHistory)]
 pub fn history() -> Html {
-    let (state, dispatch) = use_store::<AppState>();
+    let state = use_list_page_state();
+    let dispatch = Dispatch::<AppState>::global();
+    let history_episodes = use_selector(|lists: &EpisodeLists| lists.history.clone());
     let history = BrowserHistory::new();
 
     // check_auth(effect_dispatch);
 
     let error = use_state(|| None);
-    let (post_state, _post_dispatch) = use_store::<AppState>();
     let (audio_state, audio_dispatch) = use_store::<UIState>();
     let dropdown_open = use_state(|| false);
-    let session_dispatch = _post_dispatch.clone();
-    let session_state = post_state.clone();
+    let session_dispatch = dispatch.clone();
+    let session_state = state.clone();
     let loading = use_state(|| true);
+    // Further pages are fetched as the list scrolls to the end of what is loaded
+    let has_more = use_state(|| false);
+    let loading_more = use_state(|| false);
+    // Date inputs hold local days as YYYY-MM-DD, both ends inclusive
+    let filter_from = use_state(String::new);
+    let filter_to = use_state(String::new);
+    let podcast_filter = use_state(|| None::<i32>);
+    let (podcast_cache, _podcast_cache_dispatch) = use_store::<PodcastCache>();
+    let error_message = audio_state.error_message.clone();
+    let info_message = audio_state.info_message.clone();
+
+    let tz = user_timezone(&state.user_tz);
+    let filter = HistoryFilter {
+        podcast_id: *podcast_filter,
+        start: day_start_utc(&filter_from, tz, 0),
+        end: day_start_utc(&filter_to, tz, 1),
+    };
+    let filtered = filter != HistoryFilter::default();
 
     use_effect_with((), move |_| {
         // Check if the page reload action has already occurred to prevent redundant execution
@@ -57,6 +157,26 @@ pub fn history() -> Html {
         || ()
     });
 
+    // Clicking anywhere dismisses the snackbars
+    {
+        let ui_dispatch = audio_dispatch.clone();
+        use_effect(move || {
+            let document = web_sys::window().unwrap().document().unwrap();
+
+            let closure = Closure::wrap(Box::new(move |_event: Event| {
+                ui_dispatch.apply(UIStateMsg::ClearErrorMessage);
+                ui_dispatch.apply(UIStateMsg::ClearInfoMessage);
+            }) as Box<dyn Fn(_)>);
+
+            document.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref()).unwrap();
+
+            move || {
+                document.remove_event_listener_with_callback("click", closure.as_ref().unchecked_ref()).unwrap();
+                closure.forget();
+            }
+        });
+    }
+
     let _toggle_dropdown = {
         let dropdown_open = dropdown_open.clone();
         Callback::from(move |_: MouseEvent| {
@@ -70,27 +190,28 @@ pub fn history() -> Html {
     {
         // let episodes = episodes.clone();
         let error = error.clone();
-        let api_key = post_state.auth_details.as_ref().map(|ud| ud.api_key.clone());
-        let user_id = post_state.user_details.as_ref().map(|ud| ud.UserID.clone());
-        let server_name = post_state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+        let api_key = state.auth_details.as_ref().map(|ud| ud.api_key.clone());
+        let user_id = state.user_details.as_ref().map(|ud| ud.UserID.clone());
+        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
 
-        let effect_dispatch = dispatch.clone();
+        let has_more = has_more.clone();
 
         // fetch_episodes(api_key.flatten(), user_id, server_name, dispatch, error, pod_req::call_get_recent_eps);
 
+        // Changing a filter starts over from the first page
         use_effect_with(
-            (api_key.clone(), user_id.clone(), server_name.clone()),
-            move |_| {
+            (api_key.clone(), user_id.clone(), server_name.clone(), filter.clone()),
+            move |(_, _, _, filter)| {
                 let error_clone = error.clone();
                 if let (Some(api_key), Some(user_id), Some(server_name)) = (api_key.clone(), user_id.clone(), server_name.clone()) {
-                    let dispatch = effect_dispatch.clone();
+                    let filter = filter.clone();
     
                     wasm_bindgen_futures::spawn_local(async move {
-                        match pod_req::call_get_user_history(&server_name, &api_key, &user_id).await {
+                        match pod_req::call_get_user_history_page(&server_name, &api_key, &user_id, 0, EPISODE_PAGE_SIZE, &filter).await {
                             Ok(fetched_episodes) => {
-                                dispatch.reduce_mut(move |state| {
-                                    state.episode_history = Some(HistoryDataResponse { data: fetched_episodes });
-                                });
+                                has_more.set(fetched_episodes.len() == EPISODE_PAGE_SIZE);
+                                Dispatch::<EpisodeCache>::global().apply(EpisodeCacheMsg::Ingest(fetched_episodes.iter().map(CachedEpisode::from).collect()));
+                                Dispatch::<EpisodeLists>::global().apply(EpisodeListsMsg::History(fetched_episodes));
                                 loading_ep.set(false);
                             },
                             Err(e) => {
@@ -105,6 +226,148 @@ pub fn history() -> Html {
         );
     }
 
+    let on_end_reached = {
+        let has_more = has_more.clone();
+        let loading_more = loading_more.clone();
+        let api_key = state.auth_details.as_ref().and_then(|ud| ud.api_key.clone());
+        let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+        let loaded = history_episodes.as_ref().as_ref().map_or(0, |history| history.len());
+        let filter = filter.clone();
+        Callback::from(move |_: ()| {
+            let (true, false, Some(user_id), Some(server_name)) = (*has_more, *loading_more, user_id, server_name.clone()) else {
+                return;
+            };
+            loading_more.set(true);
+            let api_key = api_key.clone();
+            let has_more = has_more.clone();
+            let loading_more = loading_more.clone();
+            let filter = filter.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match pod_req::call_get_user_history_page(&server_name, &api_key, &user_id, loaded, EPISODE_PAGE_SIZE, &filter).await {
+                    Ok(page) => {
+                        has_more.set(page.len() == EPISODE_PAGE_SIZE);
+                        Dispatch::<EpisodeCache>::global().apply(EpisodeCacheMsg::Ingest(page.iter().map(CachedEpisode::from).collect()));
+                        Dispatch::<EpisodeLists>::global().apply(EpisodeListsMsg::MoreHistory(page));
+                    }
+                    Err(e) => {
+                        has_more.set(false);
+                        web_sys::console::log_1(&format!("Unable to load more history: {:?}", e).into());
+                    }
+                }
+                loading_more.set(false);
+            });
+        })
+    };
+
+    // The podcast filter lists every subscription, not just those in the loaded pages
+    {
+        let api_key = state.auth_details.as_ref().and_then(|ud| ud.api_key.clone());
+        let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+        let has_podcasts = !podcast_cache.podcasts.is_empty();
+        use_effect_with((server_name, user_id, has_podcasts), move |(server_name, user_id, has_podcasts)| {
+            if let (Some(server_name), Some(user_id), false) = (server_name.clone(), *user_id, *has_podcasts) {
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(podcasts) = pod_req::call_get_podcasts(&server_name, &api_key, &user_id).await {
+                        Dispatch::<PodcastCache>::global().apply(PodcastCacheMsg::Sync(podcasts));
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_from_change = {
+        let filter_from = filter_from.clone();
+        Callback::from(move |e: Event| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                filter_from.set(input.value());
+            }
+        })
+    };
+
+    let on_to_change = {
+        let filter_to = filter_to.clone();
+        Callback::from(move |e: Event| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                filter_to.set(input.value());
+            }
+        })
+    };
+
+    let on_podcast_change = {
+        let podcast_filter = podcast_filter.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                podcast_filter.set(select.value().parse::<i32>().ok());
+            }
+        })
+    };
+
+    let on_reset_filters = {
+        let filter_from = filter_from.clone();
+        let filter_to = filter_to.clone();
+        let podcast_filter = podcast_filter.clone();
+        Callback::from(move |_: MouseEvent| {
+            filter_from.set(String::new());
+            filter_to.set(String::new());
+            podcast_filter.set(None);
+        })
+    };
+
+    // Exports what the list currently shows, filters included
+    let on_export = {
+        let episodes = (*history_episodes).clone().unwrap_or_default();
+        let user_tz = state.user_tz.clone();
+        Callback::from(move |json: bool| {
+            let result = if json {
+                download_text("pinepods-history.json", "application/json", &history_json(&episodes, &user_tz))
+            } else {
+                download_text("pinepods-history.csv", "text/csv", &history_csv(&episodes, &user_tz))
+            };
+            if result.is_err() {
+                Dispatch::<UIState>::global().reduce_mut(|state| state.error_message = Some(t("history.export_error")));
+            }
+        })
+    };
+
+    let on_clear = {
+        let audio_dispatch = audio_dispatch.clone();
+        let api_key = state.auth_details.as_ref().and_then(|ud| ud.api_key.clone());
+        let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+        let has_more = has_more.clone();
+        Callback::from(move |_: MouseEvent| {
+            let confirmed = web_sys::window()
+                .and_then(|window| window.confirm_with_message(&t("history.confirm_clear")).ok())
+                .unwrap_or(false);
+            let (true, Some(user_id), Some(server_name)) = (confirmed, user_id, server_name.clone()) else {
+                return;
+            };
+            let api_key = api_key.clone();
+            let audio_dispatch = audio_dispatch.clone();
+            let has_more = has_more.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match pod_req::call_clear_history(&server_name, &api_key, user_id).await {
+                    Ok(removed) => {
+                        // Progress lives with the history, so every episode we know of is unplayed again
+                        let progress: Vec<(i32, i32)> = Dispatch::<EpisodeCache>::global().get().episodes.keys().map(|episode_id| (*episode_id, 0)).collect();
+                        Dispatch::<EpisodeCache>::global().apply(EpisodeCacheMsg::SetProgressBatch(progress));
+                        Dispatch::<EpisodeLists>::global().apply(EpisodeListsMsg::History(Vec::new()));
+                        has_more.set(false);
+                        audio_dispatch.reduce_mut(|state| state.info_message = Some(t_plural("history.cleared", removed)));
+                    }
+                    Err(e) => audio_dispatch.reduce_mut(|state| state.error_message = Some(format!("{}: {}", t("history.clear_error"), e))),
+                }
+            });
+        })
+    };
+
+    let mut podcast_options: Vec<(i32, String)> = podcast_cache.podcasts.values().map(|podcast| (podcast.PodcastID, podcast.PodcastName.clone())).collect();
+    podcast_options.sort_by_key(|(_, name)| name.to_lowercase());
+    let has_entries = history_episodes.as_ref().as_ref().map_or(false, |history| !history.is_empty());
+
     html! {
         <>
         <div class="main-container">
@@ -128,6 +391,45 @@ pub fn history() -> Html {
                         html! {
                             <div>
                             <h1 class="text-2xl item_container-text font-bold text-center mb-6">{"History"}</h1>
+                            <div class="flex flex-wrap items-end gap-2 mb-4">
+                                <label class="flex flex-col item_container-text text-sm">
+                                    { t("history.from") }
+                                    <input type="date" value={(*filter_from).clone()} max={(*filter_to).clone()} onchange={on_from_change} class="search-bar-input border text-sm rounded-lg p-2" />
+                                </label>
+                                <label class="flex flex-col item_container-text text-sm">
+                                    { t("history.to") }
+                                    <input type="date" value={(*filter_to).clone()} min={(*filter_from).clone()} onchange={on_to_change} class="search-bar-input border text-sm rounded-lg p-2" />
+                                </label>
+                                <label class="flex flex-col item_container-text text-sm">
+                                    { t("history.podcast") }
+                                    <select onchange={on_podcast_change} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                                        <option value="" selected={podcast_filter.is_none()}>{ t("history.all_podcasts") }</option>
+                                        { for podcast_options.iter().map(|(podcast_id, name)| html! {
+                                            <option value={podcast_id.to_string()} selected={*podcast_filter == Some(*podcast_id)}>{ name }</option>
+                                        }) }
+                                    </select>
+                                </label>
+                                if filtered {
+                                    <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_reset_filters}>
+                                        <span class="material-icons icon-space">{"filter_alt_off"}</span>
+                                        <span>{ t("history.reset_filters") }</span>
+                                    </button>
+                                }
+                            </div>
+                            <div class="flex flex-wrap items-center gap-2 mb-4">
+                                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_export.reform(|_: MouseEvent| false)} disabled={!has_entries}>
+                                    <span class="material-icons icon-space">{"file_download"}</span>
+                                    <span>{ t("history.export_csv") }</span>
+                                </button>
+                                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_export.reform(|_: MouseEvent| true)} disabled={!has_entries}>
+                                    <span class="material-icons icon-space">{"file_download"}</span>
+                                    <span>{ t("history.export_json") }</span>
+                                </button>
+                                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_clear}>
+                                    <span class="material-icons icon-space">{"delete_sweep"}</span>
+                                    <span>{ t("history.clear") }</span>
+                                </button>
+                            </div>
                             </div>
                         }
                     }
@@ -136,18 +438,39 @@ pub fn history() -> Html {
                         {
                             
 
-                            if let Some(history_eps) = state.episode_history.clone() {
-                                if history_eps.data.is_empty() {
+                            if let Some(history_eps) = (*history_episodes).clone() {
+                                if history_eps.is_empty() && filtered {
                                     empty_message(
-                                        "No Episode History Found",
-                                        "This one is pretty straightforward. You should get listening! Podcasts you listen to will show up here!."
+                                        &t("history.no_matches_title"),
+                                        &t("history.no_matches_body")
+                                    )
+                                } else if history_eps.is_empty() {
+                                    empty_message(
+                                        &t("empty.history.title"),
+                                        &t("empty.history.body")
                                     )
                                 } else {
-                                    
-                                    history_eps.data.into_iter().map(|episode| {
-                                        let api_key = post_state.auth_details.as_ref().map(|ud| ud.api_key.clone());
-                                        let user_id = post_state.user_details.as_ref().map(|ud| ud.UserID.clone());
-                                        let server_name = post_state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+                                    let episodes = history_eps;
+                                    let item_count = episodes.len();
+                                    // Entries arrive newest first, so a day heading goes above the first entry of each day
+                                    let days: Rc<Vec<Option<NaiveDate>>> = Rc::new(episodes.iter().map(|episode| episode.ListenDate.as_deref().and_then(|listen_date| listened_at(listen_date, tz)).map(|datetime| datetime.date_naive())).collect());
+                                    let today = Utc::now().with_timezone(&tz).date_naive();
+                                    let episode_ids: Vec<i32> = episodes.iter().map(|episode| episode.EpisodeID).collect();
+                                    let item_keys: Vec<Key> = episode_ids.iter().map(|episode_id| Key::from(*episode_id)).collect();
+                                    let render_item = {
+                                        let state = state.clone();
+                                        let dispatch = dispatch.clone();
+                                        let history = history.clone();
+                                        let audio_state = audio_state.clone();
+                                        let audio_dispatch = audio_dispatch.clone();
+                                        Callback::from(move |index: usize| {
+                                        let episode = episodes[index].clone();
+                                        let day_heading = days[index]
+                                            .filter(|day| index == 0 || days[index - 1] != Some(*day))
+                                            .map(|day| day_label(day, today, match_date_format(state.date_format.as_deref())));
+                                        let api_key = state.auth_details.as_ref().map(|ud| ud.api_key.clone());
+                                        let user_id = state.user_details.as_ref().map(|ud| ud.UserID.clone());
+                                        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
                                         let history_clone = history.clone();
                                         let id_string = &episode.EpisodeID.to_string();
                 
@@ -235,18 +558,36 @@ pub fn history() -> Html {
                                             episode_duration_clone,
                                             episode_listened_clone,
                                             "history",
-                                            Callback::from(|_| {}), 
-                                            false,
                                         );
 
-                                        item
-                                    }).collect::<Html>()
+                                        html! {
+                                            <>
+                                                if let Some(day_heading) = day_heading {
+                                                    <h2 class="item_container-text text-lg font-bold mt-4 mb-2">{ day_heading }</h2>
+                                                }
+                                                { item }
+                                            </>
+                                        }
+                                        })
+                                    };
+                                    html! {
+                                        <>
+                                            <BulkActionBar episode_ids={episode_ids} />
+                                            <VirtualList
+                                                item_count={item_count}
+                                                render_item={render_item}
+                                                item_keys={item_keys}
+                                                on_end_reached={on_end_reached.clone()}
+                                                loading_more={*loading_more}
+                                            />
+                                        </>
+                                    }
                                 }
 
                             } else {
                                 empty_message(
-                                    "No Episode History Found",
-                                    "This one is pretty straightforward. You should get listening! Podcasts you listen to will show up here!."
+                                    &t("empty.history.title"),
+                                    &t("empty.history.body")
                                 )
                             }
                         }
@@ -259,6 +600,12 @@ pub fn history() -> Html {
                     html! {}
                 }
             }
+            if let Some(error) = error_message {
+                <div class="error-snackbar">{ error }</div>
+            }
+            if let Some(info) = info_message {
+                <div class="info-snackbar">{ info }</div>
+            }
         </div>
         <App_drawer />
         </>
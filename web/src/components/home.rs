@@ -5,10 +5,9 @@ use super::gen_components::{Search_nav, empty_message, episode_item, on_shownote
 use crate::requests::pod_req;
 use yewdux::prelude::*;
 use yew_router::history::BrowserHistory;
-use crate::components::context::{AppState, UIState, ExpandedDescriptions};
+use crate::components::context::{AppState, UIState, ExpandedDescriptions, CachedEpisode, EpisodeCache, EpisodeCacheMsg, EpisodeLists, EpisodeListsMsg, use_list_page_state};
 use crate::components::audio::AudioPlayer;
-use crate::components::gen_funcs::{sanitize_html_with_blank_target, truncate_description, format_datetime, parse_date, DateFormat};
-use crate::requests::pod_req::RecentEps;
+use crate::components::gen_funcs::{sanitize_html_with_blank_target, truncate_description, format_datetime, format_relative_time, parse_date, DateFormat};
 use crate::requests::pod_req::Episode as EpisodeData; 
 use crate::components::audio::on_play_click;
 use crate::components::desc_impl::AppStateMsg;
@@ -20,12 +19,33 @@ use wasm_bindgen::JsCast;
 use crate::requests::login_requests::use_check_authentication;
 
 use wasm_bindgen::prelude::*;
+use crate::components::i18n::{t, t_plural};
+use crate::components::folders::{FolderFilter, use_folders};
+use crate::components::virtual_list::VirtualList;
+use yew::virtual_dom::Key;
+use crate::components::selection::BulkActionBar;
+use std::rc::Rc;
+use crate::components::context::EpisodeList;
+use crate::components::gen_funcs::ListenState;
+use crate::components::home_rails::{
+    is_new_since, load_last_visit, rail, save_last_visit, HomeLayout, HomeLayoutEditor, HomeRail, HomeSection, RAIL_LENGTH,
+};
+use crate::components::goals::{goals_available, GoalsPanel};
+use crate::components::persistence::user_key;
+use crate::requests::pod_req::HistoryFilter;
 
+// How much recent history is scanned for episodes still being listened to
+const HISTORY_SCAN: usize = 50;
 
 #[function_component(Home)]
 pub fn home() -> Html {
-    let (state, dispatch) = use_store::<AppState>();
+    let state = use_list_page_state();
+    let dispatch = Dispatch::<AppState>::global();
+    let recent = use_selector(|lists: &EpisodeLists| lists.recent.clone());
+    let queued = use_selector(|lists: &EpisodeLists| lists.queue.clone());
     let effect_dispatch = dispatch.clone();
+    let folder_state = use_folders();
+    let folder_filter = folder_state.active_podcast_ids();
 
     let session_dispatch = effect_dispatch.clone();
     let session_state = state.clone();
@@ -61,11 +81,11 @@ pub fn home() -> Html {
     
 
     let error = use_state(|| None);
-    let (post_state, _post_dispatch) = use_store::<AppState>();
     let (audio_state, audio_dispatch) = use_store::<UIState>();
     let error_message = audio_state.error_message.clone();
     let info_message = audio_state.info_message.clone();
     let loading = use_state(|| true);
+    let (cache, _cache_dispatch) = use_store::<EpisodeCache>();
     
 
     {
@@ -95,11 +115,9 @@ pub fn home() -> Html {
     {
         // let episodes = episodes.clone();
         let error = error.clone();
-        let api_key = post_state.auth_details.as_ref().map(|ud| ud.api_key.clone());
-        let user_id = post_state.user_details.as_ref().map(|ud| ud.UserID.clone());
-        let server_name = post_state.auth_details.as_ref().map(|ud| ud.server_name.clone());
-
-        let effect_dispatch = dispatch.clone();
+        let api_key = state.auth_details.as_ref().map(|ud| ud.api_key.clone());
+        let user_id = state.user_details.as_ref().map(|ud| ud.UserID.clone());
+        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
 
         // fetch_episodes(api_key.flatten(), user_id, server_name, dispatch, error, pod_req::call_get_recent_eps);
 
@@ -108,14 +126,11 @@ pub fn home() -> Html {
             move |_| {
                 let error_clone = error.clone();
                 if let (Some(api_key), Some(user_id), Some(server_name)) = (api_key.clone(), user_id.clone(), server_name.clone()) {
-                    let dispatch = effect_dispatch.clone();
-
                     wasm_bindgen_futures::spawn_local(async move {
                         match pod_req::call_get_recent_eps(&server_name, &api_key, &user_id).await {
                             Ok(fetched_episodes) => {
-                                dispatch.reduce_mut(move |state| {
-                                    state.server_feed_results = Some(RecentEps { episodes: Some(fetched_episodes) });
-                                });
+                                Dispatch::<EpisodeCache>::global().apply(EpisodeCacheMsg::Ingest(fetched_episodes.iter().map(CachedEpisode::from).collect()));
+                                Dispatch::<EpisodeLists>::global().apply(EpisodeListsMsg::Recent(fetched_episodes));
                                 loading_ep.set(false);
                             },
                             Err(e) => {
@@ -130,57 +145,232 @@ pub fn home() -> Html {
         );
     }
 
+    // Layout and last visit are per user. The visit is recorded when leaving Home,
+    // so a reload still shows what was new when the page was first opened.
+    let view_key = (*use_selector(|state: &AppState| user_key(state))).clone();
+    let show_goals = *use_selector(|state: &AppState| goals_available(state));
+    let layout = use_state(HomeLayout::default);
+    let last_visit = use_state(|| None::<String>);
+    let customizing = use_state(|| false);
+    {
+        let layout = layout.clone();
+        let last_visit = last_visit.clone();
+        use_effect_with(view_key.clone(), move |view_key| {
+            let view_key = view_key.clone();
+            if let Some(key) = &view_key {
+                layout.set(HomeLayout::load(key));
+                last_visit.set(load_last_visit(key));
+            }
+            move || {
+                if let Some(key) = &view_key {
+                    save_last_visit(key);
+                }
+            }
+        });
+    }
+
+    let update_layout = {
+        let layout = layout.clone();
+        let view_key = view_key.clone();
+        Callback::from(move |new_layout: HomeLayout| {
+            if let Some(key) = &view_key {
+                new_layout.save(key);
+            }
+            layout.set(new_layout);
+        })
+    };
+    let toggle_section = {
+        let layout = layout.clone();
+        let update_layout = update_layout.clone();
+        Callback::from(move |section: HomeSection| update_layout.emit(layout.toggle_collapsed(section)))
+    };
+    let toggle_customizing = {
+        let customizing = customizing.clone();
+        Callback::from(move |_: MouseEvent| customizing.set(!*customizing))
+    };
+
+    // Recently listened episodes, of which the rail keeps the ones not finished yet
+    let history_ids = use_state(Vec::<i32>::new);
+    {
+        let history_ids = history_ids.clone();
+        let api_key = state.auth_details.as_ref().map(|ud| ud.api_key.clone());
+        let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+        use_effect_with((api_key, user_id, server_name), move |(api_key, user_id, server_name)| {
+            if let (Some(api_key), Some(user_id), Some(server_name)) = (api_key.clone(), *user_id, server_name.clone()) {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let cache_dispatch = Dispatch::<EpisodeCache>::global();
+                    if let Ok(episodes) = pod_req::call_get_user_history_page(&server_name, &api_key, &user_id, 0, HISTORY_SCAN, &HistoryFilter::default()).await {
+                        cache_dispatch.apply(EpisodeCacheMsg::Ingest(episodes.iter().map(CachedEpisode::from).collect()));
+                        history_ids.set(episodes.iter().map(|episode| episode.EpisodeID).collect());
+                    }
+                    if let Ok(episodes) = pod_req::call_get_queued_episodes(&server_name, &api_key, &user_id).await {
+                        cache_dispatch.apply(EpisodeCacheMsg::SyncList(EpisodeList::Queue, episodes.iter().map(CachedEpisode::from).collect()));
+                        Dispatch::<EpisodeLists>::global().apply(EpisodeListsMsg::Queue(episodes));
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let in_folder = |episode: &CachedEpisode| match (&folder_filter, episode.podcast_id) {
+        (Some(folder_ids), Some(podcast_id)) => folder_ids.contains(&podcast_id),
+        _ => true,
+    };
+
+    let continue_listening: Vec<Rc<CachedEpisode>> = history_ids
+        .iter()
+        .filter_map(|episode_id| cache.episodes.get(episode_id).cloned())
+        .filter(|episode| episode.listen_state() == ListenState::InProgress)
+        .filter(|episode| in_folder(episode))
+        .take(RAIL_LENGTH)
+        .collect();
+
+    let up_next: Vec<Rc<CachedEpisode>> = {
+        let removed = cache.removed_from(EpisodeList::Queue);
+        queued
+            .as_ref()
+            .as_ref()
+            .map(|queued| {
+                queued
+                    .iter()
+                    .filter(|episode| !removed.contains(&episode.EpisodeID))
+                    .map(|episode| cache.episodes.get(&episode.EpisodeID).cloned().unwrap_or_else(|| Rc::new(CachedEpisode::from(episode))))
+                    .filter(|episode| in_folder(episode))
+                    .take(RAIL_LENGTH)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    // New episodes grouped by show, in the order the newest of each arrived
+    let new_since_visit: Vec<(String, Vec<Rc<CachedEpisode>>)> = {
+        let mut groups: Vec<(String, Vec<Rc<CachedEpisode>>)> = Vec::new();
+        if let (Some(last_visit), Some(episodes)) = (last_visit.as_deref(), recent.as_ref().as_ref()) {
+            for episode in episodes.iter().filter(|episode| is_new_since(&episode.EpisodePubDate, last_visit)) {
+                let cached = cache.episodes.get(&episode.EpisodeID).cloned().unwrap_or_else(|| Rc::new(CachedEpisode::from(episode)));
+                if !in_folder(&cached) {
+                    continue;
+                }
+                match groups.iter_mut().find(|(podcast, _)| *podcast == episode.PodcastName) {
+                    Some((_, group)) => group.push(cached),
+                    None => groups.push((episode.PodcastName.clone(), vec![cached])),
+                }
+            }
+        }
+        groups
+    };
+
+    let recent_section = if *loading {
+        html! {
+            <div class="loading-animation">
+                <div class="frame1"></div>
+                <div class="frame2"></div>
+                <div class="frame3"></div>
+                <div class="frame4"></div>
+                <div class="frame5"></div>
+                <div class="frame6"></div>
+            </div>
+        }
+    } else {
+        if let Some(recent_eps) = recent.as_ref().as_ref() {
+            let mut episodes = recent_eps.to_vec();
+            // Episodes from servers that don't send a podcast id stay visible
+            if let Some(folder_ids) = &folder_filter {
+                episodes.retain(|episode| episode.PodcastID.map_or(true, |id| folder_ids.contains(&id)));
+            }
+
+            if episodes.is_empty() {
+                // Render "No Recent Episodes Found" if episodes list is empty
+                empty_message(
+                    &t("empty.home.title"),
+                    &t("empty.home.body")
+                )
+            } else {
+                let episodes = Rc::new(episodes);
+                let item_count = episodes.len();
+                let episode_ids: Vec<i32> = episodes.iter().map(|episode| episode.EpisodeID).collect();
+                let item_keys: Vec<Key> = episode_ids.iter().map(|episode_id| Key::from(*episode_id)).collect();
+                let render_item = Callback::from(move |index: usize| {
+                    html! {
+                        <Episode
+                            episode={episodes[index].clone()}
+                        />
+                    }
+                });
+                html! {
+                    <>
+                        <BulkActionBar episode_ids={episode_ids} />
+                        <VirtualList item_count={item_count} render_item={render_item} item_keys={item_keys} />
+                    </>
+                }
+            }
+        } else {
+            empty_message(
+                &t("empty.home.title"),
+                &t("empty.home.body")
+            )
+        }
+    };
+
+    let render_section = |section: HomeSection| {
+        let collapsed = layout.collapsed.contains(&section);
+        let (summary, content) = match section {
+            HomeSection::Goals => (None, html! { <GoalsPanel /> }),
+            HomeSection::ContinueListening => (
+                Some(t_plural("home.episode_count", continue_listening.len() as i64)),
+                rail(&continue_listening, &t("home.continue_listening_empty")),
+            ),
+            HomeSection::UpNext => (
+                Some(t_plural("home.episode_count", up_next.len() as i64)),
+                rail(&up_next, &t("home.up_next_empty")),
+            ),
+            HomeSection::NewSinceLastVisit => {
+                let count: usize = new_since_visit.iter().map(|(_, episodes)| episodes.len()).sum();
+                let content = if last_visit.is_none() {
+                    html! { <p class="item_container-text text-sm px-2">{ t("home.new_first_visit") }</p> }
+                } else if new_since_visit.is_empty() {
+                    html! { <p class="item_container-text text-sm px-2">{ t("home.new_empty") }</p> }
+                } else {
+                    html! {
+                        <>
+                        { for new_since_visit.iter().map(|(podcast, episodes)| html! {
+                            <div class="mb-4">
+                                <h3 class="item_container-text font-semibold mb-2 px-2">{ podcast }</h3>
+                                { rail(episodes, "") }
+                            </div>
+                        }) }
+                        </>
+                    }
+                };
+                (Some(t_plural("home.episode_count", count as i64)), content)
+            }
+            HomeSection::Recent => (None, recent_section.clone()),
+        };
+        html! {
+            <HomeRail key={format!("{:?}", section)} section={section} collapsed={collapsed} on_toggle={toggle_section.clone()} summary={summary}>
+                { content }
+            </HomeRail>
+        }
+    };
+
     html! {
         <>
         <div class="main-container">
             <Search_nav />
             <UseScrollToTop />
-            {
-                if *loading { // If loading is true, display the loading animation
-                    html! {
-                        <div class="loading-animation">
-                            <div class="frame1"></div>
-                            <div class="frame2"></div>
-                            <div class="frame3"></div>
-                            <div class="frame4"></div>
-                            <div class="frame5"></div>
-                            <div class="frame6"></div>
-                        </div>
-                    }
-                } else {
-                    if let Some(recent_eps) = state.server_feed_results.clone() {
-                        let int_recent_eps = recent_eps.clone();
-                        if let Some(episodes) = int_recent_eps.episodes {
-                    
-                            if episodes.is_empty() {
-                                // Render "No Recent Episodes Found" if episodes list is empty
-                                empty_message(
-                                    "No Recent Episodes Found",
-                                    "You can add new podcasts by using the search bar above. Search for your favorite podcast and click the plus button to add it."
-                                )
-                            } else {
-                                episodes.into_iter().map(|episode| {
-                                    html! {
-                                        <Episode
-                                            episode={episode.clone()}
-                                        />
-                                    }
-                                }).collect::<Html>()
-                            }
-                        } else {
-                            empty_message(
-                                "No Recent Episodes Found",
-                                "You can add new podcasts by using the search bar above. Search for your favorite podcast and click the plus button to add it."
-                            )
-                        }
-                    } else {
-                        empty_message(
-                            "No Recent Episodes Found",
-                            "You can add new podcasts by using the search bar above. Search for your favorite podcast and click the plus button to add it."
-                        )
-                    }
-                }
+            <div class="flex justify-end items-center gap-2 px-4 mb-2">
+                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={toggle_customizing} aria-expanded={customizing.to_string()}>
+                    <span class="material-icons icon-space">{"tune"}</span>
+                    <span>{ t("home.customize") }</span>
+                </button>
+                <FolderFilter />
+            </div>
+            if *customizing {
+                <HomeLayoutEditor layout={(*layout).clone()} on_change={update_layout.clone()} />
             }
+            { for layout.visible().filter(|section| *section != HomeSection::Goals || show_goals).map(render_section) }
         {
             if let Some(audio_props) = &audio_state.currently_playing {
                 html! { <AudioPlayer src={audio_props.src.clone()} title={audio_props.title.clone()} artwork_url={audio_props.artwork_url.clone()} duration={audio_props.duration.clone()} episode_id={audio_props.episode_id.clone()} duration_sec={audio_props.duration_sec.clone()} start_pos_sec={audio_props.start_pos_sec.clone()} /> }
@@ -210,7 +400,8 @@ pub struct EpisodeProps {
 
 #[function_component(Episode)]
 pub fn episode(props: &EpisodeProps) -> Html {
-    let (state, dispatch) = use_store::<AppState>();
+    let state = use_list_page_state();
+    let dispatch = Dispatch::<AppState>::global();
     // let (post_state, _post_dispatch) = use_store::<AppState>();
     let (audio_state, audio_dispatch) = use_store::<UIState>();
     let (desc_state, desc_dispatch) = use_store::<ExpandedDescriptions>();
@@ -314,7 +505,7 @@ pub fn episode(props: &EpisodeProps) -> Html {
     
     let datetime = parse_date(&props.episode.EpisodePubDate, &state.user_tz);
     // let datetime = parse_date(&episode.EpisodePubDate, &state.user_tz, &state.date_format);
-    let format_release = format!("{}", format_datetime(&datetime, &state.hour_preference, date_format));
+    let format_release = format!("{} ({})", format_datetime(&datetime, &state.hour_preference, date_format), format_relative_time(&datetime));
     let item = episode_item(
         Box::new(props.episode.clone()),
         sanitized_description.clone(),
@@ -326,8 +517,6 @@ pub fn episode(props: &EpisodeProps) -> Html {
         episode_duration_clone,
         episode_listened_clone,
         "home",
-        Callback::from(|_| {}), 
-        false,
     );
 
     item
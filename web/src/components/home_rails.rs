@@ -0,0 +1,286 @@
+use std::rc::Rc;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+use yewdux::prelude::*;
+use yew_router::history::BrowserHistory;
+use crate::components::audio::on_play_click;
+use crate::components::context::{AppState, CachedEpisode, UIState, use_list_page_state};
+use crate::components::goals::goals_available;
+use crate::components::gen_components::{on_shownotes_click, EpisodeProgress};
+use crate::components::i18n::{t, t_args};
+use crate::components::persistence::{load_user_preference, save_user_preference};
+
+const HOME_LAYOUT_PREFERENCE: &str = "home_layout";
+const HOME_LAST_VISIT_PREFERENCE: &str = "home_last_visit";
+
+// Most rails show this many episodes before the user has to go to the full page
+pub const RAIL_LENGTH: usize = 12;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum HomeSection {
+    Goals,
+    ContinueListening,
+    UpNext,
+    NewSinceLastVisit,
+    Recent,
+}
+
+impl HomeSection {
+    pub const ALL: [HomeSection; 5] = [
+        HomeSection::Goals,
+        HomeSection::ContinueListening,
+        HomeSection::UpNext,
+        HomeSection::NewSinceLastVisit,
+        HomeSection::Recent,
+    ];
+
+    fn key(&self) -> &'static str {
+        match self {
+            HomeSection::Goals => "goals",
+            HomeSection::ContinueListening => "continue_listening",
+            HomeSection::UpNext => "up_next",
+            HomeSection::NewSinceLastVisit => "new_since_last_visit",
+            HomeSection::Recent => "recent",
+        }
+    }
+
+    pub fn title(&self) -> String {
+        t(&format!("home.section.{}", self.key()))
+    }
+}
+
+// Which Home sections show, in what order, and which are folded away. Remembered per user.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct HomeLayout {
+    pub order: Vec<HomeSection>,
+    pub hidden: Vec<HomeSection>,
+    pub collapsed: Vec<HomeSection>,
+}
+
+impl Default for HomeLayout {
+    fn default() -> Self {
+        HomeLayout {
+            order: HomeSection::ALL.to_vec(),
+            hidden: Vec::new(),
+            collapsed: Vec::new(),
+        }
+    }
+}
+
+impl HomeLayout {
+    // Sections added after the layout was saved take their default place rather than disappearing
+    pub fn load(user_key: &str) -> Self {
+        let mut layout: HomeLayout = load_user_preference(user_key, HOME_LAYOUT_PREFERENCE);
+        layout.order.dedup();
+        for (index, section) in HomeSection::ALL.into_iter().enumerate() {
+            if !layout.order.contains(&section) {
+                layout.order.insert(index.min(layout.order.len()), section);
+            }
+        }
+        layout
+    }
+
+    pub fn save(&self, user_key: &str) {
+        save_user_preference(user_key, HOME_LAYOUT_PREFERENCE, self);
+    }
+
+    pub fn visible(&self) -> impl Iterator<Item = HomeSection> + '_ {
+        self.order.iter().copied().filter(|section| !self.hidden.contains(section))
+    }
+
+    fn toggle(list: &mut Vec<HomeSection>, section: HomeSection) {
+        if list.contains(&section) {
+            list.retain(|other| *other != section);
+        } else {
+            list.push(section);
+        }
+    }
+
+    pub fn toggle_collapsed(&self, section: HomeSection) -> Self {
+        let mut layout = self.clone();
+        Self::toggle(&mut layout.collapsed, section);
+        layout
+    }
+
+    fn toggle_hidden(&self, section: HomeSection) -> Self {
+        let mut layout = self.clone();
+        Self::toggle(&mut layout.hidden, section);
+        layout
+    }
+
+    fn moved(&self, index: usize, to: usize) -> Self {
+        let mut layout = self.clone();
+        if index < layout.order.len() && to < layout.order.len() {
+            layout.order.swap(index, to);
+        }
+        layout
+    }
+}
+
+// When Home was last left, as a UTC timestamp in the same format as publish dates
+pub fn load_last_visit(user_key: &str) -> Option<String> {
+    load_user_preference(user_key, HOME_LAST_VISIT_PREFERENCE)
+}
+
+pub fn save_last_visit(user_key: &str) {
+    let now = Utc::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+    save_user_preference(user_key, HOME_LAST_VISIT_PREFERENCE, &Some(now));
+}
+
+#[derive(Properties, PartialEq)]
+pub struct HomeRailProps {
+    pub section: HomeSection,
+    pub collapsed: bool,
+    pub on_toggle: Callback<HomeSection>,
+    // Shown next to the title, e.g. how many episodes the rail holds
+    #[prop_or_default]
+    pub summary: Option<String>,
+    #[prop_or_default]
+    pub children: Html,
+}
+
+// Collapsible titled section of the Home page
+#[function_component(HomeRail)]
+pub fn home_rail(props: &HomeRailProps) -> Html {
+    let section = props.section;
+    let on_toggle = props.on_toggle.reform(move |_: MouseEvent| section);
+    let content_id = format!("home-section-{}", section.key());
+
+    html! {
+        <section class="mb-6" aria-labelledby={format!("{}-title", content_id)}>
+            <button
+                class="flex items-center w-full text-left item_container-text mb-2"
+                onclick={on_toggle}
+                aria-expanded={(!props.collapsed).to_string()}
+                aria-controls={content_id.clone()}
+            >
+                <span class="material-icons" aria-hidden="true">{ if props.collapsed { "expand_more" } else { "expand_less" } }</span>
+                <h2 id={format!("{}-title", content_id)} class="text-xl font-bold ml-1">{ section.title() }</h2>
+                if let Some(summary) = &props.summary {
+                    <span class="text-sm ml-2">{ summary }</span>
+                }
+            </button>
+            if !props.collapsed {
+                <div id={content_id}>{ props.children.clone() }</div>
+            }
+        </section>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct RailCardProps {
+    pub episode: Rc<CachedEpisode>,
+}
+
+// Compact episode card for the horizontal rails
+#[function_component(RailCard)]
+pub fn rail_card(props: &RailCardProps) -> Html {
+    let state = use_list_page_state();
+    let dispatch = Dispatch::<AppState>::global();
+    let (ui_state, ui_dispatch) = use_store::<UIState>();
+    let history = BrowserHistory::new();
+    let episode = &props.episode;
+
+    let on_play = on_play_click(
+        episode.url.clone(),
+        episode.title.clone(),
+        episode.artwork.clone(),
+        episode.duration,
+        episode.episode_id,
+        episode.listen_duration,
+        state.auth_details.as_ref().and_then(|ud| ud.api_key.clone()).unwrap_or_default(),
+        state.user_details.as_ref().map(|ud| ud.UserID).unwrap_or_default(),
+        state.auth_details.as_ref().map(|ud| ud.server_name.clone()).unwrap_or_default(),
+        ui_dispatch,
+        ui_state,
+        None,
+    );
+    let on_shownotes = on_shownotes_click(history, dispatch, episode.episode_id);
+
+    html! {
+        <div class="home-rail-card item-container border-solid border rounded-lg shadow-md p-3 flex flex-col" role="listitem">
+            <img src={episode.artwork.clone()} alt={t_args("home.cover_for", &[("title", episode.title.as_str())])} class="object-cover rounded w-full mb-2 item-container img" loading="lazy" />
+            <p class="item_container-text font-semibold cursor-pointer line-clamp-2" role="link" tabindex="0" onclick={on_shownotes}>{ &episode.title }</p>
+            <p class="item_container-text text-sm truncate mb-2">{ &episode.podcast_name }</p>
+            <div class="mt-auto flex items-center justify-between gap-2">
+                <EpisodeProgress episode_id={episode.episode_id} episode_duration={episode.duration} listen_duration={episode.listen_duration} />
+                <button
+                    class="item-container-button border-solid border selector-button rounded-full flex items-center justify-center w-10 h-10 flex-shrink-0"
+                    onclick={on_play}
+                    aria-label={t_args("home.play", &[("title", episode.title.as_str())])}
+                >
+                    <span class="material-bonus-color material-icons" aria-hidden="true">{"play_arrow"}</span>
+                </button>
+            </div>
+        </div>
+    }
+}
+
+// A horizontally scrolling row of episode cards
+pub fn rail(episodes: &[Rc<CachedEpisode>], empty: &str) -> Html {
+    if episodes.is_empty() {
+        return html! { <p class="item_container-text text-sm px-2">{ empty }</p> };
+    }
+    html! {
+        <div class="home-rail flex gap-4 overflow-x-auto pb-2" role="list">
+            { for episodes.iter().map(|episode| html! { <RailCard key={episode.episode_id} episode={episode.clone()} /> }) }
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct HomeLayoutEditorProps {
+    pub layout: HomeLayout,
+    pub on_change: Callback<HomeLayout>,
+}
+
+// Show, hide and reorder the Home sections
+#[function_component(HomeLayoutEditor)]
+pub fn home_layout_editor(props: &HomeLayoutEditorProps) -> Html {
+    let count = props.layout.order.len();
+    let show_goals = *use_selector(|state: &AppState| goals_available(state));
+
+    html! {
+        <div class="item-container border-solid border rounded-lg p-4 mb-6">
+            <p class="item_container-text font-bold mb-2">{ t("home.customize_title") }</p>
+            <ul class="space-y-2">
+                { for props.layout.order.iter().enumerate().filter(|(_, section)| **section != HomeSection::Goals || show_goals).map(|(index, section)| {
+                    let section = *section;
+                    let layout = props.layout.clone();
+                    let on_toggle = {
+                        let layout = layout.clone();
+                        props.on_change.reform(move |_: Event| layout.toggle_hidden(section))
+                    };
+                    let on_up = {
+                        let layout = layout.clone();
+                        props.on_change.reform(move |_: MouseEvent| layout.moved(index, index.saturating_sub(1)))
+                    };
+                    let on_down = props.on_change.reform(move |_: MouseEvent| layout.moved(index, index + 1));
+                    let title = section.title();
+                    html! {
+                        <li class="flex items-center gap-2 item_container-text">
+                            <label class="flex items-center gap-2 flex-grow">
+                                <input type="checkbox" class="form-checkbox h-4 w-4" checked={!props.layout.hidden.contains(&section)} onchange={on_toggle} />
+                                <span>{ title.clone() }</span>
+                            </label>
+                            <button class="item-container-button selector-button rounded p-1" onclick={on_up} disabled={index == 0} aria-label={t_args("home.move_up", &[("section", title.as_str())])}>
+                                <span class="material-icons" aria-hidden="true">{"arrow_upward"}</span>
+                            </button>
+                            <button class="item-container-button selector-button rounded p-1" onclick={on_down} disabled={index + 1 == count} aria-label={t_args("home.move_down", &[("section", title.as_str())])}>
+                                <span class="material-icons" aria-hidden="true">{"arrow_downward"}</span>
+                            </button>
+                        </li>
+                    }
+                }) }
+            </ul>
+        </div>
+    }
+}
+
+// Publish dates and the last visit share one format, so they compare as strings
+pub fn is_new_since(pub_date: &str, last_visit: &str) -> bool {
+    pub_date.replace(' ', "T").as_str() > last_visit
+}
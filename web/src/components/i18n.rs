@@ -0,0 +1,175 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use web_sys::{console, window};
+use yewdux::prelude::*;
+use crate::components::context::LocaleState;
+use crate::requests::setting_reqs::{call_get_language, call_set_language, SetLanguageRequest};
+
+// Message catalogs live in src/locales/<code>.json as flat key/value maps. Plural forms use
+// `key.one` / `key.other` and placeholders are written as `{name}`. Missing keys fall back to
+// English and then to the key itself so a half translated catalog never breaks the UI.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Locale {
+    En,
+    Es,
+    De,
+    Fr,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 4] = [Locale::En, Locale::Es, Locale::De, Locale::Fr];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Es => "es",
+            Locale::De => "de",
+            Locale::Fr => "fr",
+        }
+    }
+
+    // Accepts bare codes as well as browser tags like "de-AT"
+    pub fn from_code(code: &str) -> Option<Locale> {
+        let prefix = code.split(|c| c == '-' || c == '_').next().unwrap_or("").to_lowercase();
+        Locale::ALL.iter().copied().find(|locale| locale.code() == prefix)
+    }
+
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::Es => "Español",
+            Locale::De => "Deutsch",
+            Locale::Fr => "Français",
+        }
+    }
+
+    fn catalog_source(&self) -> &'static str {
+        match self {
+            Locale::En => include_str!("../locales/en.json"),
+            Locale::Es => include_str!("../locales/es.json"),
+            Locale::De => include_str!("../locales/de.json"),
+            Locale::Fr => include_str!("../locales/fr.json"),
+        }
+    }
+
+    fn plural_category(&self, count: i64) -> &'static str {
+        match self {
+            Locale::Fr => if count == 0 || count == 1 { "one" } else { "other" },
+            _ => if count == 1 { "one" } else { "other" },
+        }
+    }
+}
+
+thread_local! {
+    static ACTIVE_LOCALE: RefCell<Option<Locale>> = RefCell::new(None);
+    static CATALOGS: RefCell<HashMap<Locale, Rc<HashMap<String, String>>>> = RefCell::new(HashMap::new());
+}
+
+// Catalogs are only parsed the first time a locale is actually used
+fn catalog(locale: Locale) -> Rc<HashMap<String, String>> {
+    CATALOGS.with(|catalogs| {
+        catalogs
+            .borrow_mut()
+            .entry(locale)
+            .or_insert_with(|| {
+                Rc::new(serde_json::from_str(locale.catalog_source()).unwrap_or_else(|e| {
+                    console::log_1(&format!("Unable to parse {} catalog: {:?}", locale.code(), e).into());
+                    HashMap::new()
+                }))
+            })
+            .clone()
+    })
+}
+
+fn detect_locale() -> Locale {
+    if let Some(window) = window() {
+        if let Ok(Some(local_storage)) = window.local_storage() {
+            if let Ok(Some(stored)) = local_storage.get_item("selected_language") {
+                if let Some(locale) = Locale::from_code(&stored) {
+                    return locale;
+                }
+            }
+        }
+        if let Some(language) = window.navigator().language() {
+            if let Some(locale) = Locale::from_code(&language) {
+                return locale;
+            }
+        }
+    }
+    Locale::En
+}
+
+pub fn current_locale() -> Locale {
+    ACTIVE_LOCALE.with(|active| *active.borrow_mut().get_or_insert_with(detect_locale))
+}
+
+pub fn set_locale(locale: Locale) {
+    ACTIVE_LOCALE.with(|active| *active.borrow_mut() = Some(locale));
+    if let Some(window) = window() {
+        if let Ok(Some(local_storage)) = window.local_storage() {
+            let _ = local_storage.set_item("selected_language", locale.code());
+        }
+        if let Some(document) = window.document() {
+            if let Some(root) = document.document_element() {
+                let _ = root.set_attribute("lang", locale.code());
+            }
+        }
+    }
+}
+
+// The language follows the user between devices. At login the one saved on the server wins,
+// and a choice made on this device before languages were saved is uploaded instead.
+pub async fn sync_user_locale(server_name: &str, api_key: &str, user_id: i32) {
+    match call_get_language(server_name, api_key, &user_id).await {
+        Ok(Some(code)) => {
+            if let Some(locale) = Locale::from_code(&code) {
+                if locale != current_locale() {
+                    set_locale(locale);
+                    Dispatch::<LocaleState>::global().reduce_mut(|state| state.locale = Some(locale.code().to_string()));
+                }
+            }
+        }
+        Ok(None) => {
+            let stored = window()
+                .and_then(|w| w.local_storage().ok().flatten())
+                .and_then(|storage| storage.get_item("selected_language").ok().flatten());
+            if let Some(code) = stored {
+                let request = SetLanguageRequest { user_id, new_language: code };
+                if let Err(e) = call_set_language(server_name, &Some(api_key.to_string()), &request).await {
+                    console::log_1(&format!("Unable to save language: {:?}", e).into());
+                }
+            }
+        }
+        Err(e) => console::log_1(&format!("Unable to load language: {:?}", e).into()),
+    }
+}
+
+fn lookup(key: &str) -> Option<String> {
+    let locale = current_locale();
+    catalog(locale)
+        .get(key)
+        .cloned()
+        .or_else(|| catalog(Locale::En).get(key).cloned())
+}
+
+pub fn t(key: &str) -> String {
+    lookup(key).unwrap_or_else(|| key.to_string())
+}
+
+pub fn t_args(key: &str, args: &[(&str, &str)]) -> String {
+    let mut message = t(key);
+    for (name, value) in args {
+        message = message.replace(&format!("{{{}}}", name), value);
+    }
+    message
+}
+
+pub fn t_plural(key: &str, count: i64) -> String {
+    let category = current_locale().plural_category(count);
+    let message = lookup(&format!("{}.{}", key, category))
+        .or_else(|| lookup(&format!("{}.other", key)))
+        .unwrap_or_else(|| key.to_string());
+    message.replace("{count}", &count.to_string())
+}
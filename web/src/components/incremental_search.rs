@@ -0,0 +1,182 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use gloo_timers::callback::Timeout;
+use web_sys::{AbortController, AbortSignal, KeyboardEvent};
+use yew::prelude::*;
+use crate::components::i18n::t;
+use crate::components::persistence::{load_user_preference, save_user_preference};
+
+// Search-as-you-type support shared by the podcast discovery bar and the library search:
+// a debounced copy of the query, cancellation of the request the user has typed past,
+// a persisted list of recent searches and a keyboard driven suggestion list.
+
+pub const SEARCH_DEBOUNCE_MS: u32 = 350;
+const MAX_RECENT_SEARCHES: usize = 8;
+
+// Follows `value` once it has stopped changing for `delay_ms`
+#[hook]
+pub fn use_debounced<T>(value: T, delay_ms: u32) -> T
+where
+    T: Clone + PartialEq + 'static,
+{
+    let debounced = use_state(|| value.clone());
+    {
+        let debounced = debounced.clone();
+        use_effect_with(value, move |value| {
+            let value = value.clone();
+            let timeout = Timeout::new(delay_ms, move || debounced.set(value));
+            // Dropping the timeout cancels it, so only the last keystroke gets through
+            move || drop(timeout)
+        });
+    }
+    (*debounced).clone()
+}
+
+// At most one request in flight. Starting a new one aborts the previous fetch, and the
+// generation lets a response that was already on its way be recognised as stale.
+#[derive(Default)]
+pub struct InFlight {
+    controller: RefCell<Option<AbortController>>,
+    generation: Cell<u32>,
+}
+
+impl InFlight {
+    pub fn start(&self) -> (u32, Option<AbortSignal>) {
+        self.cancel();
+        let controller = AbortController::new().ok();
+        let signal = controller.as_ref().map(|controller| controller.signal());
+        *self.controller.borrow_mut() = controller;
+        (self.generation.get(), signal)
+    }
+
+    pub fn cancel(&self) {
+        if let Some(controller) = self.controller.borrow_mut().take() {
+            controller.abort();
+        }
+        self.generation.set(self.generation.get().wrapping_add(1));
+    }
+
+    pub fn is_current(&self, generation: u32) -> bool {
+        self.generation.get() == generation
+    }
+}
+
+#[hook]
+pub fn use_in_flight() -> Rc<InFlight> {
+    let in_flight = use_memo((), |_| InFlight::default());
+    {
+        let in_flight = in_flight.clone();
+        use_effect_with((), move |_| move || in_flight.cancel());
+    }
+    in_flight
+}
+
+// Recent searches are kept per user, one list for each search box
+pub fn load_recent_searches(user_key: &str, list: &str) -> Vec<String> {
+    load_user_preference(user_key, &format!("recent_searches.{}", list))
+}
+
+pub fn remember_search(user_key: &str, list: &str, query: &str) -> Vec<String> {
+    let query = query.trim();
+    let mut recent = load_recent_searches(user_key, list);
+    if !query.is_empty() {
+        recent.retain(|previous| !previous.eq_ignore_ascii_case(query));
+        recent.insert(0, query.to_string());
+        recent.truncate(MAX_RECENT_SEARCHES);
+        save_user_preference(user_key, &format!("recent_searches.{}", list), &recent);
+    }
+    recent
+}
+
+pub fn clear_recent_searches(user_key: &str, list: &str) {
+    save_user_preference(user_key, &format!("recent_searches.{}", list), &Vec::<String>::new());
+}
+
+// Recent searches that start with what has been typed so far, all of them for an empty box
+pub fn matching_recent(recent: &[String], query: &str) -> Vec<String> {
+    let query = query.trim().to_lowercase();
+    recent.iter()
+        .filter(|previous| query.is_empty() || (previous.to_lowercase().starts_with(&query) && previous.to_lowercase() != query))
+        .cloned()
+        .collect()
+}
+
+pub enum SuggestionKey {
+    Move(Option<usize>),
+    Choose(usize),
+    Close,
+}
+
+// Arrow keys move the highlight and wrap around, Enter picks the highlighted suggestion
+// and Escape closes the list. Other keys are left to the input.
+pub fn suggestion_key(e: &KeyboardEvent, highlighted: Option<usize>, count: usize) -> Option<SuggestionKey> {
+    let key = match e.key().as_str() {
+        "ArrowDown" if count > 0 => SuggestionKey::Move(Some(highlighted.map_or(0, |i| (i + 1) % count))),
+        "ArrowUp" if count > 0 => SuggestionKey::Move(Some(highlighted.map_or(count - 1, |i| (i + count - 1) % count))),
+        "Enter" => SuggestionKey::Choose(highlighted.filter(|i| *i < count)?),
+        "Escape" => SuggestionKey::Close,
+        _ => return None,
+    };
+    e.prevent_default();
+    Some(key)
+}
+
+pub fn suggestion_id(list_id: &str, index: usize) -> String {
+    format!("{}-option-{}", list_id, index)
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Suggestion {
+    pub label: String,
+    // Material icon shown before the label
+    pub icon: &'static str,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SuggestionListProps {
+    pub id: String,
+    pub suggestions: Vec<Suggestion>,
+    pub highlighted: Option<usize>,
+    pub on_select: Callback<usize>,
+    // Offered while the list shows recent searches
+    #[prop_or_default]
+    pub on_clear: Option<Callback<()>>,
+}
+
+// The listbox under a search input. The input keeps focus and points at the highlighted
+// option through aria-activedescendant, so screen readers follow the arrow keys.
+#[function_component(SuggestionList)]
+pub fn suggestion_list(props: &SuggestionListProps) -> Html {
+    if props.suggestions.is_empty() {
+        return html! {};
+    }
+    // Picking with the mouse must not blur the input first, or the list closes under the pointer
+    let keep_focus = Callback::from(|e: MouseEvent| e.prevent_default());
+
+    html! {
+        <div class="search-suggestions absolute z-20 w-full rounded-lg shadow" onmousedown={keep_focus}>
+            <ul id={props.id.clone()} role="listbox" class="py-1 text-sm">
+                { for props.suggestions.iter().enumerate().map(|(index, suggestion)| {
+                    let selected = props.highlighted == Some(index);
+                    html! {
+                        <li
+                            id={suggestion_id(&props.id, index)}
+                            role="option"
+                            aria-selected={selected.to_string()}
+                            class={classes!("search-suggestion", "flex", "items-center", selected.then_some("search-suggestion-active"))}
+                            onclick={props.on_select.reform(move |_: MouseEvent| index)}
+                        >
+                            <span class="material-icons icon-space" aria-hidden="true">{ suggestion.icon }</span>
+                            <span class="truncate">{ &suggestion.label }</span>
+                        </li>
+                    }
+                }) }
+            </ul>
+            if let Some(on_clear) = &props.on_clear {
+                <button type="button" class="search-suggestions-clear text-sm w-full text-left" onclick={on_clear.reform(|_: MouseEvent| ())}>
+                    { t("search_suggestions.clear_recent") }
+                </button>
+            }
+        </div>
+    }
+}
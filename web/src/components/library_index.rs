@@ -0,0 +1,887 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use gloo_timers::callback::Timeout;
+use serde::{Deserialize, Serialize};
+use web_sys::window;
+use yew::prelude::*;
+use yewdux::prelude::*;
+use crate::components::context::{AppState, CachedEpisode, EpisodeCache, PodcastCache};
+use crate::components::gen_funcs::ListenState;
+use crate::components::persistence::user_key;
+use crate::requests::pod_req::Podcast;
+
+// A full-text index of the user's library that lives in the client, so the library search
+// ranks results itself and keeps working offline. Subscribed podcasts and every episode the
+// client has cached are indexed. `LibraryIndexer` feeds changes from the caches in as they
+// happen and keeps a compact copy of the documents in local storage, which is re-indexed on
+// the next visit. Feeds don't give the server transcripts yet, so titles and descriptions
+// are the only episode text.
+
+const INDEX_STORAGE_KEY: &str = "librarySearchIndex";
+const SAVE_DELAY_MS: u32 = 2_000;
+// Local storage is small, so only the most recent episodes are kept for offline use
+const MAX_STORED_EPISODES: usize = 4_000;
+const MAX_DESCRIPTION_CHARS: usize = 1_500;
+
+// BM25 parameters
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+// Any word the user is still typing matches as a prefix, for a little less
+const PREFIX_WEIGHT: f64 = 0.5;
+const PHRASE_BOOST: f64 = 1.5;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Field {
+    Title,
+    Podcast,
+    Description,
+}
+
+const FIELDS: [Field; 3] = [Field::Title, Field::Podcast, Field::Description];
+
+impl Field {
+    fn weight(self) -> f64 {
+        match self {
+            Field::Title => 3.0,
+            Field::Podcast => 1.5,
+            Field::Description => 1.0,
+        }
+    }
+
+    fn slot(self) -> usize {
+        match self {
+            Field::Title => 0,
+            Field::Podcast => 1,
+            Field::Description => 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum DocKey {
+    Episode(i32),
+    Podcast(i32),
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct IndexedPodcast {
+    pub podcast_id: i32,
+    pub name: String,
+    pub author: String,
+    pub description: String,
+    pub artwork: String,
+}
+
+impl From<&Podcast> for IndexedPodcast {
+    fn from(podcast: &Podcast) -> Self {
+        IndexedPodcast {
+            podcast_id: podcast.PodcastID,
+            name: podcast.PodcastName.clone(),
+            author: podcast.Author.clone().unwrap_or_default(),
+            description: plain_text(podcast.Description.as_deref().unwrap_or_default()),
+            artwork: podcast.ArtworkURL.clone().unwrap_or_default(),
+        }
+    }
+}
+
+enum Document {
+    Episode(CachedEpisode),
+    Podcast(IndexedPodcast),
+}
+
+impl Document {
+    fn field_text(&self, field: Field) -> &str {
+        match (self, field) {
+            (Document::Episode(episode), Field::Title) => &episode.title,
+            (Document::Episode(episode), Field::Podcast) => &episode.podcast_name,
+            (Document::Episode(episode), Field::Description) => &episode.description,
+            (Document::Podcast(podcast), Field::Title) => &podcast.name,
+            (Document::Podcast(podcast), Field::Podcast) => &podcast.author,
+            (Document::Podcast(podcast), Field::Description) => &podcast.description,
+        }
+    }
+}
+
+struct IndexedDocument {
+    document: Document,
+    lengths: [u32; 3],
+    terms: Vec<String>,
+}
+
+// Where a term occurs in one document: field and word position
+type Occurrences = Vec<(Field, u32)>;
+
+#[derive(Default)]
+struct LibraryIndex {
+    user: Option<String>,
+    documents: HashMap<DocKey, IndexedDocument>,
+    postings: HashMap<String, HashMap<DocKey, Occurrences>>,
+    total_lengths: [u64; 3],
+}
+
+impl LibraryIndex {
+    fn insert(&mut self, key: DocKey, document: Document) {
+        self.remove(key);
+        let mut lengths = [0u32; 3];
+        let mut terms = HashSet::new();
+        for field in FIELDS {
+            let tokens = tokenize(document.field_text(field));
+            lengths[field.slot()] = tokens.len() as u32;
+            self.total_lengths[field.slot()] += tokens.len() as u64;
+            for (position, token) in tokens.into_iter().enumerate() {
+                self.postings.entry(token.clone()).or_default().entry(key).or_default().push((field, position as u32));
+                terms.insert(token);
+            }
+        }
+        self.documents.insert(key, IndexedDocument { document, lengths, terms: terms.into_iter().collect() });
+    }
+
+    fn remove(&mut self, key: DocKey) {
+        let Some(indexed) = self.documents.remove(&key) else {
+            return;
+        };
+        for field in FIELDS {
+            self.total_lengths[field.slot()] -= indexed.lengths[field.slot()] as u64;
+        }
+        for term in indexed.terms {
+            if let Some(docs) = self.postings.get_mut(&term) {
+                docs.remove(&key);
+                if docs.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    fn upsert_episode(&mut self, incoming: &CachedEpisode) {
+        let key = DocKey::Episode(incoming.episode_id);
+        // Progress updates can create a cache entry that carries nothing but the position
+        if incoming.title.is_empty() {
+            if let Some(IndexedDocument { document: Document::Episode(existing), .. }) = self.documents.get_mut(&key) {
+                existing.listen_duration = incoming.listen_duration.or(existing.listen_duration);
+            }
+            return;
+        }
+        let mut episode = incoming.clone();
+        episode.description = plain_text(&episode.description);
+        if let Some(IndexedDocument { document: Document::Episode(existing), .. }) = self.documents.get(&key) {
+            if episode_unchanged(existing, &episode) {
+                return;
+            }
+            episode.podcast_id = episode.podcast_id.or(existing.podcast_id);
+        }
+        self.insert(key, Document::Episode(episode));
+    }
+
+    // Replaces the podcast documents and drops episodes of podcasts no longer subscribed
+    fn sync_podcasts(&mut self, podcasts: Vec<IndexedPodcast>) {
+        let subscribed: HashSet<i32> = podcasts.iter().map(|podcast| podcast.podcast_id).collect();
+        let stale: Vec<DocKey> = self.documents.iter()
+            .filter(|(key, indexed)| match (key, &indexed.document) {
+                (DocKey::Podcast(id), _) => !subscribed.contains(id),
+                (_, Document::Episode(episode)) => episode.podcast_id.is_some_and(|id| !subscribed.contains(&id)),
+                _ => false,
+            })
+            .map(|(key, _)| *key)
+            .collect();
+        for key in stale {
+            self.remove(key);
+        }
+        for podcast in podcasts {
+            let key = DocKey::Podcast(podcast.podcast_id);
+            let unchanged = matches!(self.documents.get(&key), Some(IndexedDocument { document: Document::Podcast(existing), .. }) if *existing == podcast);
+            if !unchanged {
+                self.insert(key, Document::Podcast(podcast));
+            }
+        }
+    }
+
+    fn average_length(&self, field: Field) -> f64 {
+        match self.documents.len() {
+            0 => 1.0,
+            count => (self.total_lengths[field.slot()] as f64 / count as f64).max(1.0),
+        }
+    }
+
+    fn idf(&self, document_frequency: usize) -> f64 {
+        let count = self.documents.len() as f64;
+        let frequency = document_frequency as f64;
+        ((count - frequency + 0.5) / (frequency + 0.5) + 1.0).ln()
+    }
+
+    // BM25 over the weighted fields for one term in one document
+    fn term_score(&self, key: DocKey, occurrences: &Occurrences, idf: f64) -> f64 {
+        let Some(indexed) = self.documents.get(&key) else {
+            return 0.0;
+        };
+        FIELDS.iter().map(|field| {
+            let frequency = occurrences.iter().filter(|(f, _)| f == field).count() as f64;
+            if frequency == 0.0 {
+                return 0.0;
+            }
+            let length = indexed.lengths[field.slot()] as f64;
+            let norm = 1.0 - B + B * length / self.average_length(*field);
+            field.weight() * frequency * (K1 + 1.0) / (frequency + K1 * norm)
+        }).sum::<f64>() * idf
+    }
+
+    // Scores for every document holding the word, or a word starting with it
+    fn word_scores(&self, word: &str, as_prefix: bool) -> HashMap<DocKey, f64> {
+        let mut scores = HashMap::new();
+        let mut add = |term: &str, weight: f64| {
+            if let Some(docs) = self.postings.get(term) {
+                let idf = self.idf(docs.len());
+                for (key, occurrences) in docs {
+                    let score = self.term_score(*key, occurrences, idf) * weight;
+                    let entry = scores.entry(*key).or_insert(0.0f64);
+                    *entry = entry.max(score);
+                }
+            }
+        };
+        add(word, 1.0);
+        if as_prefix {
+            let longer: Vec<&String> = self.postings.keys().filter(|term| term.len() > word.len() && term.starts_with(word)).collect();
+            for term in longer {
+                add(term, PREFIX_WEIGHT);
+            }
+        }
+        scores
+    }
+
+    // Documents where the words follow each other in one field
+    fn phrase_matches(&self, words: &[String]) -> HashSet<DocKey> {
+        let Some(first) = words.first().and_then(|word| self.postings.get(word)) else {
+            return HashSet::new();
+        };
+        first.iter()
+            .filter(|(key, starts)| starts.iter().any(|(field, start)| {
+                words.iter().enumerate().skip(1).all(|(offset, word)| {
+                    self.postings.get(word)
+                        .and_then(|docs| docs.get(*key))
+                        .is_some_and(|occurrences| occurrences.contains(&(*field, start + offset as u32)))
+                })
+            }))
+            .map(|(key, _)| *key)
+            .collect()
+    }
+
+    fn search(&self, query: &LibraryQuery, limit: usize) -> LibraryResults {
+        if query.is_empty() {
+            return LibraryResults::default();
+        }
+        let mut scores: Option<HashMap<DocKey, f64>> = None;
+        // Every word must match, so each one narrows the candidates
+        let mut narrow = |found: HashMap<DocKey, f64>| {
+            scores = Some(match scores.take() {
+                None => found,
+                Some(previous) => previous.into_iter()
+                    .filter_map(|(key, score)| found.get(&key).map(|extra| (key, score + extra)))
+                    .collect(),
+            });
+        };
+        for (index, word) in query.words.iter().enumerate() {
+            let as_prefix = query.last_word_partial && index == query.words.len() - 1;
+            narrow(self.word_scores(word, as_prefix));
+        }
+        for phrase in &query.phrases {
+            let matching = self.phrase_matches(phrase);
+            let mut found = HashMap::new();
+            for word in phrase {
+                for (key, score) in self.word_scores(word, false) {
+                    if matching.contains(&key) {
+                        *found.entry(key).or_insert(0.0) += score * PHRASE_BOOST;
+                    }
+                }
+            }
+            narrow(found);
+        }
+
+        let candidates: Vec<(DocKey, f64)> = match scores {
+            Some(scores) => scores.into_iter().collect(),
+            // Only filters given, so everything that passes them is a result
+            None => self.documents.keys().map(|key| (*key, 0.0)).collect(),
+        };
+
+        let mut hits: Vec<(&IndexedDocument, f64)> = candidates.into_iter()
+            .filter_map(|(key, score)| self.documents.get(&key).map(|indexed| (indexed, score)))
+            .filter(|(indexed, _)| query.accepts(&indexed.document))
+            .collect();
+        hits.sort_by(|(a, a_score), (b, b_score)| {
+            b_score.total_cmp(a_score).then_with(|| published(&b.document).cmp(published(&a.document)))
+        });
+
+        let mut results = LibraryResults::default();
+        for (indexed, _) in hits {
+            match &indexed.document {
+                Document::Episode(episode) if results.episodes.len() < limit => results.episodes.push(episode.clone()),
+                Document::Podcast(podcast) if results.podcasts.len() < limit => results.podcasts.push(podcast.clone()),
+                _ => {}
+            }
+        }
+        results
+    }
+
+    fn stored(&self) -> StoredLibrary {
+        let mut episodes: Vec<CachedEpisode> = Vec::new();
+        let mut podcasts = Vec::new();
+        for indexed in self.documents.values() {
+            match &indexed.document {
+                Document::Episode(episode) => episodes.push(episode.clone()),
+                Document::Podcast(podcast) => podcasts.push(podcast.clone()),
+            }
+        }
+        episodes.sort_by(|a, b| b.pub_date.cmp(&a.pub_date));
+        episodes.truncate(MAX_STORED_EPISODES);
+        StoredLibrary { user: self.user.clone().unwrap_or_default(), episodes, podcasts }
+    }
+}
+
+fn published(document: &Document) -> &str {
+    match document {
+        Document::Episode(episode) => &episode.pub_date,
+        Document::Podcast(_) => "",
+    }
+}
+
+// Membership flags change often and aren't searchable, so they don't force a re-index
+fn episode_unchanged(existing: &CachedEpisode, incoming: &CachedEpisode) -> bool {
+    existing.title == incoming.title
+        && existing.podcast_name == incoming.podcast_name
+        && existing.description == incoming.description
+        && existing.pub_date == incoming.pub_date
+        && existing.duration == incoming.duration
+        && existing.listen_duration == incoming.listen_duration
+        && existing.artwork == incoming.artwork
+        && existing.url == incoming.url
+}
+
+// Lowercased words, split on anything that isn't a letter or digit
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+// Descriptions arrive as HTML. Tags are dropped, common entities decoded and the result
+// shortened so the stored copy stays small.
+fn plain_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len().min(MAX_DESCRIPTION_CHARS));
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let decoded = text
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">");
+    decoded.split_whitespace().collect::<Vec<_>>().join(" ").chars().take(MAX_DESCRIPTION_CHARS).collect()
+}
+
+// Parsed library search. Plain words and "quoted phrases" are matched against the text,
+// the rest are filters:
+//   podcast:name  (quotes allow spaces)   before:2024-01-31   after:2023-06
+//   duration>30   duration<1h             played:no / played:yes
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct LibraryQuery {
+    words: Vec<String>,
+    phrases: Vec<Vec<String>>,
+    // The input doesn't end in a space, so the last word may still be growing
+    last_word_partial: bool,
+    podcast: Option<String>,
+    before: Option<String>,
+    after: Option<String>,
+    // Seconds
+    min_duration: Option<i32>,
+    max_duration: Option<i32>,
+    played: Option<bool>,
+}
+
+impl LibraryQuery {
+    pub fn parse(input: &str) -> Self {
+        let mut query = LibraryQuery::default();
+        let mut ends_with_word = false;
+        for (raw, quoted) in split_query(input) {
+            ends_with_word = false;
+            if quoted {
+                let phrase = tokenize(&raw);
+                match phrase.len() {
+                    0 => {}
+                    1 => query.words.extend(phrase),
+                    _ => query.phrases.push(phrase),
+                }
+                continue;
+            }
+            if query.apply_filter(&raw) {
+                continue;
+            }
+            let words = tokenize(&raw);
+            ends_with_word = !words.is_empty();
+            query.words.extend(words);
+        }
+        query.last_word_partial = ends_with_word && !input.ends_with(char::is_whitespace);
+        query
+    }
+
+    // Filters are `key:value` or `duration>value`. Anything unrecognised is searched as text.
+    fn apply_filter(&mut self, raw: &str) -> bool {
+        let lower = raw.to_lowercase();
+        if let Some(rest) = lower.strip_prefix("duration") {
+            let parsed = rest.strip_prefix('>').map(|value| (true, value))
+                .or_else(|| rest.strip_prefix('<').map(|value| (false, value)));
+            if let Some((minimum, seconds)) = parsed.and_then(|(minimum, value)| parse_duration(value).map(|seconds| (minimum, seconds))) {
+                if minimum {
+                    self.min_duration = Some(seconds);
+                } else {
+                    self.max_duration = Some(seconds);
+                }
+                return true;
+            }
+            return false;
+        }
+        let Some((key, value)) = raw.split_once(':') else {
+            return false;
+        };
+        let value = value.trim_matches('"');
+        match key.to_lowercase().as_str() {
+            "podcast" if !value.is_empty() => self.podcast = Some(value.to_lowercase()),
+            "before" if is_date_prefix(value) => self.before = Some(value.to_string()),
+            "after" if is_date_prefix(value) => self.after = Some(value.to_string()),
+            "played" => match value.to_lowercase().as_str() {
+                "yes" | "true" => self.played = Some(true),
+                "no" | "false" => self.played = Some(false),
+                _ => return false,
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty() && self.phrases.is_empty() && !self.has_filters()
+    }
+
+    fn has_filters(&self) -> bool {
+        self.podcast.is_some()
+            || self.before.is_some()
+            || self.after.is_some()
+            || self.min_duration.is_some()
+            || self.max_duration.is_some()
+            || self.played.is_some()
+    }
+
+    // Filters that only make sense for episodes keep podcasts out of the results
+    fn episode_filters(&self) -> bool {
+        self.before.is_some() || self.after.is_some() || self.min_duration.is_some() || self.max_duration.is_some() || self.played.is_some()
+    }
+
+    // The words and phrases alone, for asking the server which doesn't know the filters
+    pub fn text(&self) -> String {
+        let mut parts: Vec<String> = self.phrases.iter().map(|phrase| phrase.join(" ")).collect();
+        parts.extend(self.words.iter().cloned());
+        if parts.is_empty() {
+            if let Some(podcast) = &self.podcast {
+                parts.push(podcast.clone());
+            }
+        }
+        parts.join(" ")
+    }
+
+    fn accepts(&self, document: &Document) -> bool {
+        match document {
+            Document::Podcast(podcast) => {
+                !self.episode_filters() && self.podcast.as_ref().map_or(true, |name| podcast.name.to_lowercase().contains(name))
+            }
+            Document::Episode(episode) => {
+                let date = episode.pub_date.as_str();
+                self.podcast.as_ref().map_or(true, |name| episode.podcast_name.to_lowercase().contains(name))
+                    && self.before.as_ref().map_or(true, |before| date_prefix(date, before) < before.as_str())
+                    && self.after.as_ref().map_or(true, |after| date_prefix(date, after) > after.as_str())
+                    && self.min_duration.map_or(true, |seconds| episode.duration > seconds)
+                    && self.max_duration.map_or(true, |seconds| episode.duration < seconds)
+                    && self.played.map_or(true, |played| (episode.listen_state() == ListenState::Played) == played)
+            }
+        }
+    }
+}
+
+// Splits on whitespace outside quotes, marking the pieces that were a quoted phrase.
+// `podcast:"some name"` stays one piece with its quotes.
+fn split_query(input: &str) -> Vec<(String, bool)> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut phrase = false;
+    for c in input.chars() {
+        match c {
+            '"' if in_quotes => {
+                in_quotes = false;
+                if phrase {
+                    pieces.push((std::mem::take(&mut current), true));
+                    phrase = false;
+                } else {
+                    current.push(c);
+                }
+            }
+            '"' => {
+                in_quotes = true;
+                if current.is_empty() {
+                    phrase = true;
+                } else {
+                    current.push(c);
+                }
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    pieces.push((std::mem::take(&mut current), false));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        pieces.push((current, phrase));
+    }
+    pieces
+}
+
+// Minutes by default, or with an m or h suffix
+fn parse_duration(value: &str) -> Option<i32> {
+    let (number, unit) = match value.strip_suffix('h') {
+        Some(hours) => (hours, 3600),
+        None => (value.strip_suffix('m').unwrap_or(value), 60),
+    };
+    number.parse::<f64>().ok().filter(|n| *n >= 0.0).map(|n| (n * unit as f64) as i32)
+}
+
+// YYYY, YYYY-MM or YYYY-MM-DD
+fn is_date_prefix(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+    let lengths_ok = match parts.as_slice() {
+        [year] => year.len() == 4,
+        [year, month] => year.len() == 4 && month.len() == 2,
+        [year, month, day] => year.len() == 4 && month.len() == 2 && day.len() == 2,
+        _ => false,
+    };
+    lengths_ok && parts.iter().all(|part| part.chars().all(|c| c.is_ascii_digit()))
+}
+
+// The publication date cut to the precision of the bound it is compared with
+fn date_prefix<'a>(date: &'a str, bound: &str) -> &'a str {
+    date.get(..bound.len()).unwrap_or(date)
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct LibraryResults {
+    pub podcasts: Vec<IndexedPodcast>,
+    pub episodes: Vec<CachedEpisode>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct StoredLibrary {
+    user: String,
+    episodes: Vec<CachedEpisode>,
+    podcasts: Vec<IndexedPodcast>,
+}
+
+thread_local! {
+    static LIBRARY_INDEX: RefCell<LibraryIndex> = RefCell::new(LibraryIndex::default());
+}
+
+pub fn search_library(query: &LibraryQuery, limit: usize) -> LibraryResults {
+    LIBRARY_INDEX.with(|index| index.borrow().search(query, limit))
+}
+
+// For pages that want fresh results searchable before the indexer sees the cache change
+pub fn index_episodes<'a>(episodes: impl IntoIterator<Item = &'a CachedEpisode>) {
+    LIBRARY_INDEX.with(|index| {
+        let mut index = index.borrow_mut();
+        for episode in episodes {
+            index.upsert_episode(episode);
+        }
+    });
+}
+
+pub fn is_offline() -> bool {
+    window().is_some_and(|window| !window.navigator().on_line())
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    window().and_then(|w| w.local_storage().ok().flatten())
+}
+
+// Starts over with what was stored for this user, if anything
+fn load_index(user: &str) {
+    let stored = local_storage()
+        .and_then(|storage| storage.get_item(INDEX_STORAGE_KEY).ok().flatten())
+        .and_then(|raw| serde_json::from_str::<StoredLibrary>(&raw).ok())
+        .filter(|stored| stored.user == user)
+        .unwrap_or_default();
+    LIBRARY_INDEX.with(|index| {
+        let mut index = index.borrow_mut();
+        *index = LibraryIndex { user: Some(user.to_string()), ..LibraryIndex::default() };
+        for podcast in stored.podcasts {
+            index.insert(DocKey::Podcast(podcast.podcast_id), Document::Podcast(podcast));
+        }
+        for episode in stored.episodes {
+            index.insert(DocKey::Episode(episode.episode_id), Document::Episode(episode));
+        }
+    });
+}
+
+fn save_index() {
+    let Some(storage) = local_storage() else {
+        return;
+    };
+    let mut stored = LIBRARY_INDEX.with(|index| index.borrow().stored());
+    if stored.user.is_empty() {
+        return;
+    }
+    // Over quota, try again with the older half of the episodes left out
+    while let Ok(serialized) = serde_json::to_string(&stored) {
+        if storage.set_item(INDEX_STORAGE_KEY, &serialized).is_ok() || stored.episodes.is_empty() {
+            break;
+        }
+        stored.episodes.truncate(stored.episodes.len() / 2);
+    }
+}
+
+// Mounted once next to the router. It keeps the index in step with the episode and podcast
+// caches, re-indexing only the entries that changed, and saves a copy shortly after.
+#[function_component(LibraryIndexer)]
+pub fn library_indexer() -> Html {
+    let view_key = use_selector(|state: &AppState| user_key(state));
+    let persisted_podcasts = use_selector(|state: &AppState| {
+        state.podcast_feed_return.as_ref().and_then(|response| response.pods.clone())
+    });
+    let episode_cache = use_store_value::<EpisodeCache>();
+    let podcast_cache = use_store_value::<PodcastCache>();
+    let indexed = use_mut_ref(HashMap::<i32, Rc<CachedEpisode>>::new);
+    let pending_save = use_mut_ref(|| None::<Timeout>);
+
+    let schedule_save = {
+        let pending_save = pending_save.clone();
+        move || {
+            *pending_save.borrow_mut() = Some(Timeout::new(SAVE_DELAY_MS, save_index));
+        }
+    };
+
+    {
+        let indexed = indexed.clone();
+        use_effect_with((*view_key).clone(), move |view_key| {
+            indexed.borrow_mut().clear();
+            if let Some(key) = view_key {
+                load_index(key);
+            }
+            || ()
+        });
+    }
+
+    {
+        let schedule_save = schedule_save.clone();
+        use_effect_with((episode_cache, (*view_key).clone()), move |(episode_cache, view_key)| {
+            if view_key.is_some() {
+                let mut indexed = indexed.borrow_mut();
+                let changed: Vec<Rc<CachedEpisode>> = episode_cache.episodes.iter()
+                    .filter(|(id, episode)| !indexed.get(*id).is_some_and(|seen| Rc::ptr_eq(seen, *episode)))
+                    .map(|(_, episode)| episode.clone())
+                    .collect();
+                if !changed.is_empty() {
+                    index_episodes(changed.iter().map(|episode| episode.as_ref()));
+                    indexed.extend(changed.into_iter().map(|episode| (episode.episode_id, episode)));
+                    schedule_save();
+                }
+            }
+            || ()
+        });
+    }
+
+    // The podcast cache is filled by the podcasts page, the persisted list covers the rest
+    use_effect_with((podcast_cache, persisted_podcasts, (*view_key).clone()), move |(podcast_cache, persisted_podcasts, view_key)| {
+        if view_key.is_some() {
+            let podcasts: Option<Vec<IndexedPodcast>> = if !podcast_cache.podcasts.is_empty() {
+                Some(podcast_cache.podcasts.values().map(|podcast| IndexedPodcast::from(podcast.as_ref())).collect())
+            } else {
+                (**persisted_podcasts).as_ref().map(|pods| pods.iter().map(IndexedPodcast::from).collect())
+            };
+            if let Some(podcasts) = podcasts {
+                LIBRARY_INDEX.with(|index| index.borrow_mut().sync_podcasts(podcasts));
+                schedule_save();
+            }
+        }
+        || ()
+    });
+
+    use_effect_with((), move |_| move || drop(pending_save.borrow_mut().take()));
+
+    html! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn episode(episode_id: i32, podcast_id: i32, podcast_name: &str, title: &str, description: &str, pub_date: &str, duration: i32) -> CachedEpisode {
+        CachedEpisode {
+            title: title.to_string(),
+            description: description.to_string(),
+            ..CachedEpisode::for_test(episode_id, Some(podcast_id), podcast_name, pub_date, duration)
+        }
+    }
+
+    fn podcast(podcast_id: i32, name: &str) -> IndexedPodcast {
+        IndexedPodcast { podcast_id, name: name.to_string(), ..Default::default() }
+    }
+
+    fn library() -> LibraryIndex {
+        let mut index = LibraryIndex::default();
+        index.sync_podcasts(vec![podcast(1, "The Daily"), podcast(2, "Rust Weekly")]);
+        for episode in [
+            episode(10, 2, "Rust Weekly", "Async Rust in practice", "Executors and <b>futures</b> explained", "2024-01-10T00:00:00", 3600),
+            episode(11, 2, "Rust Weekly", "Borrow checker tips", "Why rust and async don't always mix", "2024-02-05T00:00:00", 1200),
+            episode(12, 1, "The Daily", "Election night", "Results as they come in", "2024-03-01T00:00:00", 1500),
+            episode(13, 1, "The Daily", "Climate change today", "A look at the climate", "2023-06-15T00:00:00", 2400),
+        ] {
+            index.upsert_episode(&episode);
+        }
+        index
+    }
+
+    fn episode_ids(results: &LibraryResults) -> Vec<i32> {
+        results.episodes.iter().map(|episode| episode.episode_id).collect()
+    }
+
+    fn search(index: &LibraryIndex, input: &str) -> LibraryResults {
+        index.search(&LibraryQuery::parse(input), 20)
+    }
+
+    #[test]
+    fn parses_words_and_tracks_the_partial_last_word() {
+        let query = LibraryQuery::parse("Daily News");
+        assert_eq!(query.words, vec!["daily", "news"]);
+        assert!(query.last_word_partial);
+        assert!(!LibraryQuery::parse("daily news ").last_word_partial);
+    }
+
+    #[test]
+    fn parses_phrases_and_filters() {
+        let query = LibraryQuery::parse("\"Climate Change\" podcast:\"The Daily\" duration>30 duration<1.5h before:2024-02 after:2023 played:no");
+        assert_eq!(query.phrases, vec![vec!["climate".to_string(), "change".to_string()]]);
+        assert!(query.words.is_empty());
+        assert_eq!(query.podcast.as_deref(), Some("the daily"));
+        assert_eq!(query.min_duration, Some(1800));
+        assert_eq!(query.max_duration, Some(5400));
+        assert_eq!(query.before.as_deref(), Some("2024-02"));
+        assert_eq!(query.after.as_deref(), Some("2023"));
+        assert_eq!(query.played, Some(false));
+        assert!(!query.last_word_partial);
+        assert_eq!(query.text(), "climate change");
+    }
+
+    #[test]
+    fn unrecognised_filters_are_searched_as_text() {
+        assert_eq!(LibraryQuery::parse("before:yesterday").words, vec!["before", "yesterday"]);
+        assert_eq!(LibraryQuery::parse("after:2023-6").words, vec!["after", "2023", "6"]);
+        assert_eq!(LibraryQuery::parse("played:maybe").words, vec!["played", "maybe"]);
+        assert_eq!(LibraryQuery::parse("duration>long").words, vec!["duration", "long"]);
+    }
+
+    #[test]
+    fn single_quoted_word_is_a_plain_word() {
+        let query = LibraryQuery::parse("\"rust\"");
+        assert_eq!(query.words, vec!["rust"]);
+        assert!(query.phrases.is_empty());
+    }
+
+    #[test]
+    fn empty_and_filter_only_queries() {
+        assert!(LibraryQuery::parse("   ").is_empty());
+        assert_eq!(search(&library(), "   "), LibraryResults::default());
+        let query = LibraryQuery::parse("podcast:daily");
+        assert!(!query.is_empty());
+        assert_eq!(query.text(), "daily");
+    }
+
+    #[test]
+    fn title_matches_outrank_description_matches() {
+        let index = library();
+        assert_eq!(episode_ids(&search(&index, "async ")), vec![10, 11]);
+    }
+
+    #[test]
+    fn every_word_has_to_match() {
+        let index = library();
+        assert_eq!(episode_ids(&search(&index, "borrow async ")), vec![11]);
+        assert!(search(&index, "borrow election ").episodes.is_empty());
+    }
+
+    #[test]
+    fn last_word_matches_as_a_prefix_while_typing() {
+        let index = library();
+        assert_eq!(episode_ids(&search(&index, "elec")), vec![12]);
+        assert!(search(&index, "elec ").episodes.is_empty());
+    }
+
+    #[test]
+    fn phrases_need_the_words_in_order() {
+        let index = library();
+        assert_eq!(episode_ids(&search(&index, "\"async rust\"")), vec![10]);
+        assert_eq!(episode_ids(&search(&index, "\"rust and async\"")), vec![11]);
+    }
+
+    #[test]
+    fn descriptions_are_indexed_without_markup() {
+        let index = library();
+        assert_eq!(episode_ids(&search(&index, "futures ")), vec![10]);
+        assert!(search(&index, "b ").episodes.is_empty());
+    }
+
+    #[test]
+    fn filters_alone_list_newest_first() {
+        let index = library();
+        let results = search(&index, "podcast:daily");
+        assert_eq!(episode_ids(&results), vec![12, 13]);
+        assert_eq!(results.podcasts, vec![podcast(1, "The Daily")]);
+    }
+
+    #[test]
+    fn episode_filters_leave_podcasts_out() {
+        let index = library();
+        let results = search(&index, "rust duration>30");
+        assert_eq!(episode_ids(&results), vec![10]);
+        assert!(results.podcasts.is_empty());
+        assert_eq!(episode_ids(&search(&index, "before:2024-02")), vec![10, 13]);
+        assert_eq!(episode_ids(&search(&index, "after:2024-01-31")), vec![12, 11]);
+    }
+
+    #[test]
+    fn limit_applies_to_episodes_and_podcasts_separately() {
+        let index = library();
+        let results = index.search(&LibraryQuery::parse("podcast:e"), 1);
+        assert_eq!(results.episodes.len(), 1);
+        assert_eq!(results.podcasts.len(), 1);
+    }
+
+    #[test]
+    fn unsubscribing_drops_the_podcast_and_its_episodes() {
+        let mut index = library();
+        index.sync_podcasts(vec![podcast(1, "The Daily")]);
+        assert!(search(&index, "rust ").episodes.is_empty());
+        assert!(search(&index, "rust ").podcasts.is_empty());
+        assert_eq!(episode_ids(&search(&index, "climate ")), vec![13]);
+    }
+}
@@ -6,10 +6,12 @@ use yew_router::history::{BrowserHistory, History};
 use crate::requests::login_requests::{self, call_check_mfa_enabled};
 use crate::requests::login_requests::{TimeZoneInfo, call_first_login_done, call_setup_timezone_info, call_verify_mfa, call_self_service_login_status, call_reset_password_create_code, ResetCodePayload, ResetForgotPasswordPayload, call_verify_and_reset_password, call_get_time_info, call_verify_key};
 use crate::components::context::{AppState, UIState};
+use crate::components::persistence::load_persisted_state;
 use md5;
 use yewdux::prelude::*;
 use crate::requests::login_requests::{AddUserRequest, call_add_login_user};
 use crate::requests::setting_reqs::call_get_theme;
+use crate::components::i18n::{sync_user_locale, t, t_args};
 use crate::components::gen_funcs::{encode_password, validate_user_input, ValidationError};
 use crate::components::episodes_layout::UIStateMsg;
 use chrono_tz::{TZ_VARIANTS, Tz};
@@ -116,111 +118,87 @@ pub fn login() -> Html {
                             crate::components::setting_components::theme_options::changeTheme(&stored_theme);
                         }
 
-                        if let Ok(Some(user_state)) = storage.get_item("userState") {
-                            let app_state_result = AppState::deserialize(&user_state);
-
-                            if let Ok(Some(auth_state)) = storage.get_item("userAuthState") {
-                                match AppState::deserialize(&auth_state) {
-                                    Ok(auth_details) => { // Successful deserialization of auth state
-                                        if let Ok(Some(server_state)) = storage.get_item("serverState") {
-                                            let server_details_result = AppState::deserialize(&server_state);
-
-                                            if let Ok(app_state) = app_state_result { // Successful deserialization of user state
-                                                if let Ok(server_details) = server_details_result { // Successful deserialization of server state
-                                                    // Check if the deserialized state contains valid data
-                                                    if app_state.user_details.is_some() && auth_details.auth_details.is_some() && server_details.server_details.is_some() {
-
-                                                        let auth_state_clone = auth_details.clone();
-                                                        let email = &app_state.user_details.as_ref().unwrap().Email;
-                                                        let user_id = app_state.user_details.as_ref().unwrap().UserID.clone();
-                                                        // Safely access server_name and api_key
-                                                        let auth_details_clone = auth_state_clone.auth_details.clone();
-                                                        if let Some(auth_details) = auth_details_clone.as_ref() {
-                                                            let server_name = auth_details.server_name.clone();
-                                                            let api_key = auth_details.api_key.clone().unwrap_or_default();
-                                                            
-                                                            // Now verify the API key
-                                                            // let wasm_user_id = user_id.clone();
-                                                            let wasm_app_state = app_state.clone();
-                                                            let wasm_auth_details: login_requests::LoginServerRequest = auth_details.clone();
-                                                            let wasm_email = email.clone();
-                                                            let wasm_user_id = user_id.clone();
-                                                            wasm_bindgen_futures::spawn_local(async move {
-                                                                match call_verify_key(&server_name.clone(), &api_key.clone()).await {
-                                                                    Ok(_) => {
-                                                                        // API key is valid, user can stay logged in
-                                                                        let final_dispatch = effect_displatch.clone();
-                                                                        let gravatar_url = generate_gravatar_url(&Some(wasm_email.clone().unwrap()), 80);
-                                                                        // Auto login logic here
-                                                                        final_dispatch.reduce_mut(move |state| {
-                                                                            state.user_details = wasm_app_state.user_details;
-                                                                            state.auth_details = Some(wasm_auth_details.clone());
-                                                                            state.server_details = server_details.server_details;
-                                                                            state.gravatar_url = Some(gravatar_url);
-                
-                                                                        });
-                                                                        // let mut error_message = app_state.error_message;
-                                                                        // Retrieve the originally requested route, if any
-                                                                        let session_storage = window.session_storage().unwrap().unwrap();
-                                                                        session_storage.set_item("isAuthenticated", "true").unwrap();
-                                                                        let requested_route = session_storage.get_item("requested_route").unwrap_or(None);
-                                                                        // Get Theme
-                                                                        let theme_api = api_key.clone();
-                                                                        let theme_server = server_name.clone();
-                                                                        wasm_bindgen_futures::spawn_local(async move {
-                                                                            match call_get_theme(theme_server, theme_api, &wasm_user_id).await{
-                                                                                Ok(theme) => {
-                                                                                    crate::components::setting_components::theme_options::changeTheme(&theme);
-                                                                                    if let Some(window) = web_sys::window() {
-                                                                                        if let Ok(Some(local_storage)) = window.local_storage() {
-                                                                                            match local_storage.set_item("selected_theme", &theme) {
-                                                                                                Ok(_) => console::log_1(&"Updated theme in local storage".into()),
-                                                                                                Err(e) => console::log_1(&format!("Error updating theme in local storage: {:?}", e).into()),
-                                                                                            }
-                                                                                        }
-                                                                                    }
-                                                                                }
-                                                                                Err(_e) => {
-                                                                                    // console::log_1(&format!("Error getting theme: {:?}", e).into());
-                                                                                }
-                                                                            }
-                                                                        });
-                                                                        wasm_bindgen_futures::spawn_local(async move {
-                                                                            match call_get_time_info(server_name, api_key, &wasm_user_id).await{
-                                                                                Ok(tz_response) => {
-                                                                                    effect_displatch.reduce_mut(move |state| {
-                                                                                        state.user_tz = Some(tz_response.timezone);
-                                                                                        state.hour_preference = Some(tz_response.hour_pref);
-                                                                                        state.date_format = Some(tz_response.date_format);
-                                                                                    });
-                                                                                }
-                                                                                Err(_e) => {
-                                                                                    // console::log_1(&format!("Error getting theme: {:?}", e).into());
-                                                                                }
-                                                                            }
-                                                                        });
-                                                                        let redirect_route = requested_route.unwrap_or_else(|| "/home".to_string());
-                                                                        history.push(&redirect_route); // Redirect to the requested or home page
-                                                                    }
-                                                                    Err(_) => {
-                                                                        // API key is not valid, redirect to login
-                                                                        history.push("/");
-                                                                    }
+                        if let Some(persisted) = load_persisted_state() {
+                            if let (Some(user_details), Some(auth_details), Some(server_details)) = (
+                                persisted.user_details.clone(),
+                                persisted.auth_details.clone(),
+                                persisted.server_details.clone(),
+                            ) {
+                                let server_name = auth_details.server_name.clone();
+                                let api_key = auth_details.api_key.clone().unwrap_or_default();
+                                let wasm_user_id = user_details.UserID.clone();
+                                let wasm_email = user_details.Email.clone();
+                                wasm_bindgen_futures::spawn_local(async move {
+                                    match call_verify_key(&server_name.clone(), &api_key.clone()).await {
+                                        Ok(_) => {
+                                            // API key is valid, user can stay logged in
+                                            let final_dispatch = effect_displatch.clone();
+                                            let gravatar_url = generate_gravatar_url(&wasm_email, 80);
+                                            // Auto login logic here. Cached preferences and library data are shown
+                                            // straight away and replaced once the fresh copies arrive.
+                                            let cached = persisted.clone();
+                                            final_dispatch.reduce_mut(move |state| {
+                                                state.user_details = Some(user_details);
+                                                state.auth_details = Some(auth_details);
+                                                state.server_details = Some(server_details);
+                                                state.gravatar_url = Some(gravatar_url);
+                                                cached.apply_cached(state);
+                                            });
+                                            // Retrieve the originally requested route, if any
+                                            let session_storage = window.session_storage().unwrap().unwrap();
+                                            session_storage.set_item("isAuthenticated", "true").unwrap();
+                                            let requested_route = session_storage.get_item("requested_route").unwrap_or(None);
+                                            // Get Theme
+                                            let theme_api = api_key.clone();
+                                            let theme_server = server_name.clone();
+                                            wasm_bindgen_futures::spawn_local(async move {
+                                                sync_user_locale(&theme_server, &theme_api, wasm_user_id).await;
+                                                match call_get_theme(theme_server, theme_api, &wasm_user_id).await{
+                                                    Ok(theme) => {
+                                                        crate::components::setting_components::theme_options::changeTheme(&theme);
+                                                        if let Some(window) = web_sys::window() {
+                                                            if let Ok(Some(local_storage)) = window.local_storage() {
+                                                                match local_storage.set_item("selected_theme", &theme) {
+                                                                    Ok(_) => console::log_1(&"Updated theme in local storage".into()),
+                                                                    Err(e) => console::log_1(&format!("Error updating theme in local storage: {:?}", e).into()),
                                                                 }
-                                                            });
-
-                                                        } else {
-                                                            console::log_1(&"Auth details are None".into());
+                                                            }
                                                         }
                                                     }
+                                                    Err(_e) => {
+                                                        // console::log_1(&format!("Error getting theme: {:?}", e).into());
+                                                    }
                                                 }
-                                            }
+                                            });
+                                            wasm_bindgen_futures::spawn_local(async move {
+                                                match call_get_time_info(server_name, api_key, &wasm_user_id).await{
+                                                    Ok(tz_response) => {
+                                                        effect_displatch.reduce_mut(move |state| {
+                                                            state.user_tz = Some(tz_response.timezone);
+                                                            state.hour_preference = Some(tz_response.hour_pref);
+                                                            state.date_format = Some(tz_response.date_format);
+                                                            state.store_app_state();
+                                                        });
+                                                    }
+                                                    Err(_e) => {
+                                                        // console::log_1(&format!("Error getting theme: {:?}", e).into());
+                                                    }
+                                                }
+                                            });
+                                            // Requested route wins, then wherever the user was last time
+                                            let redirect_route = requested_route
+                                                .or(persisted.last_route)
+                                                .unwrap_or_else(|| "/home".to_string());
+                                            history.push(&redirect_route);
+                                        }
+                                        Err(_) => {
+                                            // API key is not valid, redirect to login
+                                            history.push("/");
                                         }
-                                    },
-                                    Err(e) => {
-                                        web_sys::console::log_1(&format!("Error deserializing auth state: {:?}", e).into());
                                     }
-                                }
+                                });
+                            } else {
+                                console::log_1(&"Persisted state is missing login details".into());
                             }
                         }
                     }
@@ -321,6 +299,7 @@ pub fn login() -> Html {
                                                 let theme_api = api_key.clone();
                                                 let theme_server = server_name.clone();
                                                 wasm_bindgen_futures::spawn_local(async move {
+                                                    sync_user_locale(&theme_server, theme_api.as_deref().unwrap_or_default(), user_id).await;
                                                     match call_get_theme(theme_server, theme_api.unwrap(), &user_id).await{
                                                         Ok(theme) => {
                                                             crate::components::setting_components::theme_options::changeTheme(&theme);
@@ -358,7 +337,7 @@ pub fn login() -> Html {
 
                                         },
                                         Err(_) => {
-                                            post_state.reduce_mut(|state| state.error_message = Option::from("Error Checking MFA Status".to_string()));
+                                            post_state.reduce_mut(|state| state.error_message = Option::from(t("login.error.mfa_status")));
                                         }
                                     }
                                 } else {
@@ -366,12 +345,12 @@ pub fn login() -> Html {
                                 }
                             },
                             Err(_) => {
-                                post_state.reduce_mut(|state| state.error_message = Option::from("Error checking first login status".to_string()));
+                                post_state.reduce_mut(|state| state.error_message = Option::from(t("login.error.first_login")));
                             }
                         }
                     },
                     Err(_) => {
-                        post_state.reduce_mut(|state| state.error_message = Option::from("Your credentials appear to be incorrect".to_string()));
+                        post_state.reduce_mut(|state| state.error_message = Option::from(t("login.error.credentials")));
                         // Handle error
                     }
                 }
@@ -525,17 +504,17 @@ pub fn login() -> Html {
                                 Ok(success) => {
                                     if success {
                                         page_state.set(PageState::Default);
-                                        dispatch.reduce_mut(|state| state.info_message = Option::from(format!("You can now login!")));
+                                        dispatch.reduce_mut(|state| state.info_message = Option::from(t("login.user_created")));
                                     } else {
                                         console::log_1(&"Error adding user".into());
                                         page_state.set(PageState::Default);
-                                        dispatch.reduce_mut(|state| state.error_message = Option::from(format!("Error adding user")));
+                                        dispatch.reduce_mut(|state| state.error_message = Option::from(t("login.error.add_user")));
 
                                     }
                                 }
                                 Err(e) => {
                                     page_state.set(PageState::Default);
-                                    dispatch.reduce_mut(|state| state.error_message = Option::from(format!("Error adding user: {:?}", e)));
+                                    dispatch.reduce_mut(|state| state.error_message = Option::from(t_args("login.error.add_user_detail", &[("error", &format!("{:?}", e))])));
                                 }
                             }
                         });
@@ -556,52 +535,52 @@ pub fn login() -> Html {
                 <div class="modal-container relative rounded-lg shadow">
                     <div class="flex items-center justify-between p-4 md:p-5 border-b rounded-t">
                         <h3 class="text-xl font-semibold">
-                            {"Create New User"}
+                            { t("login.create_user") }
                         </h3>
                         <button onclick={on_close_modal.clone()} class="end-2.5 text-gray-400 bg-transparent hover:bg-gray-200 hover:text-gray-900 rounded-lg text-sm w-8 h-8 ms-auto inline-flex justify-center items-center dark:hover:bg-gray-600 dark:hover:text-white">
                             <svg class="w-3 h-3" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 14 14">
                                 <path stroke="currentColor" stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="m1 1 6 6m0 0 6 6M7 7l6-6M7 7l-6 6"/>
                             </svg>
-                            <span class="sr-only">{"Close modal"}</span>
+                            <span class="sr-only">{ t("a11y.close_modal") }</span>
                         </button>
                     </div>
                     <div class="p-4 md:p-5">
                         <form class="space-y-4" action="#">
                             <div>
-                                <label for="username" class="block mb-2 text-sm font-medium">{"Username"}</label>
+                                <label for="username" class="block mb-2 text-sm font-medium">{ t("login.username") }</label>
                                 <input oninput={on_username_change.clone()} type="text" id="username" name="username" class="search-bar-input border text-sm rounded-lg block w-full p-2.5" required=true />
                                 {
                                     match *username_error {
                                         username_error_notice::Hidden => html! {},
-                                        username_error_notice::Shown => html! {<p class="text-red-500 text-xs italic">{"Username must be at least 4 characters long"}</p>},
+                                        username_error_notice::Shown => html! {<p class="text-red-500 text-xs italic">{ t("login.error.username_short") }</p>},
                                     }
                                 }
                             </div>
                             <div>
-                                <label for="fullname" class="block mb-2 text-sm font-medium">{"Full Name"}</label>
+                                <label for="fullname" class="block mb-2 text-sm font-medium">{ t("login.full_name") }</label>
                                 <input oninput={on_fullname_change} type="text" id="fullname" name="fullname" class="search-bar-input border text-sm rounded-lg block w-full p-2.5" required=true />
                             </div>
                             <div>
-                                <label for="email" class="block mb-2 text-sm font-medium">{"Email"}</label>
+                                <label for="email" class="block mb-2 text-sm font-medium">{ t("login.email") }</label>
                                 <input oninput={on_email_change} type="email" id="email" name="email" class="search-bar-input border text-sm rounded-lg block w-full p-2.5" required=true />
                                 {
                                     match *email_error {
                                         email_error_notice::Hidden => html! {},
-                                        email_error_notice::Shown => html! {<p class="text-red-500 text-xs italic">{"Invalid email address"}</p>},
+                                        email_error_notice::Shown => html! {<p class="text-red-500 text-xs italic">{ t("login.error.invalid_email") }</p>},
                                     }
                                 }
                             </div>
                             <div>
-                                <label for="password" class="block mb-2 text-sm font-medium">{"Password"}</label>
+                                <label for="password" class="block mb-2 text-sm font-medium">{ t("login.password") }</label>
                                 <input oninput={on_password_change.clone()} type="password" id="password" name="password" class="search-bar-input border text-sm rounded-lg block w-full p-2.5" required=true />
                                 {
                                     match *password_error {
                                         password_error_notice::Hidden => html! {},
-                                        password_error_notice::Shown => html! {<p class="text-red-500 text-xs italic">{"Password must be at least 6 characters long"}</p>},
+                                        password_error_notice::Shown => html! {<p class="text-red-500 text-xs italic">{ t("login.error.password_short") }</p>},
                                     }
                                 }
                             </div>
-                            <button type="submit" onclick={on_create_submit} class="download-button w-full focus:ring-4 focus:outline-none font-medium rounded-lg text-sm px-5 py-2.5 text-center">{"Submit"}</button>
+                            <button type="submit" onclick={on_create_submit} class="download-button w-full focus:ring-4 focus:outline-none font-medium rounded-lg text-sm px-5 py-2.5 text-center">{ t("login.submit") }</button>
                         </form>
                     </div>
                 </div>
@@ -656,12 +635,12 @@ pub fn login() -> Html {
                             page_state.set(PageState::EnterCode);
                         } else {
                             page_state.set(PageState::Default);
-                            dispatch.reduce_mut(|state| state.error_message = Option::from(format!("Error Sending Reset Email")));
+                            dispatch.reduce_mut(|state| state.error_message = Option::from(t("login.error.reset_email")));
                         }
                     }
                     Err(e) => {
                         page_state.set(PageState::Default);
-                        dispatch.reduce_mut(|state| state.error_message = Option::from(format!("Error sending reset: {:?}", e)));
+                        dispatch.reduce_mut(|state| state.error_message = Option::from(t_args("login.error.reset_send", &[("error", &format!("{:?}", e))])));
                     }
                 }
             });
@@ -675,29 +654,29 @@ pub fn login() -> Html {
                 <div class="modal-container relative rounded-lg shadow">
                     <div class="flex items-center justify-between p-4 md:p-5 border-b rounded-t">
                         <h3 class="text-xl font-semibold">
-                            {"Forgot Password"}
+                            { t("login.forgot_password.title") }
                         </h3>
                         <button onclick={on_close_modal.clone()} class="end-2.5 text-gray-400 bg-transparent hover:bg-gray-200 hover:text-gray-900 rounded-lg text-sm w-8 h-8 ms-auto inline-flex justify-center items-center dark:hover:bg-gray-600 dark:hover:text-white">
                             <svg class="w-3 h-3" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 14 14">
                                 <path stroke="currentColor" stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="m1 1 6 6m0 0 6 6M7 7l6-6M7 7l-6 6"/>
                             </svg>
-                            <span class="sr-only">{"Close modal"}</span>
+                            <span class="sr-only">{ t("a11y.close_modal") }</span>
                         </button>
                     </div>
                     <div class="p-4 md:p-5">
                         <form class="space-y-4" action="#">
                             <p class="text-m font-semibold">
-                            {"Please enter your username and email to reset your password."}
+                            { t("login.forgot_password.body") }
                             </p>
                             <div>
-                                <label for="username" class="block mb-2 text-sm font-medium text-gray-900 dark:text-white">{"Username"}</label>
+                                <label for="username" class="block mb-2 text-sm font-medium text-gray-900 dark:text-white">{ t("login.username") }</label>
                                 <input oninput={on_forgot_username_change} type="text" id="username" name="username" class="search-bar-input border text-sm rounded-lg block w-full p-2.5" required=true />
                             </div>
                             <div>
-                                <label for="email" class="block mb-2 text-sm font-medium">{"Email"}</label>
+                                <label for="email" class="block mb-2 text-sm font-medium">{ t("login.email") }</label>
                                 <input oninput={on_forgot_email_change} type="email" id="email" name="email" class="search-bar-input border text-sm rounded-lg block w-full p-2.5" required=true />
                             </div>
-                            <button onclick={on_reset_submit} type="submit" class="download-button w-full focus:ring-4 focus:outline-none font-medium rounded-lg text-sm px-5 py-2.5 text-center">{"Submit"}</button>
+                            <button onclick={on_reset_submit} type="submit" class="download-button w-full focus:ring-4 focus:outline-none font-medium rounded-lg text-sm px-5 py-2.5 text-center">{ t("login.submit") }</button>
                         </form>
                     </div>
                 </div>
@@ -748,18 +727,18 @@ pub fn login() -> Html {
                                     page_state.set(PageState::Default);
                                 } else {
                                     page_state.set(PageState::Default);
-                                    dispatch.reduce_mut(|state| state.error_message = Option::from(format!("Error Sending Reset Email")));
+                                    dispatch.reduce_mut(|state| state.error_message = Option::from(t("login.error.reset_email")));
                                 }
                             }
                             Err(e) => {
                                 page_state.set(PageState::Default);
-                                dispatch.reduce_mut(|state| state.error_message = Option::from(format!("Error Resetting Password: {:?}", e)));
+                                dispatch.reduce_mut(|state| state.error_message = Option::from(t_args("login.error.reset_password", &[("error", &format!("{:?}", e))])));
                             }
                         }
                     });
                 },
                 Err(e) => {
-                    dispatch.reduce_mut(|state| state.error_message = Option::from(format!("Unable to hash new password: {:?}", e)));
+                    dispatch.reduce_mut(|state| state.error_message = Option::from(t_args("login.error.hash_password", &[("error", &format!("{:?}", e))])));
                     page_state.set(PageState::Default);
                 }
             }
@@ -772,23 +751,23 @@ pub fn login() -> Html {
                 <div class="modal-container relative rounded-lg shadow">
                     <div class="flex items-center justify-between p-4 md:p-5 border-b rounded-t">
                         <h3 class="text-xl font-semibold">
-                            {"Password Reset"}
+                            { t("login.reset.title") }
                         </h3>
                         <button onclick={on_close_modal.clone()} class="end-2.5 text-gray-400 bg-transparent hover:bg-gray-200 hover:text-gray-900 rounded-lg text-sm w-8 h-8 ms-auto inline-flex justify-center items-center dark:hover:bg-gray-600 dark:hover:text-white">
                             <svg class="w-3 h-3" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 14 14">
                                 <path stroke="currentColor" stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="m1 1 6 6m0 0 6 6M7 7l6-6M7 7l-6 6"/>
                             </svg>
-                            <span class="sr-only">{"Close modal"}</span>
+                            <span class="sr-only">{ t("a11y.close_modal") }</span>
                         </button>
                     </div>
                     <div class="p-4 md:p-5">
                         <form class="space-y-4" action="#">
                             <p class="text-m font-semibold">
-                            {"An email has been sent to your email address. Please enter a new password and the code contained within the email to reset your password."}
+                            { t("login.reset.body") }
                             </p>
-                            <input oninput={on_reset_code_change} type="text" id="reset_code" name="reset_code" class="search-bar-input border text-sm rounded-lg block w-full p-2.5" placeholder="Enter Password Reset Code" />
-                            <input oninput={on_reset_password_change} type="text" id="reset_password" name="reset_password" class="search-bar-input border text-sm rounded-lg block w-full p-2.5" placeholder="Enter your new password" />
-                            <button type="submit" onclick={on_reset_code_submit} class="download-button w-full focus:ring-4 focus:outline-none font-medium rounded-lg text-sm px-5 py-2.5 text-center">{"Submit"}</button>
+                            <input oninput={on_reset_code_change} type="text" id="reset_code" name="reset_code" class="search-bar-input border text-sm rounded-lg block w-full p-2.5" placeholder={t("login.reset.code_placeholder")} />
+                            <input oninput={on_reset_password_change} type="text" id="reset_password" name="reset_password" class="search-bar-input border text-sm rounded-lg block w-full p-2.5" placeholder={t("login.reset.password_placeholder")} />
+                            <button type="submit" onclick={on_reset_code_submit} class="download-button w-full focus:ring-4 focus:outline-none font-medium rounded-lg text-sm px-5 py-2.5 text-center">{ t("login.submit") }</button>
                         </form>
                     </div>
                 </div>
@@ -883,18 +862,18 @@ pub fn login() -> Html {
                                     }
                                 },
                                 Err(_) => {
-                                    post_state.reduce_mut(|state| state.error_message = Option::from("Error Checking MFA Status".to_string()));
+                                    post_state.reduce_mut(|state| state.error_message = Option::from(t("login.error.mfa_status")));
                                 }
                             }
                         } else {
-                            post_state.reduce_mut(|state| state.error_message = Option::from("Error Setting up Time Zone".to_string()));
+                            post_state.reduce_mut(|state| state.error_message = Option::from(t("login.error.time_zone")));
                             page_state.set(PageState::Default);
-                            dispatch.reduce_mut(|state| state.error_message = Option::from(format!("Error setting up time zone")));
+                            dispatch.reduce_mut(|state| state.error_message = Option::from(t("login.error.time_zone")));
                         }
                     },
                     Err(e) => {
                         page_state.set(PageState::Default);
-                        dispatch.reduce_mut(|state| state.error_message = Option::from(format!("Error setting up time zone: {:?}", e)));
+                        dispatch.reduce_mut(|state| state.error_message = Option::from(t_args("login.error.time_zone_detail", &[("error", &format!("{:?}", e))])));
                     }
                 }
             });
@@ -913,35 +892,35 @@ pub fn login() -> Html {
                 <div class="modal-container relative rounded-lg shadow">
                     <div class="flex items-center justify-between p-4 md:p-5 border-b rounded-t">
                         <h3 class="text-xl font-semibold">
-                            {"Time Zone Setup"}
+                            { t("login.time_zone.title") }
                         </h3>
                         <button onclick={on_close_modal.clone()} class="end-2.5 text-gray-400 bg-transparent hover:bg-gray-200 hover:text-gray-900 rounded-lg text-sm w-8 h-8 ms-auto inline-flex justify-center items-center dark:hover:bg-gray-600 dark:hover:text-white">
                             <svg class="w-3 h-3" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 14 14">
                                 <path stroke="currentColor" stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="m1 1 6 6m0 0 6 6M7 7l6-6M7 7l-6 6"/>
                             </svg>
-                            <span class="sr-only">{"Close modal"}</span>
+                            <span class="sr-only">{ t("a11y.close_modal") }</span>
                         </button>
                     </div>
                     <div class="p-4 md:p-5">
                         <form class="space-y-4" action="#">
                             <p class="text-m font-semibold">
-                            {"Welcome to Pinepods! This appears to be your first time logging in. To start, let's get some basic information about your time and time zone preferences. This will determine how times appear throughout the app."}
+                            { t("login.time_zone.body") }
                             </p>
                             <div>
-                                <label for="hour_format" style="margin-right: 10px;">{"Hour Format:"}</label>
+                                <label for="hour_format" style="margin-right: 10px;">{ t("login.time_zone.hour_format") }</label>
                                 <select id="hour_format" name="hour_format" class="email-select border p-2 rounded" oninput={on_time_pref_change}>
-                                    <option value="12">{"12 Hour"}</option>
-                                    <option value="24">{"24 Hour"}</option>
+                                    <option value="12">{ t("login.time_zone.hour_12") }</option>
+                                    <option value="24">{ t("login.time_zone.hour_24") }</option>
                                 </select>
                             </div>
                             <div>
-                                <label for="time_zone" style="margin-right: 10px;">{"Time Zone:"}</label>
+                                <label for="time_zone" style="margin-right: 10px;">{ t("login.time_zone.time_zone") }</label>
                                 <select id="time_zone" name="time_zone" class="email-select border p-2 rounded" oninput={on_tz_change}>
                                     { for TZ_VARIANTS.iter().map(|tz| render_time_zone_option(*tz)) }
                                 </select>
                             </div>
                             <div>
-                            <label for="date_format" style="margin-right: 10px;">{"Date Format:"}</label>
+                            <label for="date_format" style="margin-right: 10px;">{ t("login.time_zone.date_format") }</label>
                             <select id="date_format" name="date_format" class="email-select border p-2 rounded" oninput={on_df_change}>
                                 <option value="MDY">{"MDY (MM-DD-YYYY)"}</option>
                                 <option value="DMY">{"DMY (DD-MM-YYYY)"}</option>
@@ -953,7 +932,7 @@ pub fn login() -> Html {
                                 <option value="JIS">{"JIS (YYYY-MM-DD)"}</option>
                             </select>
                         </div>
-                            <button type="submit" onclick={on_time_zone_submit} class="download-button w-full focus:ring-4 focus:outline-none font-medium rounded-lg text-sm px-5 py-2.5 text-center">{"Submit"}</button>
+                            <button type="submit" onclick={on_time_zone_submit} class="download-button w-full focus:ring-4 focus:outline-none font-medium rounded-lg text-sm px-5 py-2.5 text-center">{ t("login.submit") }</button>
                         </form>
                     </div>
                 </div>
@@ -1001,6 +980,9 @@ pub fn login() -> Html {
                             let theme_api = api_key.clone();
                             let theme_server = server_name.clone();
                             wasm_bindgen_futures::spawn_local(async move {
+                                if let (Some(server), Some(Some(api)), Some(id)) = (&theme_server, &theme_api, user_id) {
+                                    sync_user_locale(server, api, id).await;
+                                }
                                 match call_get_theme(theme_server.unwrap(), theme_api.unwrap().unwrap(), &user_id.unwrap()).await{
                                     Ok(theme) => {
                                         crate::components::setting_components::theme_options::changeTheme(&theme);
@@ -1027,13 +1009,13 @@ pub fn login() -> Html {
                             history.push("/home"); // Use the route path
                         } else {
                             page_state.set(PageState::Default);
-                            dispatch.reduce_mut(|state| state.error_message = Option::from(format!("Error setting up time zone")));
+                            dispatch.reduce_mut(|state| state.error_message = Option::from(t("login.error.time_zone")));
 
                         }
                     }
                     Err(e) => {
                         page_state.set(PageState::Default);
-                        dispatch.reduce_mut(|state| state.error_message = Option::from(format!("Error setting up time zone: {:?}", e)));
+                        dispatch.reduce_mut(|state| state.error_message = Option::from(t_args("login.error.time_zone_detail", &[("error", &format!("{:?}", e))])));
                     }
                 }
             });
@@ -1046,22 +1028,22 @@ pub fn login() -> Html {
                 <div class="relative bg-white rounded-lg shadow dark:bg-gray-700">
                     <div class="flex items-center justify-between p-4 md:p-5 border-b rounded-t dark:border-gray-600">
                         <h3 class="text-xl font-semibold text-gray-900 dark:text-white">
-                            {"MFA Login"}
+                            { t("login.mfa.title") }
                         </h3>
                         <button onclick={on_close_modal.clone()} class="end-2.5 text-gray-400 bg-transparent hover:bg-gray-200 hover:text-gray-900 rounded-lg text-sm w-8 h-8 ms-auto inline-flex justify-center items-center dark:hover:bg-gray-600 dark:hover:text-white">
                             <svg class="w-3 h-3" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 14 14">
                                 <path stroke="currentColor" stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="m1 1 6 6m0 0 6 6M7 7l6-6M7 7l-6 6"/>
                             </svg>
-                            <span class="sr-only">{"Close modal"}</span>
+                            <span class="sr-only">{ t("a11y.close_modal") }</span>
                         </button>
                     </div>
                     <div class="p-4 md:p-5">
                         <form class="space-y-4" action="#">
                             <p class="text-m font-semibold text-gray-900 dark:text-white">
-                            {"Welcome to Pinepods! Please enter your MFA Code Below."}
+                            { t("login.mfa.body") }
                             </p>
-                            <input oninput={on_mfa_change} type="text" id="mfa_code" name="mfa_code" class="w-full px-3 py-2 text-gray-700 border rounded-lg focus:outline-none" placeholder="Enter MFA Code" />
-                            <button type="submit" onclick={on_mfa_submit} class="w-full text-white bg-blue-700 hover:bg-blue-800 focus:ring-4 focus:outline-none focus:ring-blue-300 font-medium rounded-lg text-sm px-5 py-2.5 text-center dark:bg-blue-600 dark:hover:bg-blue-700 dark:focus:ring-blue-800">{"Submit"}</button>
+                            <input oninput={on_mfa_change} type="text" id="mfa_code" name="mfa_code" class="w-full px-3 py-2 text-gray-700 border rounded-lg focus:outline-none" placeholder={t("login.mfa.placeholder")} />
+                            <button type="submit" onclick={on_mfa_submit} class="w-full text-white bg-blue-700 hover:bg-blue-800 focus:ring-4 focus:outline-none focus:ring-blue-300 font-medium rounded-lg text-sm px-5 py-2.5 text-center dark:bg-blue-600 dark:hover:bg-blue-700 dark:focus:ring-blue-800">{ t("login.submit") }</button>
                         </form>
                     </div>
                 </div>
@@ -1088,19 +1070,19 @@ pub fn login() -> Html {
             <div class="flex justify-center items-center h-screen">
                 <div class="modal-container flex flex-col space-y-4 w-full max-w-xs p-8 border rounded-lg shadow-lg">
                     <div class="flex justify-center items-center">
-                        <img class="object-scale-down h-20 w-66" src="static/assets/favicon.png" alt="Pinepods Logo" />
+                        <img class="object-scale-down h-20 w-66" src="static/assets/favicon.png" alt={t("a11y.pinepods_logo")} />
                     </div>
                     <h1 class="item_container-text text-xl font-bold mb-2 text-center">{"Pinepods"}</h1>
-                    <p class="item_container-text text-center">{"A Forest of Podcasts, Rooted in the Spirit of Self-Hosting"}</p>
+                    <p class="item_container-text text-center">{ t("login.tagline") }</p>
                     <input
                         type="text"
-                        placeholder="Username"
+                        placeholder={t("login.username")}
                         class="search-bar-input border text-sm rounded-lg block w-full p-2.5"
                         oninput={on_login_username_change}
                     />
                     <input
                         type="password"
-                        placeholder="Password"
+                        placeholder={t("login.password")}
                         class="search-bar-input border text-sm rounded-lg block w-full p-2.5"
                         oninput={on_login_password_change}
                     />
@@ -1110,7 +1092,7 @@ pub fn login() -> Html {
                             onclick={on_forgot_password}
                             class="login-link text-sm"
                         >
-                            {"Forgot Password?"}
+                            { t("login.forgot_password_link") }
                         </button>
                         // <button
                         //     onclick={on_create_new_user}
@@ -1125,7 +1107,7 @@ pub fn login() -> Html {
                                         onclick={on_create_new_user.clone()}
                                         class="text-sm login-link"
                                     >
-                                        {"Create New User"}
+                                        { t("login.create_user") }
                                     </button>
                                 }
                             } else {
@@ -1137,7 +1119,7 @@ pub fn login() -> Html {
                         onclick={on_submit_click}
                         class="p-2 download-button rounded"
                     >
-                        {"Login"}
+                        { t("login.login") }
                     </button>
                 </div>
                 {
@@ -1160,7 +1142,7 @@ pub fn login() -> Html {
                         onclick={on_different_server}
                         class="p-2 bg-gray-500 text-white rounded hover:bg-gray-600"
                     >
-                        {"Connect to Different Server"}
+                        { t("login.different_server") }
                     </button>
                 </div>
             </div>
@@ -1283,6 +1265,13 @@ pub fn login() -> Html {
                         let key_copy = login_request.clone();
                         let user_copy = user_details.clone();
                         dispatch.reduce_mut(move |state| {
+                            // Keep the previous account's preferences with its saved profile and drop its cached data
+                            crate::components::profiles::snapshot_active_profile(state);
+                            crate::components::context::clear_entity_caches();
+                            *state = AppState {
+                                reload_occured: state.reload_occured,
+                                ..AppState::default()
+                            };
                             state.user_details = Some(user_details);
                             state.auth_details = Some(login_request);
                             state.server_details = Some(server_details);
@@ -1311,6 +1300,7 @@ pub fn login() -> Html {
                                                 let theme_api = api_key.clone();
                                                 let theme_server = server_name.clone();
                                                 wasm_bindgen_futures::spawn_local(async move {
+                                                    sync_user_locale(&theme_server, theme_api.as_deref().unwrap_or_default(), user_id).await;
                                                     match call_get_theme(theme_server, theme_api.unwrap(), &user_id).await{
                                                         Ok(theme) => {
                                                             crate::components::setting_components::theme_options::changeTheme(&theme);
@@ -1347,7 +1337,7 @@ pub fn login() -> Html {
 
                                         },
                                         Err(_) => {
-                                            post_state.reduce_mut(|state| state.error_message = Option::from("Error Checking MFA Status".to_string()));
+                                            post_state.reduce_mut(|state| state.error_message = Option::from(t("login.error.mfa_status")));
                                         }
                                     }
                                 } else {
@@ -1355,13 +1345,13 @@ pub fn login() -> Html {
                                 }
                             },
                             Err(_) => {
-                                post_state.reduce_mut(|state| state.error_message = Option::from("Error checking first login status".to_string()));
+                                post_state.reduce_mut(|state| state.error_message = Option::from(t("login.error.first_login")));
                             }
                         }
                     },
                     Err(_) => {
                         // console::log_1(&format!("Error logging into server: {}", server_name).into());
-                        post_state.reduce_mut(|state| state.error_message = Option::from("Your credentials appear to be incorrect".to_string()));
+                        post_state.reduce_mut(|state| state.error_message = Option::from(t("login.error.credentials")));
                         // Handle error
                     }
                 }
@@ -1429,7 +1419,7 @@ pub fn login() -> Html {
             if let Ok(value_int) = value_str.parse::<i32>() {
                 time_pref.set(value_int);
             } else {
-                time_state_error.reduce_mut(|state| state.error_message = Option::from("Error parsing time preference".to_string()));
+                time_state_error.reduce_mut(|state| state.error_message = Option::from(t("login.error.time_preference")));
             }
         })
     };
@@ -1482,18 +1472,18 @@ pub fn login() -> Html {
                                     }
                                 },
                                 Err(_) => {
-                                    post_state.reduce_mut(|state| state.error_message = Option::from("Error Checking MFA Status".to_string()));
+                                    post_state.reduce_mut(|state| state.error_message = Option::from(t("login.error.mfa_status")));
                                 }
                             }
                         } else {
-                            post_state.reduce_mut(|state| state.error_message = Option::from("Error Setting up Time Zone".to_string()));
+                            post_state.reduce_mut(|state| state.error_message = Option::from(t("login.error.time_zone")));
                             page_state.set(PageState::Default);
                         }
                     },
                     Err(e) => {
                         page_state.set(PageState::Default);
                         // dispatch.reduce_mut(|state| state.error_message = Option::from(format!("Error setting up time zone: {:?}", e)));
-                        post_state.reduce_mut(|state| state.error_message = Option::from(format!("Error setting up time zone: {:?}", e)));
+                        post_state.reduce_mut(|state| state.error_message = Option::from(t_args("login.error.time_zone_detail", &[("error", &format!("{:?}", e))])));
                     }
                 }
             });
@@ -1512,35 +1502,35 @@ pub fn login() -> Html {
                 <div class="modal-container relative rounded-lg shadow">
                     <div class="flex items-center justify-between p-4 md:p-5 border-b rounded-t dark:border-gray-600">
                         <h3 class="text-xl font-semibold">
-                            {"Time Zone Setup"}
+                            { t("login.time_zone.title") }
                         </h3>
                         <button onclick={on_close_modal.clone()} class="end-2.5 text-gray-400 bg-transparent hover:bg-gray-200 hover:text-gray-900 rounded-lg text-sm w-8 h-8 ms-auto inline-flex justify-center items-center dark:hover:bg-gray-600 dark:hover:text-white">
                             <svg class="w-3 h-3" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 14 14">
                                 <path stroke="currentColor" stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="m1 1 6 6m0 0 6 6M7 7l6-6M7 7l-6 6"/>
                             </svg>
-                            <span class="sr-only">{"Close modal"}</span>
+                            <span class="sr-only">{ t("a11y.close_modal") }</span>
                         </button>
                     </div>
                     <div class="p-4 md:p-5">
                         <form class="space-y-4" action="#">
                             <p class="text-m font-semibold">
-                            {"Welcome to Pinepods! This appears to be your first time logging in. To start, let's get some basic information about your time and time zone preferences. This will determine how times appear throughout the app."}
+                            { t("login.time_zone.body") }
                             </p>
                             <div>
-                                <label for="hour_format" style="margin-right: 10px;">{"Hour Format:"}</label>
+                                <label for="hour_format" style="margin-right: 10px;">{ t("login.time_zone.hour_format") }</label>
                                 <select id="hour_format" name="hour_format" class="email-select border p-2 rounded" oninput={on_time_pref_change}>
-                                    <option value="12">{"12 Hour"}</option>
-                                    <option value="24">{"24 Hour"}</option>
+                                    <option value="12">{ t("login.time_zone.hour_12") }</option>
+                                    <option value="24">{ t("login.time_zone.hour_24") }</option>
                                 </select>
                             </div>
                             <div>
-                                <label for="time_zone" style="margin-right: 10px;">{"Time Zone:"}</label>
+                                <label for="time_zone" style="margin-right: 10px;">{ t("login.time_zone.time_zone") }</label>
                                 <select id="time_zone" name="time_zone" class="email-select border p-2 rounded" oninput={on_tz_change}>
                                     { for TZ_VARIANTS.iter().map(|tz| render_time_zone_option(*tz)) }
                                 </select>
                             </div>
                             <div>
-                            <label for="date_format" style="margin-right: 10px;">{"Date Format:"}</label>
+                            <label for="date_format" style="margin-right: 10px;">{ t("login.time_zone.date_format") }</label>
                             <select id="date_format" name="date_format" class="email-select border p-2 rounded" oninput={on_df_change}>
                                 <option value="MDY">{"MDY (MM-DD-YYYY)"}</option>
                                 <option value="DMY">{"DMY (DD-MM-YYYY)"}</option>
@@ -1552,7 +1542,7 @@ pub fn login() -> Html {
                                 <option value="JIS">{"JIS (YYYY-MM-DD)"}</option>
                             </select>
                         </div>
-                            <button type="submit" onclick={on_time_zone_submit} class="download-button w-full focus:ring-4 focus:outline-none font-medium rounded-lg text-sm px-5 py-2.5 text-center">{"Submit"}</button>
+                            <button type="submit" onclick={on_time_zone_submit} class="download-button w-full focus:ring-4 focus:outline-none font-medium rounded-lg text-sm px-5 py-2.5 text-center">{ t("login.submit") }</button>
                         </form>
                     </div>
                 </div>
@@ -1597,6 +1587,9 @@ pub fn login() -> Html {
                             let theme_api = api_key.clone();
                             let theme_server = server_name.clone();
                             wasm_bindgen_futures::spawn_local(async move {
+                                if let (Some(server), Some(Some(api)), Some(id)) = (&theme_server, &theme_api, user_id) {
+                                    sync_user_locale(server, api, id).await;
+                                }
                                 match call_get_theme(theme_server.unwrap(), theme_api.unwrap().unwrap(), &user_id.unwrap()).await{
                                     Ok(theme) => {
                                         crate::components::setting_components::theme_options::changeTheme(&theme);
@@ -1631,13 +1624,13 @@ pub fn login() -> Html {
                             history.push("/home"); // Use the route path
                         } else {
                             page_state.set(PageState::Default);
-                            post_state.reduce_mut(|state| state.error_message = Option::from(format!("Error validating MFA Code")));
+                            post_state.reduce_mut(|state| state.error_message = Option::from(t("login.error.mfa_validate")));
 
                         }
                     }
                     Err(e) => {
                         page_state.set(PageState::Default);
-                        post_state.reduce_mut(|state| state.error_message = Option::from(format!("Error setting up time zone: {:?}", e)));
+                        post_state.reduce_mut(|state| state.error_message = Option::from(t_args("login.error.time_zone_detail", &[("error", &format!("{:?}", e))])));
 
                     }
                 }
@@ -1651,22 +1644,22 @@ pub fn login() -> Html {
                 <div class="relative bg-white rounded-lg shadow dark:bg-gray-700">
                     <div class="flex items-center justify-between p-4 md:p-5 border-b rounded-t dark:border-gray-600">
                         <h3 class="text-xl font-semibold text-gray-900 dark:text-white">
-                            {"MFA Login"}
+                            { t("login.mfa.title") }
                         </h3>
                         <button onclick={on_close_modal.clone()} class="end-2.5 text-gray-400 bg-transparent hover:bg-gray-200 hover:text-gray-900 rounded-lg text-sm w-8 h-8 ms-auto inline-flex justify-center items-center dark:hover:bg-gray-600 dark:hover:text-white">
                             <svg class="w-3 h-3" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 14 14">
                                 <path stroke="currentColor" stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="m1 1 6 6m0 0 6 6M7 7l6-6M7 7l-6 6"/>
                             </svg>
-                            <span class="sr-only">{"Close modal"}</span>
+                            <span class="sr-only">{ t("a11y.close_modal") }</span>
                         </button>
                     </div>
                     <div class="p-4 md:p-5">
                         <form class="space-y-4" action="#">
                             <p class="text-m font-semibold text-gray-900 dark:text-white">
-                            {"Welcome to Pinepods! Please enter your MFA Code Below."}
+                            { t("login.mfa.body") }
                             </p>
-                            <input oninput={on_mfa_change} type="text" id="mfa_code" name="mfa_code" class="w-full px-3 py-2 text-gray-700 border rounded-lg focus:outline-none" placeholder="Enter MFA Code" />
-                            <button type="submit" onclick={on_mfa_submit} class="w-full text-white bg-blue-700 hover:bg-blue-800 focus:ring-4 focus:outline-none focus:ring-blue-300 font-medium rounded-lg text-sm px-5 py-2.5 text-center dark:bg-blue-600 dark:hover:bg-blue-700 dark:focus:ring-blue-800">{"Submit"}</button>
+                            <input oninput={on_mfa_change} type="text" id="mfa_code" name="mfa_code" class="w-full px-3 py-2 text-gray-700 border rounded-lg focus:outline-none" placeholder={t("login.mfa.placeholder")} />
+                            <button type="submit" onclick={on_mfa_submit} class="w-full text-white bg-blue-700 hover:bg-blue-800 focus:ring-4 focus:outline-none focus:ring-blue-300 font-medium rounded-lg text-sm px-5 py-2.5 text-center dark:bg-blue-600 dark:hover:bg-blue-700 dark:focus:ring-blue-800">{ t("login.submit") }</button>
                         </form>
                     </div>
                 </div>
@@ -1688,33 +1681,33 @@ pub fn login() -> Html {
         <div class="flex justify-center items-center h-screen">
             <div class="modal-container flex flex-col space-y-4 w-full max-w-xs p-8 border rounded-lg shadow-lg">
                 <div class="flex justify-center items-center">
-                    <img class="object-scale-down h-20 w-66" src="static/assets/favicon.png" alt="Pinepods Logo" />
+                    <img class="object-scale-down h-20 w-66" src="static/assets/favicon.png" alt={t("a11y.pinepods_logo")} />
                 </div>
                 <h1 class="item_container-text text-xl font-bold mb-2 text-center">{"Pinepods"}</h1>
-                <p class="item_container-text text-center">{"A Forest of Podcasts, Rooted in the Spirit of Self-Hosting"}</p>
+                <p class="item_container-text text-center">{ t("login.tagline") }</p>
                 <input
                     type="text"
-                    placeholder="Server Name"
+                    placeholder={t("login.server_name")}
                     class="search-bar-input border text-sm rounded-lg block w-full p-2.5"
                     oninput={on_server_name_change}
                     onkeypress={handle_key_press.clone()}
                 />
                 <input
                     type="text"
-                    placeholder="Username"
+                    placeholder={t("login.username")}
                     class="search-bar-input border text-sm rounded-lg block w-full p-2.5"
                     oninput={on_username_change}
                     onkeypress={handle_key_press.clone()}
                 />
                 <input
                     type="password"
-                    placeholder="Password"
+                    placeholder={t("login.password")}
                     class="search-bar-input border text-sm rounded-lg block w-full p-2.5"
                     oninput={on_password_change}
                     onkeypress={handle_key_press.clone()}
                 />
                 <button onclick={on_submit_click} class="p-2 download-button rounded">
-                    {"Login"}
+                    { t("login.login") }
                 </button>
             </div>
             // Conditional rendering for the error banner
@@ -1728,7 +1721,7 @@ pub fn login() -> Html {
             // Connect to Different Server button at bottom right
             <div class="fixed bottom-4 right-4">
                 <button onclick={on_different_server} class="p-2 bg-gray-500 text-white rounded hover:bg-gray-600">
-                    {"Connect to Local Server"}
+                    { t("login.local_server") }
                 </button>
             </div>
         </div>
@@ -1742,22 +1735,8 @@ pub fn login() -> Html {
 pub fn logout() -> Html {
     let history = BrowserHistory::new();
 
-    // Clear local and session storage except for 'user_theme'
-    let window = web_sys::window().expect("no global `window` exists");
-    let local_storage = window.local_storage().expect("localStorage not enabled").expect("localStorage is null");
-    let session_storage = window.session_storage().expect("sessionStorage not enabled").expect("sessionStorage is null");
-
-    // Save 'user_theme' value
-    let selected_theme = local_storage.get_item("selected_theme").expect("failed to get 'selected_theme'");
-
-    // Clear storages
-    local_storage.clear().expect("failed to clear localStorage");
-    session_storage.clear().expect("failed to clear sessionStorage");
-
-    // Restore 'user_theme' value
-    if let Some(theme) = selected_theme {
-        local_storage.set_item("selected_theme", &theme).expect("failed to set 'selected_theme'");
-    }
+    // Forget the active account but keep the other saved profiles and the theme
+    crate::components::profiles::clear_active_session();
 
     // Redirect to root path
     history.push("/");
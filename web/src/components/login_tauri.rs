@@ -6,10 +6,12 @@ use yew_router::history::{BrowserHistory, History};
 use crate::requests::login_requests::{self, call_check_mfa_enabled};
 use crate::requests::login_requests::{TimeZoneInfo, call_first_login_done, call_setup_timezone_info, call_verify_mfa, call_self_service_login_status, call_reset_password_create_code, ResetCodePayload, ResetForgotPasswordPayload, call_verify_and_reset_password, call_get_time_info, call_verify_key};
 use crate::components::context::{AppState, UIState};
+use crate::components::persistence::load_persisted_state;
 use md5;
 use yewdux::prelude::*;
 use crate::requests::login_requests::{AddUserRequest, call_add_login_user};
 use crate::requests::setting_reqs::call_get_theme;
+use crate::components::i18n::{sync_user_locale, t, t_args};
 use crate::components::gen_funcs::{encode_password, validate_user_input, ValidationError};
 use crate::components::episodes_layout::UIStateMsg;
 use chrono_tz::{TZ_VARIANTS, Tz};
@@ -117,111 +119,87 @@ pub fn login() -> Html {
                             crate::components::setting_components::theme_options::changeTheme(&stored_theme);
                         }
 
-                        if let Ok(Some(user_state)) = storage.get_item("userState") {
-                            let app_state_result = AppState::deserialize(&user_state);
-
-                            if let Ok(Some(auth_state)) = storage.get_item("userAuthState") {
-                                match AppState::deserialize(&auth_state) {
-                                    Ok(auth_details) => { // Successful deserialization of auth state
-                                        if let Ok(Some(server_state)) = storage.get_item("serverState") {
-                                            let server_details_result = AppState::deserialize(&server_state);
-
-                                            if let Ok(app_state) = app_state_result { // Successful deserialization of user state
-                                                if let Ok(server_details) = server_details_result { // Successful deserialization of server state
-                                                    // Check if the deserialized state contains valid data
-                                                    if app_state.user_details.is_some() && auth_details.auth_details.is_some() && server_details.server_details.is_some() {
-
-                                                        let auth_state_clone = auth_details.clone();
-                                                        let email = &app_state.user_details.as_ref().unwrap().Email;
-                                                        let user_id = app_state.user_details.as_ref().unwrap().UserID.clone();
-                                                        // Safely access server_name and api_key
-                                                        let auth_details_clone = auth_state_clone.auth_details.clone();
-                                                        if let Some(auth_details) = auth_details_clone.as_ref() {
-                                                            let server_name = auth_details.server_name.clone();
-                                                            let api_key = auth_details.api_key.clone().unwrap_or_default();
-                                                            
-                                                            // Now verify the API key
-                                                            // let wasm_user_id = user_id.clone();
-                                                            let wasm_app_state = app_state.clone();
-                                                            let wasm_auth_details: login_requests::LoginServerRequest = auth_details.clone();
-                                                            let wasm_email = email.clone();
-                                                            let wasm_user_id = user_id.clone();
-                                                            wasm_bindgen_futures::spawn_local(async move {
-                                                                match call_verify_key(&server_name.clone(), &api_key.clone()).await {
-                                                                    Ok(_) => {
-                                                                        // API key is valid, user can stay logged in
-                                                                        let final_dispatch = effect_displatch.clone();
-                                                                        let gravatar_url = generate_gravatar_url(&Some(wasm_email.clone().unwrap()), 80);
-                                                                        // Auto login logic here
-                                                                        final_dispatch.reduce_mut(move |state| {
-                                                                            state.user_details = wasm_app_state.user_details;
-                                                                            state.auth_details = Some(wasm_auth_details.clone());
-                                                                            state.server_details = server_details.server_details;
-                                                                            state.gravatar_url = Some(gravatar_url);
-                
-                                                                        });
-                                                                        // let mut error_message = app_state.error_message;
-                                                                        // Retrieve the originally requested route, if any
-                                                                        let session_storage = window.session_storage().unwrap().unwrap();
-                                                                        session_storage.set_item("isAuthenticated", "true").unwrap();
-                                                                        let requested_route = session_storage.get_item("requested_route").unwrap_or(None);
-                                                                        // Get Theme
-                                                                        let theme_api = api_key.clone();
-                                                                        let theme_server = server_name.clone();
-                                                                        wasm_bindgen_futures::spawn_local(async move {
-                                                                            match call_get_theme(theme_server, theme_api, &wasm_user_id).await{
-                                                                                Ok(theme) => {
-                                                                                    crate::components::setting_components::theme_options::changeTheme(&theme);
-                                                                                    if let Some(window) = web_sys::window() {
-                                                                                        if let Ok(Some(local_storage)) = window.local_storage() {
-                                                                                            match local_storage.set_item("selected_theme", &theme) {
-                                                                                                Ok(_) => console::log_1(&"Updated theme in local storage".into()),
-                                                                                                Err(e) => console::log_1(&format!("Error updating theme in local storage: {:?}", e).into()),
-                                                                                            }
-                                                                                        }
-                                                                                    }
-                                                                                }
-                                                                                Err(_e) => {
-                                                                                    // console::log_1(&format!("Error getting theme: {:?}", e).into());
-                                                                                }
-                                                                            }
-                                                                        });
-                                                                        wasm_bindgen_futures::spawn_local(async move {
-                                                                            match call_get_time_info(server_name, api_key, &wasm_user_id).await{
-                                                                                Ok(tz_response) => {
-                                                                                    effect_displatch.reduce_mut(move |state| {
-                                                                                        state.user_tz = Some(tz_response.timezone);
-                                                                                        state.hour_preference = Some(tz_response.hour_pref);
-                                                                                        state.date_format = Some(tz_response.date_format);
-                                                                                    });
-                                                                                }
-                                                                                Err(_e) => {
-                                                                                    // console::log_1(&format!("Error getting theme: {:?}", e).into());
-                                                                                }
-                                                                            }
-                                                                        });
-                                                                        let redirect_route = requested_route.unwrap_or_else(|| "/home".to_string());
-                                                                        history.push(&redirect_route); // Redirect to the requested or home page
-                                                                    }
-                                                                    Err(_) => {
-                                                                        // API key is not valid, redirect to login
-                                                                        history.push("/");
-                                                                    }
+                        if let Some(persisted) = load_persisted_state() {
+                            if let (Some(user_details), Some(auth_details), Some(server_details)) = (
+                                persisted.user_details.clone(),
+                                persisted.auth_details.clone(),
+                                persisted.server_details.clone(),
+                            ) {
+                                let server_name = auth_details.server_name.clone();
+                                let api_key = auth_details.api_key.clone().unwrap_or_default();
+                                let wasm_user_id = user_details.UserID.clone();
+                                let wasm_email = user_details.Email.clone();
+                                wasm_bindgen_futures::spawn_local(async move {
+                                    match call_verify_key(&server_name.clone(), &api_key.clone()).await {
+                                        Ok(_) => {
+                                            // API key is valid, user can stay logged in
+                                            let final_dispatch = effect_displatch.clone();
+                                            let gravatar_url = generate_gravatar_url(&wasm_email, 80);
+                                            // Auto login logic here. Cached preferences and library data are shown
+                                            // straight away and replaced once the fresh copies arrive.
+                                            let cached = persisted.clone();
+                                            final_dispatch.reduce_mut(move |state| {
+                                                state.user_details = Some(user_details);
+                                                state.auth_details = Some(auth_details);
+                                                state.server_details = Some(server_details);
+                                                state.gravatar_url = Some(gravatar_url);
+                                                cached.apply_cached(state);
+                                            });
+                                            // Retrieve the originally requested route, if any
+                                            let session_storage = window.session_storage().unwrap().unwrap();
+                                            session_storage.set_item("isAuthenticated", "true").unwrap();
+                                            let requested_route = session_storage.get_item("requested_route").unwrap_or(None);
+                                            // Get Theme
+                                            let theme_api = api_key.clone();
+                                            let theme_server = server_name.clone();
+                                            wasm_bindgen_futures::spawn_local(async move {
+                                                sync_user_locale(&theme_server, &theme_api, wasm_user_id).await;
+                                                match call_get_theme(theme_server, theme_api, &wasm_user_id).await{
+                                                    Ok(theme) => {
+                                                        crate::components::setting_components::theme_options::changeTheme(&theme);
+                                                        if let Some(window) = web_sys::window() {
+                                                            if let Ok(Some(local_storage)) = window.local_storage() {
+                                                                match local_storage.set_item("selected_theme", &theme) {
+                                                                    Ok(_) => console::log_1(&"Updated theme in local storage".into()),
+                                                                    Err(e) => console::log_1(&format!("Error updating theme in local storage: {:?}", e).into()),
                                                                 }
-                                                            });
-
-                                                        } else {
-                                                            console::log_1(&"Auth details are None".into());
+                                                            }
                                                         }
                                                     }
+                                                    Err(_e) => {
+                                                        // console::log_1(&format!("Error getting theme: {:?}", e).into());
+                                                    }
                                                 }
-                                            }
+                                            });
+                                            wasm_bindgen_futures::spawn_local(async move {
+                                                match call_get_time_info(server_name, api_key, &wasm_user_id).await{
+                                                    Ok(tz_response) => {
+                                                        effect_displatch.reduce_mut(move |state| {
+                                                            state.user_tz = Some(tz_response.timezone);
+                                                            state.hour_preference = Some(tz_response.hour_pref);
+                                                            state.date_format = Some(tz_response.date_format);
+                                                            state.store_app_state();
+                                                        });
+                                                    }
+                                                    Err(_e) => {
+                                                        // console::log_1(&format!("Error getting theme: {:?}", e).into());
+                                                    }
+                                                }
+                                            });
+                                            // Requested route wins, then wherever the user was last time
+                                            let redirect_route = requested_route
+                                                .or(persisted.last_route)
+                                                .unwrap_or_else(|| "/home".to_string());
+                                            history.push(&redirect_route);
+                                        }
+                                        Err(_) => {
+                                            // API key is not valid, redirect to login
+                                            history.push("/");
                                         }
-                                    },
-                                    Err(e) => {
-                                        web_sys::console::log_1(&format!("Error deserializing auth state: {:?}", e).into());
                                     }
-                                }
+                                });
+                            } else {
+                                console::log_1(&"Persisted state is missing login details".into());
                             }
                         }
                     }
@@ -328,6 +306,7 @@ pub fn login() -> Html {
                                                 let theme_api = api_key.clone();
                                                 let theme_server = server_name.clone();
                                                 wasm_bindgen_futures::spawn_local(async move {
+                                                    sync_user_locale(&theme_server, theme_api.as_deref().unwrap_or_default(), user_id).await;
                                                     match call_get_theme(theme_server, theme_api.unwrap(), &user_id).await{
                                                         Ok(theme) => {
                                                             crate::components::setting_components::theme_options::changeTheme(&theme);
@@ -364,7 +343,7 @@ pub fn login() -> Html {
 
                                         },
                                         Err(_) => {
-                                            post_state.reduce_mut(|state| state.error_message = Option::from("Error Checking MFA Status".to_string()));
+                                            post_state.reduce_mut(|state| state.error_message = Option::from(t("login.error.mfa_status")));
                                         }
                                     }
                                 } else {
@@ -372,13 +351,13 @@ pub fn login() -> Html {
                                 }
                             },
                             Err(_) => {
-                                post_state.reduce_mut(|state| state.error_message = Option::from("Error checking first login status".to_string()));
+                                post_state.reduce_mut(|state| state.error_message = Option::from(t("login.error.first_login")));
                             }
                         }
                     },
                     Err(_) => {
                         // console::log_1(&format!("Error logging into server: {}", server_name).into());
-                        post_state.reduce_mut(|state| state.error_message = Option::from("Your credentials appear to be incorrect".to_string()));
+                        post_state.reduce_mut(|state| state.error_message = Option::from(t("login.error.credentials")));
                         // Handle error
                     }
                 }
@@ -441,7 +420,7 @@ pub fn login() -> Html {
             if let Ok(value_int) = value_str.parse::<i32>() {
                 time_pref.set(value_int);
             } else {
-                time_state_error.reduce_mut(|state| state.error_message = Option::from("Error parsing time preference".to_string()));
+                time_state_error.reduce_mut(|state| state.error_message = Option::from(t("login.error.time_preference")));
             }
         })
     };
@@ -494,18 +473,18 @@ pub fn login() -> Html {
                                     }
                                 },
                                 Err(_) => {
-                                    post_state.reduce_mut(|state| state.error_message = Option::from("Error Checking MFA Status".to_string()));
+                                    post_state.reduce_mut(|state| state.error_message = Option::from(t("login.error.mfa_status")));
                                 }
                             }
                         } else {
-                            post_state.reduce_mut(|state| state.error_message = Option::from("Error Setting up Time Zone".to_string()));
+                            post_state.reduce_mut(|state| state.error_message = Option::from(t("login.error.time_zone")));
                             page_state.set(PageState::Default);
                         }
                     },
                     Err(e) => {
                         page_state.set(PageState::Default);
                         // dispatch.reduce_mut(|state| state.error_message = Option::from(format!("Error setting up time zone: {:?}", e)));
-                        post_state.reduce_mut(|state| state.error_message = Option::from(format!("Error setting up time zone: {:?}", e)));
+                        post_state.reduce_mut(|state| state.error_message = Option::from(t_args("login.error.time_zone_detail", &[("error", &format!("{:?}", e))])));
                     }
                 }
             });
@@ -524,35 +503,35 @@ pub fn login() -> Html {
                 <div class="modal-container relative rounded-lg shadow">
                     <div class="flex items-center justify-between p-4 md:p-5 border-b rounded-t dark:border-gray-600">
                         <h3 class="text-xl font-semibold">
-                            {"Time Zone Setup"}
+                            { t("login.time_zone.title") }
                         </h3>
                         <button onclick={on_close_modal.clone()} class="end-2.5 text-gray-400 bg-transparent hover:bg-gray-200 hover:text-gray-900 rounded-lg text-sm w-8 h-8 ms-auto inline-flex justify-center items-center dark:hover:bg-gray-600 dark:hover:text-white">
                             <svg class="w-3 h-3" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 14 14">
                                 <path stroke="currentColor" stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="m1 1 6 6m0 0 6 6M7 7l6-6M7 7l-6 6"/>
                             </svg>
-                            <span class="sr-only">{"Close modal"}</span>
+                            <span class="sr-only">{ t("a11y.close_modal") }</span>
                         </button>
                     </div>
                     <div class="p-4 md:p-5">
                         <form class="space-y-4" action="#">
                             <p class="text-m font-semibold">
-                            {"Welcome to Pinepods! This appears to be your first time logging in. To start, let's get some basic information about your time and time zone preferences. This will determine how times appear throughout the app."}
+                            { t("login.time_zone.body") }
                             </p>
                             <div>
-                                <label for="hour_format" style="margin-right: 10px;">{"Hour Format:"}</label>
+                                <label for="hour_format" style="margin-right: 10px;">{ t("login.time_zone.hour_format") }</label>
                                 <select id="hour_format" name="hour_format" class="email-select border p-2 rounded" oninput={on_time_pref_change}>
-                                    <option value="12">{"12 Hour"}</option>
-                                    <option value="24">{"24 Hour"}</option>
+                                    <option value="12">{ t("login.time_zone.hour_12") }</option>
+                                    <option value="24">{ t("login.time_zone.hour_24") }</option>
                                 </select>
                             </div>
                             <div>
-                                <label for="time_zone" style="margin-right: 10px;">{"Time Zone:"}</label>
+                                <label for="time_zone" style="margin-right: 10px;">{ t("login.time_zone.time_zone") }</label>
                                 <select id="time_zone" name="time_zone" class="email-select border p-2 rounded" oninput={on_tz_change}>
                                     { for TZ_VARIANTS.iter().map(|tz| render_time_zone_option(*tz)) }
                                 </select>
                             </div>
                             <div>
-                            <label for="date_format" style="margin-right: 10px;">{"Date Format:"}</label>
+                            <label for="date_format" style="margin-right: 10px;">{ t("login.time_zone.date_format") }</label>
                             <select id="date_format" name="date_format" class="email-select border p-2 rounded" oninput={on_df_change}>
                                 <option value="MDY">{"MDY (MM-DD-YYYY)"}</option>
                                 <option value="DMY">{"DMY (DD-MM-YYYY)"}</option>
@@ -564,7 +543,7 @@ pub fn login() -> Html {
                                 <option value="JIS">{"JIS (YYYY-MM-DD)"}</option>
                             </select>
                         </div>
-                            <button type="submit" onclick={on_time_zone_submit} class="download-button w-full focus:ring-4 focus:outline-none font-medium rounded-lg text-sm px-5 py-2.5 text-center">{"Submit"}</button>
+                            <button type="submit" onclick={on_time_zone_submit} class="download-button w-full focus:ring-4 focus:outline-none font-medium rounded-lg text-sm px-5 py-2.5 text-center">{ t("login.submit") }</button>
                         </form>
                     </div>
                 </div>
@@ -609,6 +588,9 @@ pub fn login() -> Html {
                             let theme_api = api_key.clone();
                             let theme_server = server_name.clone();
                             wasm_bindgen_futures::spawn_local(async move {
+                                if let (Some(server), Some(Some(api)), Some(id)) = (&theme_server, &theme_api, user_id) {
+                                    sync_user_locale(server, api, id).await;
+                                }
                                 match call_get_theme(theme_server.unwrap(), theme_api.unwrap().unwrap(), &user_id.unwrap()).await{
                                     Ok(theme) => {
                                         crate::components::setting_components::theme_options::changeTheme(&theme);
@@ -643,13 +625,13 @@ pub fn login() -> Html {
                             history.push("/home"); // Use the route path
                         } else {
                             page_state.set(PageState::Default);
-                            post_state.reduce_mut(|state| state.error_message = Option::from(format!("Error validating MFA Code")));
+                            post_state.reduce_mut(|state| state.error_message = Option::from(t("login.error.mfa_validate")));
 
                         }
                     }
                     Err(e) => {
                         page_state.set(PageState::Default);
-                        post_state.reduce_mut(|state| state.error_message = Option::from(format!("Error setting up time zone: {:?}", e)));
+                        post_state.reduce_mut(|state| state.error_message = Option::from(t_args("login.error.time_zone_detail", &[("error", &format!("{:?}", e))])));
 
                     }
                 }
@@ -663,22 +645,22 @@ pub fn login() -> Html {
                 <div class="relative bg-white rounded-lg shadow dark:bg-gray-700">
                     <div class="flex items-center justify-between p-4 md:p-5 border-b rounded-t dark:border-gray-600">
                         <h3 class="text-xl font-semibold text-gray-900 dark:text-white">
-                            {"MFA Login"}
+                            { t("login.mfa.title") }
                         </h3>
                         <button onclick={on_close_modal.clone()} class="end-2.5 text-gray-400 bg-transparent hover:bg-gray-200 hover:text-gray-900 rounded-lg text-sm w-8 h-8 ms-auto inline-flex justify-center items-center dark:hover:bg-gray-600 dark:hover:text-white">
                             <svg class="w-3 h-3" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 14 14">
                                 <path stroke="currentColor" stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="m1 1 6 6m0 0 6 6M7 7l6-6M7 7l-6 6"/>
                             </svg>
-                            <span class="sr-only">{"Close modal"}</span>
+                            <span class="sr-only">{ t("a11y.close_modal") }</span>
                         </button>
                     </div>
                     <div class="p-4 md:p-5">
                         <form class="space-y-4" action="#">
                             <p class="text-m font-semibold text-gray-900 dark:text-white">
-                            {"Welcome to Pinepods! Please enter your MFA Code Below."}
+                            { t("login.mfa.body") }
                             </p>
-                            <input oninput={on_mfa_change} type="text" id="mfa_code" name="mfa_code" class="w-full px-3 py-2 text-gray-700 border rounded-lg focus:outline-none" placeholder="Enter MFA Code" />
-                            <button type="submit" onclick={on_mfa_submit} class="w-full text-white bg-blue-700 hover:bg-blue-800 focus:ring-4 focus:outline-none focus:ring-blue-300 font-medium rounded-lg text-sm px-5 py-2.5 text-center dark:bg-blue-600 dark:hover:bg-blue-700 dark:focus:ring-blue-800">{"Submit"}</button>
+                            <input oninput={on_mfa_change} type="text" id="mfa_code" name="mfa_code" class="w-full px-3 py-2 text-gray-700 border rounded-lg focus:outline-none" placeholder={t("login.mfa.placeholder")} />
+                            <button type="submit" onclick={on_mfa_submit} class="w-full text-white bg-blue-700 hover:bg-blue-800 focus:ring-4 focus:outline-none focus:ring-blue-300 font-medium rounded-lg text-sm px-5 py-2.5 text-center dark:bg-blue-600 dark:hover:bg-blue-700 dark:focus:ring-blue-800">{ t("login.submit") }</button>
                         </form>
                     </div>
                 </div>
@@ -700,33 +682,33 @@ pub fn login() -> Html {
         <div class="flex justify-center items-center h-screen">
             <div class="modal-container flex flex-col space-y-4 w-full max-w-xs p-8 border rounded-lg shadow-lg">
                 <div class="flex justify-center items-center">
-                    <img class="object-scale-down h-20 w-66" src="static/assets/favicon.png" alt="Pinepods Logo" />
+                    <img class="object-scale-down h-20 w-66" src="static/assets/favicon.png" alt={t("a11y.pinepods_logo")} />
                 </div>
                 <h1 class="item_container-text text-xl font-bold mb-2 text-center">{"Pinepods"}</h1>
-                <p class="item_container-text text-center">{"A Forest of Podcasts, Rooted in the Spirit of Self-Hosting"}</p>
+                <p class="item_container-text text-center">{ t("login.tagline") }</p>
                 <input
                     type="text"
-                    placeholder="Server Name"
+                    placeholder={t("login.server_name")}
                     class="search-bar-input border text-sm rounded-lg block w-full p-2.5"
                     oninput={on_server_name_change}
                     onkeypress={handle_key_press.clone()}
                 />
                 <input
                     type="text"
-                    placeholder="Username"
+                    placeholder={t("login.username")}
                     class="search-bar-input border text-sm rounded-lg block w-full p-2.5"
                     oninput={on_username_change}
                     onkeypress={handle_key_press.clone()}
                 />
                 <input
                     type="password"
-                    placeholder="Password"
+                    placeholder={t("login.password")}
                     class="search-bar-input border text-sm rounded-lg block w-full p-2.5"
                     oninput={on_password_change}
                     onkeypress={handle_key_press.clone()}
                 />
                 <button onclick={on_submit_click} class="p-2 download-button rounded">
-                    {"Login"}
+                    { t("login.login") }
                 </button>
             </div>
             // Conditional rendering for the error banner
@@ -754,22 +736,8 @@ pub fn login() -> Html {
 pub fn logout() -> Html {
     let history = BrowserHistory::new();
 
-    // Clear local and session storage except for 'user_theme'
-    let window = web_sys::window().expect("no global `window` exists");
-    let local_storage = window.local_storage().expect("localStorage not enabled").expect("localStorage is null");
-    let session_storage = window.session_storage().expect("sessionStorage not enabled").expect("sessionStorage is null");
-
-    // Save 'user_theme' value
-    let selected_theme = local_storage.get_item("selected_theme").expect("failed to get 'selected_theme'");
-
-    // Clear storages
-    local_storage.clear().expect("failed to clear localStorage");
-    session_storage.clear().expect("failed to clear sessionStorage");
-
-    // Restore 'user_theme' value
-    if let Some(theme) = selected_theme {
-        local_storage.set_item("selected_theme", &theme).expect("failed to set 'selected_theme'");
-    }
+    // Forget the active account but keep the other saved profiles and the theme
+    crate::components::profiles::clear_active_session();
 
     // Redirect to root path
     history.push("/");
@@ -10,6 +10,11 @@ pub(crate) mod queue;
 pub(crate) mod search;
 pub(crate) mod routes;
 pub(crate) mod home;
+pub(crate) mod home_rails;
+pub(crate) mod notifications;
+pub(crate) mod stats_charts;
+pub(crate) mod goals;
+pub(crate) mod year_review;
 
 pub(crate) mod context;
 pub(crate) mod gen_components;
@@ -18,10 +23,24 @@ pub(crate) mod search_new;
 pub(crate) mod podcast_layout;
 pub(crate) mod episodes_layout;
 pub(crate) mod podcasts;
+pub(crate) mod podcast_settings;
+pub(crate) mod folders;
+pub(crate) mod virtual_list;
+pub(crate) mod selection;
+pub(crate) mod catch_up;
+pub(crate) mod queue_order;
+pub(crate) mod playlists;
+pub(crate) mod podcast_page;
+pub(crate) mod incremental_search;
+pub(crate) mod library_index;
 mod audio;
 mod click_events;
 pub mod gen_funcs;
+pub mod i18n;
+pub(crate) mod a11y;
 pub(crate) mod episode;
+pub(crate) mod profiles;
+pub(crate) mod persistence;
 pub mod setting_components;
 
 #[cfg(feature = "server_build")]
@@ -0,0 +1,392 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use gloo_events::EventListener;
+use gloo_timers::callback::Interval;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::window;
+use yew::prelude::*;
+use yew_router::history::{BrowserHistory, History};
+use yew_router::Routable;
+use yewdux::prelude::*;
+use crate::components::context::AppState;
+use crate::components::goals::goals_available;
+use crate::components::i18n::{t, t_args, t_plural};
+use crate::components::persistence::{load_user_preference, save_user_preference, user_key};
+use crate::components::routes::Route;
+use crate::requests::pod_req::{call_get_notification_episodes, NotificationEpisode};
+use crate::requests::stat_reqs::call_get_listening_goals;
+
+// New episode notifications. Podcasts opt in through their own settings; whether this device
+// shows notifications at all, and when, is decided here. A background check compares the newest
+// publish date of each opted in podcast with the last one seen and notifies about the difference.
+
+const NOTIFICATION_PREFERENCE: &str = "notifications";
+const NOTIFICATION_LEDGER: &str = "notification_ledger";
+const CHECK_INTERVAL_MS: u32 = 15 * 60 * 1000;
+// More new episodes than this at once always arrive as a single digest
+const DIGEST_THRESHOLD: usize = 3;
+// How long after a desktop notification focusing the window counts as clicking it
+const DESKTOP_CLICK_WINDOW_MS: f64 = 5.0 * 60.0 * 1000.0;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct NotificationPreferences {
+    pub enabled: bool,
+    pub quiet_hours: bool,
+    // Local hours, the end is exclusive and may be earlier than the start to span midnight
+    pub quiet_start: u32,
+    pub quiet_end: u32,
+    // Combine everything found in one check into a single notification
+    pub digest: bool,
+    // Remind once a day, from this local hour, when the listening streak hasn't been kept up
+    pub streak_reminder: bool,
+    pub streak_reminder_hour: u32,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        NotificationPreferences {
+            enabled: false,
+            quiet_hours: false,
+            quiet_start: 22,
+            quiet_end: 7,
+            digest: false,
+            streak_reminder: false,
+            streak_reminder_hour: 20,
+        }
+    }
+}
+
+impl NotificationPreferences {
+    pub fn load(user_key: &str) -> Self {
+        load_user_preference(user_key, NOTIFICATION_PREFERENCE)
+    }
+
+    pub fn save(&self, user_key: &str) {
+        save_user_preference(user_key, NOTIFICATION_PREFERENCE, self);
+    }
+
+    fn is_quiet(&self, hour: u32) -> bool {
+        if !self.quiet_hours || self.quiet_start == self.quiet_end {
+            return false;
+        }
+        if self.quiet_start < self.quiet_end {
+            (self.quiet_start..self.quiet_end).contains(&hour)
+        } else {
+            hour >= self.quiet_start || hour < self.quiet_end
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+struct PendingNotification {
+    episode_id: i32,
+    podcast_name: String,
+    title: String,
+    artwork: String,
+}
+
+// What has been seen per podcast, plus anything held back by quiet hours
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+struct NotificationLedger {
+    last_seen: HashMap<i32, String>,
+    pending: Vec<PendingNotification>,
+    // The local day a streak reminder was last shown
+    streak_reminded: Option<String>,
+}
+
+impl NotificationLedger {
+    // Podcasts seen for the first time are only recorded, so turning notifications on
+    // doesn't announce every show's latest episode at once
+    fn record(&mut self, episodes: &[NotificationEpisode]) {
+        for episode in episodes {
+            let pub_date = episode.EpisodePubDate.replace(' ', "T");
+            let is_new = self.last_seen.get(&episode.PodcastID).map_or(false, |seen| pub_date.as_str() > seen.as_str());
+            if is_new && !self.pending.iter().any(|pending| pending.episode_id == episode.EpisodeID) {
+                self.pending.push(PendingNotification {
+                    episode_id: episode.EpisodeID,
+                    podcast_name: episode.PodcastName.clone(),
+                    title: episode.EpisodeTitle.clone(),
+                    artwork: episode.EpisodeArtwork.clone(),
+                });
+            }
+            self.last_seen.insert(episode.PodcastID, pub_date);
+        }
+    }
+}
+
+// Where clicking a notification leads
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum NotificationTarget {
+    Episode(i32),
+    Home,
+}
+
+fn open_target(target: NotificationTarget) {
+    if let Some(window) = window() {
+        let _ = window.focus();
+    }
+    let history = BrowserHistory::new();
+    match target {
+        NotificationTarget::Episode(episode_id) => {
+            Dispatch::<AppState>::global().reduce_mut(move |state| {
+                state.selected_episode_id = Some(episode_id);
+            });
+            history.push("/episode");
+        }
+        NotificationTarget::Home => history.push(Route::Home.to_path()),
+    }
+}
+
+struct OutgoingNotification {
+    title: String,
+    body: String,
+    icon: Option<String>,
+    target: NotificationTarget,
+}
+
+fn build_notifications(pending: &[PendingNotification], digest: bool) -> Vec<OutgoingNotification> {
+    if pending.is_empty() {
+        return Vec::new();
+    }
+    if digest || pending.len() > DIGEST_THRESHOLD {
+        let mut podcasts: Vec<&str> = Vec::new();
+        for notification in pending {
+            if !podcasts.contains(&notification.podcast_name.as_str()) {
+                podcasts.push(&notification.podcast_name);
+            }
+        }
+        return vec![OutgoingNotification {
+            title: t_plural("notifications.digest_title", pending.len() as i64),
+            body: podcasts.join(", "),
+            icon: None,
+            target: NotificationTarget::Home,
+        }];
+    }
+    pending
+        .iter()
+        .map(|notification| OutgoingNotification {
+            title: t_args("notifications.new_episode_title", &[("podcast", notification.podcast_name.as_str())]),
+            body: notification.title.clone(),
+            icon: Some(notification.artwork.clone()).filter(|artwork| !artwork.is_empty()),
+            target: NotificationTarget::Episode(notification.episode_id),
+        })
+        .collect()
+}
+
+#[cfg(feature = "server_build")]
+mod delivery {
+    use super::*;
+    use web_sys::{Notification, NotificationOptions, NotificationPermission};
+
+    pub async fn permission_granted() -> bool {
+        Notification::permission() == NotificationPermission::Granted
+    }
+
+    pub async fn request_permission() -> bool {
+        if let Ok(promise) = Notification::request_permission() {
+            let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+        }
+        permission_granted().await
+    }
+
+    pub fn show(notification: OutgoingNotification, _desktop_click: &DesktopClick) {
+        let options = NotificationOptions::new();
+        options.set_body(&notification.body);
+        if let Some(icon) = &notification.icon {
+            options.set_icon(icon);
+        }
+        let Ok(shown) = Notification::new_with_options(&notification.title, &options) else {
+            return;
+        };
+        let target = notification.target;
+        let handle = shown.clone();
+        let on_click = Closure::once_into_js(move || {
+            handle.close();
+            open_target(target);
+        });
+        shown.set_onclick(Some(on_click.unchecked_ref()));
+    }
+}
+
+// The desktop app goes through Tauri's notification API, which can't report clicks. Clicking a
+// native notification brings the window forward, so focusing it soon after stands in for the click.
+#[cfg(not(feature = "server_build"))]
+mod delivery {
+    use super::*;
+    use js_sys::{Function, Object, Promise, Reflect};
+
+    fn notification_api() -> Option<Object> {
+        let window = window()?;
+        let tauri = Reflect::get(&JsValue::from(window), &JsValue::from_str("__TAURI__")).ok()?;
+        Reflect::get(&tauri, &JsValue::from_str("notification")).ok()?.dyn_into::<Object>().ok()
+    }
+
+    async fn call(name: &str, argument: Option<&JsValue>) -> Option<JsValue> {
+        let api = notification_api()?;
+        let function = Reflect::get(&api, &JsValue::from_str(name)).ok()?.dyn_into::<Function>().ok()?;
+        let result = match argument {
+            Some(argument) => function.call1(&api, argument),
+            None => function.call0(&api),
+        }
+        .ok()?;
+        match result.dyn_into::<Promise>() {
+            Ok(promise) => wasm_bindgen_futures::JsFuture::from(promise).await.ok(),
+            Err(value) => Some(value),
+        }
+    }
+
+    pub async fn permission_granted() -> bool {
+        call("isPermissionGranted", None).await.and_then(|granted| granted.as_bool()) == Some(true)
+    }
+
+    pub async fn request_permission() -> bool {
+        if permission_granted().await {
+            return true;
+        }
+        call("requestPermission", None).await.and_then(|permission| permission.as_string()).as_deref() == Some("granted")
+    }
+
+    pub fn show(notification: OutgoingNotification, desktop_click: &DesktopClick) {
+        let options = Object::new();
+        let _ = Reflect::set(&options, &JsValue::from_str("title"), &JsValue::from_str(&notification.title));
+        let _ = Reflect::set(&options, &JsValue::from_str("body"), &JsValue::from_str(&notification.body));
+        *desktop_click.borrow_mut() = Some((notification.target, js_sys::Date::now()));
+        wasm_bindgen_futures::spawn_local(async move {
+            call("sendNotification", Some(&options)).await;
+        });
+    }
+}
+
+// The last desktop notification and when it was shown
+type DesktopClick = Rc<RefCell<Option<(NotificationTarget, f64)>>>;
+
+pub use delivery::request_permission;
+
+async fn check_for_new_episodes(server_name: String, api_key: Option<String>, user_id: i32, view_key: String, desktop_click: DesktopClick) {
+    let preferences = NotificationPreferences::load(&view_key);
+    if !preferences.enabled || !delivery::permission_granted().await {
+        return;
+    }
+    let Ok(episodes) = call_get_notification_episodes(&server_name, &api_key, user_id).await else {
+        return;
+    };
+    let mut ledger: NotificationLedger = load_user_preference(&view_key, NOTIFICATION_LEDGER);
+    ledger.record(&episodes);
+    let hour = js_sys::Date::new_0().get_hours();
+    if !preferences.is_quiet(hour) {
+        for notification in build_notifications(&ledger.pending, preferences.digest) {
+            delivery::show(notification, &desktop_click);
+        }
+        ledger.pending.clear();
+    }
+    save_user_preference(&view_key, NOTIFICATION_LEDGER, &ledger);
+}
+
+fn local_day() -> String {
+    let now = js_sys::Date::new_0();
+    format!("{}-{:02}-{:02}", now.get_full_year(), now.get_month() + 1, now.get_date())
+}
+
+async fn check_streak(server_name: String, api_key: Option<String>, user_id: i32, view_key: String, desktop_click: DesktopClick) {
+    let preferences = NotificationPreferences::load(&view_key);
+    if !preferences.enabled || !preferences.streak_reminder || !delivery::permission_granted().await {
+        return;
+    }
+    let hour = js_sys::Date::new_0().get_hours();
+    let today = local_day();
+    let ledger: NotificationLedger = load_user_preference(&view_key, NOTIFICATION_LEDGER);
+    if hour < preferences.streak_reminder_hour || preferences.is_quiet(hour) || ledger.streak_reminded.as_deref() == Some(today.as_str()) {
+        return;
+    }
+    let Ok(goals) = call_get_listening_goals(&server_name, &api_key, user_id).await else {
+        return;
+    };
+    if !goals.streak.at_risk() {
+        return;
+    }
+    delivery::show(
+        OutgoingNotification {
+            title: t_plural("notifications.streak_title", goals.streak.current as i64),
+            body: t("notifications.streak_body"),
+            icon: None,
+            target: NotificationTarget::Home,
+        },
+        &desktop_click,
+    );
+    // Read the ledger again, the episode check may have saved it meanwhile
+    let mut ledger: NotificationLedger = load_user_preference(&view_key, NOTIFICATION_LEDGER);
+    ledger.streak_reminded = Some(today);
+    save_user_preference(&view_key, NOTIFICATION_LEDGER, &ledger);
+}
+
+// Runs the background check while someone is signed in. Mounted once next to the router.
+#[function_component(NewEpisodeNotifier)]
+pub fn new_episode_notifier() -> Html {
+    let auth_details = use_selector(|state: &AppState| state.auth_details.clone());
+    let user_id = *use_selector(|state: &AppState| state.user_details.as_ref().map(|ud| ud.UserID));
+    let view_key = use_selector(|state: &AppState| user_key(state));
+    let has_goals = *use_selector(goals_available);
+    let desktop_click: DesktopClick = use_mut_ref(|| None);
+
+    {
+        let desktop_click = desktop_click.clone();
+        use_effect_with((), move |_| {
+            let listener = window().map(|window| {
+                EventListener::new(&window, "focus", move |_| {
+                    let clicked = desktop_click.borrow_mut().take();
+                    if let Some((target, shown_at)) = clicked {
+                        if js_sys::Date::now() - shown_at <= DESKTOP_CLICK_WINDOW_MS {
+                            open_target(target);
+                        }
+                    }
+                })
+            });
+            move || drop(listener)
+        });
+    }
+
+    use_effect_with(((*auth_details).clone(), user_id, (*view_key).clone(), has_goals), move |(auth_details, user_id, view_key, has_goals)| {
+        let mut interval = None;
+        if let (Some(auth), Some(user_id), Some(view_key)) = (auth_details.clone(), *user_id, view_key.clone()) {
+            let has_goals = *has_goals;
+            let check = move || {
+                wasm_bindgen_futures::spawn_local(check_for_new_episodes(
+                    auth.server_name.clone(),
+                    auth.api_key.clone(),
+                    user_id,
+                    view_key.clone(),
+                    desktop_click.clone(),
+                ));
+                if has_goals {
+                    wasm_bindgen_futures::spawn_local(check_streak(
+                        auth.server_name.clone(),
+                        auth.api_key.clone(),
+                        user_id,
+                        view_key.clone(),
+                        desktop_click.clone(),
+                    ));
+                }
+            };
+            check();
+            interval = Some(Interval::new(CHECK_INTERVAL_MS, check));
+        }
+        move || drop(interval)
+    });
+
+    html! {}
+}
+
+// Hour choices for the quiet hours selects, shown as 00:00 to 23:00
+pub fn hour_label(hour: u32) -> String {
+    format!("{:02}:00", hour)
+}
+
+pub fn notifications_supported() -> bool {
+    cfg!(not(feature = "server_build"))
+        || window().map_or(false, |window| js_sys::Reflect::has(&window, &JsValue::from_str("Notification")).unwrap_or(false))
+}
@@ -0,0 +1,368 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use web_sys::{console, window, Storage};
+use yewdux::prelude::Dispatch;
+use crate::components::context::{AppState, EpisodeLists, EpisodeListsMsg};
+use crate::requests::login_requests::{GetApiDetails, GetUserDetails, LoginServerRequest};
+use crate::requests::pod_req::{PodcastResponse, QueuedEpisodesResponse};
+
+// Everything the client keeps between visits lives in one versioned document. Only the
+// fields listed in `PersistedState` are ever written, so adding or renaming something on
+// `AppState` can no longer break restoring an older blob. When the layout changes, bump
+// `CURRENT_VERSION` and append a step to `MIGRATIONS` that upgrades the previous version.
+pub const STATE_KEY: &str = "pinepodsState";
+pub const CURRENT_VERSION: u64 = 1;
+
+// Keys written before the versioned document existed
+const LEGACY_USER_KEY: &str = "userState";
+const LEGACY_AUTH_KEY: &str = "userAuthState";
+const LEGACY_SERVER_KEY: &str = "serverState";
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct PersistedPreferences {
+    pub user_tz: Option<String>,
+    pub hour_preference: Option<i16>,
+    pub date_format: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct PersistedLibrary {
+    pub podcasts: Option<PodcastResponse>,
+    pub queued_episodes: Option<QueuedEpisodesResponse>,
+}
+
+#[derive(Serialize, Default, Clone, Debug, PartialEq)]
+pub struct PersistedState {
+    pub version: u64,
+    pub user_details: Option<GetUserDetails>,
+    pub auth_details: Option<LoginServerRequest>,
+    pub server_details: Option<GetApiDetails>,
+    pub preferences: PersistedPreferences,
+    pub last_route: Option<String>,
+    pub library: PersistedLibrary,
+}
+
+impl PersistedState {
+    fn from_app_state(state: &AppState, last_route: Option<String>) -> Self {
+        PersistedState {
+            version: CURRENT_VERSION,
+            user_details: state.user_details.clone(),
+            auth_details: state.auth_details.clone(),
+            server_details: state.server_details.clone(),
+            preferences: PersistedPreferences {
+                user_tz: state.user_tz.clone(),
+                hour_preference: state.hour_preference,
+                date_format: state.date_format.clone(),
+            },
+            last_route,
+            library: PersistedLibrary {
+                podcasts: state.podcast_feed_return.clone(),
+                queued_episodes: Dispatch::<EpisodeLists>::global()
+                    .get()
+                    .queue
+                    .as_ref()
+                    .map(|episodes| QueuedEpisodesResponse { episodes: episodes.to_vec() }),
+            },
+        }
+    }
+
+    // Each section is read on its own so one corrupt field only loses that field
+    fn from_value(value: &Value) -> Self {
+        fn section<T: for<'de> Deserialize<'de> + Default>(value: &Value, key: &str) -> T {
+            match value.get(key) {
+                Some(section) if !section.is_null() => serde_json::from_value(section.clone()).unwrap_or_else(|e| {
+                    log(format!("Discarding corrupt persisted section '{}': {:?}", key, e));
+                    T::default()
+                }),
+                _ => T::default(),
+            }
+        }
+
+        PersistedState {
+            version: CURRENT_VERSION,
+            user_details: section(value, "user_details"),
+            auth_details: section(value, "auth_details"),
+            server_details: section(value, "server_details"),
+            preferences: section(value, "preferences"),
+            last_route: section(value, "last_route"),
+            library: section(value, "library"),
+        }
+    }
+
+    // Restore the cached preferences and library onto a freshly logged in state
+    pub fn apply_cached(&self, state: &mut AppState) {
+        if state.user_tz.is_none() {
+            state.user_tz = self.preferences.user_tz.clone();
+            state.hour_preference = self.preferences.hour_preference;
+            state.date_format = self.preferences.date_format.clone();
+        }
+        if state.podcast_feed_return.is_none() {
+            state.podcast_feed_return = self.library.podcasts.clone();
+        }
+        let lists = Dispatch::<EpisodeLists>::global();
+        if let (None, Some(queued)) = (lists.get().queue.as_ref(), self.library.queued_episodes.as_ref()) {
+            lists.apply(EpisodeListsMsg::Queue(queued.episodes.clone()));
+        }
+    }
+}
+
+// Where migrations read and drop keys written by older clients
+trait LegacyStore {
+    fn read(&self, key: &str) -> Option<String>;
+    fn remove(&self, key: &str);
+}
+
+impl LegacyStore for Storage {
+    fn read(&self, key: &str) -> Option<String> {
+        self.get_item(key).ok().flatten()
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = self.remove_item(key);
+    }
+}
+
+// Version 0 was three separate blobs, each a partial serialized AppState
+fn migrate_v0_to_v1(storage: &dyn LegacyStore, _value: &mut Value) -> Value {
+    let read = |key: &str, field: &str| -> Value {
+        storage
+            .read(key)
+            .and_then(|blob| serde_json::from_str::<Value>(&blob).ok())
+            .and_then(|blob| blob.get(field).cloned())
+            .unwrap_or(Value::Null)
+    };
+    let migrated = json!({
+        "version": 1,
+        "user_details": read(LEGACY_USER_KEY, "user_details"),
+        "auth_details": read(LEGACY_AUTH_KEY, "auth_details"),
+        "server_details": read(LEGACY_SERVER_KEY, "server_details"),
+    });
+    for key in [LEGACY_USER_KEY, LEGACY_AUTH_KEY, LEGACY_SERVER_KEY] {
+        storage.remove(key);
+    }
+    migrated
+}
+
+// MIGRATIONS[n] upgrades a document from version n to version n + 1
+const MIGRATIONS: &[fn(&dyn LegacyStore, &mut Value) -> Value] = &[migrate_v0_to_v1];
+
+fn log(message: String) {
+    // Native unit tests have no console to write to
+    if cfg!(target_arch = "wasm32") {
+        console::log_1(&message.into());
+    }
+}
+
+fn local_storage() -> Option<Storage> {
+    window().and_then(|w| w.local_storage().ok().flatten())
+}
+
+fn migrate(storage: &dyn LegacyStore, mut value: Value) -> Option<Value> {
+    let mut version = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+    if version > CURRENT_VERSION {
+        // Written by a newer client. Reading it could lose data, so start fresh instead.
+        log(format!("Persisted state version {} is newer than supported {}", version, CURRENT_VERSION));
+        return None;
+    }
+    while version < CURRENT_VERSION {
+        value = MIGRATIONS[version as usize](storage, &mut value);
+        version += 1;
+        value["version"] = json!(version);
+    }
+    Some(value)
+}
+
+pub fn load_persisted_state() -> Option<PersistedState> {
+    let storage = local_storage()?;
+    let raw = storage.get_item(STATE_KEY).ok().flatten();
+    let has_legacy = storage.get_item(LEGACY_AUTH_KEY).ok().flatten().is_some();
+
+    let value = match raw {
+        Some(raw) => match serde_json::from_str::<Value>(&raw) {
+            Ok(value) if value.is_object() => value,
+            _ => {
+                log("Persisted state is corrupt, discarding it".to_string());
+                let _ = storage.remove_item(STATE_KEY);
+                return None;
+            }
+        },
+        None if has_legacy => json!({ "version": 0 }),
+        None => return None,
+    };
+
+    let migrated = migrate(&storage, value)?;
+    let persisted = PersistedState::from_value(&migrated);
+    write_persisted_state(&storage, &persisted);
+    Some(persisted)
+}
+
+fn write_persisted_state(storage: &Storage, persisted: &PersistedState) {
+    match serde_json::to_string(persisted) {
+        Ok(serialized) => {
+            let _ = storage.set_item(STATE_KEY, &serialized);
+        }
+        Err(e) => log(format!("Unable to persist state: {:?}", e)),
+    }
+}
+
+// The stored document as it is, without migrating or rewriting it
+fn stored_document(storage: &Storage) -> Option<Value> {
+    storage
+        .get_item(STATE_KEY)
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+        .filter(Value::is_object)
+}
+
+pub fn save_persisted_state(state: &AppState) {
+    if let Some(storage) = local_storage() {
+        let last_route = stored_document(&storage)
+            .and_then(|document| document.get("last_route").and_then(Value::as_str).map(str::to_string));
+        write_persisted_state(&storage, &PersistedState::from_app_state(state, last_route));
+    }
+}
+
+// Only touches the route, so navigating never re-serializes the rest of the document
+pub fn remember_route(path: &str) {
+    if let Some(storage) = local_storage() {
+        if let Some(mut document) = stored_document(&storage) {
+            if document.get("last_route").and_then(Value::as_str) != Some(path) {
+                document["last_route"] = json!(path);
+                let _ = storage.set_item(STATE_KEY, &document.to_string());
+            }
+        }
+    }
+}
+
+// Per-user view preferences outlive a session, so they sit in their own key that signing
+// out leaves alone. The document maps a user key to a map of named preferences.
+pub const USER_PREFERENCES_KEY: &str = "userPreferences";
+
+pub fn user_key(state: &AppState) -> Option<String> {
+    let server_name = state.auth_details.as_ref()?.server_name.trim_end_matches('/').to_string();
+    let user_id = state.user_details.as_ref()?.UserID;
+    Some(format!("{}|{}", server_name, user_id))
+}
+
+fn load_user_preferences() -> serde_json::Map<String, Value> {
+    local_storage()
+        .and_then(|storage| storage.get_item(USER_PREFERENCES_KEY).ok().flatten())
+        .and_then(|stored| serde_json::from_str::<Value>(&stored).ok())
+        .and_then(|value| value.as_object().cloned())
+        .unwrap_or_default()
+}
+
+pub fn load_user_preference<T: DeserializeOwned + Default>(user_key: &str, name: &str) -> T {
+    load_user_preferences()
+        .get(user_key)
+        .and_then(|preferences| preferences.get(name))
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_user_preference<T: Serialize>(user_key: &str, name: &str, value: &T) {
+    let Some(storage) = local_storage() else {
+        return;
+    };
+    let Ok(value) = serde_json::to_value(value) else {
+        return;
+    };
+    let mut preferences = load_user_preferences();
+    let entry = preferences.entry(user_key.to_string()).or_insert_with(|| json!({}));
+    if !entry.is_object() {
+        *entry = json!({});
+    }
+    entry[name] = value;
+    let _ = storage.set_item(USER_PREFERENCES_KEY, &Value::Object(preferences).to_string());
+}
+
+// Wipes every piece of client side data: persisted state, profiles and preferences
+pub fn reset_local_data() {
+    if let Some(window) = window() {
+        if let Ok(Some(local_storage)) = window.local_storage() {
+            let _ = local_storage.clear();
+        }
+        if let Ok(Some(session_storage)) = window.session_storage() {
+            let _ = session_storage.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct MemoryStore(RefCell<HashMap<String, String>>);
+
+    impl MemoryStore {
+        fn with(entries: &[(&str, Value)]) -> Self {
+            let store = MemoryStore::default();
+            for (key, value) in entries {
+                store.0.borrow_mut().insert(key.to_string(), value.to_string());
+            }
+            store
+        }
+    }
+
+    impl LegacyStore for MemoryStore {
+        fn read(&self, key: &str) -> Option<String> {
+            self.0.borrow().get(key).cloned()
+        }
+
+        fn remove(&self, key: &str) {
+            self.0.borrow_mut().remove(key);
+        }
+    }
+
+    fn user(id: i32) -> Value {
+        json!({ "UserID": id, "Fullname": null, "Username": "listener", "Email": null, "Hashed_PW": null, "Salt": null })
+    }
+
+    #[test]
+    fn legacy_blobs_migrate_to_the_current_version() {
+        let store = MemoryStore::with(&[
+            (LEGACY_USER_KEY, json!({ "user_details": user(7) })),
+            (LEGACY_AUTH_KEY, json!({ "auth_details": { "server_name": "https://pods.example", "username": "listener", "password": "", "api_key": "key" } })),
+        ]);
+
+        let migrated = migrate(&store, json!({ "version": 0 })).expect("version 0 is supported");
+        assert_eq!(migrated["version"], json!(CURRENT_VERSION));
+
+        let persisted = PersistedState::from_value(&migrated);
+        assert_eq!(persisted.user_details.map(|details| details.UserID), Some(7));
+        assert_eq!(persisted.auth_details.map(|auth| auth.server_name), Some("https://pods.example".to_string()));
+        assert!(persisted.server_details.is_none());
+        assert!(store.0.borrow().is_empty(), "legacy keys are removed once migrated");
+    }
+
+    #[test]
+    fn current_documents_are_left_alone() {
+        let store = MemoryStore::default();
+        let document = json!({ "version": CURRENT_VERSION, "last_route": "/queue" });
+        assert_eq!(migrate(&store, document.clone()), Some(document));
+    }
+
+    #[test]
+    fn documents_from_a_newer_client_are_not_read() {
+        let store = MemoryStore::default();
+        assert_eq!(migrate(&store, json!({ "version": CURRENT_VERSION + 1 })), None);
+    }
+
+    #[test]
+    fn a_corrupt_section_only_loses_that_section() {
+        let persisted = PersistedState::from_value(&json!({
+            "version": CURRENT_VERSION,
+            "user_details": user(3),
+            "preferences": "not an object",
+            "last_route": "/podcasts",
+        }));
+        assert_eq!(persisted.user_details.map(|details| details.UserID), Some(3));
+        assert_eq!(persisted.preferences, PersistedPreferences::default());
+        assert_eq!(persisted.last_route.as_deref(), Some("/podcasts"));
+    }
+}
@@ -0,0 +1,897 @@
+use std::collections::HashSet;
+use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+use yew::functional::hook;
+use yewdux::prelude::*;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use wasm_bindgen_futures::spawn_local;
+use yew_router::history::{BrowserHistory, History};
+use crate::components::audio::on_play_click;
+use crate::components::context::{
+    AppState, UIState, CachedEpisode, EpisodeCache, EpisodeCacheMsg, EpisodeList, FolderState, PodcastCache, PodcastCacheMsg,
+    PlaylistMsg, PlaylistPlayback, PlaylistState,
+};
+use crate::components::episodes_layout::AppStateMsg;
+use crate::components::folders::use_folders;
+use crate::components::gen_components::{empty_message, episode_item, on_shownotes_click, page_frame, use_page_setup};
+use crate::components::gen_funcs::{
+    download_text, escape_xml, format_datetime, match_date_format, parse_date, sanitize_html_with_blank_target,
+    truncate_description, ListenState,
+};
+use crate::components::i18n::{t, t_args, t_plural};
+use crate::components::queue_order::{move_item, queue_row, QueueRowControls};
+use crate::components::virtual_list::VirtualList;
+use yew::virtual_dom::Key;
+use crate::requests::pod_req::{
+    call_add_playlist_episodes, call_create_playlist, call_delete_playlist, call_get_playlist_episodes, call_get_playlists, call_get_podcasts,
+    call_get_queued_episodes, call_get_recent_eps, call_get_saved_episodes, call_get_user_history, call_set_playlist_episodes,
+    call_update_playlist, Episode, Playlist,
+};
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleStatus {
+    Unplayed,
+    InProgress,
+    Played,
+}
+
+impl RuleStatus {
+    const ALL: [RuleStatus; 3] = [RuleStatus::Unplayed, RuleStatus::InProgress, RuleStatus::Played];
+
+    fn key(&self) -> &'static str {
+        match self {
+            RuleStatus::Unplayed => "unplayed",
+            RuleStatus::InProgress => "in_progress",
+            RuleStatus::Played => "played",
+        }
+    }
+
+    fn matches(&self, state: ListenState) -> bool {
+        matches!(
+            (self, state),
+            (RuleStatus::Unplayed, ListenState::Unplayed) | (RuleStatus::InProgress, ListenState::InProgress) | (RuleStatus::Played, ListenState::Played)
+        )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleSort {
+    #[default]
+    NewestFirst,
+    OldestFirst,
+    ShortestFirst,
+    LongestFirst,
+}
+
+impl RuleSort {
+    const ALL: [RuleSort; 4] = [RuleSort::NewestFirst, RuleSort::OldestFirst, RuleSort::ShortestFirst, RuleSort::LongestFirst];
+
+    fn key(&self) -> &'static str {
+        match self {
+            RuleSort::NewestFirst => "newest_first",
+            RuleSort::OldestFirst => "oldest_first",
+            RuleSort::ShortestFirst => "shortest_first",
+            RuleSort::LongestFirst => "longest_first",
+        }
+    }
+}
+
+// Rules of a smart playlist, stored on the server as JSON and evaluated here against the episode cache
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SmartRules {
+    pub status: Option<RuleStatus>,
+    pub folder_id: Option<i32>,
+    pub min_minutes: Option<i32>,
+    pub max_minutes: Option<i32>,
+    pub sort: RuleSort,
+    pub limit: Option<usize>,
+}
+
+impl SmartRules {
+    // Rules that don't parse match everything rather than hiding the playlist
+    pub fn parse(rules: &str) -> Self {
+        serde_json::from_str(rules).unwrap_or_default()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn evaluate(&self, cache: &EpisodeCache, folders: &FolderState, podcasts: &PodcastCache) -> Vec<Rc<CachedEpisode>> {
+        let folder_podcasts = self.folder_id.map(|folder_id| folders.podcast_ids(folder_id).unwrap_or_default());
+        // Not every list tells us an episode's podcast id, so those are matched to the folder by podcast name
+        let folder_names: Option<HashSet<&str>> = folder_podcasts.as_ref().map(|podcast_ids| {
+            podcasts
+                .podcasts
+                .values()
+                .filter(|podcast| podcast_ids.contains(&podcast.PodcastID))
+                .map(|podcast| podcast.PodcastName.as_str())
+                .collect()
+        });
+
+        let mut episodes: Vec<Rc<CachedEpisode>> = cache
+            .episodes
+            .values()
+            // Entries created from a progress update alone have nothing to show
+            .filter(|episode| !episode.title.is_empty())
+            .filter(|episode| self.status.map_or(true, |status| status.matches(episode.listen_state())))
+            .filter(|episode| match (&folder_podcasts, &folder_names) {
+                (Some(podcast_ids), Some(names)) => match episode.podcast_id {
+                    Some(podcast_id) => podcast_ids.contains(&podcast_id),
+                    None => names.contains(episode.podcast_name.as_str()),
+                },
+                _ => true,
+            })
+            .filter(|episode| self.min_minutes.map_or(true, |minutes| episode.duration >= minutes * 60))
+            .filter(|episode| self.max_minutes.map_or(true, |minutes| episode.duration <= minutes * 60))
+            .cloned()
+            .collect();
+
+        // Publish dates are ISO timestamps, so they sort as strings
+        match self.sort {
+            RuleSort::NewestFirst => episodes.sort_by(|a, b| b.pub_date.cmp(&a.pub_date)),
+            RuleSort::OldestFirst => episodes.sort_by(|a, b| a.pub_date.cmp(&b.pub_date)),
+            RuleSort::ShortestFirst => episodes.sort_by_key(|episode| episode.duration),
+            RuleSort::LongestFirst => episodes.sort_by_key(|episode| std::cmp::Reverse(episode.duration)),
+        }
+        if let Some(limit) = self.limit {
+            episodes.truncate(limit);
+        }
+        episodes
+    }
+}
+
+// Episodes of any playlist in play order. Manual playlists list what the cache holds of their episodes.
+fn playlist_episodes(playlist: &Playlist, cache: &EpisodeCache, folders: &FolderState, podcasts: &PodcastCache) -> Vec<Rc<CachedEpisode>> {
+    match &playlist.Rules {
+        Some(rules) => SmartRules::parse(rules).evaluate(cache, folders, podcasts),
+        None => playlist.EpisodeIDs.iter().filter_map(|episode_id| cache.episodes.get(episode_id).cloned()).collect(),
+    }
+}
+
+fn export_filename(name: &str, extension: &str) -> String {
+    let stem: String = name.chars().map(|c| if c.is_alphanumeric() || c == '-' { c } else { '_' }).collect();
+    format!("{}.{}", stem, extension)
+}
+
+fn playlist_m3u(name: &str, episodes: &[Rc<CachedEpisode>]) -> String {
+    let mut m3u = format!("#EXTM3U\n#PLAYLIST:{}\n", name);
+    for episode in episodes {
+        m3u.push_str(&format!("#EXTINF:{},{} - {}\n{}\n", episode.duration, episode.podcast_name, episode.title, episode.url));
+    }
+    m3u
+}
+
+fn playlist_opml(name: &str, episodes: &[Rc<CachedEpisode>]) -> String {
+    let mut opml = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">\n  <head>\n    <title>{}</title>\n  </head>\n  <body>\n",
+        escape_xml(name)
+    );
+    for episode in episodes {
+        opml.push_str(&format!(
+            "    <outline type=\"link\" text=\"{}\" title=\"{}\" url=\"{}\" />\n",
+            escape_xml(&format!("{} - {}", episode.podcast_name, episode.title)),
+            escape_xml(&episode.title),
+            escape_xml(&episode.url)
+        ));
+    }
+    opml.push_str("  </body>\n</opml>\n");
+    opml
+}
+
+// Playlists are fetched once per account and then kept in sync by the components that change them
+#[hook]
+pub fn use_playlists() -> Rc<PlaylistState> {
+    let (state, _dispatch) = use_store::<AppState>();
+    let (playlist_state, playlist_dispatch) = use_store::<PlaylistState>();
+    let api_key = state.auth_details.as_ref().and_then(|ud| ud.api_key.clone());
+    let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+    let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+    let loaded = playlist_state.playlists.is_some();
+
+    use_effect_with((server_name, user_id, loaded), move |(server_name, user_id, loaded)| {
+        if let (Some(server_name), Some(user_id), false) = (server_name.clone(), *user_id, *loaded) {
+            spawn_local(async move {
+                if let Ok(playlists) = call_get_playlists(&server_name, &api_key, user_id).await {
+                    playlist_dispatch.apply(PlaylistMsg::Loaded(playlists));
+                }
+            });
+        }
+        || ()
+    });
+
+    playlist_state
+}
+
+// Smart playlists only see what the cache holds, so fill it from the lists the server can give us in one go
+#[hook]
+fn use_library_refresh() {
+    let auth_details = use_selector(|state: &AppState| state.auth_details.clone());
+    let user_id = *use_selector(|state: &AppState| state.user_details.as_ref().map(|ud| ud.UserID));
+    let server_name = auth_details.as_ref().as_ref().map(|ud| ud.server_name.clone());
+    let api_key = auth_details.as_ref().as_ref().and_then(|ud| ud.api_key.clone());
+
+    use_effect_with((server_name, user_id), move |(server_name, user_id)| {
+        if let (Some(server_name), Some(user_id)) = (server_name.clone(), *user_id) {
+            spawn_local(async move {
+                let cache_dispatch = Dispatch::<EpisodeCache>::global();
+                if let Ok(podcasts) = call_get_podcasts(&server_name, &api_key, &user_id).await {
+                    Dispatch::<PodcastCache>::global().apply(PodcastCacheMsg::Sync(podcasts));
+                }
+                if let Ok(episodes) = call_get_recent_eps(&server_name, &api_key, &user_id).await {
+                    cache_dispatch.apply(EpisodeCacheMsg::Ingest(episodes.iter().map(CachedEpisode::from).collect()));
+                }
+                if let Ok(episodes) = call_get_user_history(&server_name, &api_key, &user_id).await {
+                    cache_dispatch.apply(EpisodeCacheMsg::Ingest(episodes.iter().map(CachedEpisode::from).collect()));
+                }
+                if let Ok(episodes) = call_get_saved_episodes(&server_name, &api_key, &user_id).await {
+                    cache_dispatch.apply(EpisodeCacheMsg::SyncList(EpisodeList::Saved, episodes.iter().map(CachedEpisode::from).collect()));
+                }
+                if let Ok(episodes) = call_get_queued_episodes(&server_name, &api_key, &user_id).await {
+                    cache_dispatch.apply(EpisodeCacheMsg::SyncList(EpisodeList::Queue, episodes.iter().map(CachedEpisode::from).collect()));
+                }
+            });
+        }
+        || ()
+    });
+}
+
+fn show_error(key: &str, error: anyhow::Error) {
+    let message = format!("{}: {}", t(key), error);
+    Dispatch::<UIState>::global().reduce_mut(|ui_state| ui_state.error_message = Some(message));
+}
+
+// Appends episodes to a manual playlist and reports the result in the snackbar
+pub fn add_to_playlist(server_name: String, api_key: Option<String>, user_id: i32, playlist: Playlist, episode_ids: Vec<i32>) {
+    spawn_local(async move {
+        match call_add_playlist_episodes(&server_name, &api_key, user_id, playlist.PlaylistID, &episode_ids).await {
+            Ok(saved_ids) => {
+                Dispatch::<PlaylistState>::global().apply(PlaylistMsg::SetEpisodes(playlist.PlaylistID, saved_ids));
+                let message = t_args("playlists.added", &[("name", playlist.Name.as_str())]);
+                Dispatch::<UIState>::global().reduce_mut(|ui_state| ui_state.info_message = Some(message));
+            }
+            Err(e) => show_error("playlists.save_error", e),
+        }
+    });
+}
+
+#[function_component(Playlists)]
+pub fn playlists() -> Html {
+    use_page_setup();
+    use_library_refresh();
+    let (state, _dispatch) = use_store::<AppState>();
+    let (ui_state, _ui_dispatch) = use_store::<UIState>();
+    let (cache, _cache_dispatch) = use_store::<EpisodeCache>();
+    let (podcasts, _podcasts_dispatch) = use_store::<PodcastCache>();
+    let folder_state = use_folders();
+    let playlist_state = use_playlists();
+    let new_name = use_state(String::new);
+    let new_smart = use_state(|| false);
+    let history = BrowserHistory::new();
+
+    let api_key = state.auth_details.as_ref().and_then(|ud| ud.api_key.clone());
+    let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+    let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+
+    let on_name_input = {
+        let new_name = new_name.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                new_name.set(input.value());
+            }
+        })
+    };
+
+    let on_kind_change = {
+        let new_smart = new_smart.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                new_smart.set(select.value() == "smart");
+            }
+        })
+    };
+
+    let on_create = {
+        let new_name = new_name.clone();
+        let new_smart = new_smart.clone();
+        let api_key = api_key.clone();
+        let server_name = server_name.clone();
+        let history = history.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let name = new_name.trim().to_string();
+            let (false, Some(server_name), Some(user_id)) = (name.is_empty(), server_name.clone(), user_id) else {
+                return;
+            };
+            new_name.set(String::new());
+            let rules = new_smart.then(|| SmartRules::default().to_json());
+            let api_key = api_key.clone();
+            let history = history.clone();
+            spawn_local(async move {
+                match call_create_playlist(&server_name, &api_key, user_id, &name, rules.as_deref()).await {
+                    Ok(playlist) => {
+                        let playlist_id = playlist.PlaylistID;
+                        Dispatch::<PlaylistState>::global().apply(PlaylistMsg::Saved(playlist));
+                        history.push(format!("/playlists/{}", playlist_id));
+                    }
+                    Err(e) => show_error("playlists.save_error", e),
+                }
+            });
+        })
+    };
+
+    let playlists = playlist_state.playlists.clone().unwrap_or_default();
+    let content = html! {
+        <div class="p-4">
+            <h1 class="text-2xl item_container-text font-bold text-center mb-6">{ t("playlists.title") }</h1>
+            <form onsubmit={on_create} class="flex flex-wrap items-center gap-2 mb-6">
+                <input type="text" value={(*new_name).clone()} oninput={on_name_input} placeholder={t("playlists.new_placeholder")} aria-label={t("playlists.new_placeholder")} class="search-bar-input border text-sm rounded-lg p-2 flex-grow min-w-0" />
+                <select aria-label={t("playlists.kind")} onchange={on_kind_change} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                    <option value="manual" selected={!*new_smart}>{ t("playlists.manual") }</option>
+                    <option value="smart" selected={*new_smart}>{ t("playlists.smart") }</option>
+                </select>
+                <button type="submit" class="download-button font-bold py-2 px-4 rounded inline-flex items-center">
+                    <span class="material-icons icon-space">{"playlist_add"}</span>
+                    <span>{ t("playlists.create") }</span>
+                </button>
+            </form>
+            if playlists.is_empty() {
+                { empty_message(&t("playlists.empty_title"), &t("playlists.empty_body")) }
+            }
+            <ul class="space-y-3">
+                { for playlists.iter().map(|playlist| {
+                    let playlist_id = playlist.PlaylistID;
+                    let count = match &playlist.Rules {
+                        Some(_) => playlist_episodes(playlist, &cache, &folder_state, &podcasts).len(),
+                        None => playlist.EpisodeIDs.len(),
+                    };
+                    let open = {
+                        let history = history.clone();
+                        Callback::from(move |_: MouseEvent| history.push(format!("/playlists/{}", playlist_id)))
+                    };
+                    let on_delete = {
+                        let api_key = api_key.clone();
+                        let server_name = server_name.clone();
+                        let name = playlist.Name.clone();
+                        Callback::from(move |_: MouseEvent| {
+                            let confirmed = web_sys::window()
+                                .and_then(|window| window.confirm_with_message(&t_args("playlists.confirm_delete", &[("name", &name)])).ok())
+                                .unwrap_or(false);
+                            let (true, Some(server_name), Some(user_id)) = (confirmed, server_name.clone(), user_id) else {
+                                return;
+                            };
+                            let api_key = api_key.clone();
+                            spawn_local(async move {
+                                match call_delete_playlist(&server_name, &api_key, user_id, playlist_id).await {
+                                    Ok(_) => Dispatch::<PlaylistState>::global().apply(PlaylistMsg::Deleted(playlist_id)),
+                                    Err(e) => show_error("playlists.save_error", e),
+                                }
+                            });
+                        })
+                    };
+                    html! {
+                        <li class="item-container flex items-center p-4 rounded-lg shadow-md">
+                            <span class="material-icons icon-space item_container-text" aria-hidden="true">
+                                { if playlist.Rules.is_some() { "auto_awesome" } else { "queue_music" } }
+                            </span>
+                            <button onclick={open} class="flex-grow text-left item_container-text">
+                                <span class="font-bold text-lg block">{ &playlist.Name }</span>
+                                <span class="text-sm">
+                                    { format!("{} · {}", if playlist.Rules.is_some() { t("playlists.smart") } else { t("playlists.manual") }, t_plural("playlists.episode_count", count as i64)) }
+                                </span>
+                            </button>
+                            <button onclick={on_delete} aria-label={t_args("playlists.delete", &[("name", playlist.Name.as_str())])} class="item-container-button selector-button rounded p-2">
+                                <span class="material-icons" aria-hidden="true">{"delete"}</span>
+                            </button>
+                        </li>
+                    }
+                }) }
+            </ul>
+        </div>
+    };
+
+    page_frame(&ui_state, content)
+}
+
+#[derive(Properties, PartialEq, Clone)]
+struct RulesEditorProps {
+    rules: SmartRules,
+    on_change: Callback<SmartRules>,
+}
+
+#[function_component(RulesEditor)]
+fn rules_editor(props: &RulesEditorProps) -> Html {
+    let folder_state = use_folders();
+    let folders = folder_state.folders.clone().unwrap_or_default();
+
+    // Each control rebuilds the rules from the current ones with its own field changed
+    let select_change = |apply: fn(&mut SmartRules, String)| {
+        let rules = props.rules.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let value = e
+                .target_dyn_into::<HtmlSelectElement>()
+                .map(|select| select.value())
+                .or_else(|| e.target_dyn_into::<HtmlInputElement>().map(|input| input.value()));
+            if let Some(value) = value {
+                let mut rules = rules.clone();
+                apply(&mut rules, value);
+                on_change.emit(rules);
+            }
+        })
+    };
+
+    let on_status = select_change(|rules, value| rules.status = RuleStatus::ALL.into_iter().find(|status| status.key() == value));
+    let on_folder = select_change(|rules, value| rules.folder_id = value.parse().ok());
+    let on_min = select_change(|rules, value| rules.min_minutes = value.parse().ok().filter(|minutes| *minutes > 0));
+    let on_max = select_change(|rules, value| rules.max_minutes = value.parse().ok().filter(|minutes| *minutes > 0));
+    let on_sort = select_change(|rules, value| rules.sort = RuleSort::ALL.into_iter().find(|sort| sort.key() == value).unwrap_or_default());
+    let on_limit = select_change(|rules, value| rules.limit = value.parse().ok().filter(|limit| *limit > 0));
+
+    let rules = &props.rules;
+    let label_class = "flex flex-col item_container-text text-sm";
+    let select_class = "theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline";
+    let input_class = "search-bar-input border text-sm rounded-lg p-2 w-28";
+
+    html! {
+        <fieldset class="flex flex-wrap items-end gap-4 mb-6">
+            <legend class="item_container-text font-bold mb-2">{ t("playlists.rules") }</legend>
+            <label class={label_class}>
+                { t("playlists.rule_status") }
+                <select onchange={on_status} class={select_class}>
+                    <option value="" selected={rules.status.is_none()}>{ t("playlists.any") }</option>
+                    { for RuleStatus::ALL.iter().map(|status| html! {
+                        <option value={status.key()} selected={rules.status == Some(*status)}>{ t(&format!("playlists.status.{}", status.key())) }</option>
+                    }) }
+                </select>
+            </label>
+            <label class={label_class}>
+                { t("playlists.rule_folder") }
+                <select onchange={on_folder} class={select_class}>
+                    <option value="" selected={rules.folder_id.is_none()}>{ t("folders.all") }</option>
+                    { for folders.iter().map(|folder| html! {
+                        <option value={folder.FolderID.to_string()} selected={rules.folder_id == Some(folder.FolderID)}>{ &folder.FolderName }</option>
+                    }) }
+                </select>
+            </label>
+            <label class={label_class}>
+                { t("playlists.rule_min_minutes") }
+                <input type="number" min="0" value={rules.min_minutes.map(|minutes| minutes.to_string()).unwrap_or_default()} onchange={on_min} class={input_class} />
+            </label>
+            <label class={label_class}>
+                { t("playlists.rule_max_minutes") }
+                <input type="number" min="0" value={rules.max_minutes.map(|minutes| minutes.to_string()).unwrap_or_default()} onchange={on_max} class={input_class} />
+            </label>
+            <label class={label_class}>
+                { t("playlists.rule_sort") }
+                <select onchange={on_sort} class={select_class}>
+                    { for RuleSort::ALL.iter().map(|sort| html! {
+                        <option value={sort.key()} selected={rules.sort == *sort}>{ t(&format!("playlists.sort.{}", sort.key())) }</option>
+                    }) }
+                </select>
+            </label>
+            <label class={label_class}>
+                { t("playlists.rule_limit") }
+                <input type="number" min="0" value={rules.limit.map(|limit| limit.to_string()).unwrap_or_default()} onchange={on_limit} class={input_class} />
+            </label>
+        </fieldset>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct PlaylistPageProps {
+    pub playlist_id: i32,
+}
+
+#[function_component(PlaylistPage)]
+pub fn playlist_page(props: &PlaylistPageProps) -> Html {
+    use_page_setup();
+    use_library_refresh();
+    let (state, dispatch) = use_store::<AppState>();
+    let (ui_state, ui_dispatch) = use_store::<UIState>();
+    let (cache, _cache_dispatch) = use_store::<EpisodeCache>();
+    let (podcasts, _podcasts_dispatch) = use_store::<PodcastCache>();
+    let folder_state = use_folders();
+    let playlist_state = use_playlists();
+    let dragging = use_state(|| None::<usize>);
+    let drop_target = use_state(|| None::<usize>);
+    let history = BrowserHistory::new();
+
+    let api_key = state.auth_details.as_ref().and_then(|ud| ud.api_key.clone());
+    let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+    let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+    let playlist_id = props.playlist_id;
+
+    // Manual playlists can hold episodes no other list has fetched, so load them into the cache
+    {
+        let api_key = api_key.clone();
+        use_effect_with((server_name.clone(), user_id, playlist_id), move |(server_name, user_id, playlist_id)| {
+            if let (Some(server_name), Some(user_id)) = (server_name.clone(), *user_id) {
+                let playlist_id = *playlist_id;
+                spawn_local(async move {
+                    if let Ok(episodes) = call_get_playlist_episodes(&server_name, &api_key, user_id, playlist_id).await {
+                        let episode_ids = episodes.iter().map(|episode| episode.EpisodeID).collect();
+                        Dispatch::<EpisodeCache>::global().apply(EpisodeCacheMsg::Ingest(episodes.iter().map(CachedEpisode::from).collect()));
+                        Dispatch::<PlaylistState>::global().apply(PlaylistMsg::SetEpisodes(playlist_id, episode_ids));
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let Some(playlist) = playlist_state.get(playlist_id).cloned() else {
+        let content = if playlist_state.playlists.is_some() {
+            empty_message(&t("playlists.not_found_title"), &t("playlists.not_found_body"))
+        } else {
+            html! {}
+        };
+        return page_frame(&ui_state, content);
+    };
+    let smart_rules = playlist.Rules.as_deref().map(SmartRules::parse);
+    let episodes = Rc::new(playlist_episodes(&playlist, &cache, &folder_state, &podcasts));
+
+    // Saves name and rules together, since the server stores them as one record
+    let save = {
+        let api_key = api_key.clone();
+        let server_name = server_name.clone();
+        let playlist = playlist.clone();
+        Callback::from(move |(name, rules): (String, Option<String>)| {
+            let (Some(server_name), Some(user_id)) = (server_name.clone(), user_id) else {
+                return;
+            };
+            let api_key = api_key.clone();
+            let updated = Playlist { Name: name, Rules: rules, ..playlist.clone() };
+            let previous = playlist.clone();
+            Dispatch::<PlaylistState>::global().apply(PlaylistMsg::Saved(updated.clone()));
+            spawn_local(async move {
+                if let Err(e) = call_update_playlist(&server_name, &api_key, user_id, updated.PlaylistID, &updated.Name, updated.Rules.as_deref()).await {
+                    Dispatch::<PlaylistState>::global().apply(PlaylistMsg::Saved(previous));
+                    show_error("playlists.save_error", e);
+                }
+            });
+        })
+    };
+
+    let on_rename = {
+        let save = save.clone();
+        let rules = playlist.Rules.clone();
+        Callback::from(move |e: Event| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                let name = input.value().trim().to_string();
+                if !name.is_empty() {
+                    save.emit((name, rules.clone()));
+                }
+            }
+        })
+    };
+
+    let on_rules_change = {
+        let save = save.clone();
+        let name = playlist.Name.clone();
+        Callback::from(move |rules: SmartRules| save.emit((name.clone(), Some(rules.to_json()))))
+    };
+
+    let set_episodes = {
+        let api_key = api_key.clone();
+        let server_name = server_name.clone();
+        Callback::from(move |episode_ids: Vec<i32>| {
+            let (Some(server_name), Some(user_id)) = (server_name.clone(), user_id) else {
+                return;
+            };
+            let api_key = api_key.clone();
+            Dispatch::<PlaylistState>::global().apply(PlaylistMsg::SetEpisodes(playlist_id, episode_ids.clone()));
+            spawn_local(async move {
+                match call_set_playlist_episodes(&server_name, &api_key, user_id, playlist_id, &episode_ids).await {
+                    Ok(saved_ids) => Dispatch::<PlaylistState>::global().apply(PlaylistMsg::SetEpisodes(playlist_id, saved_ids)),
+                    Err(e) => {
+                        show_error("playlists.save_error", e);
+                        // The server's copy wins after a failed save
+                        if let Ok(playlists) = call_get_playlists(&server_name, &api_key, user_id).await {
+                            Dispatch::<PlaylistState>::global().apply(PlaylistMsg::Loaded(playlists));
+                        }
+                    }
+                }
+            });
+        })
+    };
+
+    let on_move = {
+        let set_episodes = set_episodes.clone();
+        let episodes = episodes.clone();
+        Callback::from(move |(from, to): (usize, usize)| {
+            let mut episode_ids: Vec<i32> = episodes.iter().map(|episode| episode.episode_id).collect();
+            if from == to || from >= episode_ids.len() {
+                return;
+            }
+            move_item(&mut episode_ids, from, to);
+            set_episodes.emit(episode_ids);
+        })
+    };
+
+    let on_play_all = {
+        let episodes = episodes.clone();
+        let ui_dispatch = ui_dispatch.clone();
+        let ui_state = ui_state.clone();
+        let api_key = api_key.clone();
+        let server_name = server_name.clone();
+        Callback::from(move |e: MouseEvent| {
+            let (Some(first), Some(server_name), Some(api_key), Some(user_id)) = (episodes.first(), server_name.clone(), api_key.clone(), user_id) else {
+                return;
+            };
+            // Set first, so starting the first episode doesn't end playlist playback straight away
+            let episode_ids = episodes.iter().map(|episode| episode.episode_id).collect();
+            ui_dispatch.reduce_mut(|ui_state| ui_state.playing_playlist = Some(PlaylistPlayback { playlist_id, episode_ids }));
+            on_play_click(
+                first.url.clone(),
+                first.title.clone(),
+                first.artwork.clone(),
+                first.duration,
+                first.episode_id,
+                first.listen_duration,
+                api_key,
+                user_id,
+                server_name,
+                ui_dispatch.clone(),
+                ui_state.clone(),
+                None,
+            )
+            .emit(e);
+        })
+    };
+
+    let on_export = {
+        let episodes = episodes.clone();
+        let name = playlist.Name.clone();
+        Callback::from(move |opml: bool| {
+            let (filename, mime_type, content) = if opml {
+                (export_filename(&name, "opml"), "text/x-opml", playlist_opml(&name, &episodes))
+            } else {
+                (export_filename(&name, "m3u"), "audio/x-mpegurl", playlist_m3u(&name, &episodes))
+            };
+            if download_text(&filename, mime_type, &content).is_err() {
+                let message = t("playlists.export_error");
+                Dispatch::<UIState>::global().reduce_mut(|ui_state| ui_state.error_message = Some(message));
+            }
+        })
+    };
+
+    let is_manual = smart_rules.is_none();
+    let item_count = episodes.len();
+    let item_keys: Vec<Key> = episodes.iter().map(|episode| Key::from(episode.episode_id)).collect();
+    let render_item = {
+        let episodes = episodes.clone();
+        let dragging = dragging.clone();
+        let drop_target = drop_target.clone();
+        let on_move = on_move.clone();
+        let set_episodes = set_episodes.clone();
+        let state = state.clone();
+        let dispatch = dispatch.clone();
+        let ui_state = ui_state.clone();
+        let ui_dispatch = ui_dispatch.clone();
+        let history = history.clone();
+        Callback::from(move |index: usize| {
+            let cached = episodes[index].clone();
+            let episode = Episode::from(cached.as_ref());
+            let episode_id = episode.EpisodeID;
+            let is_expanded = state.expanded_descriptions.contains(&episode_id.to_string());
+            let sanitized_description = sanitize_html_with_blank_target(&episode.EpisodeDescription);
+            let (description, _is_truncated) = if is_expanded {
+                (sanitized_description, false)
+            } else {
+                truncate_description(sanitized_description, 300)
+            };
+
+            let toggle_expanded = {
+                let dispatch = dispatch.clone();
+                let state = state.clone();
+                Callback::from(move |_: MouseEvent| {
+                    let guid = episode_id.to_string();
+                    if state.expanded_descriptions.contains(&guid) {
+                        dispatch.apply(AppStateMsg::CollapseEpisode(guid));
+                    } else {
+                        dispatch.apply(AppStateMsg::ExpandEpisode(guid));
+                    }
+                })
+            };
+
+            let on_play = on_play_click(
+                episode.EpisodeURL.clone(),
+                episode.EpisodeTitle.clone(),
+                episode.EpisodeArtwork.clone(),
+                episode.EpisodeDuration,
+                episode_id,
+                episode.ListenDuration,
+                state.auth_details.as_ref().and_then(|ud| ud.api_key.clone()).unwrap_or_default(),
+                state.user_details.as_ref().map(|ud| ud.UserID).unwrap_or_default(),
+                state.auth_details.as_ref().map(|ud| ud.server_name.clone()).unwrap_or_default(),
+                ui_dispatch.clone(),
+                ui_state.clone(),
+                None,
+            );
+            let on_shownotes = on_shownotes_click(history.clone(), dispatch.clone(), episode_id);
+            let datetime = parse_date(&episode.EpisodePubDate, &state.user_tz);
+            let format_release = format_datetime(&datetime, &state.hour_preference, match_date_format(state.date_format.as_deref()));
+            let duration = episode.EpisodeDuration;
+            let listen_duration = episode.ListenDuration;
+            let title = episode.EpisodeTitle.clone();
+            let item = episode_item(Box::new(episode), description, is_expanded, &format_release, on_play, on_shownotes, toggle_expanded, duration, listen_duration, "playlist");
+
+            if !is_manual {
+                return item;
+            }
+            let on_remove = {
+                let set_episodes = set_episodes.clone();
+                let episodes = episodes.clone();
+                Callback::from(move |_: MouseEvent| {
+                    set_episodes.emit(episodes.iter().map(|episode| episode.episode_id).filter(|id| *id != episode_id).collect());
+                })
+            };
+            let controls = html! {
+                <div class="flex flex-col items-center">
+                    <QueueRowControls index={index} count={item_count} title={title.clone()} on_move={on_move.clone()} dragging={dragging.clone()} drop_target={drop_target.clone()} />
+                    <button onclick={on_remove} title={t("playlists.remove")} aria-label={t_args("playlists.remove_episode", &[("title", title.as_str())])} class="item-container-button selector-button rounded p-1 mt-2 mr-2">
+                        <span class="material-icons" aria-hidden="true">{"remove_circle_outline"}</span>
+                    </button>
+                </div>
+            };
+            queue_row(index, &dragging, &drop_target, &on_move, controls, item)
+        })
+    };
+
+    let content = html! {
+        <div class="p-4">
+            <input
+                type="text"
+                value={playlist.Name.clone()}
+                onchange={on_rename}
+                aria-label={t("playlists.name")}
+                class="text-2xl item_container-text font-bold text-center mb-6 w-full bg-transparent border-none"
+            />
+            if let Some(rules) = smart_rules {
+                <RulesEditor rules={rules} on_change={on_rules_change} />
+            }
+            <div class="flex flex-wrap items-center gap-2 mb-4">
+                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_play_all} disabled={episodes.is_empty()}>
+                    <span class="material-icons icon-space">{"play_arrow"}</span>
+                    <span>{ t("playlists.play_all") }</span>
+                </button>
+                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_export.reform(|_: MouseEvent| false)} disabled={episodes.is_empty()}>
+                    <span class="material-icons icon-space">{"file_download"}</span>
+                    <span>{ t("playlists.export_m3u") }</span>
+                </button>
+                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_export.reform(|_: MouseEvent| true)} disabled={episodes.is_empty()}>
+                    <span class="material-icons icon-space">{"file_download"}</span>
+                    <span>{ t("playlists.export_opml") }</span>
+                </button>
+                <span class="item_container-text">{ t_plural("playlists.episode_count", item_count as i64) }</span>
+            </div>
+            if episodes.is_empty() {
+                if is_manual {
+                    { empty_message(&t("playlists.empty_manual_title"), &t("playlists.empty_manual_body")) }
+                } else {
+                    { empty_message(&t("playlists.empty_smart_title"), &t("playlists.empty_smart_body")) }
+                }
+            } else {
+                <VirtualList item_count={item_count} render_item={render_item} item_keys={item_keys} />
+            }
+        </div>
+    };
+
+    page_frame(&ui_state, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::requests::pod_req::{Folder, Podcast};
+
+    fn episode(episode_id: i32, podcast_id: Option<i32>, podcast_name: &str, pub_date: &str, duration: i32, listen_duration: Option<i32>) -> (i32, Rc<CachedEpisode>) {
+        let episode = CachedEpisode { listen_duration, ..CachedEpisode::for_test(episode_id, podcast_id, podcast_name, pub_date, duration) };
+        (episode_id, Rc::new(episode))
+    }
+
+    fn podcast(podcast_id: i32, name: &str) -> (i32, Rc<Podcast>) {
+        let podcast = Podcast {
+            PodcastID: podcast_id,
+            PodcastName: name.to_string(),
+            ArtworkURL: None,
+            Description: None,
+            EpisodeCount: 0,
+            WebsiteURL: None,
+            FeedURL: String::new(),
+            Author: None,
+            Categories: String::new(),
+            Explicit: false,
+            LatestEpisodeDate: None,
+            TotalListenTime: None,
+            DateAdded: None,
+        };
+        (podcast_id, Rc::new(podcast))
+    }
+
+    // Two podcasts, the first of them in folder 7
+    fn library() -> (EpisodeCache, FolderState, PodcastCache) {
+        let cache = EpisodeCache {
+            episodes: [
+                episode(1, Some(10), "Daily", "2024-01-01T00:00:00", 1800, None),
+                episode(2, Some(10), "Daily", "2024-01-03T00:00:00", 600, Some(300)),
+                episode(3, Some(20), "Weekly", "2024-01-02T00:00:00", 3600, Some(3590)),
+                // Only known by name, as some lists don't carry the podcast id
+                episode(4, None, "Daily", "2024-01-04T00:00:00", 2400, None),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let folders = FolderState {
+            folders: Some(vec![Folder { FolderID: 7, FolderName: "News".to_string(), PodcastIDs: vec![10] }]),
+            active_folder: None,
+        };
+        let podcasts = PodcastCache { podcasts: [podcast(10, "Daily"), podcast(20, "Weekly")].into_iter().collect() };
+        (cache, folders, podcasts)
+    }
+
+    fn ids(episodes: &[Rc<CachedEpisode>]) -> Vec<i32> {
+        episodes.iter().map(|episode| episode.episode_id).collect()
+    }
+
+    #[test]
+    fn empty_rules_match_everything_newest_first() {
+        let (cache, folders, podcasts) = library();
+        assert_eq!(ids(&SmartRules::default().evaluate(&cache, &folders, &podcasts)), vec![4, 2, 3, 1]);
+    }
+
+    #[test]
+    fn status_uses_the_listen_state() {
+        let (cache, folders, podcasts) = library();
+        let rules = |status| SmartRules { status: Some(status), ..Default::default() };
+        assert_eq!(ids(&rules(RuleStatus::Unplayed).evaluate(&cache, &folders, &podcasts)), vec![4, 1]);
+        assert_eq!(ids(&rules(RuleStatus::InProgress).evaluate(&cache, &folders, &podcasts)), vec![2]);
+        // Ten seconds left of an hour counts as played
+        assert_eq!(ids(&rules(RuleStatus::Played).evaluate(&cache, &folders, &podcasts)), vec![3]);
+    }
+
+    #[test]
+    fn folder_matches_by_podcast_id_or_name() {
+        let (cache, folders, podcasts) = library();
+        let rules = SmartRules { folder_id: Some(7), ..Default::default() };
+        assert_eq!(ids(&rules.evaluate(&cache, &folders, &podcasts)), vec![4, 2, 1]);
+        // A folder that no longer exists matches nothing
+        let rules = SmartRules { folder_id: Some(8), ..Default::default() };
+        assert!(rules.evaluate(&cache, &folders, &podcasts).is_empty());
+    }
+
+    #[test]
+    fn duration_bounds_are_inclusive() {
+        let (cache, folders, podcasts) = library();
+        let rules = SmartRules { min_minutes: Some(30), max_minutes: Some(40), ..Default::default() };
+        assert_eq!(ids(&rules.evaluate(&cache, &folders, &podcasts)), vec![4, 1]);
+    }
+
+    #[test]
+    fn sorts_and_limits() {
+        let (cache, folders, podcasts) = library();
+        let rules = |sort| SmartRules { sort, limit: Some(2), ..Default::default() };
+        assert_eq!(ids(&rules(RuleSort::OldestFirst).evaluate(&cache, &folders, &podcasts)), vec![1, 3]);
+        assert_eq!(ids(&rules(RuleSort::ShortestFirst).evaluate(&cache, &folders, &podcasts)), vec![2, 1]);
+        assert_eq!(ids(&rules(RuleSort::LongestFirst).evaluate(&cache, &folders, &podcasts)), vec![3, 4]);
+    }
+
+    #[test]
+    fn progress_only_entries_are_skipped() {
+        let (mut cache, folders, podcasts) = library();
+        cache.episodes.insert(5, Rc::new(CachedEpisode { episode_id: 5, listen_duration: Some(10), ..Default::default() }));
+        assert!(!ids(&SmartRules::default().evaluate(&cache, &folders, &podcasts)).contains(&5));
+    }
+
+    #[test]
+    fn rules_round_trip_and_bad_rules_match_everything() {
+        let rules = SmartRules { status: Some(RuleStatus::InProgress), folder_id: Some(3), sort: RuleSort::LongestFirst, limit: Some(5), ..Default::default() };
+        assert_eq!(SmartRules::parse(&rules.to_json()), rules);
+        assert_eq!(SmartRules::parse("not json"), SmartRules::default());
+    }
+}
@@ -1,17 +1,18 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use serde::{Deserialize, Serialize};
-use web_sys::MouseEvent;
+use web_sys::{Event, HtmlInputElement, HtmlSelectElement, MouseEvent};
 use yew::{Callback, function_component, Html, html};
 use yew::prelude::*;
 use yew_router::history::{BrowserHistory, History};
-use yewdux::use_store;
+use yewdux::prelude::*;
 use super::app_drawer::App_drawer;
-use super::gen_components::{UseScrollToTop, Search_nav};
-use crate::components::context::{AppState, UIState};
+use super::gen_components::{empty_message, UseScrollToTop, Search_nav};
+use crate::components::context::{AppState, UIState, PodcastCache, PodcastCacheMsg};
 use crate::components::audio::AudioPlayer;
-use crate::requests::search_pods::{call_parse_podcast_url, Podcast, UnifiedPodcast};
-use crate::requests::pod_req::{call_check_podcast, call_add_podcast, call_remove_podcasts_name, RemovePodcastValuesName, PodcastValues};
-use std::collections::HashSet;
+use crate::requests::search_pods::{call_parse_podcast_url, UnifiedPodcast};
+use crate::requests::search_providers::{normalize_feed_url, provider_name};
+use crate::components::i18n::{t, t_args, t_plural};
+use crate::requests::pod_req::{call_add_podcast, call_get_podcasts, call_remove_podcasts_name, Podcast, PodcastResponse, RemovePodcastValuesName, PodcastValues};
 use crate::requests::login_requests::use_check_authentication;
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
@@ -29,12 +30,144 @@ pub struct ClickedFeedURL {
     
 }
 
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+enum ExplicitFilter {
+    #[default]
+    Any,
+    Clean,
+    Explicit,
+}
+
+impl ExplicitFilter {
+    const ALL: [ExplicitFilter; 3] = [ExplicitFilter::Any, ExplicitFilter::Clean, ExplicitFilter::Explicit];
+
+    fn value(&self) -> &'static str {
+        match self {
+            ExplicitFilter::Any => "any",
+            ExplicitFilter::Clean => "clean",
+            ExplicitFilter::Explicit => "explicit",
+        }
+    }
+
+    fn from_value(value: &str) -> Self {
+        ExplicitFilter::ALL.into_iter().find(|filter| filter.value() == value).unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+enum DiscoverySort {
+    // The order the merged directory results came in
+    #[default]
+    Relevance,
+    Updated,
+    Episodes,
+}
+
+impl DiscoverySort {
+    const ALL: [DiscoverySort; 3] = [DiscoverySort::Relevance, DiscoverySort::Updated, DiscoverySort::Episodes];
+
+    fn value(&self) -> &'static str {
+        match self {
+            DiscoverySort::Relevance => "relevance",
+            DiscoverySort::Updated => "updated",
+            DiscoverySort::Episodes => "episodes",
+        }
+    }
+
+    fn from_value(value: &str) -> Self {
+        DiscoverySort::ALL.into_iter().find(|sort| sort.value() == value).unwrap_or_default()
+    }
+}
+
+const UPDATED_WITHIN_DAYS: [i64; 4] = [7, 30, 90, 365];
+
+// Narrowing and ordering of the directory results. Fields a directory doesn't provide
+// (language, last update) fail their filter, since nothing says the podcast matches.
+#[derive(Clone, PartialEq, Default, Debug)]
+struct DiscoveryFilters {
+    explicit: ExplicitFilter,
+    language: Option<String>,
+    category: Option<String>,
+    min_episodes: Option<i32>,
+    updated_within_days: Option<i64>,
+    sort: DiscoverySort,
+}
+
+impl DiscoveryFilters {
+    fn matches(&self, podcast: &UnifiedPodcast, now_secs: i64) -> bool {
+        let explicit_ok = match self.explicit {
+            ExplicitFilter::Any => true,
+            ExplicitFilter::Clean => !podcast.explicit,
+            ExplicitFilter::Explicit => podcast.explicit,
+        };
+        explicit_ok
+            && self.language.as_ref().map_or(true, |language| language_code(podcast).as_ref() == Some(language))
+            && self.category.as_ref().map_or(true, |category| categories(podcast).any(|c| c == category))
+            && self.min_episodes.map_or(true, |minimum| podcast.episodeCount >= minimum)
+            && self.updated_within_days.map_or(true, |days| podcast.lastUpdateTime > 0 && now_secs - podcast.lastUpdateTime <= days * 86_400)
+    }
+
+    // Sorting is stable, so ties keep their relevance order
+    fn apply<'a>(&self, podcasts: &'a [UnifiedPodcast]) -> Vec<&'a UnifiedPodcast> {
+        let now_secs = (js_sys::Date::now() / 1000.0) as i64;
+        let mut shown: Vec<&UnifiedPodcast> = podcasts.iter().filter(|podcast| self.matches(podcast, now_secs)).collect();
+        match self.sort {
+            DiscoverySort::Relevance => {}
+            DiscoverySort::Updated => shown.sort_by(|a, b| b.lastUpdateTime.cmp(&a.lastUpdateTime)),
+            DiscoverySort::Episodes => shown.sort_by(|a, b| b.episodeCount.cmp(&a.episodeCount)),
+        }
+        shown
+    }
+
+    // Sorting alone never hides anything
+    fn is_filtering(&self) -> bool {
+        *self != DiscoveryFilters { sort: self.sort, ..DiscoveryFilters::default() }
+    }
+}
+
+// "en-US", "en_us" and "EN" all filter as "en"
+fn language_code(podcast: &UnifiedPodcast) -> Option<String> {
+    podcast.language.as_deref()
+        .and_then(|language| language.split(['-', '_']).next())
+        .map(|code| code.trim().to_lowercase())
+        .filter(|code| !code.is_empty())
+}
+
+fn categories(podcast: &UnifiedPodcast) -> impl Iterator<Item = &str> {
+    podcast.categories.iter().flat_map(|categories| categories.values()).map(|category| category.trim()).filter(|category| !category.is_empty())
+}
+
+// Feed URLs of the user's subscriptions, compared the way search results are deduplicated
+fn subscribed_feeds(podcast_cache: &PodcastCache, persisted: Option<&Vec<Podcast>>) -> HashSet<String> {
+    if !podcast_cache.podcasts.is_empty() {
+        podcast_cache.podcasts.values().map(|podcast| normalize_feed_url(&podcast.FeedURL)).collect()
+    } else {
+        persisted.into_iter().flatten().map(|podcast| normalize_feed_url(&podcast.FeedURL)).collect()
+    }
+}
+
+// Fetches the subscriptions so badges follow adds and removals made here or elsewhere
+async fn refresh_subscriptions(server_name: String, api_key: Option<String>, user_id: i32) {
+    if let Ok(podcasts) = call_get_podcasts(&server_name, &api_key, &user_id).await {
+        Dispatch::<PodcastCache>::global().apply(PodcastCacheMsg::Sync(podcasts.clone()));
+        Dispatch::<AppState>::global().reduce_mut(move |state| {
+            state.podcast_feed_return = Some(PodcastResponse { pods: Some(podcasts) });
+        });
+    }
+}
+
+fn select_value(e: &Event) -> Option<String> {
+    e.target_dyn_into::<HtmlSelectElement>().map(|select| select.value())
+}
+
 #[function_component(PodLayout)]
 pub fn pod_layout() -> Html {
     // let dispatch = Dispatch::<AppState>::global();
     // let state: Rc<AppState> = dispatch.get();
     let (state, dispatch) = use_store::<AppState>();
     let (audio_state, audio_dispatch) = use_store::<UIState>();
+    let podcast_cache = use_store_value::<PodcastCache>();
+    let filters = use_state(DiscoveryFilters::default);
 
     let search_results = state.search_results.clone();    
 
@@ -70,24 +203,135 @@ pub fn pod_layout() -> Html {
         || ()
     });
 
+    {
+        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+        let api_key = state.auth_details.as_ref().and_then(|ud| ud.api_key.clone());
+        let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+        use_effect_with((server_name, user_id), move |(server_name, user_id)| {
+            if let (Some(server_name), Some(user_id)) = (server_name.clone(), *user_id) {
+                wasm_bindgen_futures::spawn_local(refresh_subscriptions(server_name, api_key, user_id));
+            }
+            || ()
+        });
+    }
+
+    // A new search may not have the language or category picked for the last one
+    {
+        let filters = filters.clone();
+        use_effect_with(search_results.clone(), move |_| {
+            filters.set(DiscoveryFilters { language: None, category: None, ..(*filters).clone() });
+            || ()
+        });
+    }
+
+    let subscribed = subscribed_feeds(&podcast_cache, state.podcast_feed_return.as_ref().and_then(|response| response.pods.as_ref()));
+
+    let update_filters = |change: fn(&mut DiscoveryFilters, String)| {
+        let filters = filters.clone();
+        Callback::from(move |e: Event| {
+            if let Some(value) = select_value(&e) {
+                let mut updated = (*filters).clone();
+                change(&mut updated, value);
+                filters.set(updated);
+            }
+        })
+    };
+    let on_explicit_change = update_filters(|filters, value| filters.explicit = ExplicitFilter::from_value(&value));
+    let on_language_change = update_filters(|filters, value| filters.language = Some(value).filter(|value| !value.is_empty()));
+    let on_category_change = update_filters(|filters, value| filters.category = Some(value).filter(|value| !value.is_empty()));
+    let on_updated_change = update_filters(|filters, value| filters.updated_within_days = value.parse().ok());
+    let on_sort_change = update_filters(|filters, value| filters.sort = DiscoverySort::from_value(&value));
+
+    let on_min_episodes_input = {
+        let filters = filters.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                let min_episodes = input.value().trim().parse::<i32>().ok().filter(|minimum| *minimum > 0);
+                filters.set(DiscoveryFilters { min_episodes, ..(*filters).clone() });
+            }
+        })
+    };
+
+    let on_reset_filters = {
+        let filters = filters.clone();
+        Callback::from(move |_: MouseEvent| filters.set(DiscoveryFilters { sort: filters.sort, ..DiscoveryFilters::default() }))
+    };
+
     html! {
         <>
             <div class="main-container">
                 <Search_nav />
                 <UseScrollToTop />
-                <h1 class="item_container-text text-2xl font-bold my-4 center-text">{ "Podcast Search Results" }</h1>
+                <h1 class="item_container-text text-2xl font-bold my-4 center-text">{ t("discovery.title") }</h1>
                 {
                     if let Some(results) = search_results {
-                        let podcasts = results.feeds.as_ref().map_or_else(
-                            || results.results.as_ref().map(|r| r.iter().map(|item| item.clone().into()).collect::<Vec<UnifiedPodcast>>()),
-                            |f| Some(f.iter().map(|item| item.clone().into()).collect::<Vec<UnifiedPodcast>>())
-                        );
-                
-                        if let Some(podcasts) = podcasts {
+                        let failed = results.failed.iter().map(|id| provider_name(id)).collect::<Vec<_>>().join(", ");
+
+                        if !results.podcasts.is_empty() {
+                            let languages: BTreeSet<String> = results.podcasts.iter().filter_map(language_code).collect();
+                            let category_options: BTreeSet<&str> = results.podcasts.iter().flat_map(categories).collect();
+                            let shown = filters.apply(&results.podcasts);
+                            let total = results.podcasts.len().to_string();
+                            let shown_count = shown.len().to_string();
                             html! {
                                 <div>
-                                    { for podcasts.iter().map(|podcast| html! {
-                                        <PodcastItem podcast={podcast.clone()} />
+                                    if !failed.is_empty() {
+                                        <p class="item_container-text text-sm mb-4">{ t_args("search_providers.failed", &[("providers", failed.as_str())]) }</p>
+                                    }
+                                    <div class="flex flex-wrap items-center gap-2 mb-4" role="group" aria-label={t("discovery.filters")}>
+                                        <select aria-label={t("discovery.explicit_label")} onchange={on_explicit_change} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                                            { for ExplicitFilter::ALL.iter().map(|filter| html! {
+                                                <option value={filter.value()} selected={filters.explicit == *filter}>{ t(&format!("discovery.explicit.{}", filter.value())) }</option>
+                                            }) }
+                                        </select>
+                                        if !languages.is_empty() {
+                                            <select aria-label={t("discovery.language_label")} onchange={on_language_change} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                                                <option value="" selected={filters.language.is_none()}>{ t("discovery.any_language") }</option>
+                                                { for languages.iter().map(|language| html! {
+                                                    <option value={language.clone()} selected={filters.language.as_ref() == Some(language)}>{ language.to_uppercase() }</option>
+                                                }) }
+                                            </select>
+                                        }
+                                        if !category_options.is_empty() {
+                                            <select aria-label={t("discovery.category_label")} onchange={on_category_change} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                                                <option value="" selected={filters.category.is_none()}>{ t("discovery.any_category") }</option>
+                                                { for category_options.iter().map(|category| html! {
+                                                    <option value={category.to_string()} selected={filters.category.as_deref() == Some(*category)}>{ *category }</option>
+                                                }) }
+                                            </select>
+                                        }
+                                        <select aria-label={t("discovery.updated_label")} onchange={on_updated_change} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                                            <option value="" selected={filters.updated_within_days.is_none()}>{ t("discovery.updated_any") }</option>
+                                            { for UPDATED_WITHIN_DAYS.iter().map(|days| html! {
+                                                <option value={days.to_string()} selected={filters.updated_within_days == Some(*days)}>{ t_plural("discovery.updated_within", *days) }</option>
+                                            }) }
+                                        </select>
+                                        <input
+                                            type="number"
+                                            min="1"
+                                            class="search-bar-input border text-sm rounded-lg block w-40 p-2.5"
+                                            placeholder={t("discovery.min_episodes")}
+                                            aria-label={t("discovery.min_episodes")}
+                                            value={filters.min_episodes.map(|minimum| minimum.to_string()).unwrap_or_default()}
+                                            oninput={on_min_episodes_input}
+                                        />
+                                        <select aria-label={t("discovery.sort_label")} onchange={on_sort_change} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                                            { for DiscoverySort::ALL.iter().map(|sort| html! {
+                                                <option value={sort.value()} selected={filters.sort == *sort}>{ t(&format!("discovery.sort.{}", sort.value())) }</option>
+                                            }) }
+                                        </select>
+                                        if filters.is_filtering() {
+                                            <button type="button" onclick={on_reset_filters} class="download-button font-bold py-2 px-4 rounded inline-flex items-center">{ t("discovery.reset") }</button>
+                                        }
+                                    </div>
+                                    <p class="item_container-text text-sm mb-4" role="status">
+                                        { t_args("discovery.showing", &[("shown", shown_count.as_str()), ("total", total.as_str())]) }
+                                    </p>
+                                    if shown.is_empty() {
+                                        { empty_message(&t("discovery.no_matches_title"), &t("discovery.no_matches_body")) }
+                                    }
+                                    { for shown.into_iter().map(|podcast| html! {
+                                        <PodcastItem key={podcast.url.clone()} podcast={podcast.clone()} subscribed={subscribed.contains(&normalize_feed_url(&podcast.url))} />
                                     })}
                                 </div>
                             }
@@ -96,8 +340,11 @@ pub fn pod_layout() -> Html {
                                 <>
                                     <div class="empty-episodes-container">
                                         <img src="static/assets/favicon.png" alt="Logo" class="logo"/>
-                                        <h1>{ "No Podcast Search Results Found" }</h1>
-                                        <p>{"Try searching again with a different set of keywords."}</p>
+                                        <h1>{ t("empty.podcast_search.title") }</h1>
+                                        <p>{ t("empty.podcast_search.body") }</p>
+                                        if !failed.is_empty() {
+                                            <p>{ t_args("search_providers.failed", &[("providers", failed.as_str())]) }</p>
+                                        }
                                     </div>
                                 </>
                             }
@@ -107,8 +354,8 @@ pub fn pod_layout() -> Html {
                             <>
                                 <div class="empty-episodes-container">
                                     <img src="static/assets/favicon.png" alt="Logo" class="logo"/>
-                                    <h1>{ "No Podcast Search Results Found" }</h1>
-                                    <p>{"Try searching again with a different set of keywords."}</p>
+                                    <h1>{ t("empty.podcast_search.title") }</h1>
+                                    <p>{ t("empty.podcast_search.body") }</p>
                                 </div>
                             </>
                         }
@@ -130,189 +377,90 @@ pub fn pod_layout() -> Html {
 #[derive(Properties, PartialEq, Clone)]
 pub struct PodcastProps {
     pub podcast: UnifiedPodcast, // Assuming Podcast is a struct that holds podcast details
+    // Whether the feed is among the user's subscriptions
+    pub subscribed: bool,
 }
 
-// Assuming you have a PodcastItem component
 #[function_component(PodcastItem)]
 pub fn podcast_item(props: &PodcastProps) -> Html {
-    // Local state to track if this particular podcast is added
-    let is_added = use_state(|| false);
     let podcast = props.podcast.clone();
     let (state, dispatch) = use_store::<AppState>();
     let api_key = state.auth_details.as_ref().map(|ud| ud.api_key.clone());
     let user_id = state.user_details.as_ref().map(|ud| ud.UserID.clone());
     let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
     let history = BrowserHistory::new();
-    let history_clone = history.clone();
-    // let api_key_feed = state.auth_details.as_ref().map(|ud| ud.api_key.clone());
-    // let server_name_feed = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
-
+    // Set after subscribing or unsubscribing here, until the refreshed subscriptions arrive
+    let toggled = use_state(|| None::<bool>);
+    let working = use_state(|| false);
 
-        // Use a Set to track added podcast URLs for efficiency
-    let added_podcasts = use_state(|| HashSet::new());
-
-    // On mount, check if the podcast is in the database
-    let effect_user_id = user_id.unwrap().clone();
-    let effect_api_key = api_key.clone();
-    let added_clone = added_podcasts.clone();
-    let server_name_mount = server_name.clone();
-    // let api_key_mount = api_key.clone();
     {
-        let is_added = is_added.clone();
-        let podcast = podcast.clone();
-        let user_id = effect_user_id.clone();
-        let api_key = effect_api_key.clone();
-        let server_name = server_name_mount.clone();
-        let added_podcasts = added_clone.clone(); // Clone this for use in the effect
-
-        use_effect_with(
-            &(),
-            move |_| {
-                let is_added = is_added.clone();
-                let podcast = podcast.clone();
-                wasm_bindgen_futures::spawn_local(async move {
-                    let added = call_check_podcast(&server_name.unwrap(), &api_key.unwrap().unwrap(), user_id, &podcast.title, &podcast.url).await.unwrap_or_default().exists;
-                    is_added.set(added);
-                    let mut new_set = (*added_podcasts).clone();
-                    if added {
-                        new_set.insert(podcast.url.clone());
-                    } else {
-                        new_set.remove(&podcast.url);
-                    }
-                    added_podcasts.set(new_set);
-                });
-                || ()
-            },
-        );
+        let toggled = toggled.clone();
+        use_effect_with(props.subscribed, move |_| {
+            toggled.set(None);
+            || ()
+        });
     }
-
-    let podcast_add = podcast.clone();
+    let is_added = (*toggled).unwrap_or(props.subscribed);
 
     let toggle_podcast = {
-        let podcast_add = podcast_add.clone();
-
-        let pod_title_og = podcast_add.title.clone();
-        let pod_artwork_og = podcast_add.artwork.clone();
-        let pod_author_og = podcast_add.author.clone();
-        let categories_og = podcast_add.categories.unwrap_or_default().clone();
-        let pod_description_og = podcast_add.description.clone();
-        let pod_episode_count_og = podcast_add.episodeCount.clone();
-        let pod_feed_url_og = podcast_add.url.clone();
-        let pod_website_og = podcast_add.link.clone();
-        let pod_explicit_og = podcast_add.explicit.clone();
-
-        let api_key_clone = api_key.clone();
-        let server_name_clone = server_name.clone();
-        let user_id_clone = user_id.clone();
-
-        let added_podcasts = added_podcasts.clone();
-        let dispatch = dispatch.clone(); // Clone the dispatch for updating global state after removing
-        let podcast_url = podcast.url.clone(); // The URL of the podcast to toggle
-        let pod_title_og_clone = pod_title_og.clone();
-
-        Callback::from(move |_: MouseEvent| {
-            dispatch.reduce_mut(|state| state.is_loading = Some(true));
-            // Create a new set from the current state for modifications.
-            let user_id = user_id_clone.clone();
-            let api_key = api_key_clone.clone();
-            let server_name = server_name_clone.clone();
-
-            let current_set = (*added_podcasts).clone();
+        let podcast = podcast.clone();
+        let api_key = api_key.clone().flatten();
+        let server_name = server_name.clone();
+        let dispatch = dispatch.clone();
+        let toggled = toggled.clone();
+        let working = working.clone();
 
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            let (Some(server_name), Some(user_id), false) = (server_name.clone(), user_id, *working) else {
+                return;
+            };
+            working.set(true);
+            let podcast = podcast.clone();
+            let api_key = api_key.clone();
             let dispatch = dispatch.clone();
-            let added_podcasts = added_podcasts.clone();
-            let podcast_url = podcast_url.clone();
-            
-            if current_set.contains(&podcast_url) {
-                // If the podcast was added, remove it from the set and call remove_podcast.
-                // Call remove_podcast asynchronously.
-                let pod_title_og = pod_title_og_clone.clone();
-                let pod_feed_url_og = pod_feed_url_og.clone();
-                let value_id = user_id.clone().unwrap();
-                let podcast_url = podcast_url.clone();
-                wasm_bindgen_futures::spawn_local(async move {
-                    let pod_title = pod_title_og.clone();
-                    let pod_feed_url = pod_feed_url_og.clone();
-                    let podcast_url = podcast_url.clone();
+            let toggled = toggled.clone();
+            let working = working.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = if is_added {
                     let podcast_values = RemovePodcastValuesName {
-                        podcast_name: pod_title,
-                        podcast_url: pod_feed_url,
-                        user_id: value_id
+                        podcast_name: podcast.title.clone(),
+                        podcast_url: podcast.url.clone(),
+                        user_id,
                     };
-                    match call_remove_podcasts_name(&server_name.unwrap(), &api_key.unwrap(), &podcast_values).await {
-                        Ok(_) => {
-                            // If successful, update the state to remove the podcast
-                            let mut new_set = current_set.clone();
-                            new_set.remove(&podcast_url);
-                            added_podcasts.set(new_set);
-                            dispatch.reduce_mut(|state| {
-                                state.info_message = Some("Podcast successfully removed".to_string());
-                            });
-                            dispatch.reduce_mut(|state| state.is_loading = Some(false));
-                        },
-                        Err(e) => {
-                            dispatch.reduce_mut(|state| {
-                                state.error_message = Some(format!("Error removing podcast: {:?}", e));
-                            });
-                            dispatch.reduce_mut(|state| state.is_loading = Some(false));
-                        }
-                    }
-                });
-            } else {
-                // If the podcast was not added, add it to the set and call add_podcast.
-                let pod_title_og = pod_title_og.clone();
-                let pod_artwork_og = pod_artwork_og.clone();
-                let pod_author_og = pod_author_og.clone();
-                let categories_og = categories_og.clone();
-                let pod_description_og = pod_description_og.clone();
-                let pod_episode_count_og = pod_episode_count_og.clone();
-                let pod_feed_url_og = pod_feed_url_og.clone();
-                let pod_website_og = pod_website_og.clone();
-                let pod_explicit_og = pod_explicit_og.clone();
-                
-                wasm_bindgen_futures::spawn_local(async move {
-                    let pod_title = pod_title_og.clone();
-                    let pod_artwork = pod_artwork_og.clone();
-                    let pod_author = pod_author_og.clone();
-                    let categories = categories_og.clone();
-                    let pod_description = pod_description_og.clone();
-                    let pod_episode_count = pod_episode_count_og.clone();
-                    let pod_feed_url = pod_feed_url_og.clone();
-                    let pod_website = pod_website_og.clone();
-                    let pod_explicit = pod_explicit_og.clone();
-                    let value_id = user_id.clone().unwrap();
+                    call_remove_podcasts_name(&server_name, &api_key, &podcast_values).await
+                } else {
                     let podcast_values = PodcastValues {
-                        pod_title,
-                        pod_artwork,
-                        pod_author,
-                        categories,
-                        pod_description,
-                        pod_episode_count,
-                        pod_feed_url,
-                        pod_website,
-                        pod_explicit,
-                        user_id: value_id
+                        pod_title: podcast.title.clone(),
+                        pod_artwork: podcast.artwork.clone(),
+                        pod_author: podcast.author.clone(),
+                        categories: podcast.categories.clone().unwrap_or_default(),
+                        pod_description: podcast.description.clone(),
+                        pod_episode_count: podcast.episodeCount,
+                        pod_feed_url: podcast.url.clone(),
+                        pod_website: podcast.link.clone(),
+                        pod_explicit: podcast.explicit,
+                        user_id,
                     };
-                    match call_add_podcast(&server_name.unwrap(), &api_key.unwrap(), user_id.unwrap(), &podcast_values).await {
-                        Ok(_) => {
-                            // If successful, update the state to add the podcast
-                            let mut new_set = current_set.clone();
-                            new_set.insert(podcast_url.clone());
-                            added_podcasts.set(new_set);
-                            dispatch.reduce_mut(|state| {
-                                state.info_message = Some("Podcast successfully added".to_string());
-                            });
-                            dispatch.reduce_mut(|state| state.is_loading = Some(false));
-                        },
-                        Err(e) => {
-                            dispatch.reduce_mut(|state| {
-                                state.error_message = Some(format!("Error adding podcast: {:?}", e));
-                            });
-                            dispatch.reduce_mut(|state| state.is_loading = Some(false));
-                        }
+                    call_add_podcast(&server_name, &api_key, user_id, &podcast_values).await
+                };
+                match result {
+                    Ok(_) => {
+                        toggled.set(Some(!is_added));
+                        dispatch.reduce_mut(|state| {
+                            state.info_message = Some(t(if is_added { "discovery.unsubscribed" } else { "discovery.subscribed_message" }));
+                        });
+                        refresh_subscriptions(server_name, api_key, user_id).await;
                     }
-                });
-            }
+                    Err(e) => {
+                        dispatch.reduce_mut(|state| {
+                            state.error_message = Some(format!("{}: {:?}", t(if is_added { "discovery.unsubscribe_failed" } else { "discovery.subscribe_failed" }), e));
+                        });
+                    }
+                }
+                working.set(false);
+            });
         })
     };
 
@@ -325,14 +473,7 @@ pub fn podcast_item(props: &PodcastProps) -> Html {
     let podcast_episode_count_clone = podcast.episodeCount.clone();
     let podcast_categories_clone = podcast.categories.clone();
     let podcast_link_clone = podcast.link.clone();
-    let history = history_clone.clone();
-    // let is_added = added_podcasts.contains(&podcast.url);
-    // let button_text = if is_added { "Remove" } else { "Add" };
-    // let button_class = if is_added { "bg-red-500" } else { "bg-blue-500" };
-    let is_added = added_podcasts.contains(&podcast.url);
-    let button_text = if is_added { "delete" } else { "add" };
-    let button_class = if is_added { "bg-red-500" } else { "bg-blue-500" };
-    
+
     let on_title_click = {
         let dispatch = dispatch.clone();
         let history = history.clone(); // Clone history for use inside the closure
@@ -382,28 +523,54 @@ pub fn podcast_item(props: &PodcastProps) -> Html {
         })
     };
 
+    let updated = (podcast.lastUpdateTime > 0)
+        .then(|| chrono::DateTime::<chrono::Utc>::from_timestamp(podcast.lastUpdateTime, 0))
+        .flatten()
+        .map(|date| date.format("%Y-%m-%d").to_string());
+    let language = language_code(&podcast);
+    let button_label = t(if is_added { "discovery.unsubscribe" } else { "discovery.subscribe" });
+    let button_class = if is_added { "bg-red-500" } else { "bg-blue-500" };
+
     html! {
-        <div>
-            {
-                html! {
-                    <div key={podcast.id.to_string()} class="item-container flex mb-4 shadow-md rounded-lg overflow-hidden">
-                        <img src={podcast.image.clone()} alt={format!("Cover for {}", &podcast.title)} class="w-1/6 items-center object-cover"/>
-                        <div class="flex items-start flex-col p-4 space-y-2 w-11/12">
-                            <a onclick={on_title_click.clone()} class="item_container-text text-xl font-semibold hover:underline">{ &podcast.title }</a>
-                            <p class="item_container-text">{ &podcast.description }</p>
-                            <p class="header-text">{ format!("Episode Count: {}", &podcast.episodeCount) }</p>
-                        </div>
-                        <div class="button-container flex justify-center items-center w-1/4"> // Modified for better clarity
-                            <button class={format!("item-container-button selector-button font-bold py-2 px-4 rounded {}", button_class)} style={"min-width: 35px;"}>
-                                <span class="material-icons" onclick={toggle_podcast}>{ button_text }</span>
-                                // { button_text }
-                            </button>
-                        </div>
-                    </div>
-                }
-            }
+        <div key={podcast.id.to_string()} class="item-container flex mb-4 shadow-md rounded-lg overflow-hidden">
+            <img src={podcast.image.clone()} alt={format!("Cover for {}", &podcast.title)} class="w-1/6 items-center object-cover"/>
+            <div class="flex items-start flex-col p-4 space-y-2 w-11/12">
+                <div class="flex flex-wrap items-center gap-2">
+                    <a onclick={on_title_click.clone()} class="item_container-text text-xl font-semibold hover:underline">{ &podcast.title }</a>
+                    if is_added {
+                        <span class="discovery-badge text-xs font-bold px-2 py-1 rounded">{ t("discovery.subscribed") }</span>
+                    }
+                    if podcast.explicit {
+                        <span class="discovery-badge text-xs font-bold px-2 py-1 rounded">{ t("discovery.explicit_badge") }</span>
+                    }
+                </div>
+                <p class="item_container-text">{ &podcast.description }</p>
+                <p class="header-text">
+                    { format!("Episode Count: {}", &podcast.episodeCount) }
+                    if let Some(updated) = &updated {
+                        { " · " }{ t_args("discovery.updated_on", &[("date", updated.as_str())]) }
+                    }
+                    if let Some(language) = &language {
+                        { " · " }{ language.to_uppercase() }
+                    }
+                </p>
+                <p class="header-text text-sm">
+                    { t_args("search_providers.found_on", &[("providers", podcast.sources.iter().map(|id| provider_name(id)).collect::<Vec<_>>().join(", ").as_str())]) }
+                </p>
+            </div>
+            <div class="button-container flex justify-center items-center w-1/4"> // Modified for better clarity
+                <button
+                    type="button"
+                    class={format!("item-container-button selector-button font-bold py-2 px-4 rounded {}", button_class)}
+                    style={"min-width: 35px;"}
+                    aria-label={format!("{}: {}", button_label, &podcast.title)}
+                    aria-pressed={is_added.to_string()}
+                    disabled={*working}
+                    onclick={toggle_podcast}
+                >
+                    <span class="material-icons" aria-hidden="true">{ if is_added { "delete" } else { "add" } }</span>
+                </button>
+            </div>
         </div>
     }
-    
-    
-}
\ No newline at end of file
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yewdux::prelude::*;
+use crate::components::context::{AppState, UIState};
+use crate::components::episodes_layout::EpisodeLayout;
+use crate::components::gen_components::empty_message;
+use crate::components::i18n::t;
+use crate::components::gen_components::{page_frame, use_page_setup};
+use crate::components::podcast_layout::ClickedFeedURL;
+use crate::requests::pod_req::{call_get_podcast_details, PodcastDetails, EPISODE_PAGE_SIZE};
+use crate::requests::search_pods::call_get_podcast_episodes_page;
+
+// A subscribed podcast addressed by its id, so the page survives a reload or a shared link.
+// The episode layout still reads the clicked podcast from the app state, so that is rebuilt
+// from the server whenever it doesn't already hold this podcast.
+
+#[derive(Properties, PartialEq)]
+pub struct PodcastPageProps {
+    pub podcast_id: i32,
+}
+
+fn clicked_podcast(details: &PodcastDetails) -> ClickedFeedURL {
+    ClickedFeedURL {
+        podcast_title: details.PodcastName.clone(),
+        podcast_url: details.FeedURL.clone(),
+        podcast_description: details.Description.clone().unwrap_or_default(),
+        podcast_author: details.Author.clone().unwrap_or_default(),
+        podcast_artwork: details.ArtworkURL.clone().unwrap_or_default(),
+        podcast_explicit: details.Explicit,
+        podcast_episode_count: details.EpisodeCount,
+        podcast_categories: Some(serde_json::from_str(&details.Categories).unwrap_or_else(|_| HashMap::new())),
+        podcast_link: details.WebsiteURL.clone().unwrap_or_default(),
+    }
+}
+
+#[function_component(PodcastPage)]
+pub fn podcast_page(props: &PodcastPageProps) -> Html {
+    use_page_setup();
+    let (state, dispatch) = use_store::<AppState>();
+    let (ui_state, _ui_dispatch) = use_store::<UIState>();
+    let details = use_state(|| None::<PodcastDetails>);
+    let not_found = use_state(|| false);
+
+    let api_key = state.auth_details.as_ref().and_then(|ud| ud.api_key.clone());
+    let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+    let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+    let podcast_id = props.podcast_id;
+
+    {
+        let details = details.clone();
+        let not_found = not_found.clone();
+        let loaded_feed = state.clicked_podcast_info.as_ref()
+            .filter(|_| state.podcast_added == Some(true) && state.podcast_feed_results.is_some())
+            .map(|podcast| podcast.podcast_url.clone());
+        use_effect_with((server_name, user_id, podcast_id), move |(server_name, user_id, podcast_id)| {
+            details.set(None);
+            not_found.set(false);
+            if let (Some(server_name), Some(user_id)) = (server_name.clone(), *user_id) {
+                let podcast_id = *podcast_id;
+                spawn_local(async move {
+                    let fetched = match call_get_podcast_details(&server_name, &api_key, user_id, podcast_id).await {
+                        Ok(fetched) => fetched,
+                        Err(_) => {
+                            not_found.set(true);
+                            return;
+                        }
+                    };
+                    // Coming from a podcast list the first page of episodes is already loaded
+                    if loaded_feed.as_deref() != Some(fetched.FeedURL.as_str()) {
+                        match call_get_podcast_episodes_page(&server_name, &api_key, &user_id, &podcast_id, 0, EPISODE_PAGE_SIZE).await {
+                            Ok(podcast_feed_results) => {
+                                let podcast = clicked_podcast(&fetched);
+                                dispatch.reduce_mut(move |state| {
+                                    state.podcast_added = Some(true);
+                                    state.podcast_feed_results = Some(podcast_feed_results);
+                                    state.clicked_podcast_info = Some(podcast);
+                                });
+                            }
+                            Err(_) => {
+                                not_found.set(true);
+                                return;
+                            }
+                        }
+                    }
+                    details.set(Some(fetched));
+                });
+            }
+            || ()
+        });
+    }
+
+    match (*details).clone() {
+        Some(details) => html! {
+            <EpisodeLayout key={podcast_id} details={details} />
+        },
+        None if *not_found => page_frame(&ui_state, empty_message(&t("podcast_page.not_found_title"), &t("podcast_page.not_found_body"))),
+        None => page_frame(&ui_state, html! {
+            <p class="item_container-text text-center my-4">{ t("podcast_page.loading") }</p>
+        }),
+    }
+}
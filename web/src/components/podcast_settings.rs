@@ -0,0 +1,192 @@
+use yew::prelude::*;
+use yewdux::prelude::*;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use wasm_bindgen_futures::spawn_local;
+use crate::components::context::{AppState, UIState};
+use crate::components::i18n::t;
+use crate::components::notifications::NotificationPreferences;
+use crate::components::persistence::user_key;
+use crate::requests::pod_req::{call_get_podcast_settings, call_update_podcast_settings, PodcastSettings};
+
+const SPEEDS: [f64; 8] = [0.75, 1.0, 1.1, 1.25, 1.5, 1.75, 2.0, 2.5];
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct PodcastSettingsPanelProps {
+    pub podcast_id: i32,
+    // Fired once the stored settings load and after every saved change
+    #[prop_or_default]
+    pub on_change: Callback<PodcastSettings>,
+}
+
+#[function_component(PodcastSettingsPanel)]
+pub fn podcast_settings_panel(props: &PodcastSettingsPanelProps) -> Html {
+    let (state, _dispatch) = use_store::<AppState>();
+    let (_ui_state, ui_dispatch) = use_store::<UIState>();
+    let settings = use_state(|| PodcastSettings::defaults(props.podcast_id));
+
+    let api_key = state.auth_details.as_ref().and_then(|ud| ud.api_key.clone());
+    let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+    let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+    // Notifications only show once they are also turned on for this device
+    let device_notifications = user_key(&state).map_or(false, |key| NotificationPreferences::load(&key).enabled);
+
+    {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        let api_key = api_key.clone();
+        let server_name = server_name.clone();
+        use_effect_with(props.podcast_id, move |podcast_id| {
+            let podcast_id = *podcast_id;
+            if let (Some(server_name), Some(user_id)) = (server_name, user_id) {
+                spawn_local(async move {
+                    let loaded = call_get_podcast_settings(&server_name, &api_key, user_id, podcast_id)
+                        .await
+                        .unwrap_or_else(|_| PodcastSettings::defaults(podcast_id));
+                    on_change.emit(loaded.clone());
+                    settings.set(loaded);
+                });
+            }
+            || ()
+        });
+    }
+
+    // Every control saves straight away, so there is no submit button to forget
+    let save = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |updated: PodcastSettings| {
+            let previous = (*settings).clone();
+            settings.set(updated.clone());
+            let (Some(server_name), Some(user_id)) = (server_name.clone(), user_id) else {
+                return;
+            };
+            let api_key = api_key.clone();
+            let settings = settings.clone();
+            let on_change = on_change.clone();
+            let ui_dispatch = ui_dispatch.clone();
+            spawn_local(async move {
+                match call_update_podcast_settings(&server_name, &api_key, user_id, &updated).await {
+                    Ok(_) => {
+                        // Apply a new speed to an episode of this podcast that is already playing
+                        ui_dispatch.reduce_mut(|ui_state| {
+                            if ui_state.playback_settings.as_ref().map(|current| current.PodcastID) == Some(updated.PodcastID) {
+                                if let Some(audio) = &ui_state.audio_element {
+                                    audio.set_playback_rate(updated.PlaybackSpeed);
+                                }
+                                ui_state.playback_settings = Some(updated.clone());
+                            }
+                        });
+                        on_change.emit(updated);
+                    }
+                    Err(e) => {
+                        settings.set(previous);
+                        ui_dispatch.reduce_mut(|ui_state| ui_state.error_message = Some(format!("{}: {}", t("podcast_settings.save_error"), e)));
+                    }
+                }
+            });
+        })
+    };
+
+    let on_speed_change = {
+        let settings = settings.clone();
+        let save = save.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                if let Ok(speed) = select.value().parse::<f64>() {
+                    save.emit(PodcastSettings { PlaybackSpeed: speed, ..(*settings).clone() });
+                }
+            }
+        })
+    };
+
+    let on_sort_change = {
+        let settings = settings.clone();
+        let save = save.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                save.emit(PodcastSettings { EpisodeSortOrder: select.value(), ..(*settings).clone() });
+            }
+        })
+    };
+
+    let skip_change = |is_start: bool| {
+        let settings = settings.clone();
+        let save = save.clone();
+        Callback::from(move |e: Event| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                let seconds = input.value().parse::<i32>().unwrap_or(0).max(0);
+                let mut updated = (*settings).clone();
+                if is_start {
+                    updated.StartSkip = seconds;
+                } else {
+                    updated.EndSkip = seconds;
+                }
+                save.emit(updated);
+            }
+        })
+    };
+    let on_start_skip_change = skip_change(true);
+    let on_end_skip_change = skip_change(false);
+
+    let toggle = |update: fn(&mut PodcastSettings)| {
+        let settings = settings.clone();
+        let save = save.clone();
+        Callback::from(move |_: Event| {
+            let mut updated = (*settings).clone();
+            update(&mut updated);
+            save.emit(updated);
+        })
+    };
+    let on_auto_download_change = toggle(|s| s.AutoDownload = !s.AutoDownload);
+    let on_auto_queue_change = toggle(|s| s.AutoQueue = !s.AutoQueue);
+    let on_notify_change = toggle(|s| s.NotifyNewEpisodes = !s.NotifyNewEpisodes);
+
+    html! {
+        <div class="p-4 item-container rounded-lg shadow-md mb-4">
+            <p class="item_container-text text-lg font-bold mb-2">{ t("podcast_settings.header") }</p>
+            <p class="item_container-text text-md mb-4">{ t("podcast_settings.description") }</p>
+            <div class="grid grid-cols-1 md:grid-cols-2 gap-4">
+                <label class="flex flex-col item_container-text">
+                    <span class="mb-1">{ t("podcast_settings.playback_speed") }</span>
+                    <select onchange={on_speed_change} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                        { for SPEEDS.iter().map(|speed| html! {
+                            <option value={speed.to_string()} selected={(settings.PlaybackSpeed - speed).abs() < f64::EPSILON}>{ format!("{}x", speed) }</option>
+                        }) }
+                    </select>
+                </label>
+                <label class="flex flex-col item_container-text">
+                    <span class="mb-1">{ t("podcast_settings.sort_order") }</span>
+                    <select onchange={on_sort_change} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                        <option value="newest" selected={!settings.oldest_first()}>{ t("podcast_settings.sort_newest") }</option>
+                        <option value="oldest" selected={settings.oldest_first()}>{ t("podcast_settings.sort_oldest") }</option>
+                    </select>
+                </label>
+                <label class="flex flex-col item_container-text">
+                    <span class="mb-1">{ t("podcast_settings.skip_intro") }</span>
+                    <input type="number" min="0" value={settings.StartSkip.to_string()} onchange={on_start_skip_change} class="search-bar-input border text-sm rounded-lg block w-full p-2.5" />
+                </label>
+                <label class="flex flex-col item_container-text">
+                    <span class="mb-1">{ t("podcast_settings.skip_outro") }</span>
+                    <input type="number" min="0" value={settings.EndSkip.to_string()} onchange={on_end_skip_change} class="search-bar-input border text-sm rounded-lg block w-full p-2.5" />
+                </label>
+            </div>
+            <div class="flex flex-col space-y-2 mt-4">
+                <label class="flex items-center space-x-2 item_container-text">
+                    <input type="checkbox" class="form-checkbox h-5 w-5" checked={settings.AutoDownload} onchange={on_auto_download_change} />
+                    <span>{ t("podcast_settings.auto_download") }</span>
+                </label>
+                <label class="flex items-center space-x-2 item_container-text">
+                    <input type="checkbox" class="form-checkbox h-5 w-5" checked={settings.AutoQueue} onchange={on_auto_queue_change} />
+                    <span>{ t("podcast_settings.auto_queue") }</span>
+                </label>
+                <label class="flex items-center space-x-2 item_container-text">
+                    <input type="checkbox" class="form-checkbox h-5 w-5" checked={settings.NotifyNewEpisodes} onchange={on_notify_change} />
+                    <span>{ t("podcast_settings.notify") }</span>
+                </label>
+                if settings.NotifyNewEpisodes && !device_notifications {
+                    <p class="item_container-text text-sm">{ t("podcast_settings.notify_device_off") }</p>
+                }
+            </div>
+        </div>
+    }
+}
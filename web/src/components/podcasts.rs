@@ -1,20 +1,23 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::rc::Rc;
 
 use yew::{function_component, Html, html};
 use yew::prelude::*;
 use yewdux::prelude::*;
 use super::app_drawer::App_drawer;
-use crate::components::gen_components::{UseScrollToTop, Search_nav};
-use crate::requests::pod_req::{PodcastResponse, RemovePodcastValues, call_remove_podcasts};
+use crate::components::gen_components::{UseScrollToTop, Search_nav, empty_message};
+use crate::requests::pod_req::{Podcast, PodcastResponse, RemovePodcastValues, call_remove_podcasts};
 use crate::requests::pod_req;
-use web_sys::console;
-use crate::components::context::{AppState, UIState};
+use web_sys::{console, HtmlInputElement, HtmlSelectElement};
+use crate::components::context::{AppState, UIState, PodcastCache, PodcastCacheMsg};
 use yew_router::history::BrowserHistory;
 use crate::components::audio::AudioPlayer;
 use crate::components::click_events::create_on_title_click;
+use crate::components::folders::{FolderFilter, FolderPicker, use_folders};
 use crate::requests::login_requests::use_check_authentication;
-use crate::components::episodes_layout::SafeHtml;
+use crate::components::persistence::{user_key, load_user_preference, save_user_preference};
+use crate::components::i18n::{t, t_args, t_plural};
+use serde::{Deserialize, Serialize};
 
 enum AppStateMsg {
     // ... other messages ...
@@ -46,6 +49,96 @@ impl Reducer<AppState> for AppStateMsg {
     }
 }
 
+const PODCAST_VIEW_PREFERENCE: &str = "podcasts_view";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+enum PodcastSort {
+    #[default]
+    Name,
+    RecentlyUpdated,
+    MostEpisodes,
+    MostListened,
+    DateAdded,
+}
+
+impl PodcastSort {
+    const ALL: [PodcastSort; 5] = [
+        PodcastSort::Name,
+        PodcastSort::RecentlyUpdated,
+        PodcastSort::MostEpisodes,
+        PodcastSort::MostListened,
+        PodcastSort::DateAdded,
+    ];
+
+    fn value(&self) -> &'static str {
+        match self {
+            PodcastSort::Name => "name",
+            PodcastSort::RecentlyUpdated => "recently_updated",
+            PodcastSort::MostEpisodes => "most_episodes",
+            PodcastSort::MostListened => "most_listened",
+            PodcastSort::DateAdded => "date_added",
+        }
+    }
+
+    fn from_value(value: &str) -> Self {
+        PodcastSort::ALL.into_iter().find(|sort| sort.value() == value).unwrap_or_default()
+    }
+
+    fn label(&self) -> String {
+        t(&format!("podcasts.sort.{}", self.value()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+enum PodcastViewMode {
+    #[default]
+    List,
+    Grid,
+}
+
+// Saved per user through the persistence layer so each account keeps its own layout
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+struct PodcastViewPreferences {
+    #[serde(default)]
+    sort: PodcastSort,
+    #[serde(default)]
+    view: PodcastViewMode,
+}
+
+fn podcast_categories(podcast: &Podcast) -> HashMap<String, String> {
+    serde_json::from_str(&podcast.Categories).unwrap_or_else(|_| HashMap::new())
+}
+
+fn visible_podcasts(pods: Vec<Podcast>, text_filter: &str, category: &Option<String>, folder: &Option<HashSet<i32>>, sort: PodcastSort) -> Vec<Podcast> {
+    let needle = text_filter.trim().to_lowercase();
+    let mut visible: Vec<Podcast> = pods
+        .into_iter()
+        .filter(|podcast| {
+            needle.is_empty()
+                || podcast.PodcastName.to_lowercase().contains(&needle)
+                || podcast.Author.as_deref().unwrap_or_default().to_lowercase().contains(&needle)
+        })
+        .filter(|podcast| match category {
+            Some(category) => podcast_categories(podcast).values().any(|c| c == category),
+            None => true,
+        })
+        .filter(|podcast| folder.as_ref().map_or(true, |ids| ids.contains(&podcast.PodcastID)))
+        .collect();
+
+    match sort {
+        PodcastSort::Name => visible.sort_by_key(|podcast| podcast.PodcastName.to_lowercase()),
+        // Dates are ISO formatted so comparing the strings orders them chronologically
+        PodcastSort::RecentlyUpdated => visible.sort_by(|a, b| b.LatestEpisodeDate.cmp(&a.LatestEpisodeDate)),
+        PodcastSort::MostEpisodes => visible.sort_by(|a, b| b.EpisodeCount.cmp(&a.EpisodeCount)),
+        PodcastSort::MostListened => visible.sort_by(|a, b| b.TotalListenTime.unwrap_or(0).cmp(&a.TotalListenTime.unwrap_or(0))),
+        // Podcasts without a recorded date sort after the dated ones, newest id first
+        PodcastSort::DateAdded => visible.sort_by(|a, b| {
+            b.DateAdded.cmp(&a.DateAdded).then_with(|| b.PodcastID.cmp(&a.PodcastID))
+        }),
+    }
+    visible
+}
+
 #[function_component(Podcasts)]
 pub fn podcasts() -> Html {
     let (state, dispatch) = use_store::<AppState>();
@@ -89,6 +182,97 @@ pub fn podcasts() -> Html {
     let api_key = state.auth_details.as_ref().map(|ud| ud.api_key.clone());
     let user_id = state.user_details.as_ref().map(|ud| ud.UserID.clone());
     let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+    // Sort and layout are remembered per user, the text and category filters are per visit
+    let view_key = user_key(&state);
+    let view_preferences = use_state(PodcastViewPreferences::default);
+    {
+        let view_preferences = view_preferences.clone();
+        use_effect_with(view_key.clone(), move |view_key| {
+            if let Some(view_key) = view_key {
+                view_preferences.set(load_user_preference(view_key, PODCAST_VIEW_PREFERENCE));
+            }
+            || ()
+        });
+    }
+    let text_filter = use_state(String::new);
+    let category_filter = use_state(|| None::<String>);
+
+    let update_view_preferences = {
+        let view_preferences = view_preferences.clone();
+        let view_key = view_key.clone();
+        Callback::from(move |updated: PodcastViewPreferences| {
+            if let Some(view_key) = &view_key {
+                save_user_preference(view_key, PODCAST_VIEW_PREFERENCE, &updated);
+            }
+            view_preferences.set(updated);
+        })
+    };
+
+    let on_sort_change = {
+        let view_preferences = view_preferences.clone();
+        let update_view_preferences = update_view_preferences.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                update_view_preferences.emit(PodcastViewPreferences {
+                    sort: PodcastSort::from_value(&select.value()),
+                    ..(*view_preferences).clone()
+                });
+            }
+        })
+    };
+
+    let set_view_mode = {
+        let view_preferences = view_preferences.clone();
+        let update_view_preferences = update_view_preferences.clone();
+        move |view: PodcastViewMode| {
+            let view_preferences = view_preferences.clone();
+            let update_view_preferences = update_view_preferences.clone();
+            Callback::from(move |_: MouseEvent| {
+                update_view_preferences.emit(PodcastViewPreferences {
+                    view,
+                    ..(*view_preferences).clone()
+                });
+            })
+        }
+    };
+
+    let on_text_filter = {
+        let text_filter = text_filter.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                text_filter.set(input.value());
+            }
+        })
+    };
+
+    let on_category_change = {
+        let category_filter = category_filter.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                let value = select.value();
+                category_filter.set(if value.is_empty() { None } else { Some(value) });
+            }
+        })
+    };
+
+    // Category options come from every subscribed show's category map
+    let all_categories: Vec<String> = state
+        .podcast_feed_return
+        .as_ref()
+        .and_then(|podcasts| podcasts.pods.as_ref())
+        .map(|pods| {
+            pods.iter()
+                .flat_map(|podcast| podcast_categories(podcast).into_values())
+                .filter(|category| !category.trim().is_empty())
+                .collect::<BTreeSet<String>>()
+                .into_iter()
+                .collect()
+        })
+        .unwrap_or_default();
+    let is_grid = view_preferences.view == PodcastViewMode::Grid;
+    let folder_state = use_folders();
+    let folder_filter = folder_state.active_podcast_ids();
+
     // Fetch episodes on component mount
     {
         let api_key = api_key.clone();
@@ -113,8 +297,11 @@ pub fn podcasts() -> Html {
                     wasm_bindgen_futures::spawn_local(async move {
                         match pod_req::call_get_podcasts(&server_name, &api_key, &user_id).await {
                             Ok(fetched_podcasts) => {
+                                Dispatch::<PodcastCache>::global().apply(PodcastCacheMsg::Sync(fetched_podcasts.clone()));
                                 dispatch.reduce_mut(move |state| {
                                     state.podcast_feed_return = Some(PodcastResponse { pods: Some(fetched_podcasts) });
+                                    // Keep a copy for the next visit so the page renders before the fetch
+                                    state.store_app_state();
                                 });
                             },
                             Err(e) => console::log_1(&format!("Unable to parse Podcasts: {:?}", &e).into()),
@@ -126,42 +313,73 @@ pub fn podcasts() -> Html {
         );
     }
 
+    let no_podcasts = html! {
+        <div class="empty-episodes-container">
+            <img src="static/assets/favicon.png" alt="Logo" class="logo"/>
+            <h1>{ t("podcasts.empty.title") }</h1>
+            <p>{ t("podcasts.empty.body") }</p>
+        </div>
+    };
+    let toggle_class = |selected: bool| classes!("selector-button", "py-2", "px-3", (!selected).then_some("opacity-50"));
+
     html! {
         <>
         <div class="main-container">
             <Search_nav />
             <UseScrollToTop />
+            <div class="flex flex-wrap items-center gap-2 px-4 mb-4">
+                <input
+                    type="text"
+                    class="search-bar-input border text-sm rounded-lg p-2.5 flex-grow"
+                    placeholder={t("podcasts.filter_placeholder")}
+                    aria-label={t("podcasts.filter_placeholder")}
+                    value={(*text_filter).clone()}
+                    oninput={on_text_filter}
+                />
+                <select aria-label={t("podcasts.category")} onchange={on_category_change} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                    <option value="" selected={category_filter.is_none()}>{ t("podcasts.all_categories") }</option>
+                    {
+                        for all_categories.iter().map(|category| html! {
+                            <option value={category.clone()} selected={category_filter.as_deref() == Some(category.as_str())}>{ category }</option>
+                        })
+                    }
+                </select>
+                <FolderFilter />
+                <select aria-label={t("podcasts.sort_by")} onchange={on_sort_change} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                    {
+                        for PodcastSort::ALL.iter().map(|sort| html! {
+                            <option value={sort.value()} selected={view_preferences.sort == *sort}>{ sort.label() }</option>
+                        })
+                    }
+                </select>
+                <div class="flex space-x-1" role="group" aria-label={t("podcasts.view")}>
+                    <button class={toggle_class(!is_grid)} aria-pressed={(!is_grid).to_string()} aria-label={t("podcasts.view_list")} onclick={set_view_mode(PodcastViewMode::List)}>
+                        <span class="material-icons" aria-hidden="true">{"view_list"}</span>
+                    </button>
+                    <button class={toggle_class(is_grid)} aria-pressed={is_grid.to_string()} aria-label={t("podcasts.view_grid")} onclick={set_view_mode(PodcastViewMode::Grid)}>
+                        <span class="material-icons" aria-hidden="true">{"grid_view"}</span>
+                    </button>
+                </div>
+            </div>
             {
-                if let Some(podcasts) = state.podcast_feed_return.clone() {
-                    let int_podcasts = podcasts.clone();
-                    if let Some(pods) = int_podcasts.pods.clone() {
-                        if pods.is_empty() {
-                                                    // Render "No Recent Episodes Found" if episodes list is empty
-                            html! {
-                        <div class="empty-episodes-container">
-                            <img src="static/assets/favicon.png" alt="Logo" class="logo"/>
-                            <h1>{ "No Podcasts Found" }</h1>
-                            <p>{"You can add new podcasts by using the search bar above. Search for your favorite podcast and click the plus button to add it."}</p>
-                        </div>
-                            }
+                if let Some(pods) = state.podcast_feed_return.clone().and_then(|podcasts| podcasts.pods) {
+                    if pods.is_empty() {
+                        no_podcasts.clone()
+                    } else {
+                        let visible = visible_podcasts(pods, &text_filter, &category_filter, &folder_filter, view_preferences.sort);
+                        if visible.is_empty() {
+                            empty_message(
+                                &t("podcasts.no_matches.title"),
+                                &t("podcasts.no_matches.body")
+                            )
                         } else {
-                        pods.into_iter().map(|podcast| {
-                            // let state_ep = state.clone();
-                            // let audio_state_ep = audio_state.clone();
+                        let items = visible.into_iter().map(|podcast| {
                             let api_key_iter = api_key.clone();
                             let server_name_iter = server_name.clone().unwrap();
                             let history = history_clone.clone();
 
-                            // let id_string = &podcast.PodcastID.to_string();
-    
                             let dispatch = dispatch.clone();
                             let podcast_id_loop = podcast.PodcastID.clone();
-                            // let podcast_url_clone = podcast.FeedURL.clone();
-                            // let podcast_title_clone = podcast.PodcastName.clone();
-                            // let podcast_ep_count = podcast.EpisodeCount.clone();
-                            // let podcast_artwork_clone = podcast.ArtworkURL.clone();
-                            let podcast_description_clone = podcast.Description.clone();
-                            // let categories: HashMap<String, String> = serde_json::from_str(&podcast_categories_clone).unwrap_or_else(|_| HashMap::new());
                             let on_remove_click = {
                                 let dispatch_remove = dispatch.clone();
                                 let podcast_feed_return = podcast_feed_return.clone();
@@ -196,12 +414,13 @@ pub fn podcasts() -> Html {
                                                     Ok(success) => {
                                                         if success {
                                                             dispatch_clone.apply(AppStateMsg::RemovePodcast(podcast_id));
+                                                            Dispatch::<PodcastCache>::global().apply(PodcastCacheMsg::Remove(podcast_id));
                                                             dispatch_clone.reduce_mut(|state| {
-                                                                state.info_message = Some("Podcast successfully removed".to_string())
+                                                                state.info_message = Some(t("podcasts.removed"))
                                                             });
                                                         } else {
                                                             dispatch_clone.reduce_mut(|state| {
-                                                                state.error_message = Some("Failed to remove podcast".to_string())
+                                                                state.error_message = Some(t("podcasts.remove_failed"))
                                                             });
                                                         }
                                                     },
@@ -216,8 +435,7 @@ pub fn podcasts() -> Html {
                                     }
                                 })
                             };
-                            let categories: HashMap<String, String> = serde_json::from_str(&podcast.Categories)
-                                .unwrap_or_else(|_| HashMap::new());
+                            let categories = podcast_categories(&podcast);
                             let on_title_click = create_on_title_click(
                                 dispatch.clone(),
                                 server_name_iter,
@@ -234,57 +452,50 @@ pub fn podcasts() -> Html {
                                 podcast.WebsiteURL.clone().unwrap_or_else(|| String::from("No Website Provided")),
                                 user_id.unwrap(),
                             );
-                            
-    
-                            html! {
-                                <div>
-                                    <div class="item-container flex items-center mb-4 shadow-md rounded-lg overflow-hidden">
-                                        <img onclick={on_title_click.clone()} src={podcast.ArtworkURL.clone()} alt={format!("Cover for {}", &podcast.PodcastName)} class="w-2/12 object-cover"/>
-                                        <div class="flex flex-col p-4 space-y-2 w-8/12">
-                                            <a onclick={on_title_click} class="item-container-text-link text-xl font-semibold hover:underline">{ &podcast.PodcastName }</a>
-                                            {
-                                                html! {
-                                                    <div class="item_container-text episode-description-container">
-                                                        <div>
-                                                            <SafeHtml html={podcast_description_clone.unwrap_or_default()} />
-                                                        </div>
-                                                    </div>
-                                                }
-                                            }
-                                            <p class="item_container-text">{ format!("Episode Count: {}", &podcast.EpisodeCount) }</p>
+                            let remove_label = t_args("podcasts.remove", &[("name", podcast.PodcastName.as_str())]);
+
+                            if is_grid {
+                                html! {
+                                    <div class="flex flex-col">
+                                        <img onclick={on_title_click.clone()} src={podcast.ArtworkURL.clone()} alt={t_args("a11y.cover_for", &[("title", podcast.PodcastName.as_str())])} class="w-full aspect-square object-cover rounded-lg shadow-md cursor-pointer"/>
+                                        <div class="flex items-start justify-between mt-2 space-x-2">
+                                            <a onclick={on_title_click} class="item-container-text-link text-sm font-semibold hover:underline cursor-pointer line-clamp-2">{ &podcast.PodcastName }</a>
+                                            <FolderPicker podcast_id={podcast.PodcastID} />
+                                            <button onclick={on_remove_click} aria-label={remove_label} class="selector-button rounded p-1 bg-red-500">
+                                                <span class="material-icons text-base" aria-hidden="true">{"delete"}</span>
+                                            </button>
                                         </div>
-                                        // <button class="item-container-action-button selector-button w-1/12 mx-auto font-bold py-2 px-4 rounded">
-                                        //     <span class="material-icons" onclick={on_remove_click}>{"delete"}</span>
-                                        // </button>
-                                        <div class="button-container flex justify-center items-center w-1/4"> // Modified for better clarity
-                                            <button class={"selector-button font-bold py-2 px-4 rounded bg-red-500"} style={"min-width: 35px;"}>
-                                                <span class="material-icons" onclick={on_remove_click}>{"delete"}</span>
-                                                // { button_text }
+                                    </div>
+                                }
+                            } else {
+                                html! {
+                                    <div role="listitem" class="item-container flex items-center mb-2 shadow-md rounded-lg overflow-hidden">
+                                        <img onclick={on_title_click.clone()} src={podcast.ArtworkURL.clone()} alt={t_args("a11y.cover_for", &[("title", podcast.PodcastName.as_str())])} class="w-16 h-16 md:w-20 md:h-20 object-cover cursor-pointer"/>
+                                        <div class="flex flex-col px-4 py-2 flex-grow min-w-0">
+                                            <a onclick={on_title_click} class="item-container-text-link text-lg font-semibold hover:underline cursor-pointer truncate">{ &podcast.PodcastName }</a>
+                                            <p class="item_container-text text-sm truncate">{ podcast.Author.clone().unwrap_or_default() }</p>
+                                            <p class="item_container-text text-sm">{ t_plural("podcasts.episode_count", podcast.EpisodeCount as i64) }</p>
+                                        </div>
+                                        <div class="button-container flex justify-center items-center space-x-2 px-4">
+                                            <FolderPicker podcast_id={podcast.PodcastID} />
+                                            <button onclick={on_remove_click} aria-label={remove_label} class="selector-button font-bold py-2 px-4 rounded bg-red-500" style="min-width: 35px;">
+                                                <span class="material-icons" aria-hidden="true">{"delete"}</span>
                                             </button>
                                         </div>
-                                    
                                     </div>
-                                </div>
+                                }
                             }
-                        }).collect::<Html>()
+                        }).collect::<Html>();
+
+                        if is_grid {
+                            html! { <div class="grid grid-cols-2 sm:grid-cols-3 md:grid-cols-4 lg:grid-cols-6 gap-4 px-4">{ items }</div> }
+                        } else {
+                            html! { <div role="list">{ items }</div> }
                         }
-                    } else {
-                        html! {
-                            <div class="empty-episodes-container">
-                                <img src="static/assets/favicon.png" alt="Logo" class="logo"/>
-                                <h1>{ "No Podcasts Found" }</h1>
-                                <p>{"You can add new podcasts by using the search bar above. Search for your favorite podcast and click the plus button to add it."}</p>
-                            </div>
                         }
                     }
                 } else {
-                    html! {
-                        <div class="empty-episodes-container">
-                            <img src="static/assets/favicon.png" alt="Logo" class="logo"/>
-                            <h1>{ "No Podcasts Found" }</h1>
-                            <p>{"You can add new podcasts by using the search bar above. Search for your favorite podcast and click the plus button to add it."}</p>
-                        </div>
-                    }
+                    no_podcasts
                 }
             }
         </div>
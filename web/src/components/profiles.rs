@@ -0,0 +1,310 @@
+use serde::{Deserialize, Serialize};
+use web_sys::{console, window};
+use yewdux::prelude::*;
+use yew_router::history::{BrowserHistory, History};
+use crate::components::context::{AppState, EpisodeLists, EpisodeListsMsg, LocaleState, PodcastCache, PodcastCacheMsg, UIState, clear_entity_caches};
+use crate::components::i18n::{current_locale, set_locale, t_args, Locale};
+use crate::components::persistence::{PersistedLibrary, USER_PREFERENCES_KEY};
+use crate::requests::login_requests::{GetApiDetails, GetUserDetails, LoginServerRequest, call_verify_key, call_get_time_info};
+use crate::requests::pod_req::QueuedEpisodesResponse;
+
+// Saved server/account profiles. Every successful login is remembered here so users
+// can hop between instances (say a personal server and a shared one) from the drawer
+// without typing their credentials again.
+const PROFILES_KEY: &str = "savedProfiles";
+const ACTIVE_PROFILE_KEY: &str = "activeProfile";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ServerProfile {
+    pub id: String,
+    pub display_name: String,
+    pub auth_details: LoginServerRequest,
+    pub user_details: GetUserDetails,
+    pub server_details: GetApiDetails,
+    #[serde(default)]
+    pub gravatar_url: Option<String>,
+    // Cached per-profile state, restored when switching back to this profile
+    #[serde(default)]
+    pub theme: Option<String>,
+    #[serde(default)]
+    pub user_tz: Option<String>,
+    #[serde(default)]
+    pub hour_preference: Option<i16>,
+    #[serde(default)]
+    pub date_format: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    // Subscriptions and queue as last seen, so switching back doesn't start from an empty library
+    #[serde(default)]
+    pub library: PersistedLibrary,
+}
+
+pub fn profile_id(server_name: &str, username: &str) -> String {
+    format!("{}|{}", server_name.trim_end_matches('/'), username)
+}
+
+fn default_display_name(server_name: &str, username: &str) -> String {
+    let host = server_name
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_end_matches('/');
+    format!("{}@{}", username, host)
+}
+
+pub fn load_profiles() -> Vec<ServerProfile> {
+    if let Some(window) = window() {
+        if let Ok(Some(local_storage)) = window.local_storage() {
+            if let Ok(Some(stored)) = local_storage.get_item(PROFILES_KEY) {
+                return serde_json::from_str(&stored).unwrap_or_else(|e| {
+                    console::log_1(&format!("Unable to parse saved profiles: {:?}", e).into());
+                    Vec::new()
+                });
+            }
+        }
+    }
+    Vec::new()
+}
+
+pub fn save_profiles(profiles: &[ServerProfile]) {
+    if let Some(window) = window() {
+        if let Ok(Some(local_storage)) = window.local_storage() {
+            if let Ok(serialized) = serde_json::to_string(profiles) {
+                let _ = local_storage.set_item(PROFILES_KEY, &serialized);
+            }
+        }
+    }
+}
+
+pub fn active_profile_id() -> Option<String> {
+    window()
+        .and_then(|w| w.local_storage().ok().flatten())
+        .and_then(|storage| storage.get_item(ACTIVE_PROFILE_KEY).ok().flatten())
+}
+
+fn set_active_profile_id(id: Option<&str>) {
+    if let Some(window) = window() {
+        if let Ok(Some(local_storage)) = window.local_storage() {
+            match id {
+                Some(id) => { let _ = local_storage.set_item(ACTIVE_PROFILE_KEY, id); },
+                None => { let _ = local_storage.remove_item(ACTIVE_PROFILE_KEY); },
+            }
+        }
+    }
+}
+
+fn stored_item(key: &str) -> Option<String> {
+    window()
+        .and_then(|w| w.local_storage().ok().flatten())
+        .and_then(|storage| storage.get_item(key).ok().flatten())
+}
+
+// Called whenever the logged in user is persisted so the current account always has a profile
+pub fn upsert_profile(state: &AppState) {
+    let (Some(auth_details), Some(user_details), Some(server_details)) = (
+        state.auth_details.clone(),
+        state.user_details.clone(),
+        state.server_details.clone(),
+    ) else {
+        return;
+    };
+    let username = user_details.Username.clone().unwrap_or_else(|| auth_details.username.clone());
+    let id = profile_id(&auth_details.server_name, &username);
+    let mut profiles = load_profiles();
+
+    match profiles.iter_mut().find(|p| p.id == id) {
+        Some(existing) => {
+            existing.auth_details = auth_details;
+            existing.user_details = user_details;
+            existing.server_details = server_details;
+            if state.gravatar_url.is_some() {
+                existing.gravatar_url = state.gravatar_url.clone();
+            }
+        }
+        None => {
+            profiles.push(ServerProfile {
+                id: id.clone(),
+                display_name: default_display_name(&auth_details.server_name, &username),
+                auth_details,
+                user_details,
+                server_details,
+                gravatar_url: state.gravatar_url.clone(),
+                theme: stored_item("selected_theme"),
+                user_tz: state.user_tz.clone(),
+                hour_preference: state.hour_preference,
+                date_format: state.date_format.clone(),
+                language: stored_item("selected_language"),
+                library: PersistedLibrary::default(),
+            });
+        }
+    }
+
+    save_profiles(&profiles);
+    set_active_profile_id(Some(&id));
+}
+
+// Copy the cached preferences of the active session into its profile before leaving it
+pub fn snapshot_active_profile(state: &AppState) {
+    let Some(active_id) = active_profile_id() else {
+        return;
+    };
+    let mut profiles = load_profiles();
+    if let Some(profile) = profiles.iter_mut().find(|p| p.id == active_id) {
+        profile.theme = stored_item("selected_theme").or(profile.theme.clone());
+        profile.language = stored_item("selected_language").or(profile.language.clone());
+        if state.user_tz.is_some() {
+            profile.user_tz = state.user_tz.clone();
+            profile.hour_preference = state.hour_preference;
+            profile.date_format = state.date_format.clone();
+        }
+        if state.podcast_feed_return.is_some() {
+            profile.library.podcasts = state.podcast_feed_return.clone();
+        }
+        if let Some(queued) = Dispatch::<EpisodeLists>::global().get().queue.as_ref() {
+            profile.library.queued_episodes = Some(QueuedEpisodesResponse { episodes: queued.to_vec() });
+        }
+        save_profiles(&profiles);
+    }
+}
+
+pub fn rename_profile(id: &str, display_name: &str) {
+    let mut profiles = load_profiles();
+    if let Some(profile) = profiles.iter_mut().find(|p| p.id == id) {
+        profile.display_name = display_name.trim().to_string();
+    }
+    save_profiles(&profiles);
+}
+
+pub fn remove_profile(id: &str) {
+    let mut profiles = load_profiles();
+    profiles.retain(|p| p.id != id);
+    save_profiles(&profiles);
+    if active_profile_id().as_deref() == Some(id) {
+        set_active_profile_id(None);
+    }
+}
+
+// Sign out of the active account only
+pub fn clear_active_session() {
+    clear_entity_caches();
+    if let Some(active_id) = active_profile_id() {
+        remove_profile(&active_id);
+    }
+
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let (Ok(Some(local_storage)), Ok(Some(session_storage))) = (window.local_storage(), window.session_storage()) else {
+        console::log_1(&"Storage is unavailable, the session could not be cleared".into());
+        return;
+    };
+
+    // Device wide preferences and the remaining profiles survive signing out
+    let preserved: Vec<(&str, Option<String>)> = ["selected_theme", "selected_language", PROFILES_KEY, USER_PREFERENCES_KEY]
+        .iter()
+        .map(|key| (*key, local_storage.get_item(key).unwrap_or(None)))
+        .collect();
+
+    if let Err(e) = local_storage.clear() {
+        console::log_1(&format!("Failed to clear localStorage: {:?}", e).into());
+    }
+    if let Err(e) = session_storage.clear() {
+        console::log_1(&format!("Failed to clear sessionStorage: {:?}", e).into());
+    }
+
+    for (key, value) in preserved {
+        if let Some(value) = value {
+            if let Err(e) = local_storage.set_item(key, &value) {
+                console::log_1(&format!("Failed to restore {}: {:?}", key, e).into());
+            }
+        }
+    }
+}
+
+pub fn switch_profile(profile: ServerProfile, current_state: &AppState, dispatch: Dispatch<AppState>, ui_dispatch: Dispatch<UIState>) {
+    snapshot_active_profile(current_state);
+
+    let locale_dispatch = Dispatch::<LocaleState>::global();
+    wasm_bindgen_futures::spawn_local(async move {
+        let server_name = profile.auth_details.server_name.clone();
+        let api_key = profile.auth_details.api_key.clone().unwrap_or_default();
+        match call_verify_key(&server_name, &api_key).await {
+            Ok(_) => {
+                // Stop anything the previous account was playing
+                ui_dispatch.reduce_mut(|ui_state| {
+                    if let Some(audio) = &ui_state.audio_element {
+                        let _ = audio.pause();
+                    }
+                    ui_state.audio_playing = Some(false);
+                    ui_state.currently_playing = None;
+                });
+
+                clear_entity_caches();
+                let restored = profile.clone();
+                // The other account's library never leaks in, but this one's cached copy is shown
+                // right away while the pages refresh it
+                if let Some(pods) = restored.library.podcasts.as_ref().and_then(|response| response.pods.clone()) {
+                    Dispatch::<PodcastCache>::global().apply(PodcastCacheMsg::Sync(pods));
+                }
+                if let Some(queued) = restored.library.queued_episodes.clone() {
+                    Dispatch::<EpisodeLists>::global().apply(EpisodeListsMsg::Queue(queued.episodes));
+                }
+                dispatch.reduce_mut(move |state| {
+                    *state = AppState {
+                        user_details: Some(restored.user_details),
+                        auth_details: Some(restored.auth_details),
+                        server_details: Some(restored.server_details),
+                        gravatar_url: restored.gravatar_url,
+                        user_tz: restored.user_tz,
+                        hour_preference: restored.hour_preference,
+                        date_format: restored.date_format,
+                        podcast_feed_return: restored.library.podcasts,
+                        reload_occured: Some(true),
+                        ..AppState::default()
+                    };
+                    state.store_app_state();
+                });
+
+                if let Some(theme) = &profile.theme {
+                    crate::components::setting_components::theme_options::changeTheme(theme);
+                    if let Some(window) = web_sys::window() {
+                        if let Ok(Some(local_storage)) = window.local_storage() {
+                            let _ = local_storage.set_item("selected_theme", theme);
+                        }
+                    }
+                }
+                if let Some(locale) = profile.language.as_deref().and_then(Locale::from_code) {
+                    if locale != current_locale() {
+                        set_locale(locale);
+                        locale_dispatch.reduce_mut(|state| state.locale = Some(locale.code().to_string()));
+                    }
+                }
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(session_storage)) = window.session_storage() {
+                        let _ = session_storage.set_item("isAuthenticated", "true");
+                    }
+                }
+
+                // Refresh time preferences in case they changed on the server since the last visit
+                let user_id = profile.user_details.UserID;
+                let time_dispatch = dispatch.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(tz_response) = call_get_time_info(server_name, api_key, &user_id).await {
+                        time_dispatch.reduce_mut(move |state| {
+                            state.user_tz = Some(tz_response.timezone);
+                            state.hour_preference = Some(tz_response.hour_pref);
+                            state.date_format = Some(tz_response.date_format);
+                        });
+                    }
+                });
+
+                let display_name = profile.display_name.clone();
+                ui_dispatch.reduce_mut(move |ui_state| ui_state.info_message = Some(t_args("profiles.switched", &[("name", display_name.as_str())])));
+                BrowserHistory::new().push("/home");
+            }
+            Err(_) => {
+                let display_name = profile.display_name.clone();
+                ui_dispatch.reduce_mut(move |ui_state| ui_state.error_message = Some(t_args("profiles.session_expired", &[("name", display_name.as_str())])));
+            }
+        }
+    });
+}
@@ -1,14 +1,18 @@
 use yew::{function_component, Html, html};
 use yew::prelude::*;
 use super::app_drawer::App_drawer;
+use std::rc::Rc;
 use super::gen_components::{UseScrollToTop, Search_nav, empty_message, episode_item, on_shownotes_click};
+use super::virtual_list::VirtualList;
+use yew::virtual_dom::Key;
+use super::selection::BulkActionBar;
+use super::queue_order::{move_item, queue_row, save_queue_order, QueueRowControls, QueueSaver, QueueSummary};
 use crate::requests::pod_req;
 use yewdux::prelude::*;
-use crate::components::context::{AppState, UIState};
+use crate::components::context::{AppState, UIState, CachedEpisode, EpisodeCache, EpisodeCacheMsg, EpisodeList, EpisodeLists, EpisodeListsMsg, use_list_page_state, use_removed_from};
 use yew_router::history::BrowserHistory;
 use crate::components::audio::AudioPlayer;
 use crate::components::gen_funcs::{sanitize_html_with_blank_target, truncate_description, parse_date, format_datetime, match_date_format};
-use crate::requests::pod_req::QueuedEpisodesResponse;
 use crate::components::audio::on_play_click;
 use crate::components::episodes_layout::AppStateMsg;
 // use crate::components::gen_funcs::check_auth;
@@ -17,23 +21,32 @@ use wasm_bindgen::closure::Closure;
 use web_sys::window;
 use wasm_bindgen::JsCast;
 use crate::requests::login_requests::use_check_authentication;
+use crate::components::i18n::{t, t_args};
 
 #[function_component(Queue)]
 pub fn queue() -> Html {
-    let (state, dispatch) = use_store::<AppState>();
+    let state = use_list_page_state();
+    let dispatch = Dispatch::<AppState>::global();
+    let queued = use_selector(|lists: &EpisodeLists| lists.queue.clone());
+    let removed = use_removed_from(EpisodeList::Queue);
     let history = BrowserHistory::new();
 
     // check_auth(effect_dispatch);
 
     let error = use_state(|| None);
-    let (post_state, _post_dispatch) = use_store::<AppState>();
     let (audio_state, audio_dispatch) = use_store::<UIState>();
     let error_message = audio_state.error_message.clone();
     let info_message = audio_state.info_message.clone();
 
-    let session_dispatch = _post_dispatch.clone();
-    let session_state = post_state.clone();
+    let session_dispatch = dispatch.clone();
+    let session_state = state.clone();
     let loading = use_state(|| true);
+    let dragging = use_state(|| None::<usize>);
+    let drop_target = use_state(|| None::<usize>);
+    let queue_saver = use_mut_ref(QueueSaver::default);
+    // Read out after each move. The focus request carries a counter so moving to the same index twice still refocuses.
+    let announcement = use_state(String::new);
+    let focus_request = use_state(|| None::<(usize, u32)>);
 
     use_effect_with((), move |_| {
         // Check if the page reload action has already occurred to prevent redundant execution
@@ -92,9 +105,9 @@ pub fn queue() -> Html {
     {
         // let episodes = episodes.clone();
         let error = error.clone();
-        let api_key = post_state.auth_details.as_ref().map(|ud| ud.api_key.clone());
-        let user_id = post_state.user_details.as_ref().map(|ud| ud.UserID.clone());
-        let server_name = post_state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+        let api_key = state.auth_details.as_ref().map(|ud| ud.api_key.clone());
+        let user_id = state.user_details.as_ref().map(|ud| ud.UserID.clone());
+        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
         let effect_dispatch = dispatch.clone();
 
         // fetch_episodes(api_key.flatten(), user_id, server_name, dispatch, error, pod_req::call_get_recent_eps);
@@ -109,9 +122,13 @@ pub fn queue() -> Html {
                     wasm_bindgen_futures::spawn_local(async move {
                         match pod_req::call_get_queued_episodes(&server_name, &api_key, &user_id).await {
                             Ok(fetched_episodes) => {
-                                dispatch.reduce_mut(move |state| {
-                                    state.queued_episodes = Some(QueuedEpisodesResponse { episodes: fetched_episodes });
-                                });
+                                Dispatch::<EpisodeCache>::global().apply(EpisodeCacheMsg::SyncList(
+                                    EpisodeList::Queue,
+                                    fetched_episodes.iter().map(CachedEpisode::from).collect(),
+                                ));
+                                Dispatch::<EpisodeLists>::global().apply(EpisodeListsMsg::Queue(fetched_episodes));
+                                // Keep a copy for the next visit so the page renders before the fetch
+                                dispatch.get().store_app_state();
                                 loading_ep.set(false);
                                 // web_sys::console::log_1(&format!("State after update: {:?}", state).into()); // Log state after update
                             },
@@ -127,6 +144,60 @@ pub fn queue() -> Html {
         );
     }
 
+    // Rows are keyed by index, so after a keyboard move focus would stay put while the episode moves away.
+    // Follow the episode when the move came from a focused handle.
+    use_effect_with(*focus_request, |focus_request| {
+        if let Some((index, _)) = focus_request {
+            let document = window().and_then(|window| window.document());
+            let handle_focused = document
+                .as_ref()
+                .and_then(|document| document.active_element())
+                .map_or(false, |active| active.has_attribute("data-queue-handle"));
+            let handle = document
+                .filter(|_| handle_focused)
+                .and_then(|document| document.query_selector(&format!("[data-queue-handle='{}']", index)).ok().flatten())
+                .and_then(|handle| handle.dyn_into::<web_sys::HtmlElement>().ok());
+            if let Some(handle) = handle {
+                let _ = handle.focus();
+            }
+        }
+        || ()
+    });
+
+    // Reorders the visible queue locally right away, then saves the whole order
+    let on_move = {
+        let state = state.clone();
+        let queued = queued.clone();
+        let removed = removed.clone();
+        let queue_saver = queue_saver.clone();
+        let announcement = announcement.clone();
+        let focus_request = focus_request.clone();
+        Callback::from(move |(from, to): (usize, usize)| {
+            let Some(queued) = queued.as_ref() else {
+                return;
+            };
+            let mut episodes: Vec<_> = queued.iter().filter(|episode| !removed.contains(&episode.EpisodeID)).cloned().collect();
+            if from == to || from >= episodes.len() {
+                return;
+            }
+            move_item(&mut episodes, from, to);
+            let to = to.min(episodes.len() - 1);
+            for (position, episode) in episodes.iter_mut().enumerate() {
+                episode.QueuePosition = Some(position as i32 + 1);
+            }
+            let order: Vec<i32> = episodes.iter().map(|episode| episode.EpisodeID).collect();
+            let position = (to + 1).to_string();
+            announcement.set(t_args("queue.moved", &[("title", episodes[to].EpisodeTitle.as_str()), ("position", position.as_str())]));
+            focus_request.set(Some((to, focus_request.map_or(0, |(_, count)| count.wrapping_add(1)))));
+            Dispatch::<EpisodeLists>::global().apply(EpisodeListsMsg::Queue(episodes));
+            Dispatch::<AppState>::global().get().store_app_state();
+
+            if let (Some(auth), Some(user_id)) = (state.auth_details.as_ref(), state.user_details.as_ref().map(|ud| ud.UserID)) {
+                save_queue_order(queue_saver.clone(), auth.server_name.clone(), auth.api_key.clone(), user_id, order);
+            }
+        })
+    };
+
     html! {
         <>
         <div class="main-container">
@@ -155,18 +226,36 @@ pub fn queue() -> Html {
                     }
                     
                     {
-                        if let Some(queued_eps) = state.queued_episodes.clone() {
-                            if queued_eps.episodes.is_empty() {
+                        // Episodes removed from this list on any page since the fetch drop out immediately
+                    if let Some(queued_eps) = queued.as_ref().as_ref().map(|episodes| {
+                        episodes.iter().filter(|episode| !removed.contains(&episode.EpisodeID)).cloned().collect::<Vec<_>>()
+                    }) {
+                            if queued_eps.is_empty() {
                                 // Render "No Queued Episodes Found" if episodes list is empty
                                 empty_message(
-                                    "No Queued Episodes Found",
-                                    "You can queue episodes by clicking the context button on each episode and clicking 'Queue Episode'. Doing this will play episodes in order of the queue after the currently playing episode is complete."
+                                    &t("empty.queue.title"),
+                                    &t("empty.queue.body")
                                 )
                             } else {
-                                queued_eps.episodes.into_iter().map(|episode| {
-                            let api_key = post_state.auth_details.as_ref().map(|ud| ud.api_key.clone());
-                            let user_id = post_state.user_details.as_ref().map(|ud| ud.UserID.clone());
-                            let server_name = post_state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+                                let episodes = Rc::new(queued_eps);
+                                let item_count = episodes.len();
+                                let episode_ids: Vec<i32> = episodes.iter().map(|episode| episode.EpisodeID).collect();
+                                let item_keys: Vec<Key> = episode_ids.iter().map(|episode_id| Key::from(*episode_id)).collect();
+                                let summary_episodes = episodes.clone();
+                                let render_item = {
+                                    let dragging = dragging.clone();
+                                    let drop_target = drop_target.clone();
+                                    let on_move = on_move.clone();
+                                    let state = state.clone();
+                                    let dispatch = dispatch.clone();
+                                    let history = history.clone();
+                                    let audio_state = audio_state.clone();
+                                    let audio_dispatch = audio_dispatch.clone();
+                                    Callback::from(move |index: usize| {
+                                    let episode = episodes[index].clone();
+                            let api_key = state.auth_details.as_ref().map(|ud| ud.api_key.clone());
+                            let user_id = state.user_details.as_ref().map(|ud| ud.UserID.clone());
+                            let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
                             let history_clone = history.clone();
                             let id_string = &episode.EpisodeID.to_string();
     
@@ -253,18 +342,35 @@ pub fn queue() -> Html {
                                 episode_duration_clone,
                                 episode_listened_clone,
                                 "queue",
-                                Callback::from(|_| {}), 
-                                false,
                             );
 
-                            item
-                        }).collect::<Html>()
+                            let controls = html! {
+                                <QueueRowControls
+                                    index={index}
+                                    count={item_count}
+                                    title={episode_title_clone}
+                                    on_move={on_move.clone()}
+                                    dragging={dragging.clone()}
+                                    drop_target={drop_target.clone()}
+                                />
+                            };
+                            queue_row(index, &dragging, &drop_target, &on_move, controls, item)
+                        })
+                                };
+                                html! {
+                                    <>
+                                        <QueueSummary episodes={summary_episodes} />
+                                        <div class="sr-only" role="status" aria-live="polite" aria-atomic="true">{ (*announcement).clone() }</div>
+                                        <BulkActionBar episode_ids={episode_ids} />
+                                        <VirtualList item_count={item_count} render_item={render_item} item_keys={item_keys} />
+                                    </>
+                                }
                         }
 
                     } else {
                         empty_message(
-                            "No Queued Episodes Found - State is None",
-                            "You can queue episodes by clicking the context button on each episode and clicking 'Queue Episode'. Doing this will play episodes in order of the queue after the currently playing episode is complete."
+                            &t("empty.queue.title"),
+                            &t("empty.queue.body")
                         )
                     }
                 }
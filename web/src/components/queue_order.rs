@@ -0,0 +1,301 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use chrono::{Duration, Utc};
+use chrono_tz::Tz;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yewdux::prelude::*;
+use crate::components::context::{AppState, EpisodeCache, EpisodeLists, EpisodeListsMsg, UIState};
+use crate::components::gen_funcs::{format_datetime, format_duration, match_date_format};
+use crate::components::i18n::{t, t_args};
+use crate::requests::pod_req::{call_get_queued_episodes, call_reorder_queue, QueuedEpisode};
+
+// Queue rows are wrapped in an element carrying this attribute so touch drags can find the row under the finger
+const QUEUE_INDEX_ATTR: &str = "data-queue-index";
+
+// Moves the item at `from` so it ends up at index `to`
+pub fn move_item<T>(items: &mut Vec<T>, from: usize, to: usize) {
+    if from >= items.len() || from == to {
+        return;
+    }
+    let item = items.remove(from);
+    items.insert(to.min(items.len()), item);
+}
+
+// Seconds left to hear the episode at its podcast's playback speed
+fn remaining_seconds(episode: &QueuedEpisode, cache: &EpisodeCache) -> f64 {
+    let listened = cache
+        .episodes
+        .get(&episode.EpisodeID)
+        .and_then(|cached| cached.listen_duration)
+        .or(episode.ListenDuration)
+        .unwrap_or(0);
+    let speed = episode.PlaybackSpeed.filter(|speed| *speed > 0.0).unwrap_or(1.0);
+    (episode.EpisodeDuration - listened).max(0) as f64 / speed
+}
+
+// Saves run one at a time. Orders made while one is in flight collapse into the latest,
+// so the server always ends up with the last order the user made.
+#[derive(Default)]
+pub struct QueueSaver {
+    in_flight: bool,
+    pending: Option<Vec<i32>>,
+}
+
+pub fn save_queue_order(saver: Rc<RefCell<QueueSaver>>, server_name: String, api_key: Option<String>, user_id: i32, order: Vec<i32>) {
+    {
+        let mut saver = saver.borrow_mut();
+        if saver.in_flight {
+            saver.pending = Some(order);
+            return;
+        }
+        saver.in_flight = true;
+    }
+    spawn_local(async move {
+        let mut order = order;
+        loop {
+            if let Err(e) = call_reorder_queue(&server_name, &api_key, user_id, &order).await {
+                saver.borrow_mut().pending = None;
+                Dispatch::<UIState>::global().reduce_mut(|ui_state| {
+                    ui_state.error_message = Some(format!("{}: {}", t("queue.reorder_error"), e))
+                });
+                // The server's order wins, whatever the reason the save was refused
+                if let Ok(episodes) = call_get_queued_episodes(&server_name, &api_key, &user_id).await {
+                    Dispatch::<EpisodeLists>::global().apply(EpisodeListsMsg::Queue(episodes));
+                    Dispatch::<AppState>::global().get().store_app_state();
+                }
+                break;
+            }
+            let next = saver.borrow_mut().pending.take();
+            match next {
+                Some(next) => order = next,
+                None => break,
+            }
+        }
+        saver.borrow_mut().in_flight = false;
+    });
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct QueueSummaryProps {
+    pub episodes: Rc<Vec<QueuedEpisode>>,
+}
+
+// Total listening time left in the queue and when it would run out if played straight through
+#[function_component(QueueSummary)]
+pub fn queue_summary(props: &QueueSummaryProps) -> Html {
+    let (cache, _cache_dispatch) = use_store::<EpisodeCache>();
+    let user_tz = use_selector(|state: &AppState| state.user_tz.clone());
+    let hour_preference = use_selector(|state: &AppState| state.hour_preference);
+    let date_format = use_selector(|state: &AppState| state.date_format.clone());
+
+    let remaining: f64 = props.episodes.iter().map(|episode| remaining_seconds(episode, &cache)).sum();
+    let remaining = remaining.round() as i64;
+    let tz: Tz = user_tz.as_ref().as_ref().and_then(|tz| tz.parse().ok()).unwrap_or(chrono_tz::UTC);
+    let finish = (Utc::now() + Duration::seconds(remaining)).with_timezone(&tz);
+    let finish = format_datetime(&finish, &hour_preference, match_date_format(date_format.as_deref()));
+    let remaining = format_duration(remaining);
+
+    html! {
+        <p class="item_container-text text-center mb-4">
+            { t_args("queue.remaining", &[("time", remaining.as_str()), ("finish", finish.as_str())]) }
+        </p>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct QueueRowControlsProps {
+    pub index: usize,
+    pub count: usize,
+    pub title: String,
+    // (from, to) indexes within the queue
+    pub on_move: Callback<(usize, usize)>,
+    // Which row is being dragged, shared with the page so drops know the source
+    pub dragging: UseStateHandle<Option<usize>>,
+    pub drop_target: UseStateHandle<Option<usize>>,
+}
+
+// Drag handle plus move to top and bottom. The handle also takes arrow keys, Home and End.
+#[function_component(QueueRowControls)]
+pub fn queue_row_controls(props: &QueueRowControlsProps) -> Html {
+    let index = props.index;
+    let last = props.count.saturating_sub(1);
+
+    let on_drag_start = {
+        let dragging = props.dragging.clone();
+        Callback::from(move |e: DragEvent| {
+            if let Some(data_transfer) = e.data_transfer() {
+                let _ = data_transfer.set_data("text/plain", &index.to_string());
+                data_transfer.set_effect_allowed("move");
+                // Drag the whole row rather than just the handle
+                let row = e
+                    .target()
+                    .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+                    .and_then(|handle| handle.closest(&format!("[{}]", QUEUE_INDEX_ATTR)).ok().flatten());
+                if let Some(row) = row {
+                    data_transfer.set_drag_image(&row, 0, 0);
+                }
+            }
+            dragging.set(Some(index));
+        })
+    };
+
+    let on_drag_end = {
+        let dragging = props.dragging.clone();
+        let drop_target = props.drop_target.clone();
+        Callback::from(move |_: DragEvent| {
+            dragging.set(None);
+            drop_target.set(None);
+        })
+    };
+
+    // Touch devices don't fire drag events, so follow the finger and find the row under it
+    let on_touch_start = {
+        let dragging = props.dragging.clone();
+        Callback::from(move |_: TouchEvent| dragging.set(Some(index)))
+    };
+
+    let on_touch_move = {
+        let drop_target = props.drop_target.clone();
+        Callback::from(move |e: TouchEvent| {
+            let Some(touch) = e.touches().get(0) else {
+                return;
+            };
+            let target = web_sys::window()
+                .and_then(|window| window.document())
+                .and_then(|document| document.element_from_point(touch.client_x() as f32, touch.client_y() as f32))
+                .and_then(|element| element.closest(&format!("[{}]", QUEUE_INDEX_ATTR)).ok().flatten())
+                .and_then(|row| row.get_attribute(QUEUE_INDEX_ATTR))
+                .and_then(|index| index.parse::<usize>().ok());
+            if target != *drop_target {
+                drop_target.set(target);
+            }
+        })
+    };
+
+    let on_touch_end = {
+        let dragging = props.dragging.clone();
+        let drop_target = props.drop_target.clone();
+        let on_move = props.on_move.clone();
+        Callback::from(move |_: TouchEvent| {
+            if let (Some(from), Some(to)) = (*dragging, *drop_target) {
+                on_move.emit((from, to));
+            }
+            dragging.set(None);
+            drop_target.set(None);
+        })
+    };
+
+    let on_key_down = {
+        let on_move = props.on_move.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            let to = match e.key().as_str() {
+                "ArrowUp" => index.saturating_sub(1),
+                "ArrowDown" => (index + 1).min(last),
+                "Home" => 0,
+                "End" => last,
+                _ => return,
+            };
+            // Keep the row navigation on the episode card from also handling the key
+            e.prevent_default();
+            e.stop_propagation();
+            on_move.emit((index, to));
+        })
+    };
+
+    let on_top = {
+        let on_move = props.on_move.clone();
+        Callback::from(move |_: MouseEvent| on_move.emit((index, 0)))
+    };
+
+    let on_bottom = {
+        let on_move = props.on_move.clone();
+        Callback::from(move |_: MouseEvent| on_move.emit((index, last)))
+    };
+
+    let position = (index + 1).to_string();
+    let count = props.count.to_string();
+    let handle_label = t_args("queue.handle_label", &[("title", props.title.as_str()), ("position", position.as_str()), ("count", count.as_str())]);
+
+    html! {
+        <div class="flex flex-col items-center mr-2 space-y-2">
+            <button
+                class="queue-drag-handle item-container-button selector-button rounded p-1"
+                draggable="true"
+                data-queue-handle={index.to_string()}
+                aria-label={handle_label.clone()}
+                aria-roledescription={t("queue.handle_role")}
+                title={handle_label}
+                ondragstart={on_drag_start}
+                ondragend={on_drag_end}
+                ontouchstart={on_touch_start}
+                ontouchmove={on_touch_move}
+                ontouchend={on_touch_end}
+                onkeydown={on_key_down}
+            >
+                <span class="material-icons" aria-hidden="true">{"drag_indicator"}</span>
+            </button>
+            <button
+                class="item-container-button selector-button rounded p-1"
+                title={t("queue.move_top")}
+                aria-label={t("queue.move_top")}
+                disabled={index == 0}
+                onclick={on_top}
+            >
+                <span class="material-icons" aria-hidden="true">{"vertical_align_top"}</span>
+            </button>
+            <button
+                class="item-container-button selector-button rounded p-1"
+                title={t("queue.move_bottom")}
+                aria-label={t("queue.move_bottom")}
+                disabled={index == last}
+                onclick={on_bottom}
+            >
+                <span class="material-icons" aria-hidden="true">{"vertical_align_bottom"}</span>
+            </button>
+        </div>
+    }
+}
+
+// Wraps a queue row so it accepts drops and can be found from a touch point
+pub fn queue_row(index: usize, dragging: &UseStateHandle<Option<usize>>, drop_target: &UseStateHandle<Option<usize>>, on_move: &Callback<(usize, usize)>, controls: Html, item: Html) -> Html {
+    let on_drag_over = {
+        let drop_target = drop_target.clone();
+        Callback::from(move |e: DragEvent| {
+            // Allows the drop
+            e.prevent_default();
+            if *drop_target != Some(index) {
+                drop_target.set(Some(index));
+            }
+        })
+    };
+
+    let on_drop = {
+        let dragging = dragging.clone();
+        let drop_target = drop_target.clone();
+        let on_move = on_move.clone();
+        Callback::from(move |e: DragEvent| {
+            e.prevent_default();
+            if let Some(from) = *dragging {
+                on_move.emit((from, index));
+            }
+            dragging.set(None);
+            drop_target.set(None);
+        })
+    };
+
+    let class = classes!(
+        "flex",
+        "items-start",
+        (**dragging == Some(index)).then_some("queue-row-dragging"),
+        (**drop_target == Some(index) && **dragging != Some(index)).then_some("queue-drop-target"),
+    );
+
+    html! {
+        <div {class} data-queue-index={index.to_string()} ondragover={on_drag_over} ondrop={on_drop}>
+            { controls }
+            <div class="flex-grow min-w-0">{ item }</div>
+        </div>
+    }
+}
@@ -25,6 +25,8 @@ pub enum Route {
     Search,
     #[at("/user_stats")]
     UserStats,
+    #[at("/year_review")]
+    YearReview,
     #[at("/sign_out")]
     LogOut,
     #[at("/pod_layout")]
@@ -35,6 +37,12 @@ pub enum Route {
     Podcasts,
     #[at("/episode_layout")]
     EpisodeLayout,
+    #[at("/podcast/:id")]
+    Podcast { id: i32 },
     #[at("/episode")]
     Episode,
+    #[at("/playlists")]
+    Playlists,
+    #[at("/playlists/:id")]
+    Playlist { id: i32 },
 }
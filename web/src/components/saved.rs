@@ -1,14 +1,17 @@
 use yew::{function_component, Html, html};
 use yew::prelude::*;
 use super::app_drawer::App_drawer;
+use std::rc::Rc;
 use super::gen_components::{UseScrollToTop, Search_nav, empty_message, episode_item, on_shownotes_click};
+use super::virtual_list::VirtualList;
+use yew::virtual_dom::Key;
+use super::selection::BulkActionBar;
 use crate::requests::pod_req;
 use yewdux::prelude::*;
 use yew_router::history::BrowserHistory;
-use crate::components::context::{AppState, UIState};
+use crate::components::context::{AppState, UIState, CachedEpisode, EpisodeCache, EpisodeCacheMsg, EpisodeList, EpisodeLists, EpisodeListsMsg, use_list_page_state, use_removed_from};
 use crate::components::audio::AudioPlayer;
 use crate::components::gen_funcs::{sanitize_html_with_blank_target, truncate_description, parse_date, format_datetime, match_date_format};
-use crate::requests::pod_req::SavedEpisodesResponse;
 use crate::components::audio::on_play_click;
 use crate::components::episodes_layout::AppStateMsg;
 // use crate::components::gen_funcs::check_auth;
@@ -17,23 +20,26 @@ use wasm_bindgen::closure::Closure;
 use web_sys::window;
 use wasm_bindgen::JsCast;
 use crate::requests::login_requests::use_check_authentication;
+use crate::components::i18n::t;
 
 #[function_component(Saved)]
 pub fn saved() -> Html {
-    let (state, dispatch) = use_store::<AppState>();
+    let state = use_list_page_state();
+    let dispatch = Dispatch::<AppState>::global();
+    let saved = use_selector(|lists: &EpisodeLists| lists.saved.clone());
+    let removed = use_removed_from(EpisodeList::Saved);
     let history = BrowserHistory::new();
 
     // check_auth(effect_dispatch);
 
     let error = use_state(|| None);
-    let (post_state, _post_dispatch) = use_store::<AppState>();
     let (audio_state, audio_dispatch) = use_store::<UIState>();
     let error_message = audio_state.error_message.clone();
     let info_message = audio_state.info_message.clone();
     let dropdown_open = use_state(|| false);
 
-    let session_dispatch = _post_dispatch.clone();
-    let session_state = post_state.clone();
+    let session_dispatch = dispatch.clone();
+    let session_state = state.clone();
     let loading = use_state(|| true);
 
     use_effect_with((), move |_| {
@@ -99,11 +105,9 @@ pub fn saved() -> Html {
     {
         // let episodes = episodes.clone();
         let error = error.clone();
-        let api_key = post_state.auth_details.as_ref().map(|ud| ud.api_key.clone());
-        let user_id = post_state.user_details.as_ref().map(|ud| ud.UserID.clone());
-        let server_name = post_state.auth_details.as_ref().map(|ud| ud.server_name.clone());
-
-        let effect_dispatch = dispatch.clone();
+        let api_key = state.auth_details.as_ref().map(|ud| ud.api_key.clone());
+        let user_id = state.user_details.as_ref().map(|ud| ud.UserID.clone());
+        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
 
         // fetch_episodes(api_key.flatten(), user_id, server_name, dispatch, error, pod_req::call_get_recent_eps);
 
@@ -112,14 +116,14 @@ pub fn saved() -> Html {
             move |_| {
                 let error_clone = error.clone();
                 if let (Some(api_key), Some(user_id), Some(server_name)) = (api_key.clone(), user_id.clone(), server_name.clone()) {
-                    let dispatch = effect_dispatch.clone();
-    
                     wasm_bindgen_futures::spawn_local(async move {
                         match pod_req::call_get_saved_episodes(&server_name, &api_key, &user_id).await {
                             Ok(fetched_episodes) => {
-                                dispatch.reduce_mut(move |state| {
-                                    state.saved_episodes = Some(SavedEpisodesResponse { episodes: fetched_episodes });
-                                });
+                                Dispatch::<EpisodeCache>::global().apply(EpisodeCacheMsg::SyncList(
+                                    EpisodeList::Saved,
+                                    fetched_episodes.iter().map(CachedEpisode::from).collect(),
+                                ));
+                                Dispatch::<EpisodeLists>::global().apply(EpisodeListsMsg::Saved(fetched_episodes));
                                 loading_ep.set(false);
                                 // web_sys::console::log_1(&format!("State after update: {:?}", state).into()); // Log state after update
                             },
@@ -165,18 +169,32 @@ pub fn saved() -> Html {
 
                             
                 {
-                    if let Some(saved_eps) = state.saved_episodes.clone() {
-                        if saved_eps.episodes.is_empty() {
+                    // Episodes removed from this list on any page since the fetch drop out immediately
+                    if let Some(saved_eps) = saved.as_ref().as_ref().map(|episodes| {
+                        episodes.iter().filter(|episode| !removed.contains(&episode.EpisodeID)).cloned().collect::<Vec<_>>()
+                    }) {
+                        if saved_eps.is_empty() {
                             // Render "No Queued Episodes Found" if episodes list is empty
                             empty_message(
-                                "No Saved Episodes Found",
-                                "You can save episodes by clicking the context button on each episode and clicking 'Save Episode'. Doing this will save episodes here for easy access when you want to return to them."
+                                &t("empty.saved.title"),
+                                &t("empty.saved.body")
                             )
                         } else {
-                            saved_eps.episodes.into_iter().map(|episode| {
-                                let api_key = post_state.auth_details.as_ref().map(|ud| ud.api_key.clone());
-                                let user_id = post_state.user_details.as_ref().map(|ud| ud.UserID.clone());
-                                let server_name = post_state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+                            let episodes = Rc::new(saved_eps);
+                            let item_count = episodes.len();
+                            let episode_ids: Vec<i32> = episodes.iter().map(|episode| episode.EpisodeID).collect();
+                            let item_keys: Vec<Key> = episode_ids.iter().map(|episode_id| Key::from(*episode_id)).collect();
+                            let render_item = {
+                                let state = state.clone();
+                                let dispatch = dispatch.clone();
+                                let history = history.clone();
+                                let audio_state = audio_state.clone();
+                                let audio_dispatch = audio_dispatch.clone();
+                                Callback::from(move |index: usize| {
+                                let episode = episodes[index].clone();
+                                let api_key = state.auth_details.as_ref().map(|ud| ud.api_key.clone());
+                                let user_id = state.user_details.as_ref().map(|ud| ud.UserID.clone());
+                                let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
                                 let history_clone = history.clone();
                                 let id_string = &episode.EpisodeID.to_string();
         
@@ -264,18 +282,23 @@ pub fn saved() -> Html {
                                     episode_duration_clone,
                                     episode_listened_clone,
                                     "saved",
-                                    Callback::from(|_| {}), 
-                                    false,
                                 );
 
                                 item
-                            }).collect::<Html>()
+                            })
+                            };
+                            html! {
+                                <>
+                                    <BulkActionBar episode_ids={episode_ids} />
+                                    <VirtualList item_count={item_count} render_item={render_item} item_keys={item_keys} />
+                                </>
+                            }
                         }
 
                     } else {
                         empty_message(
-                            "No Saved Episodes Found",
-                            "You can save episodes by clicking the context button on each episode and clicking 'Save Episode'. Doing this will save episodes here for easy access when you want to return to them."
+                            &t("empty.saved.title"),
+                            &t("empty.saved.body")
                         )
                     }
                 }
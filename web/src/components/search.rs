@@ -2,10 +2,14 @@ use yew::{function_component, html, use_node_ref, Html, Properties, Callback, Mo
 use yew::prelude::*;
 use super::app_drawer::App_drawer;
 use super::gen_components::{UseScrollToTop, Search_nav, empty_message, episode_item, on_shownotes_click};
-use crate::requests::search_pods::{call_search_database, SearchRequest, SearchResponse};
+use super::virtual_list::VirtualList;
+use yew::virtual_dom::Key;
+use super::selection::BulkActionBar;
+use crate::requests::pod_req::EPISODE_PAGE_SIZE;
+use crate::requests::search_pods::{call_search_database, SearchEpisode, SearchRequest};
 use yewdux::prelude::*;
-use crate::components::context::{AppState, UIState};
-use yew_router::history::BrowserHistory;
+use crate::components::context::{AppState, UIState, CachedEpisode, EpisodeCache, EpisodeCacheMsg, EpisodeLists, EpisodeListsMsg, use_list_page_state};
+use yew_router::history::{BrowserHistory, History};
 use crate::components::audio::AudioPlayer;
 use crate::components::gen_funcs::{sanitize_html_with_blank_target, truncate_description, parse_date, format_datetime, match_date_format};
 use crate::components::audio::on_play_click;
@@ -17,10 +21,17 @@ use web_sys::HtmlElement;
 use wasm_bindgen_futures::spawn_local;
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
-use async_std::task::sleep;
-use std::time::Duration;
 use web_sys::window;
 use crate::requests::login_requests::use_check_authentication;
+use crate::components::i18n::t;
+use crate::components::incremental_search::{clear_recent_searches, load_recent_searches, matching_recent, remember_search, suggestion_id, suggestion_key, use_debounced, use_in_flight, Suggestion, SuggestionKey, SuggestionList, SEARCH_DEBOUNCE_MS};
+use crate::components::persistence::user_key;
+use crate::components::library_index::{index_episodes, is_offline, search_library, IndexedPodcast, LibraryQuery};
+
+const LIBRARY_SUGGESTIONS_ID: &str = "library-suggestions";
+const LIBRARY_RESULT_LIMIT: usize = 1_000;
+// Shorter terms only search once submitted
+const MIN_LIVE_SEARCH_LEN: usize = 2;
 
 #[derive(Properties, Clone, PartialEq)]
 pub struct SearchProps {
@@ -29,8 +40,9 @@ pub struct SearchProps {
 
 #[function_component(Search)]
 pub fn search(_props: &SearchProps) -> Html {
-    let (state, dispatch) = use_store::<AppState>();
-    let search_dispatch = dispatch.clone();
+    let state = use_list_page_state();
+    let dispatch = Dispatch::<AppState>::global();
+    let search_episodes = use_selector(|lists: &EpisodeLists| lists.search.clone());
 
     let session_dispatch = dispatch.clone();
     let session_state = state.clone();
@@ -65,7 +77,6 @@ pub fn search(_props: &SearchProps) -> Html {
     });
 
     // let error = use_state(|| None);
-    let (post_state, _post_dispatch) = use_store::<AppState>();
     let (audio_state, audio_dispatch) = use_store::<UIState>();
     let error_message = audio_state.error_message.clone();
     let info_message = audio_state.info_message.clone();
@@ -97,15 +108,14 @@ pub fn search(_props: &SearchProps) -> Html {
 
     let input_ref = use_node_ref();
     let input_ref_clone1 = input_ref.clone();
-    let input_ref_clone2 = input_ref.clone();
-    let form_ref = NodeRef::default();
+    let form_ref = use_node_ref();
     let form_ref_clone1 = form_ref.clone();
     let container_ref = use_node_ref();
     let container_ref_clone1 = container_ref.clone();
 
-    let api_key = post_state.auth_details.as_ref().map(|ud| ud.api_key.clone());
-    let user_id = post_state.user_details.as_ref().map(|ud| ud.UserID.clone());
-    let server_name = post_state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+    let api_key = state.auth_details.as_ref().map(|ud| ud.api_key.clone());
+    let user_id = state.user_details.as_ref().map(|ud| ud.UserID.clone());
+    let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
 
     // let on_click = Callback::from(move |_| {
     //     if let Some(form) = input_ref_clone1.cast::<HtmlElement>() {
@@ -113,65 +123,305 @@ pub fn search(_props: &SearchProps) -> Html {
     //     }
     // });
 
-    let api_key_submit = api_key.clone();
-    let user_id_submit = user_id.clone();
-    let server_name_submit = server_name.clone();
-
-
-    let on_submit = Callback::from(move |event: SubmitEvent| {
-        event.prevent_default();
-        event.prevent_default();
-        let container_ref_submit_clone1 = container_ref_clone1.clone();
+    // The submitted term is kept so scrolling to the end can request the following page
+    let submitted_term = use_state(|| None::<String>);
+    let has_more = use_state(|| false);
+    let loading_more = use_state(|| false);
+    let search_value = use_state(String::new);
+    let recent = use_state(Vec::<String>::new);
+    let suggestions_open = use_state(|| false);
+    let highlighted = use_state(|| None::<usize>);
+    let in_flight = use_in_flight();
+    let view_key = (*use_selector(|state: &AppState| user_key(state))).clone();
 
-        if let Some(form) = form_ref_clone1.cast::<HtmlElement>() {
-            form.class_list().add_1("move-to-top").unwrap();
-        }
+    {
+        let recent = recent.clone();
+        use_effect_with(view_key.clone(), move |view_key| {
+            if let Some(key) = view_key {
+                recent.set(load_recent_searches(key, "library"));
+            }
+            || ()
+        });
+    }
 
-        if let Some(form) = input_ref_clone1.cast::<HtmlElement>() {
-            form.class_list().add_1("move-to-top").unwrap();
+    // Podcasts from the library index matching the search, shown above the episodes
+    let library_podcasts = use_state(Vec::<IndexedPodcast>::new);
+    let offline = use_state(|| false);
+    // Server results already pulled into the index for the submitted term
+    let server_loaded = use_state(|| 0usize);
+    let asked_server = use_mut_ref(|| None::<String>);
+
+    // Ranked results straight from the library index. Episodes the cache still holds keep
+    // their full description, stored ones only have the text the index kept.
+    let show_local = {
+        let library_podcasts = library_podcasts.clone();
+        move |query: &LibraryQuery| {
+            let results = search_library(query, LIBRARY_RESULT_LIMIT);
+            let cache = Dispatch::<EpisodeCache>::global().get();
+            let episodes: Vec<SearchEpisode> = results.episodes.iter()
+                .map(|episode| {
+                    let cached = cache.episodes.get(&episode.episode_id).filter(|cached| !cached.title.is_empty());
+                    SearchEpisode::from(cached.map_or(episode, |cached| cached.as_ref()))
+                })
+                .collect();
+            library_podcasts.set(results.podcasts);
+            Dispatch::<EpisodeLists>::global().apply(EpisodeListsMsg::Search(episodes));
         }
+    };
+
+    // Searches the index for the term and, when asked and online, the server too so episodes
+    // the client hasn't seen yet join the ranking. A newer search aborts the server request.
+    let run_search = {
+        let submitted_term = submitted_term.clone();
+        let has_more = has_more.clone();
+        let offline = offline.clone();
+        let server_loaded = server_loaded.clone();
+        let in_flight = in_flight.clone();
+        let asked_server = asked_server.clone();
+        let show_local = show_local.clone();
+        let api_key = api_key.clone().flatten();
+        let server_name = server_name.clone();
+        Callback::from(move |(search_term, ask_server): (String, bool)| {
+            if let Some(form) = form_ref_clone1.cast::<HtmlElement>() {
+                form.class_list().add_1("move-to-top").unwrap();
+            }
+            if let Some(form) = input_ref_clone1.cast::<HtmlElement>() {
+                form.class_list().add_1("move-to-top").unwrap();
+            }
 
-        // Clone the necessary variables
-        let server_name_submit = server_name_submit.clone();
-        let api_key_submit = api_key_submit.clone();
-        let user_id_submit = user_id_submit.clone();
-        // let search_results = search_results_clone.clone();
-        let mut search_request = None;
-        if let Some(input_element) = input_ref_clone2.cast::<HtmlInputElement>() {
-            let search_term = input_element.value();
-            search_request = Some(SearchRequest {
-                search_term,
-                user_id: user_id_submit.unwrap(), // replace with the actual user id
-            });
-        } else {
-            // web_sys::console::log_1(&"input_ref_clone2 is not an HtmlInputElement".into());
-        }
-        let future_dispatch = search_dispatch.clone();
-        let future = async move {
-            sleep(Duration::from_secs(1)).await;
-            if let Some(container) = container_ref_submit_clone1.cast::<HtmlElement>() {
-                container.class_list().add_1("shrink-input").unwrap();
+            let query = LibraryQuery::parse(&search_term);
+            submitted_term.set(Some(search_term.clone()));
+            show_local(&query);
+            offline.set(is_offline());
+            if !ask_server {
+                return;
             }
-            if let Some(search_request) = search_request {
-                let dispatch = future_dispatch.clone();
-                match call_search_database(&server_name_submit.unwrap(), &api_key_submit.flatten(), &search_request).await {
+            in_flight.cancel();
+            has_more.set(false);
+            *asked_server.borrow_mut() = Some(search_term);
+            let text = query.text();
+            let (Some(server_name), Some(user_id), false, false) = (server_name.clone(), user_id, text.is_empty(), is_offline()) else {
+                return;
+            };
+            let search_request = SearchRequest {
+                search_term: text,
+                user_id,
+                offset: 0,
+                limit: Some(EPISODE_PAGE_SIZE),
+            };
+            let (generation, signal) = in_flight.start();
+            let in_flight = in_flight.clone();
+            let api_key = api_key.clone();
+            let has_more = has_more.clone();
+            let server_loaded = server_loaded.clone();
+            let show_local = show_local.clone();
+            spawn_local(async move {
+                let response = call_search_database(&server_name, &api_key, &search_request, signal.as_ref()).await;
+                // A newer search replaced this one, its results are on their way
+                if !in_flight.is_current(generation) {
+                    return;
+                }
+                match response {
                     Ok(results) => {
-                        dispatch.reduce_mut(move |state| {
-                            state.search_episodes = Some(SearchResponse { data: results });
-                        });
-                        // Update the search results state
-                        // search_results.set(results);
+                        has_more.set(results.len() == EPISODE_PAGE_SIZE);
+                        server_loaded.set(results.len());
+                        let episodes: Vec<CachedEpisode> = results.iter().map(CachedEpisode::from).collect();
+                        index_episodes(&episodes);
+                        Dispatch::<EpisodeCache>::global().apply(EpisodeCacheMsg::Ingest(episodes));
+                        show_local(&query);
                     }
                     Err(e) => {
-                        // Handle the error
+                        // The index results stay up, so this only loses episodes not seen before
                         web_sys::console::log_1(&format!("Failed to search database: {:?}", e).into()); // Log for debugging
                     }
                 }
+            });
+        })
+    };
+
+    // The index answers on every keystroke, the server once typing pauses
+    let typed_term = search_value.trim().to_string();
+    {
+        let run_search = run_search.clone();
+        use_effect_with(typed_term.clone(), move |term| {
+            if term.chars().count() >= MIN_LIVE_SEARCH_LEN {
+                run_search.emit((term.clone(), false));
+            }
+            || ()
+        });
+    }
+    let debounced_term = use_debounced(typed_term, SEARCH_DEBOUNCE_MS);
+    {
+        let run_search = run_search.clone();
+        let asked_server = asked_server.clone();
+        use_effect_with(debounced_term, move |term| {
+            let asked = asked_server.borrow().as_deref() == Some(term.as_str());
+            if term.chars().count() >= MIN_LIVE_SEARCH_LEN && !asked {
+                run_search.emit((term.clone(), true));
             }
-        };
-        spawn_local(future);
+            || ()
+        });
+    }
+
+    // An explicit search, from Enter or a picked suggestion, is remembered and runs right away
+    let submit_search = {
+        let run_search = run_search.clone();
+        let search_value = search_value.clone();
+        let recent = recent.clone();
+        let suggestions_open = suggestions_open.clone();
+        let highlighted = highlighted.clone();
+        let view_key = view_key.clone();
+        Callback::from(move |search_term: String| {
+            let search_term = search_term.trim().to_string();
+            if search_term.is_empty() {
+                return;
+            }
+            if let Some(key) = &view_key {
+                recent.set(remember_search(key, "library", &search_term));
+            }
+            search_value.set(search_term.clone());
+            suggestions_open.set(false);
+            highlighted.set(None);
+            run_search.emit((search_term, true));
+        })
+    };
+
+    let on_submit = {
+        let submit_search = submit_search.clone();
+        let search_value = search_value.clone();
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+            submit_search.emit((*search_value).clone());
+        })
+    };
+
+    // The results take the page once the search box has finished moving up
+    let on_form_moved = Callback::from(move |e: TransitionEvent| {
+        // Transitions inside the form bubble up here too
+        if e.target() != e.current_target() {
+            return;
+        }
+        if let Some(container) = container_ref_clone1.cast::<HtmlElement>() {
+            container.class_list().add_1("shrink-input").unwrap();
+        }
+    });
+
+    let on_input = {
+        let search_value = search_value.clone();
+        let suggestions_open = suggestions_open.clone();
+        let highlighted = highlighted.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            search_value.set(input.value());
+            suggestions_open.set(true);
+            highlighted.set(None);
+        })
+    };
+
+    let term = search_value.trim().to_string();
+    let matches = matching_recent(&recent, &term);
+    let suggestions: Vec<Suggestion> = matches.iter()
+        .map(|previous| Suggestion { label: previous.clone(), icon: "history" })
+        .collect();
+    let show_suggestions = *suggestions_open && !suggestions.is_empty();
+
+    let on_select = {
+        let submit_search = submit_search.clone();
+        Callback::from(move |index: usize| {
+            if let Some(previous) = matches.get(index) {
+                submit_search.emit(previous.clone());
+            }
+        })
+    };
+
+    let on_clear_recent = (term.is_empty() && !recent.is_empty()).then(|| {
+        let recent = recent.clone();
+        let view_key = view_key.clone();
+        Callback::from(move |_: ()| {
+            if let Some(key) = &view_key {
+                clear_recent_searches(key, "library");
+            }
+            recent.set(Vec::new());
+        })
     });
 
+    let on_input_keydown = {
+        let suggestions_open = suggestions_open.clone();
+        let highlighted = highlighted.clone();
+        let on_select = on_select.clone();
+        let count = suggestions.len();
+        Callback::from(move |e: KeyboardEvent| match suggestion_key(&e, *highlighted, count) {
+            Some(SuggestionKey::Move(index)) => {
+                suggestions_open.set(true);
+                highlighted.set(index);
+            }
+            Some(SuggestionKey::Choose(index)) => on_select.emit(index),
+            Some(SuggestionKey::Close) => {
+                suggestions_open.set(false);
+                highlighted.set(None);
+            }
+            None => {}
+        })
+    };
+
+    let on_input_focus = {
+        let suggestions_open = suggestions_open.clone();
+        Callback::from(move |_: FocusEvent| suggestions_open.set(true))
+    };
+
+    let on_input_blur = {
+        let suggestions_open = suggestions_open.clone();
+        let highlighted = highlighted.clone();
+        Callback::from(move |_: FocusEvent| {
+            suggestions_open.set(false);
+            highlighted.set(None);
+        })
+    };
+
+    let on_end_reached = {
+        let has_more = has_more.clone();
+        let loading_more = loading_more.clone();
+        let api_key = api_key.clone().flatten();
+        let server_name = server_name.clone();
+        let server_loaded = server_loaded.clone();
+        let show_local = show_local.clone();
+        let query = (*submitted_term).as_deref().map(LibraryQuery::parse);
+        let search_request = query.as_ref().map(LibraryQuery::text).zip(user_id).map(|(search_term, user_id)| SearchRequest {
+            search_term,
+            user_id,
+            offset: *server_loaded,
+            limit: Some(EPISODE_PAGE_SIZE),
+        });
+        Callback::from(move |_: ()| {
+            let (true, false, Some(search_request), Some(query), Some(server_name)) = (*has_more, *loading_more, search_request.clone(), query.clone(), server_name.clone()) else {
+                return;
+            };
+            loading_more.set(true);
+            let api_key = api_key.clone();
+            let has_more = has_more.clone();
+            let loading_more = loading_more.clone();
+            let server_loaded = server_loaded.clone();
+            let show_local = show_local.clone();
+            spawn_local(async move {
+                match call_search_database(&server_name, &api_key, &search_request, None).await {
+                    Ok(page) => {
+                        has_more.set(page.len() == EPISODE_PAGE_SIZE);
+                        server_loaded.set(search_request.offset + page.len());
+                        let episodes: Vec<CachedEpisode> = page.iter().map(CachedEpisode::from).collect();
+                        index_episodes(&episodes);
+                        Dispatch::<EpisodeCache>::global().apply(EpisodeCacheMsg::Ingest(episodes));
+                        show_local(&query);
+                    }
+                    Err(e) => {
+                        has_more.set(false);
+                        web_sys::console::log_1(&format!("Failed to load more search results: {:?}", e).into());
+                    }
+                }
+                loading_more.set(false);
+            });
+        })
+    };
+
     
 
     html! {
@@ -180,7 +430,7 @@ pub fn search(_props: &SearchProps) -> Html {
             <Search_nav />
             <UseScrollToTop />
             <div class="search-container" ref={container_ref.clone()}>
-                <form class="search-page-input" onsubmit={on_submit} ref={form_ref.clone()}>
+                <form class="search-page-input" onsubmit={on_submit} ontransitionend={on_form_moved} ref={form_ref.clone()}>
                     <label for="search" class="mb-2 text-sm font-medium text-gray-900 sr-only dark:text-white">{ "Search" }</label>
                     <div class="relative">
                         <div class="absolute inset-y-0 start-0 flex items-center ps-3 pointer-events-none">
@@ -188,23 +438,80 @@ pub fn search(_props: &SearchProps) -> Html {
                                 <path stroke="currentColor" stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="m19 19-4-4m0-7A7 7 0 1 1 1 8a7 7 0 0 1 14 0Z"/>
                             </svg>
                         </div>
-                        <input type="search" id="search" class="search-bar-input block w-full p-4 ps-10 text-sm border rounded-lg" placeholder="Search for a podcast, episode, or description" ref={input_ref.clone()}/>
+                        <input
+                            type="search"
+                            id="search"
+                            class="search-bar-input block w-full p-4 ps-10 text-sm border rounded-lg"
+                            placeholder="Search for a podcast, episode, or description"
+                            autocomplete="off"
+                            role="combobox"
+                            aria-autocomplete="list"
+                            aria-controls={LIBRARY_SUGGESTIONS_ID}
+                            aria-expanded={show_suggestions.to_string()}
+                            aria-activedescendant={highlighted.filter(|_| show_suggestions).map(|index| suggestion_id(LIBRARY_SUGGESTIONS_ID, index))}
+                            value={(*search_value).clone()}
+                            oninput={on_input}
+                            onkeydown={on_input_keydown}
+                            onfocus={on_input_focus}
+                            onblur={on_input_blur}
+                            ref={input_ref.clone()}
+                        />
                         <button class="search-page-button absolute end-2.5 bottom-2.5 focus:ring-4 focus:outline-none font-medium rounded-lg text-sm px-4 py-2">{ "Search" }</button>
                     </div>
+                    if show_suggestions {
+                        <div class="relative">
+                            <SuggestionList id={LIBRARY_SUGGESTIONS_ID} suggestions={suggestions} highlighted={*highlighted} on_select={on_select} on_clear={on_clear_recent} />
+                        </div>
+                    } else {
+                        <p class="item_container-text text-xs mt-2">{ t("library_search.syntax_hint") }</p>
+                    }
                 </form>
             </div>
+            if *offline {
+                <p class="item_container-text text-sm text-center my-2" role="status">{ t("library_search.offline") }</p>
+            }
+            if !library_podcasts.is_empty() {
+                <div class="flex flex-wrap gap-2 px-4 my-2" role="list" aria-label={t("library_search.podcasts")}>
+                    { for library_podcasts.iter().map(|podcast| {
+                        let history = history.clone();
+                        let path = format!("/podcast/{}", podcast.podcast_id);
+                        html! {
+                            <button
+                                type="button"
+                                role="listitem"
+                                class="download-button font-bold py-2 px-4 rounded inline-flex items-center"
+                                onclick={Callback::from(move |_: MouseEvent| history.push(path.clone()))}
+                            >
+                                <img src={podcast.artwork.clone()} alt="" class="w-6 h-6 rounded mr-2" />
+                                <span>{ &podcast.name }</span>
+                            </button>
+                        }
+                    }) }
+                </div>
+            }
             {
-                if let Some(search_eps) = state.search_episodes.clone() {
-                    let int_search_eps = search_eps.clone();
-                    let episodes = int_search_eps.data;
+                if let Some(episodes) = (*search_episodes).clone() {
                     if episodes.is_empty() {
                                 // Render "No Recent Episodes Found" if episodes list is empty
                                 empty_message(
-                                    "No Search Results Found",
-                                    "Perhaps try again, but search for something slightly different :/"
+                                    &t("empty.search.title"),
+                                    &t("empty.search.body")
                                 )
                             } else {
-                                episodes.into_iter().map(|episode| {
+                                let item_count = episodes.len();
+                                let episode_ids: Vec<i32> = episodes.iter().map(|episode| episode.EpisodeID).collect();
+                                let item_keys: Vec<Key> = episode_ids.iter().map(|episode_id| Key::from(*episode_id)).collect();
+                                let render_item = {
+                                    let state = state.clone();
+                                    let dispatch = dispatch.clone();
+                                    let history = history.clone();
+                                    let audio_state = audio_state.clone();
+                                    let audio_dispatch = audio_dispatch.clone();
+                                    let api_key = api_key.clone();
+                                    let user_id = user_id.clone();
+                                    let server_name = server_name.clone();
+                                    Callback::from(move |index: usize| {
+                                    let episode = episodes[index].clone();
                                     let id_string = &episode.EpisodeID.to_string();
 
                                     let is_expanded = state.expanded_descriptions.contains(id_string);
@@ -291,12 +598,23 @@ pub fn search(_props: &SearchProps) -> Html {
                                         episode_duration_clone,
                                         episode_listened_clone,
                                         "search",
-                                        Callback::from(|_| {}), 
-                                        false,
                                     );
 
                                     item
-                                }).collect::<Html>()
+                                    })
+                                };
+                                html! {
+                                    <>
+                                        <BulkActionBar episode_ids={episode_ids} />
+                                        <VirtualList
+                                            item_count={item_count}
+                                            render_item={render_item}
+                                            item_keys={item_keys}
+                                            on_end_reached={on_end_reached.clone()}
+                                            loading_more={*loading_more}
+                                        />
+                                    </>
+                                }
                             }
                     // } else {
                     //     empty_message(
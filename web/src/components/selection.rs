@@ -0,0 +1,354 @@
+use std::collections::HashSet;
+use yew::prelude::*;
+use yewdux::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+use crate::components::context::{AppState, UIState, EpisodeCache, EpisodeCacheMsg, EpisodeList, EpisodeLists, EpisodeListsMsg, PlaylistState, SelectionState, SelectionMsg};
+use crate::components::playlists::add_to_playlist;
+use crate::components::i18n::{t, t_args, t_plural};
+use crate::requests::pod_req::{
+    call_download_episode, call_mark_episode_played, call_queue_episode, call_remove_downloaded_episode, call_remove_history_episode,
+    call_save_episode, DownloadEpisodeRequest, QueuePodcastRequest, SavePodcastRequest,
+};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BulkAction {
+    Queue,
+    Save,
+    Download,
+    RemoveDownload,
+    MarkPlayed,
+    MarkUnplayed,
+    RemoveFromHistory,
+}
+
+impl BulkAction {
+    pub const ALL: [BulkAction; 7] = [
+        BulkAction::Queue,
+        BulkAction::Save,
+        BulkAction::Download,
+        BulkAction::RemoveDownload,
+        BulkAction::MarkPlayed,
+        BulkAction::MarkUnplayed,
+        BulkAction::RemoveFromHistory,
+    ];
+
+    fn key(&self) -> &'static str {
+        match self {
+            BulkAction::Queue => "queue",
+            BulkAction::Save => "save",
+            BulkAction::Download => "download",
+            BulkAction::RemoveDownload => "remove_download",
+            BulkAction::MarkPlayed => "mark_played",
+            BulkAction::MarkUnplayed => "mark_unplayed",
+            BulkAction::RemoveFromHistory => "remove_history",
+        }
+    }
+
+    fn icon(&self) -> &'static str {
+        match self {
+            BulkAction::Queue => "queue",
+            BulkAction::Save => "favorite",
+            BulkAction::Download => "download",
+            BulkAction::RemoveDownload => "delete",
+            BulkAction::MarkPlayed => "check_circle",
+            BulkAction::MarkUnplayed => "radio_button_unchecked",
+            BulkAction::RemoveFromHistory => "history",
+        }
+    }
+
+    pub fn label(&self) -> String {
+        t(&format!("selection.action.{}", self.key()))
+    }
+
+    // Applies the action to one episode and records the result in the shared caches
+    async fn run(&self, server_name: &String, api_key: &Option<String>, user_id: i32, episode_id: i32) -> Result<(), anyhow::Error> {
+        let cache_dispatch = Dispatch::<EpisodeCache>::global();
+        match self {
+            BulkAction::Queue => {
+                call_queue_episode(server_name, api_key, &QueuePodcastRequest { episode_id, user_id }).await?;
+                cache_dispatch.apply(EpisodeCacheMsg::SetMembership(EpisodeList::Queue, episode_id, true));
+            },
+            BulkAction::Save => {
+                call_save_episode(server_name, api_key, &SavePodcastRequest { episode_id, user_id }).await?;
+                cache_dispatch.apply(EpisodeCacheMsg::SetMembership(EpisodeList::Saved, episode_id, true));
+            },
+            BulkAction::Download => {
+                call_download_episode(server_name, api_key, &DownloadEpisodeRequest { episode_id, user_id }).await?;
+                cache_dispatch.apply(EpisodeCacheMsg::SetMembership(EpisodeList::Downloads, episode_id, true));
+            },
+            BulkAction::RemoveDownload => {
+                call_remove_downloaded_episode(server_name, api_key, &DownloadEpisodeRequest { episode_id, user_id }).await?;
+                cache_dispatch.apply(EpisodeCacheMsg::SetMembership(EpisodeList::Downloads, episode_id, false));
+            },
+            BulkAction::MarkPlayed | BulkAction::MarkUnplayed => {
+                let played = *self == BulkAction::MarkPlayed;
+                let listen_duration = call_mark_episode_played(server_name, api_key, user_id, episode_id, played).await?;
+                cache_dispatch.apply(EpisodeCacheMsg::SetPlayed(vec![(episode_id, listen_duration)], played));
+            },
+            BulkAction::RemoveFromHistory => {
+                call_remove_history_episode(server_name, api_key, user_id, episode_id).await?;
+                cache_dispatch.apply(EpisodeCacheMsg::SetProgress(episode_id, 0));
+                Dispatch::<EpisodeLists>::global().apply(EpisodeListsMsg::RemoveFromHistory(episode_id));
+            },
+        }
+        Ok(())
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct SelectCheckboxProps {
+    pub episode_id: i32,
+    pub title: String,
+}
+
+// Shown at the start of each episode row while the page is in selection mode
+#[function_component(SelectCheckbox)]
+pub fn select_checkbox(props: &SelectCheckboxProps) -> Html {
+    let episode_id = props.episode_id;
+    let (active, checked) = *use_selector_with_deps(
+        |selection: &SelectionState, episode_id: &i32| (selection.active, selection.selected.contains(episode_id)),
+        episode_id,
+    );
+
+    if !active {
+        return html! {};
+    }
+
+    // The store decides what is checked, so the browser's own toggle is cancelled
+    let onclick = Callback::from(move |e: MouseEvent| {
+        e.prevent_default();
+        e.stop_propagation();
+        Dispatch::<SelectionState>::global().apply(SelectionMsg::Toggle { episode_id, extend: e.shift_key() });
+    });
+
+    html! {
+        <input
+            type="checkbox"
+            class="form-checkbox h-5 w-5 text-blue-600 self-center ml-4"
+            aria-label={t_args("selection.select_episode", &[("title", props.title.as_str())])}
+            checked={checked}
+            {onclick}
+        />
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct BulkActionBarProps {
+    // Ids of the episodes on the page, in the order they are listed
+    pub episode_ids: Vec<i32>,
+}
+
+#[derive(Clone, PartialEq)]
+struct Failure {
+    title: String,
+    error: String,
+}
+
+// Selection mode toggle and bulk actions for an episode list. One bar per page; leaving the
+// page ends selection mode.
+#[function_component(BulkActionBar)]
+pub fn bulk_action_bar(props: &BulkActionBarProps) -> Html {
+    let (selection, selection_dispatch) = use_store::<SelectionState>();
+    let auth_details = use_selector(|state: &AppState| state.auth_details.clone());
+    let user_id = *use_selector(|state: &AppState| state.user_details.as_ref().map(|ud| ud.UserID));
+    let ui_dispatch = Dispatch::<UIState>::global();
+    // Done and total while an action runs
+    let progress = use_state(|| None::<(usize, usize)>);
+    let failures = use_state(Vec::<Failure>::new);
+    let manual_playlists = use_selector(|state: &PlaylistState| state.manual().cloned().collect::<Vec<_>>());
+
+    {
+        let selection_dispatch = selection_dispatch.clone();
+        use_effect_with(props.episode_ids.clone(), move |episode_ids| {
+            selection_dispatch.apply(SelectionMsg::SetOrder(episode_ids.clone()));
+            || ()
+        });
+    }
+
+    {
+        let selection_dispatch = selection_dispatch.clone();
+        use_effect_with((), move |_| move || selection_dispatch.apply(SelectionMsg::Exit));
+    }
+
+    let on_start = {
+        let selection_dispatch = selection_dispatch.clone();
+        Callback::from(move |_: MouseEvent| selection_dispatch.apply(SelectionMsg::Start))
+    };
+
+    let on_exit = {
+        let selection_dispatch = selection_dispatch.clone();
+        let failures = failures.clone();
+        Callback::from(move |_: MouseEvent| {
+            failures.set(Vec::new());
+            selection_dispatch.apply(SelectionMsg::Exit);
+        })
+    };
+
+    let on_select_all = {
+        let selection_dispatch = selection_dispatch.clone();
+        Callback::from(move |_: MouseEvent| selection_dispatch.apply(SelectionMsg::SelectAll))
+    };
+
+    let on_clear = {
+        let selection_dispatch = selection_dispatch.clone();
+        Callback::from(move |_: MouseEvent| selection_dispatch.apply(SelectionMsg::Clear))
+    };
+
+    let on_dismiss_failures = {
+        let failures = failures.clone();
+        Callback::from(move |_: MouseEvent| failures.set(Vec::new()))
+    };
+
+    let run_action = {
+        let progress = progress.clone();
+        let failures = failures.clone();
+        let selection = selection.clone();
+        let selection_dispatch = selection_dispatch.clone();
+        let ui_dispatch = ui_dispatch.clone();
+        let server_name = auth_details.as_ref().as_ref().map(|ud| ud.server_name.clone());
+        let api_key = auth_details.as_ref().as_ref().and_then(|ud| ud.api_key.clone());
+        Callback::from(move |action: BulkAction| {
+            let (Some(server_name), Some(user_id)) = (server_name.clone(), user_id) else {
+                return;
+            };
+            if progress.is_some() {
+                return;
+            }
+            // Work through the episodes in the order they are listed
+            let episode_ids: Vec<i32> = selection.order.iter().copied().filter(|id| selection.selected.contains(id)).collect();
+            if episode_ids.is_empty() {
+                return;
+            }
+            let api_key = api_key.clone();
+            let progress = progress.clone();
+            let failures = failures.clone();
+            let selection_dispatch = selection_dispatch.clone();
+            let ui_dispatch = ui_dispatch.clone();
+            let total = episode_ids.len();
+            progress.set(Some((0, total)));
+            failures.set(Vec::new());
+            spawn_local(async move {
+                let mut failed_ids = HashSet::new();
+                let mut failed = Vec::new();
+                for (done, episode_id) in episode_ids.into_iter().enumerate() {
+                    if let Err(e) = action.run(&server_name, &api_key, user_id, episode_id).await {
+                        let title = Dispatch::<EpisodeCache>::global()
+                            .get()
+                            .episodes
+                            .get(&episode_id)
+                            .map(|episode| episode.title.clone())
+                            .filter(|title| !title.is_empty())
+                            .unwrap_or_else(|| episode_id.to_string());
+                        failed_ids.insert(episode_id);
+                        failed.push(Failure { title, error: e.to_string() });
+                    }
+                    progress.set(Some((done + 1, total)));
+                }
+                let succeeded = total - failed.len();
+                // Failed episodes stay selected so the action can be retried on just those
+                selection_dispatch.reduce_mut(move |selection| selection.selected = failed_ids);
+                if succeeded > 0 {
+                    ui_dispatch.reduce_mut(|ui_state| ui_state.info_message = Some(t_plural("selection.done", succeeded as i64)));
+                }
+                failures.set(failed);
+                progress.set(None);
+            });
+        })
+    };
+
+    // Playlists take the whole selection in one request, so this skips the per-episode progress
+    let on_add_to_playlist = {
+        let selection = selection.clone();
+        let manual_playlists = manual_playlists.clone();
+        let server_name = auth_details.as_ref().as_ref().map(|ud| ud.server_name.clone());
+        let api_key = auth_details.as_ref().as_ref().and_then(|ud| ud.api_key.clone());
+        Callback::from(move |e: Event| {
+            let Some(select) = e.target_dyn_into::<web_sys::HtmlSelectElement>() else {
+                return;
+            };
+            let playlist = select
+                .value()
+                .parse::<i32>()
+                .ok()
+                .and_then(|playlist_id| manual_playlists.iter().find(|playlist| playlist.PlaylistID == playlist_id).cloned());
+            select.set_value("");
+            let episode_ids: Vec<i32> = selection.order.iter().copied().filter(|id| selection.selected.contains(id)).collect();
+            if let (Some(playlist), Some(server_name), Some(user_id), false) = (playlist, server_name.clone(), user_id, episode_ids.is_empty()) {
+                add_to_playlist(server_name, api_key.clone(), user_id, playlist, episode_ids);
+            }
+        })
+    };
+
+    if !selection.active {
+        return html! {
+            <div class="flex justify-between mb-4">
+                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_start}>
+                    <span class="material-icons icon-space">{"check_box"}</span>
+                    <span class="text-lg">{ t("selection.select") }</span>
+                </button>
+            </div>
+        };
+    }
+
+    let selected_count = selection.selected.len();
+    let running = progress.is_some();
+
+    html! {
+        <div class="mb-4">
+            <div class="flex flex-wrap items-center gap-2">
+                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_exit}>
+                    <span class="material-icons icon-space">{"cancel"}</span>
+                    <span class="text-lg">{ t("selection.cancel") }</span>
+                </button>
+                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_select_all} disabled={running}>
+                    <span class="material-icons icon-space">{"select_all"}</span>
+                    <span class="text-lg">{ t("selection.select_all") }</span>
+                </button>
+                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_clear} disabled={running || selected_count == 0}>
+                    <span class="material-icons icon-space">{"deselect"}</span>
+                    <span class="text-lg">{ t("selection.clear") }</span>
+                </button>
+                <span class="item_container-text" aria-live="polite">{ t_plural("selection.selected", selected_count as i64) }</span>
+            </div>
+            <div class="flex flex-wrap items-center gap-2 mt-2" role="toolbar" aria-label={t("selection.actions")}>
+                { for BulkAction::ALL.iter().map(|action| {
+                    let action = *action;
+                    let onclick = run_action.reform(move |_: MouseEvent| action);
+                    html! {
+                        <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" {onclick} disabled={running || selected_count == 0}>
+                            <span class="material-icons icon-space">{ action.icon() }</span>
+                            <span>{ action.label() }</span>
+                        </button>
+                    }
+                }) }
+                if !manual_playlists.is_empty() {
+                    <select aria-label={t("playlists.add_selected")} onchange={on_add_to_playlist} disabled={running || selected_count == 0} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                        <option value="" selected=true>{ t("playlists.add_selected") }</option>
+                        { for manual_playlists.iter().map(|playlist| html! {
+                            <option value={playlist.PlaylistID.to_string()}>{ &playlist.Name }</option>
+                        }) }
+                    </select>
+                }
+            </div>
+            if let Some((done, total)) = *progress {
+                <div class="flex items-center space-x-2 mt-2" role="status">
+                    <span class="item_container-text">{ t_args("selection.progress", &[("done", done.to_string().as_str()), ("total", total.to_string().as_str())]) }</span>
+                    <div class="progress-bar-container">
+                        <div class="progress-bar" style={format!("width: {}%;", done * 100 / total.max(1))}></div>
+                    </div>
+                </div>
+            }
+            if !failures.is_empty() {
+                <div class="mt-2" role="alert">
+                    <p class="item_container-text font-bold">{ t_plural("selection.failed", failures.len() as i64) }</p>
+                    <ul class="item_container-text text-sm list-disc ml-6">
+                        { for failures.iter().map(|failure| html! {
+                            <li>{ format!("{}: {}", failure.title, failure.error) }</li>
+                        }) }
+                    </ul>
+                    <button class="link hover:underline cursor-pointer mt-1" onclick={on_dismiss_failures}>{ t("selection.dismiss") }</button>
+                </div>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,74 @@
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use yewdux::prelude::*;
+use crate::components::context::UIState;
+use crate::components::a11y::{load_accessibility_preferences, save_accessibility_preferences, FONT_SCALES};
+use crate::components::i18n::t;
+
+#[function_component(AccessibilityOptions)]
+pub fn accessibility_options() -> Html {
+    let (_audio_state, audio_dispatch) = use_store::<UIState>();
+    let preferences = use_state(|| load_accessibility_preferences());
+
+    let on_reduce_motion_change = {
+        let preferences = preferences.clone();
+        Callback::from(move |_: Event| {
+            let mut updated = (*preferences).clone();
+            updated.reduce_motion = !updated.reduce_motion;
+            preferences.set(updated);
+        })
+    };
+
+    let on_font_scale_change = {
+        let preferences = preferences.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                if let Ok(scale) = select.value().parse::<u16>() {
+                    let mut updated = (*preferences).clone();
+                    updated.font_scale = scale;
+                    preferences.set(updated);
+                }
+            }
+        })
+    };
+
+    let on_submit = {
+        let preferences = preferences.clone();
+        Callback::from(move |_| {
+            save_accessibility_preferences(&preferences);
+            audio_dispatch.reduce_mut(|audio_state| audio_state.info_message = Option::from(t("accessibility.updated")));
+        })
+    };
+
+    html! {
+        <div class="p-4">
+            <p class="item_container-text text-lg font-bold mb-4">{ t("accessibility.header") }</p>
+            <p class="item_container-text text-md mb-4">{ t("accessibility.description") }</p>
+
+            <label class="flex items-center space-x-3 mb-4 item_container-text">
+                <input type="checkbox" class="form-checkbox h-5 w-5" checked={preferences.reduce_motion} onchange={on_reduce_motion_change} />
+                <span>{ t("accessibility.reduce_motion") }</span>
+            </label>
+
+            <label for="font-scale-select" class="block item_container-text mb-2">{ t("accessibility.font_size") }</label>
+            <div class="theme-select-dropdown relative inline-block">
+                <select id="font-scale-select" onchange={on_font_scale_change} class="theme-select-dropdown appearance-none w-full border px-4 py-2 pr-8 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                    {
+                        for FONT_SCALES.iter().map(|scale| html! {
+                            <option value={scale.to_string()} selected={preferences.font_scale == *scale}>{ format!("{}%", scale) }</option>
+                        })
+                    }
+                </select>
+                <div class="theme-dropdown-arrow pointer-events-none absolute inset-y-0 right-0 flex items-center px-2">
+                    <svg class="fill-current h-4 w-4" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20"><path d="M6.293 9.293a1 1 0 0 1 1.414 0L10 10.586l2.293-2.293a1 1 0 1 1 1.414 1.414l-3 3a1 1 0 0 1-1.414 0l-3-3a1 1 0 0 1 0-1.414z"/></svg>
+                </div>
+            </div>
+
+            <div>
+                <button onclick={on_submit} class="theme-submit-button mt-4 font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline" type="button">
+                    { t("language.submit") }
+                </button>
+            </div>
+        </div>
+    }
+}
@@ -1,13 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use yew::prelude::*;
 use wasm_bindgen::JsCast;
 use yewdux::prelude::*;
-use crate::components::context::{UIState, AppState};
+use crate::components::context::{UIState, AppState, FolderState, FolderMsg};
 use web_sys::{FileReader, HtmlInputElement};
 use wasm_bindgen::closure::Closure;
 use crate::components::gen_funcs::parse_opml;
-use crate::requests::pod_req::{call_add_podcast, PodcastValues};
+use crate::requests::pod_req::{call_add_podcast, PodcastValues, call_create_folder, call_get_folders, call_get_podcast_id, call_set_podcast_folders};
 use crate::requests::search_pods::{call_parse_podcast_channel_info, PodcastInfo};
+use crate::components::catch_up::{catch_up, CatchUp, DEFAULT_KEEP_LATEST};
+use crate::components::i18n::t;
 
 
 // use wasm_bindgen::JsValue;
@@ -48,12 +50,14 @@ fn transform_feed_result_to_values(feed_result: PodcastInfo, podcast_to_add: &Po
 pub struct PodcastToAdd {
     title: String,
     xml_url: String,
+    folders: Vec<String>,
 }
 
 #[derive(Clone, Debug)]
 struct PodcastToImport {
     title: String,
     xml_url: String,
+    folders: Vec<String>,
     selected: bool,
 }
 
@@ -63,12 +67,8 @@ async fn add_podcasts(server_name: &str, api_key: &Option<String>, user_id: i32,
         // Parse podcast URL to get feed details
         match call_parse_podcast_channel_info(&podcast.xml_url).await {
             Ok(feed_result) => {
-                let add_podcast = PodcastToAdd {
-                    title: podcast.title.clone(),
-                    xml_url: podcast.xml_url.clone()
-                };
                 // Assuming you transform `feed_result` into `PodcastValues` needed by `call_add_podcast`
-                let podcast_values = transform_feed_result_to_values(feed_result, &add_podcast, user_id);
+                let podcast_values = transform_feed_result_to_values(feed_result, &podcast, user_id);
 
                 // Add podcast to the server
                 match call_add_podcast(server_name, api_key, user_id, &podcast_values).await {
@@ -81,6 +81,65 @@ async fn add_podcasts(server_name: &str, api_key: &Option<String>, user_id: i32,
     }
 }
 
+// Recreates the OPML's outline groups as folders, keeping any folders the podcasts were already in
+async fn import_folders(server_name: &str, api_key: &Option<String>, user_id: i32, podcasts: &[PodcastToAdd]) {
+    let folder_names: BTreeSet<&String> = podcasts.iter().flat_map(|podcast| podcast.folders.iter()).collect();
+    if folder_names.is_empty() {
+        return;
+    }
+
+    let mut folder_ids = HashMap::new();
+    for name in folder_names {
+        match call_create_folder(server_name, api_key, user_id, name).await {
+            Ok(folder) => {
+                folder_ids.insert(name.clone(), folder.FolderID);
+            }
+            Err(e) => log::error!("Failed to create folder {}: {:?}", name, e),
+        }
+    }
+
+    let existing = call_get_folders(server_name, api_key, user_id).await.unwrap_or_default();
+    for podcast in podcasts.iter().filter(|podcast| !podcast.folders.is_empty()) {
+        let podcast_id = match call_get_podcast_id(server_name, api_key, &user_id, &podcast.xml_url, &podcast.title).await {
+            Ok(podcast_id) => podcast_id,
+            Err(e) => {
+                log::error!("Failed to find imported podcast {}: {:?}", podcast.title, e);
+                continue;
+            }
+        };
+        let mut assigned: BTreeSet<i32> = existing
+            .iter()
+            .filter(|folder| folder.PodcastIDs.contains(&podcast_id))
+            .map(|folder| folder.FolderID)
+            .collect();
+        assigned.extend(podcast.folders.iter().filter_map(|name| folder_ids.get(name).copied()));
+        let assigned: Vec<i32> = assigned.into_iter().collect();
+        if let Err(e) = call_set_podcast_folders(server_name, api_key, user_id, podcast_id, &assigned).await {
+            log::error!("Failed to file podcast {} into folders: {:?}", podcast.title, e);
+        }
+    }
+
+    if let Ok(folders) = call_get_folders(server_name, api_key, user_id).await {
+        Dispatch::<FolderState>::global().apply(FolderMsg::Loaded(folders));
+    }
+}
+
+// Imported shows arrive with their whole back catalog unplayed, so optionally mark all but the newest few
+async fn catch_up_imports(server_name: &str, api_key: &Option<String>, user_id: i32, podcasts: &[PodcastToAdd], keep_latest: usize) {
+    for podcast in podcasts {
+        let podcast_id = match call_get_podcast_id(server_name, api_key, &user_id, &podcast.xml_url, &podcast.title).await {
+            Ok(podcast_id) => podcast_id,
+            Err(e) => {
+                log::error!("Failed to find imported podcast {}: {:?}", podcast.title, e);
+                continue;
+            }
+        };
+        if let Err(e) = catch_up(server_name, api_key, user_id, podcast_id, CatchUp::KeepLatest(keep_latest)).await {
+            log::error!("Failed to catch up imported podcast {}: {:?}", podcast.title, e);
+        }
+    }
+}
+
 
 
 #[function_component(ImportOptions)]
@@ -92,6 +151,8 @@ pub fn import_options() -> Html {
     let import_pods = use_state(|| Vec::new());
     let show_verification = use_state(|| false);
     let (_audio_state, audio_dispatch) = use_store::<UIState>();
+    // None leaves every imported episode unplayed
+    let catch_up_keep = use_state(|| None::<usize>);
 
 
     let onclick = {
@@ -111,7 +172,7 @@ pub fn import_options() -> Html {
                             let text = text.as_string().unwrap();
                             let import_data: Vec<PodcastToImport> = parse_opml(&text)
                                 .into_iter()
-                                .map(|(title, xml_url)| PodcastToImport { title, xml_url, selected: true })
+                                .map(|podcast| PodcastToImport { title: podcast.title, xml_url: podcast.xml_url, folders: podcast.folders, selected: true })
                                 .collect();
                             import_pods.set(import_data);
                             show_verification.set(true);
@@ -132,8 +193,10 @@ pub fn import_options() -> Html {
         let server_name = server_name_confirm.clone();
         let api_key = api_key.clone();
         let user_id = user_id.clone();
+        let catch_up_keep = catch_up_keep.clone();
         Callback::from(move |_| {
             dispatch_wasm.reduce_mut(|state| state.is_loading = Some(true));
+            let catch_up_keep = *catch_up_keep;
             // Filter for selected podcasts
             let server_name = server_name.clone();
             let api_key = api_key.clone();
@@ -142,13 +205,18 @@ pub fn import_options() -> Html {
             let selected_podcasts: Vec<PodcastToAdd> = (*import_pods)
                 .iter()
                 .filter(|podcast| podcast.selected)
-                .map(|podcast| PodcastToAdd { title: podcast.title.clone(), xml_url: podcast.xml_url.clone() })
+                .map(|podcast| PodcastToAdd { title: podcast.title.clone(), xml_url: podcast.xml_url.clone(), folders: podcast.folders.clone() })
                 .collect();
     
             wasm_bindgen_futures::spawn_local(async move {
                 // Your existing logic to add podcasts
                 if let (Some(server_name), Some(api_key), Some(user_id)) = (server_name.as_ref(), api_key.as_ref(), user_id) {
-                    add_podcasts(server_name, &Some(api_key.clone().unwrap()), user_id, selected_podcasts.clone()).await;
+                    let api_key = Some(api_key.clone().unwrap());
+                    add_podcasts(server_name, &api_key, user_id, selected_podcasts.clone()).await;
+                    import_folders(server_name, &api_key, user_id, &selected_podcasts).await;
+                    if let Some(keep_latest) = catch_up_keep {
+                        catch_up_imports(server_name, &api_key, user_id, &selected_podcasts, keep_latest).await;
+                    }
                 }
                 dispatch_wasm.reduce_mut(|state| state.is_loading = Some(false));
                 audio_dispatch.reduce_mut(|audio_state| audio_state.info_message = Option::from("Selected Podcasts Added!".to_string()));
@@ -158,6 +226,26 @@ pub fn import_options() -> Html {
     };
     
 
+    let on_catch_up_toggle = {
+        let catch_up_keep = catch_up_keep.clone();
+        Callback::from(move |_: Event| {
+            catch_up_keep.set(match *catch_up_keep {
+                Some(_) => None,
+                None => Some(DEFAULT_KEEP_LATEST),
+            });
+        })
+    };
+
+    let on_catch_up_keep = {
+        let catch_up_keep = catch_up_keep.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(value) = input.value().parse::<usize>() {
+                catch_up_keep.set(Some(value));
+            }
+        })
+    };
+
     html! {
         <div class="p-4">
             <p class="item_container-text text-lg font-bold mb-4">{"Import Options:"}</p>
@@ -174,6 +262,26 @@ pub fn import_options() -> Html {
                                 <p class="item_container-text">
                                     {"The following podcasts were found. Please unselect any podcasts you don't want to add, and then click the button below. A large amount of podcasts will take a little while to parse all the feeds and add them. The loading animation will disappear once all complete. Be patient!"}
                                 </p>
+                                <div class="flex flex-wrap items-center gap-2 my-2">
+                                    <label class="item_container-text flex items-center gap-2">
+                                        <input
+                                            type="checkbox"
+                                            checked={catch_up_keep.is_some()}
+                                            onchange={on_catch_up_toggle}
+                                        />
+                                        <span>{ t("catch_up.import_label") }</span>
+                                    </label>
+                                    if let Some(keep_latest) = *catch_up_keep {
+                                        <input
+                                            type="number"
+                                            min="0"
+                                            class="search-bar-input border text-sm rounded-lg p-2 w-20"
+                                            aria-label={t("catch_up.keep_latest_label")}
+                                            value={keep_latest.to_string()}
+                                            oninput={on_catch_up_keep}
+                                        />
+                                    }
+                                </div>
                                 <button class="settings-button" onclick={on_confirm}>{"Add them!"}</button>
                             </div>
                             {
@@ -192,6 +300,9 @@ pub fn import_options() -> Html {
                                             <label onclick={toggle_selection}>
                                                 <input type="checkbox" checked={podcast.selected} />
                                                 <span class="item_container-text">{format!("{} - {}", podcast.title, podcast.xml_url)}</span>
+                                                if !podcast.folders.is_empty() {
+                                                    <span class="item_container-text text-sm ml-2">{ format!("({})", podcast.folders.join(", ")) }</span>
+                                                }
                                             </label>
                                         </div>
                                     }
@@ -0,0 +1,72 @@
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use yewdux::prelude::*;
+use crate::components::context::{AppState, LocaleState, UIState};
+use crate::components::i18n::{t, current_locale, set_locale, Locale};
+use crate::requests::setting_reqs::{call_set_language, SetLanguageRequest};
+
+#[function_component(LanguageOptions)]
+pub fn language_options() -> Html {
+    let (_locale_state, locale_dispatch) = use_store::<LocaleState>();
+    let (_audio_state, audio_dispatch) = use_store::<UIState>();
+    let state = use_store_value::<AppState>();
+    let selected_language = use_state(|| current_locale().code().to_string());
+
+    let on_change = {
+        let selected_language = selected_language.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                selected_language.set(select.value());
+            }
+        })
+    };
+
+    let on_submit = {
+        let selected_language = selected_language.clone();
+        Callback::from(move |_| {
+            if let Some(locale) = Locale::from_code(&selected_language) {
+                set_locale(locale);
+                locale_dispatch.reduce_mut(|state| state.locale = Some(locale.code().to_string()));
+                audio_dispatch.reduce_mut(|audio_state| audio_state.info_message = Option::from(t("language.updated")));
+
+                // Saved with the account too, so the language follows the user to other devices
+                let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+                let api_key = state.auth_details.as_ref().and_then(|ud| ud.api_key.clone());
+                let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+                if let (Some(server_name), Some(user_id)) = (server_name, user_id) {
+                    let audio_dispatch = audio_dispatch.clone();
+                    let request = SetLanguageRequest { user_id, new_language: locale.code().to_string() };
+                    wasm_bindgen_futures::spawn_local(async move {
+                        if let Err(e) = call_set_language(&server_name, &api_key, &request).await {
+                            audio_dispatch.reduce_mut(|audio_state| audio_state.error_message = Option::from(format!("{}: {}", t("language.save_failed"), e)));
+                        }
+                    });
+                }
+            }
+        })
+    };
+
+    html! {
+        <div class="p-4">
+            <p class="item_container-text text-lg font-bold mb-4">{ t("language.header") }</p>
+            <p class="item_container-text text-md mb-4">{ t("language.description") }</p>
+
+            <div class="theme-select-dropdown relative inline-block">
+                <select onchange={on_change} class="theme-select-dropdown appearance-none w-full border px-4 py-2 pr-8 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                    {
+                        for Locale::ALL.iter().map(|locale| html! {
+                            <option value={locale.code()} selected={*selected_language == locale.code()}>{ locale.native_name() }</option>
+                        })
+                    }
+                </select>
+                <div class="theme-dropdown-arrow pointer-events-none absolute inset-y-0 right-0 flex items-center px-2">
+                    <svg class="fill-current h-4 w-4" xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20"><path d="M6.293 9.293a1 1 0 0 1 1.414 0L10 10.586l2.293-2.293a1 1 0 1 1 1.414 1.414l-3 3a1 1 0 0 1-1.414 0l-3-3a1 1 0 0 1 0-1.414z"/></svg>
+                </div>
+            </div>
+
+            <button onclick={on_submit} class="theme-submit-button mt-4 font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline" type="button">
+                { t("language.submit") }
+            </button>
+        </div>
+    }
+}
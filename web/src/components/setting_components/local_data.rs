@@ -0,0 +1,84 @@
+use yew::prelude::*;
+use yewdux::prelude::*;
+use yew_router::history::{BrowserHistory, History};
+use crate::components::context::{AppState, UIState, clear_entity_caches};
+use crate::components::persistence::reset_local_data;
+use crate::components::i18n::t;
+
+#[function_component(LocalDataOptions)]
+pub fn local_data_options() -> Html {
+    let (_state, dispatch) = use_store::<AppState>();
+    let (_ui_state, ui_dispatch) = use_store::<UIState>();
+    let show_confirm = use_state(|| false);
+
+    let open_confirm = {
+        let show_confirm = show_confirm.clone();
+        Callback::from(move |_: MouseEvent| show_confirm.set(true))
+    };
+
+    let close_modal = {
+        let show_confirm = show_confirm.clone();
+        Callback::from(move |_: MouseEvent| show_confirm.set(false))
+    };
+
+    let on_reset = {
+        let show_confirm = show_confirm.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(audio) = &ui_dispatch.get().audio_element {
+                let _ = audio.pause();
+            }
+            reset_local_data();
+            clear_entity_caches();
+            ui_dispatch.reduce_mut(|ui_state| *ui_state = UIState::default());
+            dispatch.reduce_mut(|state| *state = AppState::default());
+            show_confirm.set(false);
+            BrowserHistory::new().push("/");
+        })
+    };
+
+    let confirm_modal = html! {
+        <div id="reset-local-data-modal" tabindex="-1" aria-hidden="true" class="fixed top-0 right-0 left-0 z-50 flex justify-center items-center w-full h-[calc(100%-1rem)] max-h-full bg-black bg-opacity-25">
+            <div class="modal-container relative p-4 w-full max-w-md max-h-full rounded-lg shadow">
+                <div class="relative rounded-lg shadow">
+                    <div class="flex flex-col items-start justify-between p-4 md:p-5 border-b rounded-t">
+                        <button onclick={close_modal.clone()} class="self-end text-gray-400 bg-transparent hover:bg-gray-200 hover:text-gray-900 rounded-lg text-sm w-8 h-8 ms-auto inline-flex justify-center items-center dark:hover:bg-gray-600 dark:hover:text-white">
+                            <svg class="w-3 h-3" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 14 14">
+                                <path stroke="currentColor" stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="m1 1 6 6m0 0 6 6M7 7l6-6M7 7l-6 6"/>
+                            </svg>
+                            <span class="sr-only">{ t("a11y.close_modal") }</span>
+                        </button>
+                        <h3 class="text-xl font-semibold item_container-text">
+                            { t("local_data.confirm_title") }
+                        </h3>
+                        <p class="text-m font-semibold">
+                            { t("local_data.confirm_body") }
+                        </p>
+                        <div class="flex justify-between space-x-4">
+                            <button onclick={on_reset} class="mt-4 download-button font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline">
+                                { t("local_data.reset") }
+                            </button>
+                            <button onclick={close_modal.clone()} class="mt-4 download-button font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline">
+                                { t("local_data.cancel") }
+                            </button>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        </div>
+    };
+
+    html! {
+        <>
+        if *show_confirm {
+            { confirm_modal }
+        }
+            <div class="p-4">
+                <p class="item_container-text text-lg font-bold mb-4">{ t("local_data.header") }</p>
+                <p class="item_container-text text-md mb-4">{ t("local_data.description") }</p>
+                <button onclick={open_confirm} class="mt-4 settings-button font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline">
+                    { t("local_data.reset") }
+                </button>
+            </div>
+        </>
+    }
+}
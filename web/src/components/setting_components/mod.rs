@@ -11,4 +11,10 @@ pub mod user_self_service;
 pub mod email_settings;
 pub mod backup_server;
 pub mod restore_server;
+pub mod server_profiles;
+pub mod language_options;
+pub mod accessibility_options;
+pub mod local_data;
+pub mod notification_options;
+pub mod search_provider_options;
 // ...other submodule declarations if any...
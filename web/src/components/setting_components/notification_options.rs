@@ -0,0 +1,160 @@
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use yewdux::prelude::*;
+use crate::components::context::{AppState, UIState};
+use crate::components::goals::goals_available;
+use crate::components::i18n::t;
+use crate::components::notifications::{hour_label, notifications_supported, request_permission, NotificationPreferences};
+use crate::components::persistence::user_key;
+
+#[function_component(NotificationOptions)]
+pub fn notification_options() -> Html {
+    let (state, _dispatch) = use_store::<AppState>();
+    let (_audio_state, audio_dispatch) = use_store::<UIState>();
+    let view_key = user_key(&state);
+    let preferences = use_state(NotificationPreferences::default);
+
+    {
+        let preferences = preferences.clone();
+        use_effect_with(view_key.clone(), move |view_key| {
+            if let Some(key) = view_key {
+                preferences.set(NotificationPreferences::load(key));
+            }
+            || ()
+        });
+    }
+
+    let update = |change: fn(&mut NotificationPreferences)| {
+        let preferences = preferences.clone();
+        Callback::from(move |_: Event| {
+            let mut updated = (*preferences).clone();
+            change(&mut updated);
+            preferences.set(updated);
+        })
+    };
+    let on_quiet_hours_change = update(|p| p.quiet_hours = !p.quiet_hours);
+    let on_digest_change = update(|p| p.digest = !p.digest);
+    let on_streak_reminder_change = update(|p| p.streak_reminder = !p.streak_reminder);
+
+    let on_hour_change = |change: fn(&mut NotificationPreferences, u32)| {
+        let preferences = preferences.clone();
+        Callback::from(move |e: Event| {
+            if let Some(hour) = e.target_dyn_into::<HtmlSelectElement>().and_then(|select| select.value().parse::<u32>().ok()) {
+                let mut updated = (*preferences).clone();
+                change(&mut updated, hour);
+                preferences.set(updated);
+            }
+        })
+    };
+    let on_quiet_start_change = on_hour_change(|p, hour| p.quiet_start = hour);
+    let on_quiet_end_change = on_hour_change(|p, hour| p.quiet_end = hour);
+    let on_streak_hour_change = on_hour_change(|p, hour| p.streak_reminder_hour = hour);
+    let show_streak = goals_available(&state);
+
+    // Turning notifications on asks for permission first, and stays off if it is refused
+    let on_enabled_change = {
+        let preferences = preferences.clone();
+        let audio_dispatch = audio_dispatch.clone();
+        Callback::from(move |_: Event| {
+            let mut updated = (*preferences).clone();
+            if updated.enabled {
+                updated.enabled = false;
+                preferences.set(updated);
+                return;
+            }
+            let preferences = preferences.clone();
+            let audio_dispatch = audio_dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if request_permission().await {
+                    updated.enabled = true;
+                } else {
+                    audio_dispatch.reduce_mut(|audio_state| audio_state.error_message = Some(t("notifications.permission_denied")));
+                }
+                preferences.set(updated);
+            });
+        })
+    };
+
+    let on_submit = {
+        let preferences = preferences.clone();
+        let view_key = view_key.clone();
+        Callback::from(move |_| {
+            if let Some(key) = &view_key {
+                preferences.save(key);
+                audio_dispatch.reduce_mut(|audio_state| audio_state.info_message = Option::from(t("notifications.updated")));
+            }
+        })
+    };
+
+    let hour_options = |selected: u32| {
+        html! {
+            <>
+            { for (0..24).map(|hour| html! {
+                <option value={hour.to_string()} selected={hour == selected}>{ hour_label(hour) }</option>
+            }) }
+            </>
+        }
+    };
+
+    if !notifications_supported() {
+        return html! {
+            <div class="p-4">
+                <p class="item_container-text text-md">{ t("notifications.unsupported") }</p>
+            </div>
+        };
+    }
+
+    html! {
+        <div class="p-4">
+            <p class="item_container-text text-lg font-bold mb-4">{ t("notifications.header") }</p>
+            <p class="item_container-text text-md mb-4">{ t("notifications.description") }</p>
+
+            <label class="flex items-center space-x-3 mb-4 item_container-text">
+                <input type="checkbox" class="form-checkbox h-5 w-5" checked={preferences.enabled} onchange={on_enabled_change} />
+                <span>{ t("notifications.enabled") }</span>
+            </label>
+
+            <label class="flex items-center space-x-3 mb-2 item_container-text">
+                <input type="checkbox" class="form-checkbox h-5 w-5" checked={preferences.quiet_hours} onchange={on_quiet_hours_change} disabled={!preferences.enabled} />
+                <span>{ t("notifications.quiet_hours") }</span>
+            </label>
+            <div class="flex flex-wrap items-center gap-2 mb-4 item_container-text">
+                <label for="quiet-start-select">{ t("notifications.quiet_from") }</label>
+                <select id="quiet-start-select" onchange={on_quiet_start_change} disabled={!preferences.enabled || !preferences.quiet_hours} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                    { hour_options(preferences.quiet_start) }
+                </select>
+                <label for="quiet-end-select">{ t("notifications.quiet_until") }</label>
+                <select id="quiet-end-select" onchange={on_quiet_end_change} disabled={!preferences.enabled || !preferences.quiet_hours} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                    { hour_options(preferences.quiet_end) }
+                </select>
+            </div>
+            <p class="item_container-text text-sm mb-4">{ t("notifications.quiet_hours_hint") }</p>
+
+            <label class="flex items-center space-x-3 mb-2 item_container-text">
+                <input type="checkbox" class="form-checkbox h-5 w-5" checked={preferences.digest} onchange={on_digest_change} disabled={!preferences.enabled} />
+                <span>{ t("notifications.digest") }</span>
+            </label>
+            <p class="item_container-text text-sm mb-4">{ t("notifications.digest_hint") }</p>
+
+            if show_streak {
+                <label class="flex items-center space-x-3 mb-2 item_container-text">
+                    <input type="checkbox" class="form-checkbox h-5 w-5" checked={preferences.streak_reminder} onchange={on_streak_reminder_change} disabled={!preferences.enabled} />
+                    <span>{ t("notifications.streak_reminder") }</span>
+                </label>
+                <div class="flex flex-wrap items-center gap-2 mb-4 item_container-text">
+                    <label for="streak-hour-select">{ t("notifications.streak_reminder_after") }</label>
+                    <select id="streak-hour-select" onchange={on_streak_hour_change} disabled={!preferences.enabled || !preferences.streak_reminder} class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                        { hour_options(preferences.streak_reminder_hour) }
+                    </select>
+                </div>
+                <p class="item_container-text text-sm mb-4">{ t("notifications.streak_reminder_hint") }</p>
+            }
+
+            <div>
+                <button onclick={on_submit} class="theme-submit-button mt-4 font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline" type="button">
+                    { t("language.submit") }
+                </button>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,96 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yewdux::prelude::*;
+use crate::components::context::{AppState, UIState};
+use crate::components::i18n::t;
+use crate::components::persistence::user_key;
+use crate::requests::search_providers::{SearchProviderPreferences, FYYD, GPODDER, PROVIDERS};
+
+#[function_component(SearchProviderOptions)]
+pub fn search_provider_options() -> Html {
+    let (state, _dispatch) = use_store::<AppState>();
+    let (_audio_state, audio_dispatch) = use_store::<UIState>();
+    let view_key = user_key(&state);
+    let preferences = use_state(SearchProviderPreferences::default);
+
+    {
+        let preferences = preferences.clone();
+        use_effect_with(view_key.clone(), move |view_key| {
+            if let Some(key) = view_key {
+                preferences.set(SearchProviderPreferences::load(key));
+            }
+            || ()
+        });
+    }
+
+    let on_provider_change = |provider: &'static str| {
+        let preferences = preferences.clone();
+        Callback::from(move |_: Event| {
+            let mut updated = (*preferences).clone();
+            updated.toggle(provider);
+            preferences.set(updated);
+        })
+    };
+
+    let on_url_input = |change: fn(&mut SearchProviderPreferences, String)| {
+        let preferences = preferences.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                let mut updated = (*preferences).clone();
+                change(&mut updated, input.value());
+                preferences.set(updated);
+            }
+        })
+    };
+    let on_fyyd_url_input = on_url_input(|p, url| p.fyyd_url = url);
+    let on_gpodder_url_input = on_url_input(|p, url| p.gpodder_url = url);
+
+    let on_reset = {
+        let preferences = preferences.clone();
+        Callback::from(move |_: MouseEvent| preferences.set(SearchProviderPreferences::default()))
+    };
+
+    let on_submit = {
+        let preferences = preferences.clone();
+        let view_key = view_key.clone();
+        Callback::from(move |_| {
+            if let Some(key) = &view_key {
+                preferences.save(key);
+                audio_dispatch.reduce_mut(|audio_state| audio_state.info_message = Option::from(t("search_providers.updated")));
+            }
+        })
+    };
+
+    html! {
+        <div class="p-4">
+            <p class="item_container-text text-lg font-bold mb-4">{ t("search_providers.header") }</p>
+            <p class="item_container-text text-md mb-4">{ t("search_providers.description") }</p>
+
+            { for PROVIDERS.iter().map(|(id, name)| html! {
+                <label class="flex items-center space-x-3 mb-2 item_container-text">
+                    <input type="checkbox" class="form-checkbox h-5 w-5" checked={preferences.is_enabled(id)} onchange={on_provider_change(id)} />
+                    <span>{ *name }</span>
+                </label>
+            }) }
+
+            <p class="item_container-text text-sm mt-4 mb-4">{ t("search_providers.urls_hint") }</p>
+            <label class="flex flex-col item_container-text mb-4">
+                <span class="mb-1">{ t("search_providers.fyyd_url") }</span>
+                <input type="url" class="search-bar-input border text-sm rounded-lg block w-full p-2.5" value={preferences.fyyd_url.clone()} oninput={on_fyyd_url_input} disabled={!preferences.is_enabled(FYYD)} />
+            </label>
+            <label class="flex flex-col item_container-text mb-4">
+                <span class="mb-1">{ t("search_providers.gpodder_url") }</span>
+                <input type="url" class="search-bar-input border text-sm rounded-lg block w-full p-2.5" value={preferences.gpodder_url.clone()} oninput={on_gpodder_url_input} disabled={!preferences.is_enabled(GPODDER)} />
+            </label>
+
+            <div class="flex gap-2">
+                <button onclick={on_submit} class="theme-submit-button mt-4 font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline" type="button">
+                    { t("language.submit") }
+                </button>
+                <button onclick={on_reset} class="theme-submit-button mt-4 font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline" type="button">
+                    { t("search_providers.reset") }
+                </button>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,159 @@
+use yew::prelude::*;
+use yewdux::prelude::*;
+use crate::components::context::{AppState, UIState};
+use crate::components::profiles::{load_profiles, active_profile_id, rename_profile, remove_profile, switch_profile};
+use crate::components::i18n::{t, t_args};
+
+#[function_component(ServerProfiles)]
+pub fn server_profiles() -> Html {
+    let (state, dispatch) = use_store::<AppState>();
+    let (_audio_state, audio_dispatch) = use_store::<UIState>();
+    let profiles = use_state(|| load_profiles());
+    let editing_id: UseStateHandle<Option<String>> = use_state(|| None);
+    let edit_name = use_state(|| String::new());
+    let active_id = active_profile_id();
+
+    let on_name_change = {
+        let edit_name = edit_name.clone();
+        Callback::from(move |e: InputEvent| {
+            edit_name.set(e.target_unchecked_into::<web_sys::HtmlInputElement>().value());
+        })
+    };
+
+    let on_save_name = {
+        let profiles = profiles.clone();
+        let editing_id = editing_id.clone();
+        let edit_name = edit_name.clone();
+        let audio_dispatch = audio_dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(id) = (*editing_id).clone() {
+                if edit_name.trim().is_empty() {
+                    audio_dispatch.reduce_mut(|audio_state| audio_state.error_message = Option::from(t("profiles.name_empty")));
+                    return;
+                }
+                rename_profile(&id, &edit_name);
+                profiles.set(load_profiles());
+                editing_id.set(None);
+                audio_dispatch.reduce_mut(|audio_state| audio_state.info_message = Option::from(t("profiles.renamed")));
+            }
+        })
+    };
+
+    let on_cancel_edit = {
+        let editing_id = editing_id.clone();
+        Callback::from(move |_: MouseEvent| {
+            editing_id.set(None);
+        })
+    };
+
+    html! {
+        <div class="p-4">
+            <p class="item_container-text text-lg font-bold mb-4">{ t("profiles.header") }</p>
+            <p class="item_container-text text-md mb-4">{ t("profiles.description") }</p>
+            <div class="relative overflow-x-auto">
+                <table class="w-full text-sm text-left rtl:text-right">
+                    <thead class="text-xs uppercase table-header">
+                        <tr>
+                            <th scope="col" class="px-6 py-3">{ t("profiles.name") }</th>
+                            <th scope="col" class="px-6 py-3">{ t("profiles.server") }</th>
+                            <th scope="col" class="px-6 py-3">{ t("profiles.username") }</th>
+                            <th scope="col" class="px-6 py-3">{ t("profiles.actions") }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                    {
+                        for (*profiles).iter().map(|profile| {
+                            let is_active = Some(&profile.id) == active_id.as_ref();
+                            let is_editing = Some(&profile.id) == (*editing_id).as_ref();
+
+                            let on_edit = {
+                                let editing_id = editing_id.clone();
+                                let edit_name = edit_name.clone();
+                                let id = profile.id.clone();
+                                let display_name = profile.display_name.clone();
+                                Callback::from(move |_: MouseEvent| {
+                                    edit_name.set(display_name.clone());
+                                    editing_id.set(Some(id.clone()));
+                                })
+                            };
+                            let on_remove = {
+                                let profiles = profiles.clone();
+                                let audio_dispatch = audio_dispatch.clone();
+                                let id = profile.id.clone();
+                                Callback::from(move |_: MouseEvent| {
+                                    remove_profile(&id);
+                                    profiles.set(load_profiles());
+                                    audio_dispatch.reduce_mut(|audio_state| audio_state.info_message = Option::from(t("profiles.removed")));
+                                })
+                            };
+                            let on_switch = {
+                                let profile = profile.clone();
+                                let state = state.clone();
+                                let dispatch = dispatch.clone();
+                                let audio_dispatch = audio_dispatch.clone();
+                                Callback::from(move |_: MouseEvent| {
+                                    switch_profile(profile.clone(), &state, dispatch.clone(), audio_dispatch.clone());
+                                })
+                            };
+
+                            html! {
+                                <tr class="table-row border-b">
+                                    <td class="px-6 py-4">
+                                    {
+                                        if is_editing {
+                                            html! {
+                                                <input type="text" value={(*edit_name).clone()} oninput={on_name_change.clone()} class="search-bar-input border text-sm rounded-lg block w-full p-2.5" />
+                                            }
+                                        } else if is_active {
+                                            html! { <span>{ t_args("profiles.current", &[("name", profile.display_name.as_str())]) }</span> }
+                                        } else {
+                                            html! { <span>{ &profile.display_name }</span> }
+                                        }
+                                    }
+                                    </td>
+                                    <td class="px-6 py-4">{ &profile.auth_details.server_name }</td>
+                                    <td class="px-6 py-4">{ &profile.auth_details.username }</td>
+                                    <td class="px-6 py-4">
+                                        <div class="flex space-x-2">
+                                        {
+                                            if is_editing {
+                                                html! {
+                                                    <>
+                                                    <button onclick={on_save_name.clone()} aria-label={t("profiles.save")} class="settings-button font-bold py-1 px-2 rounded">
+                                                        <span class="material-icons" aria-hidden="true">{"check"}</span>
+                                                    </button>
+                                                    <button onclick={on_cancel_edit.clone()} aria-label={t("profiles.cancel")} class="settings-button font-bold py-1 px-2 rounded">
+                                                        <span class="material-icons" aria-hidden="true">{"close"}</span>
+                                                    </button>
+                                                    </>
+                                                }
+                                            } else {
+                                                html! {
+                                                    <>
+                                                    <button onclick={on_edit} aria-label={t("profiles.rename")} class="settings-button font-bold py-1 px-2 rounded">
+                                                        <span class="material-icons" aria-hidden="true">{"edit"}</span>
+                                                    </button>
+                                                    if !is_active {
+                                                        <button onclick={on_switch} aria-label={t("profiles.switch")} class="settings-button font-bold py-1 px-2 rounded">
+                                                            <span class="material-icons" aria-hidden="true">{"login"}</span>
+                                                        </button>
+                                                        <button onclick={on_remove} aria-label={t("profiles.remove")} class="settings-button font-bold py-1 px-2 rounded">
+                                                            <span class="material-icons" aria-hidden="true">{"delete"}</span>
+                                                        </button>
+                                                    }
+                                                    </>
+                                                }
+                                            }
+                                        }
+                                        </div>
+                                    </td>
+                                </tr>
+                            }
+                        })
+                    }
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
@@ -11,6 +11,7 @@ use web_sys::window;
 use wasm_bindgen::JsCast;
 use crate::requests::login_requests::use_check_authentication;
 use crate::requests::setting_reqs::call_user_admin_check;
+use crate::components::i18n::t;
 // use crate::components::gen_funcs::check_auth;
 
 #[derive(Properties, PartialEq, Clone)]
@@ -22,7 +23,7 @@ pub struct TabProps {
 }
 
 #[function_component(Tab)]
-fn tab(props: &TabProps) -> Html {
+pub fn tab(props: &TabProps) -> Html {
     let TabProps {
         is_active,
         label,
@@ -203,14 +204,14 @@ pub fn settings() -> Html {
         <Search_nav />
         <UseScrollToTop />
         <div class="my-4">
-            <h1 class="item_container-text text-2xl font-bold mb-3">{ "Settings" }</h1>
+            <h1 class="item_container-text text-2xl font-bold mb-3">{ t("settings.title") }</h1>
             <div class="item_container-text tabs flex flex-wrap text-sm font-medium text-center border-b border-gray-200">
-                <Tab is_active={*active_tab == "user"} class="me-2" label={"User Settings".to_string()} onclick={on_user_tab_click.clone()} />
-                // <Tab is_active={*active_tab == "admin"} class="me-2" label={"Admin Settings".to_string()} onclick={on_admin_tab_click.clone()} />
+                <Tab is_active={*active_tab == "user"} class="me-2" label={t("settings.tab.user")} onclick={on_user_tab_click.clone()} />
+                // <Tab is_active={*active_tab == "admin"} class="me-2" label={t("settings.tab.admin")} onclick={on_admin_tab_click.clone()} />
                 {
                     if *is_admin {
                         html! {
-                            <Tab is_active={*active_tab == "admin"} class="me-2" label={"Admin Settings".to_string()} onclick={on_admin_tab_click.clone()} />
+                            <Tab is_active={*active_tab == "admin"} class="me-2" label={t("settings.tab.admin")} onclick={on_admin_tab_click.clone()} />
                         }
                     } else {
                         html! {}
@@ -222,23 +223,29 @@ pub fn settings() -> Html {
                 if *active_tab == "user" {
                     html! {
                     <div id="accordion-collapse" data-accordion="collapse" class="bg-custom-light">
-                        <AccordionItem title="Change Theme" content={html!{ <setting_components::theme_options::ThemeOptions /> }} position={AccordionItemPosition::First}/>
-                        <AccordionItem title="MFA Settings" content={html!{ <setting_components::mfa_settings::MFAOptions /> }} position={AccordionItemPosition::Middle}/>
-                        <AccordionItem title="Export/Backup Podcasts" content={html!{ <setting_components::export_settings::ExportOptions /> }} position={AccordionItemPosition::Middle}/>
-                        <AccordionItem title="Import Podcasts" content={html!{ <setting_components::import_options::ImportOptions /> }} position={AccordionItemPosition::Middle}/>
-                        <AccordionItem title="Connect Nextcloud Podcast Sync" content={html!{ <setting_components::nextcloud_options::NextcloudOptions /> }} position={AccordionItemPosition::Middle}/>
-                        <AccordionItem title="Api Keys" content={html!{ <setting_components::api_keys::APIKeys /> }} position={AccordionItemPosition::Middle}/>
+                        <AccordionItem title={t("settings.theme")} content={html!{ <setting_components::theme_options::ThemeOptions /> }} position={AccordionItemPosition::First}/>
+                        <AccordionItem title={t("settings.language")} content={html!{ <setting_components::language_options::LanguageOptions /> }} position={AccordionItemPosition::Middle}/>
+                        <AccordionItem title={t("settings.accessibility")} content={html!{ <setting_components::accessibility_options::AccessibilityOptions /> }} position={AccordionItemPosition::Middle}/>
+                        <AccordionItem title={t("settings.notifications")} content={html!{ <setting_components::notification_options::NotificationOptions /> }} position={AccordionItemPosition::Middle}/>
+                        <AccordionItem title={t("settings.search_providers")} content={html!{ <setting_components::search_provider_options::SearchProviderOptions /> }} position={AccordionItemPosition::Middle}/>
+                        <AccordionItem title={t("settings.mfa")} content={html!{ <setting_components::mfa_settings::MFAOptions /> }} position={AccordionItemPosition::Middle}/>
+                        <AccordionItem title={t("settings.export")} content={html!{ <setting_components::export_settings::ExportOptions /> }} position={AccordionItemPosition::Middle}/>
+                        <AccordionItem title={t("settings.import")} content={html!{ <setting_components::import_options::ImportOptions /> }} position={AccordionItemPosition::Middle}/>
+                        <AccordionItem title={t("settings.nextcloud")} content={html!{ <setting_components::nextcloud_options::NextcloudOptions /> }} position={AccordionItemPosition::Middle}/>
+                        <AccordionItem title={t("settings.api_keys")} content={html!{ <setting_components::api_keys::APIKeys /> }} position={AccordionItemPosition::Middle}/>
+                        <AccordionItem title={t("settings.profiles")} content={html!{ <setting_components::server_profiles::ServerProfiles /> }} position={AccordionItemPosition::Middle}/>
+                        <AccordionItem title={t("settings.local_data")} content={html!{ <setting_components::local_data::LocalDataOptions /> }} position={AccordionItemPosition::Middle}/>
                     </div>
                     }
                 } else if *active_tab == "admin" {
                     html! {
                     <div id="accordion-collapse" data-accordion="collapse" class="bg-custom-light">
-                        <AccordionItem title="User Management" content={html!{ <setting_components::user_settings::UserSettings /> }} position={AccordionItemPosition::First}/>
-                        <AccordionItem title="Guest Settings" content={html!{ <setting_components::guest_settings::GuestSettings /> }} position={AccordionItemPosition::Middle}/>
-                        <AccordionItem title="Download Settings" content={html!{ <setting_components::download_settings::DownloadSettings /> }} position={AccordionItemPosition::Middle}/>
-                        <AccordionItem title="User Self Service Settings" content={html!{ <setting_components::user_self_service::SelfServiceSettings /> }} position={AccordionItemPosition::Middle}/>
-                        <AccordionItem title="Email Settings" content={html!{ <setting_components::email_settings::EmailSettings /> }} position={AccordionItemPosition::Middle}/>
-                        <AccordionItem title="Backup Server" content={html!{ <setting_components::backup_server::BackupServer /> }} position={AccordionItemPosition::Middle}/>
+                        <AccordionItem title={t("settings.users")} content={html!{ <setting_components::user_settings::UserSettings /> }} position={AccordionItemPosition::First}/>
+                        <AccordionItem title={t("settings.guest")} content={html!{ <setting_components::guest_settings::GuestSettings /> }} position={AccordionItemPosition::Middle}/>
+                        <AccordionItem title={t("settings.downloads")} content={html!{ <setting_components::download_settings::DownloadSettings /> }} position={AccordionItemPosition::Middle}/>
+                        <AccordionItem title={t("settings.self_service")} content={html!{ <setting_components::user_self_service::SelfServiceSettings /> }} position={AccordionItemPosition::Middle}/>
+                        <AccordionItem title={t("settings.email")} content={html!{ <setting_components::email_settings::EmailSettings /> }} position={AccordionItemPosition::Middle}/>
+                        <AccordionItem title={t("settings.backup")} content={html!{ <setting_components::backup_server::BackupServer /> }} position={AccordionItemPosition::Middle}/>
                         // <AccordionItem title="Restore Server" content={html!{ <setting_components::restore_server::RestoreServer /> }} position={AccordionItemPosition::Middle}/>
                     </div>
                     }
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use chrono::{Datelike, Duration, NaiveDate};
+use yew::prelude::*;
+use crate::components::gen_funcs::{format_day, DateFormat};
+use crate::components::i18n::{t, t_args};
+
+// SVG charts for the stats page. Shapes take their colours from the theme through the
+// stats-chart classes in styles.css, so the charts follow theme changes.
+
+const HEAT_CELL: i64 = 12;
+const HEAT_GAP: i64 = 3;
+const HEAT_LABEL_WIDTH: i64 = 28;
+const BAR_HEIGHT: i64 = 22;
+const BAR_GAP: i64 = 8;
+const BAR_LABEL_WIDTH: i64 = 150;
+const BAR_WIDTH: i64 = 300;
+const COLUMN_WIDTH: i64 = 18;
+const COLUMN_GAP: i64 = 4;
+const COLUMN_HEIGHT: i64 = 120;
+const AXIS_HEIGHT: i64 = 18;
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ChartValue {
+    pub label: String,
+    pub value: f64,
+    // Shown on hover and read out by screen readers, e.g. "2 h 5 min"
+    pub caption: String,
+}
+
+pub fn truncate_label(label: &str, max_chars: usize) -> String {
+    if label.chars().count() <= max_chars {
+        label.to_string()
+    } else {
+        format!("{}…", label.chars().take(max_chars - 1).collect::<String>())
+    }
+}
+
+// Shade 0 is an empty day, 1 to 4 split the busiest day into quarters
+fn heat_level(minutes: i64, busiest: i64) -> i64 {
+    if minutes <= 0 || busiest <= 0 {
+        0
+    } else {
+        (minutes * 4 + busiest - 1) / busiest
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct CalendarHeatmapProps {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub minutes: Rc<HashMap<NaiveDate, i64>>,
+    pub date_format: DateFormat,
+    pub label: String,
+}
+
+// A GitHub style calendar, one column per week starting on Monday
+#[function_component(CalendarHeatmap)]
+pub fn calendar_heatmap(props: &CalendarHeatmapProps) -> Html {
+    let first_monday = props.start - Duration::days(props.start.weekday().num_days_from_monday() as i64);
+    let weeks = (props.end - first_monday).num_days() / 7 + 1;
+    let busiest = props.minutes.values().copied().max().unwrap_or(0);
+    let width = HEAT_LABEL_WIDTH + weeks * (HEAT_CELL + HEAT_GAP);
+    let height = AXIS_HEIGHT + 7 * (HEAT_CELL + HEAT_GAP);
+
+    let mut cells = Vec::new();
+    let mut month_labels = Vec::new();
+    let mut day = props.start;
+    while day <= props.end {
+        let week = (day - first_monday).num_days() / 7;
+        let weekday = day.weekday().num_days_from_monday() as i64;
+        let x = HEAT_LABEL_WIDTH + week * (HEAT_CELL + HEAT_GAP);
+        if day.day() == 1 || day == props.start {
+            month_labels.push(html! {
+                <text class="stats-chart-label" x={x.to_string()} y="12">{ t(&format!("stats.month_short.{}", day.month())) }</text>
+            });
+        }
+        let minutes = props.minutes.get(&day).copied().unwrap_or(0);
+        let caption = t_args("stats.heatmap_day", &[("date", format_day(&day, props.date_format).as_str()), ("minutes", minutes.to_string().as_str())]);
+        cells.push(html! {
+            <rect
+                class={format!("stats-heat-{}", heat_level(minutes, busiest))}
+                x={x.to_string()}
+                y={(AXIS_HEIGHT + weekday * (HEAT_CELL + HEAT_GAP)).to_string()}
+                width={HEAT_CELL.to_string()}
+                height={HEAT_CELL.to_string()}
+                rx="2"
+            >
+                <title>{ caption }</title>
+            </rect>
+        });
+        day += Duration::days(1);
+    }
+
+    // Only every other weekday is labelled to keep the column narrow
+    let weekday_labels = [(0, "mon"), (2, "wed"), (4, "fri")].iter().map(|(row, name)| html! {
+        <text class="stats-chart-label" x="0" y={(AXIS_HEIGHT + row * (HEAT_CELL + HEAT_GAP) + HEAT_CELL - 2).to_string()}>{ t(&format!("stats.weekday_short.{}", name)) }</text>
+    });
+
+    html! {
+        <div class="stats-chart-scroll">
+            <svg class="stats-chart" role="img" aria-label={props.label.clone()} width={width.to_string()} height={height.to_string()} viewBox={format!("0 0 {} {}", width, height)}>
+                { for month_labels }
+                { for weekday_labels }
+                { for cells }
+            </svg>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct BarChartProps {
+    pub values: Rc<Vec<ChartValue>>,
+    pub label: String,
+}
+
+// Horizontal bars, longest first, for rankings like the top podcasts
+#[function_component(BarChart)]
+pub fn bar_chart(props: &BarChartProps) -> Html {
+    let largest = props.values.iter().map(|value| value.value).fold(0.0, f64::max);
+    let width = BAR_LABEL_WIDTH + BAR_WIDTH + 90;
+    let height = props.values.len() as i64 * (BAR_HEIGHT + BAR_GAP);
+
+    html! {
+        <svg class="stats-chart w-full" role="img" aria-label={props.label.clone()} viewBox={format!("0 0 {} {}", width, height.max(1))} preserveAspectRatio="xMinYMin meet">
+            { for props.values.iter().enumerate().map(|(index, value)| {
+                let y = index as i64 * (BAR_HEIGHT + BAR_GAP);
+                let bar = if largest > 0.0 { (value.value / largest * BAR_WIDTH as f64).max(2.0) } else { 0.0 };
+                html! {
+                    <g>
+                        <title>{ format!("{}: {}", value.label, value.caption) }</title>
+                        <text class="stats-chart-label" x="0" y={(y + BAR_HEIGHT - 6).to_string()}>{ truncate_label(&value.label, 22) }</text>
+                        <rect class="stats-chart-bar" x={BAR_LABEL_WIDTH.to_string()} y={y.to_string()} width={format!("{:.1}", bar)} height={BAR_HEIGHT.to_string()} rx="3" />
+                        <text class="stats-chart-label" x={format!("{:.1}", BAR_LABEL_WIDTH as f64 + bar + 6.0)} y={(y + BAR_HEIGHT - 6).to_string()}>{ &value.caption }</text>
+                    </g>
+                }
+            }) }
+        </svg>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ColumnChartProps {
+    pub values: Rc<Vec<ChartValue>>,
+    pub label: String,
+    // Long series only label every few columns
+    #[prop_or(1)]
+    pub label_every: usize,
+}
+
+// Vertical columns for distributions and time series, in the order given
+#[function_component(ColumnChart)]
+pub fn column_chart(props: &ColumnChartProps) -> Html {
+    let largest = props.values.iter().map(|value| value.value).fold(0.0, f64::max);
+    let width = props.values.len() as i64 * (COLUMN_WIDTH + COLUMN_GAP);
+    let height = COLUMN_HEIGHT + AXIS_HEIGHT;
+    let label_every = props.label_every.max(1);
+
+    html! {
+        <div class="stats-chart-scroll">
+            <svg class="stats-chart" role="img" aria-label={props.label.clone()} width={width.max(1).to_string()} height={height.to_string()} viewBox={format!("0 0 {} {}", width.max(1), height)}>
+                { for props.values.iter().enumerate().map(|(index, value)| {
+                    let x = index as i64 * (COLUMN_WIDTH + COLUMN_GAP);
+                    let column = if largest > 0.0 && value.value > 0.0 { (value.value / largest * COLUMN_HEIGHT as f64).max(2.0) } else { 0.0 };
+                    html! {
+                        <g>
+                            <title>{ format!("{}: {}", value.label, value.caption) }</title>
+                            // Full height hit area so empty columns still show their tooltip
+                            <rect class="stats-chart-hit" x={x.to_string()} y="0" width={COLUMN_WIDTH.to_string()} height={COLUMN_HEIGHT.to_string()} />
+                            <rect class="stats-chart-bar" x={x.to_string()} y={format!("{:.1}", COLUMN_HEIGHT as f64 - column)} width={COLUMN_WIDTH.to_string()} height={format!("{:.1}", column)} rx="2" />
+                            if index % label_every == 0 {
+                                <text class="stats-chart-label" x={x.to_string()} y={(COLUMN_HEIGHT + AXIS_HEIGHT - 4).to_string()}>{ &value.label }</text>
+                            }
+                        </g>
+                    }
+                }) }
+            </svg>
+        </div>
+    }
+}
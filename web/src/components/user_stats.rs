@@ -6,7 +6,18 @@ use crate::requests::stat_reqs;
 use yewdux::prelude::*;
 use crate::components::context::{AppState, UIState, UserStatsStore};
 use crate::components::audio::AudioPlayer;
-use crate::components::gen_funcs::{format_date, format_time_mins};
+use crate::components::gen_funcs::{format_date, format_duration, match_date_format, download_text, listen_state, listened_at, ListenState, DateFormat};
+use crate::components::i18n::{t, t_args, t_plural};
+use crate::components::goals::{goals_available, GoalsPanel};
+use crate::components::routes::Route;
+use yew_router::prelude::Link;
+use crate::components::stats_charts::{BarChart, CalendarHeatmap, ChartValue, ColumnChart};
+use crate::requests::stat_reqs::ListeningActivityResponse;
+use std::collections::HashMap;
+use std::rc::Rc;
+use chrono::{Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use web_sys::HtmlSelectElement;
 // use crate::requests::login_requests::use_check_authentication;
 
 
@@ -51,52 +62,58 @@ pub fn user_stats() -> Html {
         <>
         <div class="main-container">
             <Search_nav />
-            <h1 class="text-2xl item_container-text font-bold text-center mb-6">{"User Statistics"}</h1>
+            <h1 class="text-2xl item_container-text font-bold text-center mb-6">{ t("stats.summary_title") }</h1>
+            <div class="flex justify-center mb-6">
+                <Link<Route> to={Route::YearReview} classes="download-button font-bold py-2 px-4 rounded inline-flex items-center">
+                    <span class="material-icons icon-space">{"auto_awesome"}</span>
+                    <span>{ t("year_review.open") }</span>
+                </Link<Route>>
+            </div>
             <div class="item-container mx-auto p-6 shadow-md rounded">
                 <div class="grid grid-cols-1 md:grid-cols-3 gap-4">
 
                         {
                             if let Some(stats) = user_stats {
                                 let formatted_date = format_date(&stats.UserCreated);
-                                let time_formatted = format_time_mins(stats.TimeListened);
+                                let time_formatted = format_duration(stats.TimeListened as i64 * 60);
                                 html! {
                                     <>
                                         <div class="stats-card">
-                                            <p class="stats-label">{"User Created"}</p>
+                                            <p class="stats-label">{ t("stats.user_created") }</p>
                                             <p class="stats-value">{&formatted_date}</p>
                                         </div>
 
                                         <div class="stats-card">
-                                            <p class="stats-label">{"Podcasts Played"}</p>
+                                            <p class="stats-label">{ t("stats.podcasts_played") }</p>
                                             <p class="stats-value">{ &stats.PodcastsPlayed }</p>
                                         </div>
 
                                         <div class="stats-card">
-                                            <p class="stats-label">{"Time Listened"}</p>
+                                            <p class="stats-label">{ t("stats.total_time") }</p>
                                             <p class="stats-value">{ &time_formatted }</p>
                                         </div>
 
                                         <div class="stats-card">
-                                            <p class="stats-label">{"Podcasts Added"}</p>
+                                            <p class="stats-label">{ t("stats.podcasts_added") }</p>
                                             <p class="stats-value">{ &stats.PodcastsAdded }</p>
                                         </div>
 
                                         <div class="stats-card">
-                                            <p class="stats-label">{"Episodes Saved"}</p>
+                                            <p class="stats-label">{ t("stats.episodes_saved") }</p>
                                             <p class="stats-value">{ &stats.EpisodesSaved }</p>
                                         </div>
 
                                         <div class="stats-card">
-                                            <p class="stats-label">{"Episodes Downloaded"}</p>
+                                            <p class="stats-label">{ t("stats.episodes_downloaded") }</p>
                                             <p class="stats-value">{ &stats.EpisodesDownloaded }</p>
                                         </div>
                                         <div class="large-card col-span-1 md:col-span-3">
-                                            <img src="static/assets/favicon.png" alt="Pinepods Logo" class="large-card-image"/>
-                                            <p class="large-card-paragraph item_container-text">{"Thanks for using Pinepods! This app was born from a love for podcasts, of homelabs, and a desire to have a secure and central location to manage personal data. Feel free to reach out for questions and open an issue if you have ideas for new features. Pull Requests on this software are welcome and encouraged. If you feel that you've gotten use out of this software and are thankful for it's existence donations to my Buymeacoffee are welcome but never required. Lastly, this app will ALWAYS remain open source."}</p>
+                                            <img src="static/assets/favicon.png" alt={t("a11y.pinepods_logo")} class="large-card-image"/>
+                                            <p class="large-card-paragraph item_container-text">{ t("stats.thanks") }</p>
                                             <div class="large-card-content flex flex-col space-y-2">
-                                                <a href="https://pinepods.online" target="_blank" class="large-card-button focus:ring-4 font-medium rounded-lg text-sm px-5 py-2.5 focus:outline-none">{"Pinepods Documentation"}</a>
-                                                <a href="https://github.com/madeofpendletonwool/pinepods" target="_blank" class="large-card-button focus:ring-4 font-medium rounded-lg text-sm px-5 py-2.5 focus:outline-none">{"Pinepods Github Repo"}</a>
-                                                <a href="https://www.buymeacoffee.com/collinscoffee" target="_blank" class="large-card-button focus:ring-4 font-medium rounded-lg text-sm px-5 py-2.5 focus:outline-none">{"Buy me a Coffee"}</a>
+                                                <a href="https://pinepods.online" target="_blank" class="large-card-button focus:ring-4 font-medium rounded-lg text-sm px-5 py-2.5 focus:outline-none">{ t("stats.docs_link") }</a>
+                                                <a href="https://github.com/madeofpendletonwool/pinepods" target="_blank" class="large-card-button focus:ring-4 font-medium rounded-lg text-sm px-5 py-2.5 focus:outline-none">{ t("stats.github_link") }</a>
+                                                <a href="https://www.buymeacoffee.com/collinscoffee" target="_blank" class="large-card-button focus:ring-4 font-medium rounded-lg text-sm px-5 py-2.5 focus:outline-none">{ t("stats.coffee_link") }</a>
 
                                                 // Additional content...
                                             </div>
@@ -105,12 +122,19 @@ pub fn user_stats() -> Html {
                                     </>
                                 }
                             } else {
-                                html! { <p class="item_container-text">{"Loading user stats..."}</p> } // or handle the `None` case appropriately
+                                html! { <p class="item_container-text">{ t("stats.loading_user") }</p> } // or handle the `None` case appropriately
                             }
                         }
                     // </div>
                 </div>
             </div>
+            if goals_available(&post_state) {
+                <div class="item-container mx-auto p-6 shadow-md rounded mt-6">
+                    <h2 class="text-xl item_container-text font-bold mb-4">{ t("goals.title") }</h2>
+                    <GoalsPanel />
+                </div>
+            }
+            <ListeningDashboard />
         {
             if let Some(audio_props) = &audio_state.currently_playing {
                 html! { <AudioPlayer src={audio_props.src.clone()} title={audio_props.title.clone()} artwork_url={audio_props.artwork_url.clone()} duration={audio_props.duration.clone()} episode_id={audio_props.episode_id.clone()} duration_sec={audio_props.duration_sec.clone()} start_pos_sec={audio_props.start_pos_sec.clone()} /> }
@@ -123,3 +147,418 @@ pub fn user_stats() -> Html {
         </>
     }
 }
+
+// Ranges count back from today, in the user's timezone
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum StatsRange {
+    Week,
+    Month,
+    Quarter,
+    Year,
+    All,
+}
+
+impl StatsRange {
+    const ALL: [StatsRange; 5] = [StatsRange::Week, StatsRange::Month, StatsRange::Quarter, StatsRange::Year, StatsRange::All];
+
+    fn key(&self) -> &'static str {
+        match self {
+            StatsRange::Week => "week",
+            StatsRange::Month => "month",
+            StatsRange::Quarter => "quarter",
+            StatsRange::Year => "year",
+            StatsRange::All => "all",
+        }
+    }
+
+    fn days(&self) -> Option<i64> {
+        match self {
+            StatsRange::Week => Some(7),
+            StatsRange::Month => Some(30),
+            StatsRange::Quarter => Some(90),
+            StatsRange::Year => Some(365),
+            StatsRange::All => None,
+        }
+    }
+
+    fn from_key(key: &str) -> Self {
+        Self::ALL.into_iter().find(|range| range.key() == key).unwrap_or(StatsRange::Month)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Granularity {
+    Day,
+    Week,
+    Month,
+}
+
+impl Granularity {
+    const ALL: [Granularity; 3] = [Granularity::Day, Granularity::Week, Granularity::Month];
+
+    fn key(&self) -> &'static str {
+        match self {
+            Granularity::Day => "day",
+            Granularity::Week => "week",
+            Granularity::Month => "month",
+        }
+    }
+
+    fn from_key(key: &str) -> Self {
+        Self::ALL.into_iter().find(|granularity| granularity.key() == key).unwrap_or(Granularity::Day)
+    }
+
+    // First day of the bucket the date falls in. Weeks start on Monday.
+    fn bucket(&self, day: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Day => day,
+            Granularity::Week => day - Duration::days(day.weekday().num_days_from_monday() as i64),
+            Granularity::Month => day.with_day(1).unwrap_or(day),
+        }
+    }
+
+    fn next(&self, bucket: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Day => bucket + Duration::days(1),
+            Granularity::Week => bucket + Duration::days(7),
+            Granularity::Month => bucket.checked_add_months(chrono::Months::new(1)).unwrap_or(bucket + Duration::days(31)),
+        }
+    }
+
+    fn label(&self, bucket: NaiveDate) -> String {
+        match self {
+            Granularity::Day | Granularity::Week => bucket.format("%m/%d").to_string(),
+            Granularity::Month => format!("{} {}", t(&format!("stats.month_short.{}", bucket.month())), bucket.format("%y")),
+        }
+    }
+}
+
+// Totals for the selected range. Time per day comes from the server's daily minute counts.
+// History keeps one entry per episode, so the podcast, category and hour breakdowns count an
+// episode's listening toward when it was last played.
+#[derive(Default)]
+struct ActivitySummary {
+    seconds_per_day: HashMap<NaiveDate, i64>,
+    total_seconds: i64,
+    episodes: usize,
+    completed: usize,
+    podcasts: Vec<(String, i64)>,
+    categories: Vec<(String, i64)>,
+    seconds_by_hour: [i64; 24],
+    first_day: Option<NaiveDate>,
+}
+
+fn ranked(totals: HashMap<String, i64>) -> Vec<(String, i64)> {
+    let mut ranked: Vec<(String, i64)> = totals.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked
+}
+
+fn summarize(activity: &ListeningActivityResponse, tz: Tz) -> ActivitySummary {
+    let mut summary = ActivitySummary::default();
+    let mut podcasts: HashMap<String, i64> = HashMap::new();
+    let mut categories: HashMap<String, i64> = HashMap::new();
+    for entry in &activity.activity {
+        let Some(listened) = listened_at(&entry.ListenDate, tz) else {
+            continue;
+        };
+        let seconds = entry.ListenDuration.max(0) as i64;
+        let day = listened.date_naive();
+        *summary.seconds_per_day.entry(day).or_insert(0) += seconds;
+        summary.seconds_by_hour[listened.hour() as usize] += seconds;
+        summary.total_seconds += seconds;
+        summary.episodes += 1;
+        if listen_state(Some(entry.ListenDuration), entry.EpisodeDuration) == ListenState::Played {
+            summary.completed += 1;
+        }
+        summary.first_day = Some(summary.first_day.map_or(day, |first| first.min(day)));
+        *podcasts.entry(entry.PodcastName.clone()).or_insert(0) += seconds;
+        // A podcast in several categories counts toward each of them
+        let podcast_categories: HashMap<String, String> = entry.Categories.as_deref().and_then(|categories| serde_json::from_str(categories).ok()).unwrap_or_default();
+        for category in podcast_categories.into_values().filter(|category| !category.trim().is_empty()) {
+            *categories.entry(category).or_insert(0) += seconds;
+        }
+    }
+    // Servers without daily counts, and the guest account, keep the per-episode estimate
+    let days: Vec<(NaiveDate, i64)> = activity
+        .days
+        .iter()
+        .filter_map(|day| Some((NaiveDate::parse_from_str(&day.ListenDay, "%Y-%m-%d").ok()?, day.Minutes * 60)))
+        .collect();
+    if !days.is_empty() {
+        summary.total_seconds = days.iter().map(|(_, seconds)| seconds).sum();
+        summary.first_day = days.iter().map(|(day, _)| *day).min();
+        summary.seconds_per_day = days.into_iter().collect();
+    }
+    summary.podcasts = ranked(podcasts);
+    summary.categories = ranked(categories);
+    summary
+}
+
+fn completion_rate(summary: &ActivitySummary) -> i64 {
+    if summary.episodes == 0 {
+        0
+    } else {
+        (summary.completed as f64 / summary.episodes as f64 * 100.0).round() as i64
+    }
+}
+
+fn minutes_value(label: String, seconds: i64) -> ChartValue {
+    ChartValue {
+        label,
+        value: seconds as f64 / 60.0,
+        caption: format_duration(seconds),
+    }
+}
+
+fn series(summary: &ActivitySummary, granularity: Granularity, start: NaiveDate, end: NaiveDate) -> Vec<(NaiveDate, i64)> {
+    let mut buckets: Vec<(NaiveDate, i64)> = Vec::new();
+    let mut bucket = granularity.bucket(start);
+    while bucket <= end {
+        buckets.push((bucket, 0));
+        bucket = granularity.next(bucket);
+    }
+    for (day, seconds) in &summary.seconds_per_day {
+        let bucket = granularity.bucket(*day);
+        if let Some(entry) = buckets.iter_mut().find(|(start, _)| *start == bucket) {
+            entry.1 += seconds;
+        }
+    }
+    buckets
+}
+
+// Local midnight at the start of the range, as the UTC timestamp the server filters on
+fn range_start_utc(tz: Tz, first_day: NaiveDate) -> Option<String> {
+    let start = tz.from_local_datetime(&first_day.and_hms_opt(0, 0, 0)?).earliest()?;
+    Some(start.with_timezone(&Utc).format("%Y-%m-%dT%H:%M:%S").to_string())
+}
+
+fn stats_csv(per_day: &[(NaiveDate, i64)]) -> String {
+    let mut csv = String::from("date,minutes\n");
+    for (day, seconds) in per_day {
+        csv.push_str(&format!("{},{}\n", day.format("%Y-%m-%d"), seconds / 60));
+    }
+    csv
+}
+
+fn stats_json(range: StatsRange, summary: &ActivitySummary, per_day: &[(NaiveDate, i64)]) -> String {
+    let minutes = |seconds: &i64| seconds / 60;
+    let ranking = |entries: &[(String, i64)]| -> Vec<serde_json::Value> {
+        entries.iter().map(|(name, seconds)| serde_json::json!({ "name": name, "minutes": minutes(seconds) })).collect()
+    };
+    let export = serde_json::json!({
+        "range": range.key(),
+        "total_minutes": minutes(&summary.total_seconds),
+        "episodes": summary.episodes,
+        "completed_episodes": summary.completed,
+        "completion_rate": completion_rate(summary),
+        "days": per_day.iter().map(|(day, seconds)| serde_json::json!({ "date": day.format("%Y-%m-%d").to_string(), "minutes": minutes(seconds) })).collect::<Vec<_>>(),
+        "podcasts": ranking(&summary.podcasts),
+        "categories": ranking(&summary.categories),
+        "minutes_by_hour": summary.seconds_by_hour.iter().map(minutes).collect::<Vec<_>>(),
+    });
+    serde_json::to_string_pretty(&export).unwrap_or_default()
+}
+
+const TOP_COUNT: usize = 10;
+// The heatmap never covers more than a year, even for all time
+const HEATMAP_DAYS: i64 = 365;
+
+#[function_component(ListeningDashboard)]
+fn listening_dashboard() -> Html {
+    let (state, _dispatch) = use_store::<AppState>();
+    let (_ui_state, ui_dispatch) = use_store::<UIState>();
+    let range = use_state(|| StatsRange::Month);
+    let granularity = use_state(|| Granularity::Day);
+    let activity = use_state(|| None::<Rc<ListeningActivityResponse>>);
+
+    let tz: Tz = state.user_tz.as_ref().and_then(|tz| tz.parse().ok()).unwrap_or(chrono_tz::UTC);
+    let today = Utc::now().with_timezone(&tz).date_naive();
+    let range_first_day = range.days().map(|days| today - Duration::days(days - 1));
+
+    {
+        let activity = activity.clone();
+        let ui_dispatch = ui_dispatch.clone();
+        let api_key = state.auth_details.as_ref().and_then(|ud| ud.api_key.clone());
+        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+        let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+        let start = range_first_day.and_then(|day| range_start_utc(tz, day));
+        use_effect_with((server_name, user_id, start), move |(server_name, user_id, start)| {
+            if let (Some(server_name), Some(user_id)) = (server_name.clone(), *user_id) {
+                let start = start.clone();
+                activity.set(None);
+                wasm_bindgen_futures::spawn_local(async move {
+                    match stat_reqs::call_get_listening_activity(&server_name, &api_key, user_id, start.as_deref(), None).await {
+                        Ok(fetched) => activity.set(Some(Rc::new(fetched))),
+                        Err(e) => {
+                            activity.set(Some(Rc::new(ListeningActivityResponse::default())));
+                            ui_dispatch.reduce_mut(|state| state.error_message = Some(t_args("stats.load_failed", &[("error", e.to_string().as_str())])));
+                        }
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_range_change = {
+        let range = range.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                range.set(StatsRange::from_key(&select.value()));
+            }
+        })
+    };
+    let on_granularity_change = {
+        let granularity = granularity.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                granularity.set(Granularity::from_key(&select.value()));
+            }
+        })
+    };
+
+    let date_format: DateFormat = match_date_format(state.date_format.as_deref());
+    let summary = activity.as_ref().map(|activity| summarize(activity, tz));
+    let chart_start = summary.as_ref().map(|summary| range_first_day.or(summary.first_day).unwrap_or(today).min(today)).unwrap_or(today);
+    let per_day: Vec<(NaiveDate, i64)> = summary.as_ref().map(|summary| series(summary, Granularity::Day, chart_start, today)).unwrap_or_default();
+
+    let on_export = |json: bool| {
+        let per_day = per_day.clone();
+        let range = *range;
+        let activity = activity.clone();
+        let ui_dispatch = ui_dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(activity) = activity.as_ref() else {
+                return;
+            };
+            let filename = format!("pinepods-stats-{}.{}", range.key(), if json { "json" } else { "csv" });
+            let result = if json {
+                download_text(&filename, "application/json", &stats_json(range, &summarize(activity, tz), &per_day))
+            } else {
+                download_text(&filename, "text/csv", &stats_csv(&per_day))
+            };
+            if result.is_err() {
+                ui_dispatch.reduce_mut(|state| state.error_message = Some(t("stats.export_failed")));
+            }
+        })
+    };
+
+    let controls = html! {
+        <div class="flex flex-wrap items-end gap-4 mb-6">
+            <label class="flex flex-col item_container-text">
+                <span class="mb-1">{ t("stats.range") }</span>
+                <select class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline" onchange={on_range_change}>
+                    { for StatsRange::ALL.iter().map(|option| html! {
+                        <option value={option.key()} selected={*option == *range}>{ t(&format!("stats.range.{}", option.key())) }</option>
+                    }) }
+                </select>
+            </label>
+            <div class="flex gap-2 ml-auto">
+                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_export(false)} disabled={summary.is_none()}>
+                    <span class="material-icons icon-space">{"download"}</span>
+                    <span>{ t("stats.export_csv") }</span>
+                </button>
+                <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_export(true)} disabled={summary.is_none()}>
+                    <span class="material-icons icon-space">{"data_object"}</span>
+                    <span>{ t("stats.export_json") }</span>
+                </button>
+            </div>
+        </div>
+    };
+
+    let Some(summary) = summary else {
+        return html! {
+            <div class="item-container mx-auto p-6 shadow-md rounded mt-6">
+                <h2 class="text-xl item_container-text font-bold mb-4">{ t("stats.listening") }</h2>
+                { controls }
+                <p class="item_container-text">{ t("stats.loading") }</p>
+            </div>
+        };
+    };
+
+    let body = if summary.episodes == 0 {
+        html! { <p class="item_container-text">{ t("stats.no_activity") }</p> }
+    } else {
+        let days_in_range = (today - chart_start).num_days() + 1;
+        let heatmap_start = chart_start.max(today - Duration::days(HEATMAP_DAYS - 1));
+        let heatmap_minutes: HashMap<NaiveDate, i64> = summary.seconds_per_day.iter().map(|(day, seconds)| (*day, seconds / 60)).collect();
+        let over_time: Vec<ChartValue> = series(&summary, *granularity, chart_start, today)
+            .into_iter()
+            .map(|(bucket, seconds)| minutes_value(granularity.label(bucket), seconds))
+            .collect();
+        let label_every = (over_time.len() / 12).max(if *granularity == Granularity::Month { 1 } else { 3 });
+        let top_podcasts: Vec<ChartValue> = summary.podcasts.iter().take(TOP_COUNT).map(|(name, seconds)| minutes_value(name.clone(), *seconds)).collect();
+        let top_categories: Vec<ChartValue> = summary.categories.iter().take(TOP_COUNT).map(|(name, seconds)| minutes_value(name.clone(), *seconds)).collect();
+        let by_hour: Vec<ChartValue> = summary.seconds_by_hour.iter().enumerate().map(|(hour, seconds)| minutes_value(format!("{:02}", hour), *seconds)).collect();
+        let daily_average = summary.total_seconds / days_in_range.max(1);
+
+        html! {
+            <>
+                <div class="grid grid-cols-1 md:grid-cols-4 gap-4 mb-6">
+                    <div class="stats-card">
+                        <p class="stats-label">{ t("stats.total_time") }</p>
+                        <p class="stats-value">{ format_duration(summary.total_seconds) }</p>
+                    </div>
+                    <div class="stats-card">
+                        <p class="stats-label">{ t("stats.daily_average") }</p>
+                        <p class="stats-value">{ format_duration(daily_average) }</p>
+                    </div>
+                    <div class="stats-card">
+                        <p class="stats-label">{ t("stats.episodes") }</p>
+                        <p class="stats-value">{ t_plural("stats.episode_count", summary.episodes as i64) }</p>
+                    </div>
+                    <div class="stats-card">
+                        <p class="stats-label">{ t("stats.completion_rate") }</p>
+                        <p class="stats-value">{ format!("{}%", completion_rate(&summary)) }</p>
+                    </div>
+                </div>
+
+                <section class="mb-6">
+                    <h3 class="item_container-text font-bold mb-2">{ t("stats.calendar") }</h3>
+                    <CalendarHeatmap start={heatmap_start} end={today} minutes={Rc::new(heatmap_minutes)} date_format={date_format} label={t("stats.calendar")} />
+                </section>
+
+                <section class="mb-6">
+                    <div class="flex flex-wrap items-center gap-4 mb-2">
+                        <h3 class="item_container-text font-bold">{ t("stats.over_time") }</h3>
+                        <select class="theme-select-dropdown border px-2 py-1 rounded shadow leading-tight focus:outline-none focus:shadow-outline" aria-label={t("stats.group_by")} onchange={on_granularity_change}>
+                            { for Granularity::ALL.iter().map(|option| html! {
+                                <option value={option.key()} selected={*option == *granularity}>{ t(&format!("stats.group_by.{}", option.key())) }</option>
+                            }) }
+                        </select>
+                    </div>
+                    <ColumnChart values={Rc::new(over_time)} label={t("stats.over_time")} label_every={label_every} />
+                </section>
+
+                <div class="grid grid-cols-1 lg:grid-cols-2 gap-6 mb-6">
+                    <section>
+                        <h3 class="item_container-text font-bold mb-2">{ t("stats.top_podcasts") }</h3>
+                        <BarChart values={Rc::new(top_podcasts)} label={t("stats.top_podcasts")} />
+                    </section>
+                    <section>
+                        <h3 class="item_container-text font-bold mb-2">{ t("stats.top_categories") }</h3>
+                        if top_categories.is_empty() {
+                            <p class="item_container-text text-sm">{ t("stats.no_categories") }</p>
+                        } else {
+                            <BarChart values={Rc::new(top_categories)} label={t("stats.top_categories")} />
+                        }
+                    </section>
+                </div>
+
+                <section>
+                    <h3 class="item_container-text font-bold mb-2">{ t("stats.by_hour") }</h3>
+                    <ColumnChart values={Rc::new(by_hour)} label={t("stats.by_hour")} label_every={3} />
+                </section>
+            </>
+        }
+    };
+
+    html! {
+        <div class="item-container mx-auto p-6 shadow-md rounded mt-6">
+            <h2 class="text-xl item_container-text font-bold mb-4">{ t("stats.listening") }</h2>
+            { controls }
+            { body }
+        </div>
+    }
+}
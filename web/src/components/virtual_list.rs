@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use gloo_events::EventListener;
+use wasm_bindgen::JsCast;
+use yew::prelude::*;
+use yew::virtual_dom::Key;
+
+// Rows outside this many pixels above or below the viewport are not rendered
+const OVERSCAN_PX: f64 = 800.0;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct VirtualListProps {
+    pub item_count: usize,
+    // Builds the row at an index. Only called for rows near the viewport.
+    pub render_item: Callback<usize, Html>,
+    // One per row, usually the episode id, so rows keep their DOM and state when the list changes around them
+    pub item_keys: Vec<Key>,
+    // Used for rows that have not been on screen yet
+    #[prop_or(180.0)]
+    pub estimated_height: f64,
+    // Fired when the last rows come into view, so the page can load its next page
+    #[prop_or_default]
+    pub on_end_reached: Option<Callback<()>>,
+    #[prop_or_default]
+    pub loading_more: bool,
+}
+
+fn row_key(keys: &[Key], index: usize) -> Key {
+    keys.get(index).cloned().unwrap_or_else(|| Key::from(index))
+}
+
+// Measured heights follow the row's key, so they stay with the episode when the list is
+// filtered, reordered or grows a page
+#[derive(Default)]
+struct Layout {
+    heights: HashMap<Key, f64>,
+}
+
+impl Layout {
+    fn height(&self, key: &Key, estimated: f64) -> f64 {
+        self.heights.get(key).copied().unwrap_or(estimated)
+    }
+
+    // First and one-past-last index to render, plus the space taken by the rows skipped before and after
+    fn window(&self, keys: &[Key], count: usize, estimated: f64, view_top: f64, view_bottom: f64) -> (usize, usize, f64, f64) {
+        let mut offset = 0.0;
+        let mut start = count;
+        let mut end = count;
+        let mut before = 0.0;
+        for index in 0..count {
+            let height = self.height(&row_key(keys, index), estimated);
+            if start == count && offset + height >= view_top - OVERSCAN_PX {
+                start = index;
+                before = offset;
+            }
+            if offset > view_bottom + OVERSCAN_PX {
+                end = index;
+                break;
+            }
+            offset += height;
+        }
+        let start = start.min(end);
+        let after: f64 = (end..count).map(|index| self.height(&row_key(keys, index), estimated)).sum();
+        (start, end, before, after)
+    }
+}
+
+// Renders only the rows near the viewport of a page that scrolls as a whole. Row heights are
+// measured once they render, so lists of episodes with expanded descriptions stay accurate.
+#[function_component(VirtualList)]
+pub fn virtual_list(props: &VirtualListProps) -> Html {
+    let container_ref = use_node_ref();
+    let layout = use_mut_ref(Layout::default);
+    // Set on scroll, resize and height changes so the window is recomputed
+    let viewport = use_state(|| (0.0_f64, 0.0_f64));
+    // The listeners outlive any one render, so they compare against this rather than a stale handle
+    let last_viewport = use_mut_ref(|| (0.0_f64, 0.0_f64));
+
+    let measure_viewport = {
+        let container_ref = container_ref.clone();
+        let viewport = viewport.clone();
+        let last_viewport = last_viewport.clone();
+        Rc::new(move || {
+            let (Some(window), Some(container)) = (web_sys::window(), container_ref.cast::<web_sys::Element>()) else {
+                return;
+            };
+            let top = -container.get_bounding_client_rect().top();
+            let height = window.inner_height().ok().and_then(|h| h.as_f64()).unwrap_or(0.0);
+            let updated = (top, top + height);
+            // Small scrolls inside the overscan margin don't change which rows render
+            let previous = *last_viewport.borrow();
+            if (updated.0 - previous.0).abs() > OVERSCAN_PX / 4.0 || (updated.1 - updated.0) != (previous.1 - previous.0) {
+                *last_viewport.borrow_mut() = updated;
+                viewport.set(updated);
+            }
+        })
+    };
+
+    {
+        let measure_viewport = measure_viewport.clone();
+        use_effect_with((), move |_| {
+            let listeners = web_sys::window().map(|window| {
+                let on_scroll = {
+                    let measure_viewport = measure_viewport.clone();
+                    EventListener::new(&window, "scroll", move |_| measure_viewport())
+                };
+                let on_resize = {
+                    let measure_viewport = measure_viewport.clone();
+                    EventListener::new(&window, "resize", move |_| measure_viewport())
+                };
+                (on_scroll, on_resize)
+            });
+            measure_viewport();
+            move || drop(listeners)
+        });
+    }
+
+    let (view_top, view_bottom) = *viewport;
+    let (start, end, before, after) = layout.borrow().window(&props.item_keys, props.item_count, props.estimated_height, view_top.max(0.0), view_bottom);
+
+    // Record the real height of every rendered row and re-layout when one differs from what was assumed
+    {
+        let container_ref = container_ref.clone();
+        let layout = layout.clone();
+        let viewport = viewport.clone();
+        let last_viewport = last_viewport.clone();
+        let estimated_height = props.estimated_height;
+        use_effect(move || {
+            if let Some(container) = container_ref.cast::<web_sys::Element>() {
+                if let Ok(rows) = container.query_selector_all("[data-virtual-key]") {
+                    let mut changed = false;
+                    for i in 0..rows.length() {
+                        let Some(row) = rows.item(i).and_then(|row| row.dyn_into::<web_sys::Element>().ok()) else {
+                            continue;
+                        };
+                        let Some(key) = row.get_attribute("data-virtual-key").map(Key::from) else {
+                            continue;
+                        };
+                        let height = row.get_bounding_client_rect().height();
+                        let mut layout = layout.borrow_mut();
+                        if (layout.height(&key, estimated_height) - height).abs() > 1.0 {
+                            layout.heights.insert(key, height);
+                            changed = true;
+                        }
+                    }
+                    if changed {
+                        viewport.set(*last_viewport.borrow());
+                    }
+                }
+            }
+            || ()
+        });
+    }
+
+    // Ask for more once the rendered window reaches the end of what is loaded
+    {
+        let on_end_reached = props.on_end_reached.clone();
+        let reached_end = props.item_count > 0 && end >= props.item_count;
+        let loading_more = props.loading_more;
+        use_effect_with((reached_end, loading_more, props.item_count), move |(reached_end, loading_more, _)| {
+            if *reached_end && !*loading_more {
+                if let Some(on_end_reached) = on_end_reached {
+                    on_end_reached.emit(());
+                }
+            }
+            || ()
+        });
+    }
+
+    html! {
+        <div ref={container_ref} role="list">
+            <div style={format!("height: {}px;", before)} aria-hidden="true"></div>
+            // Most rows aren't in the DOM, so each one says where it sits in the whole list
+            { for (start..end).map(|index| (index, row_key(&props.item_keys, index))).map(|(index, key)| html! {
+                <div key={key.clone()} data-virtual-key={key.to_string()} role="listitem" aria-setsize={props.item_count.to_string()} aria-posinset={(index + 1).to_string()}>
+                    { props.render_item.emit(index) }
+                </div>
+            }) }
+            <div style={format!("height: {}px;", after)} aria-hidden="true"></div>
+            if props.loading_more {
+                <div class="loading-animation" aria-hidden="true">
+                    <div class="frame1"></div>
+                    <div class="frame2"></div>
+                    <div class="frame3"></div>
+                    <div class="frame4"></div>
+                    <div class="frame5"></div>
+                    <div class="frame6"></div>
+                </div>
+            }
+        </div>
+    }
+}
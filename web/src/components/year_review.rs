@@ -0,0 +1,387 @@
+use std::rc::Rc;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{window, CanvasRenderingContext2d, HtmlCanvasElement, HtmlSelectElement};
+use yew::prelude::*;
+use yewdux::prelude::*;
+use super::app_drawer::App_drawer;
+use super::gen_components::Search_nav;
+use crate::components::audio::AudioPlayer;
+use crate::components::context::{AppState, UIState};
+use crate::components::gen_funcs::{download_href, download_text, format_day, format_duration, match_date_format, DateFormat};
+use crate::components::i18n::{t, t_args, t_plural};
+use crate::components::stats_charts::{truncate_label, BarChart, ChartValue, ColumnChart};
+use crate::requests::stat_reqs::{call_get_year_review, YearReview};
+
+// A yearly summary of listening, with a shareable image and a printable copy.
+// Both exports are built in the browser from the same data the page shows.
+
+// How many past years can be picked besides the current one
+const PAST_YEARS: i32 = 4;
+const SHARE_WIDTH: u32 = 1080;
+const SHARE_HEIGHT: u32 = 1350;
+const SHARE_MARGIN: f64 = 80.0;
+
+fn month_label(month: usize) -> String {
+    t(&format!("stats.month_short.{}", month + 1))
+}
+
+fn hours_text(seconds: i64) -> String {
+    t_plural("year_review.hours", (seconds + 1800) / 3600)
+}
+
+// Subscription dates come back in UTC and are shown as the user's own day
+fn local_day(timestamp: &str, tz: Tz) -> Option<NaiveDate> {
+    let parsed = NaiveDateTime::parse_from_str(timestamp.get(..19)?, "%Y-%m-%dT%H:%M:%S").ok()?;
+    Some(Utc.from_utc_datetime(&parsed).with_timezone(&tz).date_naive())
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Colours for the image come from the active theme
+fn theme_color(name: &str, fallback: &str) -> String {
+    window()
+        .and_then(|window| {
+            let root = window.document()?.document_element()?;
+            window.get_computed_style(&root).ok().flatten()
+        })
+        .and_then(|style| style.get_property_value(name).ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+fn render_share_image(review: &YearReview) -> Result<String, JsValue> {
+    let document = window().and_then(|window| window.document()).ok_or_else(|| JsValue::from_str("No document to draw in"))?;
+    let canvas = document.create_element("canvas")?.dyn_into::<HtmlCanvasElement>()?;
+    canvas.set_width(SHARE_WIDTH);
+    canvas.set_height(SHARE_HEIGHT);
+    let context = canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("Canvas has no 2d context"))?
+        .dyn_into::<CanvasRenderingContext2d>()?;
+
+    let background = theme_color("--background-color", "#242424");
+    let text = theme_color("--text-color", "#F5F5F5");
+    let accent = theme_color("--standout-color", "#304BFF");
+    let width = SHARE_WIDTH as f64;
+
+    context.set_fill_style_str(&background);
+    context.fill_rect(0.0, 0.0, width, SHARE_HEIGHT as f64);
+    context.set_fill_style_str(&text);
+    context.set_text_baseline("alphabetic");
+
+    context.set_font("bold 64px sans-serif");
+    context.fill_text(&t_args("year_review.title", &[("year", review.year.to_string().as_str())]), SHARE_MARGIN, 150.0)?;
+
+    // Headline numbers in two columns
+    let headlines = [
+        (t("year_review.total_time"), hours_text(review.total_seconds)),
+        (t("year_review.episodes"), review.episodes_listened.to_string()),
+        (t("year_review.longest_streak"), t_plural("year_review.days", review.longest_streak as i64)),
+        (t("year_review.new_subscriptions"), review.new_subscriptions.len().to_string()),
+    ];
+    for (index, (label, value)) in headlines.iter().enumerate() {
+        let x = SHARE_MARGIN + (index % 2) as f64 * (width / 2.0 - SHARE_MARGIN / 2.0);
+        let y = 260.0 + (index / 2) as f64 * 140.0;
+        context.set_font("28px sans-serif");
+        context.fill_text(label, x, y)?;
+        context.set_font("bold 52px sans-serif");
+        context.fill_text(value, x, y + 60.0)?;
+    }
+
+    // Top podcasts as bars
+    context.set_font("bold 36px sans-serif");
+    context.fill_text(&t("year_review.top_podcasts"), SHARE_MARGIN, 600.0)?;
+    let largest = review.top_podcasts.iter().map(|podcast| podcast.Seconds).max().unwrap_or(0).max(1) as f64;
+    let bar_space = width - SHARE_MARGIN * 2.0;
+    for (index, podcast) in review.top_podcasts.iter().enumerate() {
+        let y = 640.0 + index as f64 * 70.0;
+        context.set_fill_style_str(&accent);
+        context.fill_rect(SHARE_MARGIN, y, (podcast.Seconds as f64 / largest * bar_space).max(4.0), 54.0);
+        context.set_fill_style_str(&text);
+        context.set_font("28px sans-serif");
+        let line = format!("{}. {} · {}", index + 1, truncate_label(&podcast.PodcastName, 40), format_duration(podcast.Seconds));
+        context.fill_text(&line, SHARE_MARGIN + 16.0, y + 38.0)?;
+    }
+
+    // Listening by month as columns
+    context.set_font("bold 36px sans-serif");
+    context.fill_text(&t("year_review.by_month"), SHARE_MARGIN, 1050.0)?;
+    let busiest = review.by_month.iter().copied().max().unwrap_or(0).max(1) as f64;
+    let column_space = (width - SHARE_MARGIN * 2.0) / 12.0;
+    for (month, seconds) in review.by_month.iter().enumerate() {
+        let height = *seconds as f64 / busiest * 160.0;
+        let x = SHARE_MARGIN + month as f64 * column_space;
+        context.set_fill_style_str(&accent);
+        context.fill_rect(x + 6.0, 1240.0 - height, column_space - 12.0, height);
+        context.set_fill_style_str(&text);
+        context.set_font("22px sans-serif");
+        context.fill_text(&month_label(month), x + 6.0, 1275.0)?;
+    }
+
+    context.set_font("24px sans-serif");
+    context.fill_text("Pinepods", SHARE_MARGIN, SHARE_HEIGHT as f64 - 30.0)?;
+
+    canvas.to_data_url_with_type("image/png")
+}
+
+// A self contained page with print friendly styling
+fn printable_html(review: &YearReview, tz: Tz, date_format: DateFormat) -> String {
+    let title = escape_html(&t_args("year_review.title", &[("year", review.year.to_string().as_str())]));
+    let mut html = format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{title}</title><style>\
+         body{{font-family:sans-serif;max-width:720px;margin:2rem auto;color:#111}}\
+         table{{border-collapse:collapse;width:100%;margin-bottom:1.5rem}}\
+         th,td{{text-align:left;padding:0.3rem 0.5rem;border-bottom:1px solid #ccc}}\
+         dl{{display:grid;grid-template-columns:auto 1fr;gap:0.3rem 1rem}}dt{{font-weight:bold}}\
+         </style></head><body><h1>{title}</h1><dl>"
+    );
+    let facts = [
+        (t("year_review.total_time"), format_duration(review.total_seconds)),
+        (t("year_review.episodes"), review.episodes_listened.to_string()),
+        (t("year_review.finished"), review.episodes_finished.to_string()),
+        (t("year_review.podcasts"), review.podcasts_listened.to_string()),
+        (t("year_review.longest_streak"), t_plural("year_review.days", review.longest_streak as i64)),
+    ];
+    for (label, value) in facts {
+        html.push_str(&format!("<dt>{}</dt><dd>{}</dd>", escape_html(&label), escape_html(&value)));
+    }
+    html.push_str("</dl>");
+
+    html.push_str(&format!("<h2>{}</h2><table>", escape_html(&t("year_review.top_podcasts"))));
+    for (index, podcast) in review.top_podcasts.iter().enumerate() {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            index + 1,
+            escape_html(&podcast.PodcastName),
+            escape_html(&format_duration(podcast.Seconds)),
+            escape_html(&t_plural("stats.episode_count", podcast.Episodes as i64)),
+        ));
+    }
+    html.push_str("</table>");
+
+    html.push_str(&format!("<h2>{}</h2><table>", escape_html(&t("year_review.by_month"))));
+    for (month, seconds) in review.by_month.iter().enumerate() {
+        html.push_str(&format!("<tr><td>{}</td><td>{}</td></tr>", escape_html(&month_label(month)), escape_html(&format_duration(*seconds))));
+    }
+    html.push_str("</table>");
+
+    if let Some(episode) = &review.most_replayed {
+        html.push_str(&format!(
+            "<h2>{}</h2><p>{} ({}), {}</p>",
+            escape_html(&t("year_review.most_replayed")),
+            escape_html(&episode.EpisodeTitle),
+            escape_html(&episode.PodcastName),
+            escape_html(&t_plural("year_review.plays", episode.Plays as i64)),
+        ));
+    }
+
+    html.push_str(&format!("<h2>{}</h2><ul>", escape_html(&t("year_review.new_subscriptions"))));
+    for subscription in &review.new_subscriptions {
+        let added = local_day(&subscription.DateAdded, tz).map(|day| format_day(&day, date_format)).unwrap_or_default();
+        html.push_str(&format!("<li>{} ({})</li>", escape_html(&subscription.PodcastName), escape_html(&added)));
+    }
+    html.push_str("</ul></body></html>");
+    html
+}
+
+#[function_component(YearReviewPage)]
+pub fn year_review_page() -> Html {
+    let (state, _dispatch) = use_store::<AppState>();
+    let (audio_state, audio_dispatch) = use_store::<UIState>();
+    let tz: Tz = state.user_tz.as_ref().and_then(|tz| tz.parse().ok()).unwrap_or(chrono_tz::UTC);
+    let this_year = Utc::now().with_timezone(&tz).year();
+    let year = use_state(|| this_year);
+    let review = use_state(|| None::<Rc<YearReview>>);
+    let date_format = match_date_format(state.date_format.as_deref());
+
+    {
+        let review = review.clone();
+        let audio_dispatch = audio_dispatch.clone();
+        let api_key = state.auth_details.as_ref().and_then(|ud| ud.api_key.clone());
+        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+        let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+        use_effect_with((server_name, user_id, *year), move |(server_name, user_id, year)| {
+            if let (Some(server_name), Some(user_id)) = (server_name.clone(), *user_id) {
+                let year = *year;
+                review.set(None);
+                wasm_bindgen_futures::spawn_local(async move {
+                    match call_get_year_review(&server_name, &api_key, user_id, year).await {
+                        Ok(fetched) => review.set(Some(Rc::new(fetched))),
+                        Err(e) => audio_dispatch.reduce_mut(|state| state.error_message = Some(t_args("year_review.load_failed", &[("error", e.to_string().as_str())]))),
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_year_change = {
+        let year = year.clone();
+        Callback::from(move |e: Event| {
+            if let Some(selected) = e.target_dyn_into::<HtmlSelectElement>().and_then(|select| select.value().parse::<i32>().ok()) {
+                year.set(selected);
+            }
+        })
+    };
+
+    let on_share_image = {
+        let review = review.clone();
+        let audio_dispatch = audio_dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(review) = review.as_ref() else {
+                return;
+            };
+            let result = render_share_image(review).and_then(|data_url| download_href(&format!("pinepods-{}-review.png", review.year), &data_url));
+            if result.is_err() {
+                audio_dispatch.reduce_mut(|state| state.error_message = Some(t("year_review.export_failed")));
+            }
+        })
+    };
+    let on_printable = {
+        let review = review.clone();
+        let audio_dispatch = audio_dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(review) = review.as_ref() else {
+                return;
+            };
+            let filename = format!("pinepods-{}-review.html", review.year);
+            if download_text(&filename, "text/html", &printable_html(review, tz, date_format)).is_err() {
+                audio_dispatch.reduce_mut(|state| state.error_message = Some(t("year_review.export_failed")));
+            }
+        })
+    };
+
+    let body = match review.as_ref() {
+        None => html! { <p class="item_container-text">{ t("year_review.loading") }</p> },
+        Some(review) if review.episodes_listened == 0 && review.new_subscriptions.is_empty() => {
+            html! { <p class="item_container-text">{ t("year_review.empty") }</p> }
+        }
+        Some(review) => {
+            let top_podcasts: Vec<ChartValue> = review
+                .top_podcasts
+                .iter()
+                .map(|podcast| ChartValue { label: podcast.PodcastName.clone(), value: podcast.Seconds as f64, caption: format_duration(podcast.Seconds) })
+                .collect();
+            let by_month: Vec<ChartValue> = review
+                .by_month
+                .iter()
+                .enumerate()
+                .map(|(month, seconds)| ChartValue { label: month_label(month), value: *seconds as f64, caption: format_duration(*seconds) })
+                .collect();
+            html! {
+                <>
+                <div class="grid grid-cols-2 md:grid-cols-4 gap-4 mb-6">
+                    <div class="stats-card">
+                        <p class="stats-label">{ t("year_review.total_time") }</p>
+                        <p class="stats-value">{ hours_text(review.total_seconds) }</p>
+                    </div>
+                    <div class="stats-card">
+                        <p class="stats-label">{ t("year_review.episodes") }</p>
+                        <p class="stats-value">{ review.episodes_listened.to_string() }</p>
+                        <p class="stats-label">{ t_args("year_review.finished_count", &[("count", review.episodes_finished.to_string().as_str())]) }</p>
+                    </div>
+                    <div class="stats-card">
+                        <p class="stats-label">{ t("year_review.longest_streak") }</p>
+                        <p class="stats-value">{ t_plural("year_review.days", review.longest_streak as i64) }</p>
+                    </div>
+                    <div class="stats-card">
+                        <p class="stats-label">{ t("year_review.new_subscriptions") }</p>
+                        <p class="stats-value">{ review.new_subscriptions.len().to_string() }</p>
+                    </div>
+                </div>
+
+                <h2 class="text-xl item_container-text font-bold mb-2">{ t("year_review.top_podcasts") }</h2>
+                if top_podcasts.is_empty() {
+                    <p class="item_container-text mb-6">{ t("year_review.no_listening") }</p>
+                } else {
+                    <div class="mb-6">
+                        <BarChart values={Rc::new(top_podcasts)} label={t("year_review.top_podcasts")} />
+                    </div>
+                }
+
+                <h2 class="text-xl item_container-text font-bold mb-2">{ t("year_review.by_month") }</h2>
+                <div class="mb-6">
+                    <ColumnChart values={Rc::new(by_month)} label={t("year_review.by_month")} />
+                </div>
+
+                <h2 class="text-xl item_container-text font-bold mb-2">{ t("year_review.most_replayed") }</h2>
+                if let Some(episode) = &review.most_replayed {
+                    <div class="flex items-center gap-4 mb-6 item_container-text">
+                        if let Some(artwork) = &episode.EpisodeArtwork {
+                            <img src={artwork.clone()} alt="" class="w-16 h-16 rounded object-cover" loading="lazy" />
+                        }
+                        <div>
+                            <p class="font-semibold">{ &episode.EpisodeTitle }</p>
+                            <p class="text-sm">{ &episode.PodcastName }</p>
+                            <p class="text-sm">{ t_plural("year_review.plays", episode.Plays as i64) }</p>
+                        </div>
+                    </div>
+                } else {
+                    <p class="item_container-text mb-6">{ t("year_review.no_replays") }</p>
+                }
+
+                <h2 class="text-xl item_container-text font-bold mb-2">{ t("year_review.new_subscriptions") }</h2>
+                if review.new_subscriptions.is_empty() {
+                    <p class="item_container-text">{ t("year_review.no_subscriptions") }</p>
+                } else {
+                    <ul class="item_container-text">
+                        { for review.new_subscriptions.iter().map(|subscription| html! {
+                            <li key={subscription.PodcastID} class="mb-1">
+                                <span class="font-semibold">{ &subscription.PodcastName }</span>
+                                if let Some(day) = local_day(&subscription.DateAdded, tz) {
+                                    <span class="text-sm ml-2">{ format_day(&day, date_format) }</span>
+                                }
+                            </li>
+                        }) }
+                    </ul>
+                }
+                </>
+            }
+        }
+    };
+
+    html! {
+        <>
+        <div class="main-container">
+            <Search_nav />
+            <div class="item-container mx-auto p-6 shadow-md rounded">
+                <div class="flex flex-wrap items-end gap-4 mb-6">
+                    <h1 class="text-2xl item_container-text font-bold">{ t_args("year_review.title", &[("year", year.to_string().as_str())]) }</h1>
+                    <label class="flex flex-col item_container-text">
+                        <span class="mb-1">{ t("year_review.year") }</span>
+                        <select class="theme-select-dropdown border px-4 py-2 rounded shadow leading-tight focus:outline-none focus:shadow-outline" onchange={on_year_change}>
+                            { for (this_year - PAST_YEARS..=this_year).rev().map(|option| html! {
+                                <option value={option.to_string()} selected={option == *year}>{ option.to_string() }</option>
+                            }) }
+                        </select>
+                    </label>
+                    <div class="flex gap-2 ml-auto">
+                        <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_share_image} disabled={review.is_none()}>
+                            <span class="material-icons icon-space">{"image"}</span>
+                            <span>{ t("year_review.share_image") }</span>
+                        </button>
+                        <button class="download-button font-bold py-2 px-4 rounded inline-flex items-center" onclick={on_printable} disabled={review.is_none()}>
+                            <span class="material-icons icon-space">{"print"}</span>
+                            <span>{ t("year_review.printable") }</span>
+                        </button>
+                    </div>
+                </div>
+                { body }
+            </div>
+        {
+            if let Some(audio_props) = &audio_state.currently_playing {
+                html! { <AudioPlayer src={audio_props.src.clone()} title={audio_props.title.clone()} artwork_url={audio_props.artwork_url.clone()} duration={audio_props.duration.clone()} episode_id={audio_props.episode_id.clone()} duration_sec={audio_props.duration_sec.clone()} start_pos_sec={audio_props.start_pos_sec.clone()} /> }
+            } else {
+                html! {}
+            }
+        }
+        </div>
+        <App_drawer />
+        </>
+    }
+}
@@ -13,12 +13,21 @@ use components::saved::Saved;
 use components::search::Search;
 use components::settings::Settings;
 use components::user_stats::UserStats;
+use components::year_review::YearReviewPage;
 use components::home::Home;
 use components::search_new::SearchNew;
 use components::podcast_layout::PodLayout;
 use components::episodes_layout::EpisodeLayout;
 use components::podcasts::Podcasts;
+use components::podcast_page::PodcastPage;
 use components::episode::Episode;
+use components::playlists::{PlaylistPage, Playlists};
+use components::context::LocaleState;
+use components::i18n::{t, current_locale};
+use components::a11y::{LiveAnnouncer, apply_accessibility_preferences, load_accessibility_preferences};
+use components::persistence::remember_route;
+use components::notifications::NewEpisodeNotifier;
+use components::library_index::LibraryIndexer;
 
 #[cfg(feature = "server_build")]
 use {
@@ -38,6 +47,7 @@ use {
 // Yew Imports
 use yew_router::prelude::*;
 use yew::prelude::*;
+use yewdux::prelude::*;
 
 
 #[function_component(NotFound)]
@@ -46,14 +56,38 @@ pub fn not_found() -> Html {
             <>
                 <div class="empty-episodes-container">
                     <img src="static/assets/favicon.png" alt="Logo" class="logo"/>
-                    <h1>{ "Page not found" }</h1>
-                    <p>{"Sorry for the inconvenience. You could eat a taco to cheer you up :)"}</p>
+                    <h1>{ t("not_found.title") }</h1>
+                    <p>{ t("not_found.body") }</p>
                 </div>
             </>
         }
 
 }
 
+// Only top level pages are restored on the next visit. The layout pages rely on
+// whichever podcast or episode was clicked, which is gone after a reload. Playlists and
+// subscribed podcasts keep their id in the path, so they come back fine.
+fn is_restorable(route: &Route) -> bool {
+    matches!(
+        route,
+        Route::Home | Route::Queue | Route::Saved | Route::Settings | Route::PodHistory
+            | Route::Downloads | Route::UserStats | Route::YearReview | Route::Podcasts | Route::Podcast { .. } | Route::Playlists | Route::Playlist { .. }
+    )
+}
+
+// Records the page after each navigation rather than while rendering it
+#[function_component(RouteRecorder)]
+fn route_recorder() -> Html {
+    let route = use_route::<Route>();
+    use_effect_with(route, |route| {
+        if let Some(route) = route.as_ref().filter(|route| is_restorable(route)) {
+            remember_route(&route.to_path());
+        }
+        || ()
+    });
+    html! {}
+}
+
 fn switch(route: Route) -> Html {
     match route {
         Route::Login => html! { <Login /> },
@@ -67,12 +101,16 @@ fn switch(route: Route) -> Html {
         Route::Downloads => html! { <Downloads /> },
         Route::Search => html! { <Search on_search={Callback::from(move |_| {})} /> },
         Route::UserStats => html! { <UserStats /> },
+        Route::YearReview => html! { <YearReviewPage /> },
         Route::LogOut => html! { <LogOut /> },
         Route::SearchNew => html! { <SearchNew /> },
         Route::PodLayout => html! { <PodLayout /> },
         Route::EpisodeLayout => html! { <EpisodeLayout /> },
+        Route::Podcast { id } => html! { <PodcastPage podcast_id={id} /> },
         Route::Podcasts => html! { <Podcasts /> },
         Route::Episode => html! { <Episode /> },
+        Route::Playlists => html! { <Playlists /> },
+        Route::Playlist { id } => html! { <PlaylistPage playlist_id={id} /> },
 
     }
 }
@@ -83,10 +121,25 @@ fn main_component() -> Html {
     // console::log_1(&format!("Initial User Context: {:?}", (*user_context).clone()).into());
     // console::log_1(&format!("Initial Auth Context: {:?}", (*user_auth_context).clone()).into());
 
+    // Keying the router on the locale remounts every page with the newly selected catalog
+    let (locale_state, _locale_dispatch) = use_store::<LocaleState>();
+    let locale_key = locale_state.locale.clone().unwrap_or_else(|| current_locale().code().to_string());
+
+    use_effect_with((), |_| {
+        apply_accessibility_preferences(&load_accessibility_preferences());
+        || ()
+    });
+
     html! {
-        <BrowserRouter>
+        <>
+        <BrowserRouter key={locale_key}>
             <Switch<Route> render={switch} />
+            <RouteRecorder />
         </BrowserRouter>
+        <LiveAnnouncer />
+        <NewEpisodeNotifier />
+        <LibraryIndexer />
+        </>
     }
 }
 
@@ -2,6 +2,7 @@ pub(crate) mod login_requests;
 pub(crate) mod models;
 pub(crate) mod pod_req;
 pub(crate) mod search_pods;
+pub(crate) mod search_providers;
 
 pub(crate) mod stat_reqs;
 pub(crate) mod setting_reqs;
\ No newline at end of file
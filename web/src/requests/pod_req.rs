@@ -3,12 +3,22 @@ use anyhow::{Context, Error};
 use gloo_net::http::Request;
 use serde::{Deserialize, Deserializer, Serialize};
 
-fn bool_from_int<'de, D>(deserializer: D) -> Result<bool, D::Error>
+pub(crate) fn bool_from_int<'de, D>(deserializer: D) -> Result<bool, D::Error>
     where
         D: Deserializer<'de>,
 {
-    let value: i32 = Deserialize::deserialize(deserializer)?;
-    Ok(value != 0)
+    // The server sends 0/1 but values cached locally round-trip as real booleans
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum BoolOrInt {
+        Bool(bool),
+        Int(i32),
+    }
+
+    match BoolOrInt::deserialize(deserializer)? {
+        BoolOrInt::Bool(value) => Ok(value),
+        BoolOrInt::Int(value) => Ok(value != 0),
+    }
 }
 
 #[derive(Deserialize, Debug, PartialEq, Clone)]
@@ -23,6 +33,8 @@ pub struct Episode {
     pub EpisodeDuration: i32,
     pub ListenDuration: Option<i32>,
     pub EpisodeID: i32,
+    #[serde(default)]
+    pub PodcastID: Option<i32>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Clone)]
@@ -197,6 +209,14 @@ pub struct Podcast {
     pub Categories: String, // Keeping as String since it's handled as empty string "{}" or "{}"
     #[serde(deserialize_with = "bool_from_int")]
     pub Explicit: bool,
+    // Older servers don't send these, so sorting falls back gracefully
+    #[serde(default)]
+    pub LatestEpisodeDate: Option<String>,
+    #[serde(default)]
+    pub TotalListenTime: Option<i64>,
+    // Unknown for podcasts added before the server recorded it
+    #[serde(default)]
+    pub DateAdded: Option<String>,
 }
 
 
@@ -411,12 +431,12 @@ pub async fn call_remove_queued_episode(
     }
 }
 
-#[derive(Debug, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct QueuedEpisodesResponse {
     pub episodes: Vec<QueuedEpisode>,
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[allow(non_snake_case)]
 pub struct QueuedEpisode {
     pub EpisodeTitle: String,
@@ -431,6 +451,9 @@ pub struct QueuedEpisode {
     pub QueueDate: String,
     pub ListenDuration: Option<i32>,
     pub EpisodeID: i32,
+    // The podcast's playback speed, so the queue can estimate real listening time
+    #[serde(default)]
+    pub PlaybackSpeed: Option<f64>,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Clone)]
@@ -467,11 +490,6 @@ pub async fn call_get_queued_episodes(
 // Save episode calls
 
 
-#[derive(Debug, Deserialize, PartialEq, Clone)]
-pub struct SavedEpisodesResponse {
-    pub episodes: Vec<SavedEpisode>,
-}
-
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 #[allow(non_snake_case)]
 pub struct SavedEpisode {
@@ -610,6 +628,11 @@ pub struct HistoryEpisode {
     pub EpisodeDuration: i32,
     pub ListenDuration: Option<i32>,
     pub EpisodeID: i32,
+    // When the user last listened, in UTC
+    #[serde(default)]
+    pub ListenDate: Option<String>,
+    #[serde(default)]
+    pub PodcastID: Option<i32>,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Clone)]
@@ -624,12 +647,47 @@ pub async fn call_get_user_history(
 ) -> Result<Vec<HistoryEpisode>, anyhow::Error> {
     // Append the user_id as a query parameter
     let url = format!("{}/api/data/user_history/{}", server_name, user_id);
+    fetch_user_history(&url, api_key).await
+}
 
+// Episode lists that scroll through long histories load them this many rows at a time
+pub const EPISODE_PAGE_SIZE: usize = 100;
+
+// Narrows the history to one podcast and/or a window of listen dates. Bounds are UTC timestamps, end exclusive.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct HistoryFilter {
+    pub podcast_id: Option<i32>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+}
+
+// Pages are ordered from the most recent listen backwards
+pub async fn call_get_user_history_page(
+    server_name: &str,
+    api_key: &Option<String>,
+    user_id: &i32,
+    offset: usize,
+    limit: usize,
+    filter: &HistoryFilter,
+) -> Result<Vec<HistoryEpisode>, anyhow::Error> {
+    let mut url = format!("{}/api/data/user_history/{}?offset={}&limit={}", server_name, user_id, offset, limit);
+    if let Some(podcast_id) = filter.podcast_id {
+        url.push_str(&format!("&podcast_id={}", podcast_id));
+    }
+    if let Some(start) = &filter.start {
+        url.push_str(&format!("&start={}", start));
+    }
+    if let Some(end) = &filter.end {
+        url.push_str(&format!("&end={}", end));
+    }
+    fetch_user_history(&url, api_key).await
+}
 
+async fn fetch_user_history(url: &str, api_key: &Option<String>) -> Result<Vec<HistoryEpisode>, anyhow::Error> {
     // Convert Option<String> to Option<&str>
     let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
 
-    let response = Request::get(&url)
+    let response = Request::get(url)
         .header("Api-Key", api_key_ref)
         .send()
         .await?;
@@ -680,11 +738,6 @@ pub async fn call_add_history(
 
 // Download calls
 
-#[derive(Debug, Deserialize, PartialEq, Clone)]
-pub struct EpisodeDownloadResponse {
-    pub episodes: Vec<EpisodeDownload>,
-}
-
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 #[allow(non_snake_case)]
 pub struct EpisodeDownload {
@@ -991,4 +1044,696 @@ pub async fn call_get_podcast_id(
 
     let response_data: PodcastIdResponse = serde_json::from_str(&response_text)?;
     Ok(response_data.episodes)
-}
\ No newline at end of file
+}
+
+// A subscribed podcast with the user's own listening of it, for the podcast page
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[allow(non_snake_case)]
+pub struct PodcastDetails {
+    pub PodcastID: i32,
+    pub PodcastName: String,
+    pub ArtworkURL: Option<String>,
+    pub Description: Option<String>,
+    pub EpisodeCount: i32,
+    pub WebsiteURL: Option<String>,
+    pub FeedURL: String,
+    pub Author: Option<String>,
+    pub Categories: String,
+    #[serde(deserialize_with = "bool_from_int")]
+    pub Explicit: bool,
+    pub TimeListened: i64,
+    pub EpisodesStarted: i64,
+    pub EpisodesCompleted: i64,
+    pub LastPlayed: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PodcastDetailsResponse {
+    podcast: PodcastDetails,
+}
+
+pub async fn call_get_podcast_details(server_name: &str, api_key: &Option<String>, user_id: i32, podcast_id: i32) -> Result<PodcastDetails, Error> {
+    let url = format!("{}/api/data/podcast_details/{}/{}", server_name, user_id, podcast_id);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+
+    let response = Request::get(&url)
+        .header("Api-Key", api_key_ref)
+        .send()
+        .await?;
+
+    if response.ok() {
+        let response_body = response.json::<PodcastDetailsResponse>().await?;
+        Ok(response_body.podcast)
+    } else {
+        Err(anyhow::Error::msg(format!("Failed to get podcast details: {}", response.status_text())))
+    }
+}
+
+// Per-podcast settings
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[allow(non_snake_case)]
+pub struct PodcastSettings {
+    pub PodcastID: i32,
+    pub PlaybackSpeed: f64,
+    pub StartSkip: i32,
+    pub EndSkip: i32,
+    pub EpisodeSortOrder: String,
+    pub AutoDownload: bool,
+    pub AutoQueue: bool,
+    pub NotifyNewEpisodes: bool,
+}
+
+impl PodcastSettings {
+    pub fn defaults(podcast_id: i32) -> Self {
+        PodcastSettings {
+            PodcastID: podcast_id,
+            PlaybackSpeed: 1.0,
+            StartSkip: 0,
+            EndSkip: 0,
+            EpisodeSortOrder: "newest".to_string(),
+            AutoDownload: false,
+            AutoQueue: false,
+            NotifyNewEpisodes: false,
+        }
+    }
+
+    pub fn oldest_first(&self) -> bool {
+        self.EpisodeSortOrder == "oldest"
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct PodcastSettingsResponse {
+    settings: PodcastSettings,
+}
+
+async fn fetch_podcast_settings(url: &str, api_key: &Option<String>) -> Result<PodcastSettings, Error> {
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+
+    let response = Request::get(url)
+        .header("Api-Key", api_key_ref)
+        .send()
+        .await?;
+
+    if response.ok() {
+        let response_body = response.json::<PodcastSettingsResponse>().await?;
+        Ok(response_body.settings)
+    } else {
+        Err(anyhow::Error::msg(format!("Failed to get podcast settings: {}", response.status_text())))
+    }
+}
+
+pub async fn call_get_podcast_settings(
+    server_name: &str,
+    api_key: &Option<String>,
+    user_id: i32,
+    podcast_id: i32,
+) -> Result<PodcastSettings, Error> {
+    let url = format!("{}/api/data/podcast_settings/{}/{}", server_name, user_id, podcast_id);
+    fetch_podcast_settings(&url, api_key).await
+}
+
+// Resolves the settings of whichever podcast the episode belongs to
+pub async fn call_get_episode_podcast_settings(
+    server_name: &str,
+    api_key: &Option<String>,
+    user_id: i32,
+    episode_id: i32,
+) -> Result<PodcastSettings, Error> {
+    let url = format!("{}/api/data/episode_podcast_settings/{}/{}", server_name, user_id, episode_id);
+    fetch_podcast_settings(&url, api_key).await
+}
+
+#[derive(Serialize, Debug)]
+#[allow(non_snake_case)]
+struct UpdatePodcastSettingsRequest<'a> {
+    user_id: i32,
+    podcast_id: i32,
+    PlaybackSpeed: f64,
+    StartSkip: i32,
+    EndSkip: i32,
+    EpisodeSortOrder: &'a str,
+    AutoDownload: bool,
+    AutoQueue: bool,
+    NotifyNewEpisodes: bool,
+}
+
+pub async fn call_update_podcast_settings(
+    server_name: &str,
+    api_key: &Option<String>,
+    user_id: i32,
+    settings: &PodcastSettings,
+) -> Result<bool, Error> {
+    let url = format!("{}/api/data/podcast_settings", server_name);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+
+    let request = UpdatePodcastSettingsRequest {
+        user_id,
+        podcast_id: settings.PodcastID,
+        PlaybackSpeed: settings.PlaybackSpeed,
+        StartSkip: settings.StartSkip,
+        EndSkip: settings.EndSkip,
+        EpisodeSortOrder: &settings.EpisodeSortOrder,
+        AutoDownload: settings.AutoDownload,
+        AutoQueue: settings.AutoQueue,
+        NotifyNewEpisodes: settings.NotifyNewEpisodes,
+    };
+    let request_body = serde_json::to_string(&request).map_err(|e| anyhow::Error::msg(format!("Serialization Error: {}", e)))?;
+
+    let response = Request::post(&url)
+        .header("Api-Key", api_key_ref)
+        .header("Content-Type", "application/json")
+        .body(request_body)?
+        .send()
+        .await?;
+
+    if response.ok() {
+        Ok(true)
+    } else {
+        let error_text = response.text().await.unwrap_or_else(|_| String::from("Failed to read error message"));
+        Err(anyhow::Error::msg(format!("Failed to update podcast settings: {} - {}", response.status_text(), error_text)))
+    }
+}
+
+// New episode notifications
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[allow(non_snake_case)]
+pub struct NotificationEpisode {
+    pub PodcastID: i32,
+    pub PodcastName: String,
+    pub EpisodeID: i32,
+    pub EpisodeTitle: String,
+    pub EpisodePubDate: String,
+    pub EpisodeArtwork: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct NotificationEpisodesResponse {
+    episodes: Vec<NotificationEpisode>,
+}
+
+// Newest episode of each podcast that has notifications turned on
+pub async fn call_get_notification_episodes(server_name: &str, api_key: &Option<String>, user_id: i32) -> Result<Vec<NotificationEpisode>, Error> {
+    let url = format!("{}/api/data/notification_episodes/{}", server_name, user_id);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+
+    let response = Request::get(&url)
+        .header("Api-Key", api_key_ref)
+        .send()
+        .await?;
+
+    if response.ok() {
+        let response_body = response.json::<NotificationEpisodesResponse>().await?;
+        Ok(response_body.episodes)
+    } else {
+        Err(anyhow::Error::msg(format!("Failed to check for new episodes: {}", response.status_text())))
+    }
+}
+
+// Folders
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[allow(non_snake_case)]
+pub struct Folder {
+    pub FolderID: i32,
+    pub FolderName: String,
+    #[serde(default)]
+    pub PodcastIDs: Vec<i32>,
+}
+
+#[derive(Deserialize, Debug)]
+struct FoldersResponse {
+    folders: Vec<Folder>,
+}
+
+pub async fn call_get_folders(server_name: &str, api_key: &Option<String>, user_id: i32) -> Result<Vec<Folder>, Error> {
+    let url = format!("{}/api/data/folders/{}", server_name, user_id);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+
+    let response = Request::get(&url)
+        .header("Api-Key", api_key_ref)
+        .send()
+        .await?;
+
+    if response.ok() {
+        let response_body = response.json::<FoldersResponse>().await?;
+        Ok(response_body.folders)
+    } else {
+        Err(anyhow::Error::msg(format!("Failed to get folders: {}", response.status_text())))
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct CreateFolderRequest<'a> {
+    user_id: i32,
+    folder_name: &'a str,
+}
+
+#[derive(Deserialize, Debug)]
+struct CreateFolderResponse {
+    folder_id: i32,
+    folder_name: String,
+}
+
+pub async fn call_create_folder(server_name: &str, api_key: &Option<String>, user_id: i32, folder_name: &str) -> Result<Folder, Error> {
+    let url = format!("{}/api/data/create_folder", server_name);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+    let request_body = serde_json::to_string(&CreateFolderRequest { user_id, folder_name })
+        .map_err(|e| anyhow::Error::msg(format!("Serialization Error: {}", e)))?;
+
+    let response = Request::post(&url)
+        .header("Api-Key", api_key_ref)
+        .header("Content-Type", "application/json")
+        .body(request_body)?
+        .send()
+        .await?;
+
+    if response.ok() {
+        let response_body = response.json::<CreateFolderResponse>().await?;
+        Ok(Folder {
+            FolderID: response_body.folder_id,
+            FolderName: response_body.folder_name,
+            PodcastIDs: Vec::new(),
+        })
+    } else {
+        let error_text = response.text().await.unwrap_or_else(|_| String::from("Failed to read error message"));
+        Err(anyhow::Error::msg(format!("Failed to create folder: {} - {}", response.status_text(), error_text)))
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct DeleteFolderRequest {
+    user_id: i32,
+    folder_id: i32,
+}
+
+pub async fn call_delete_folder(server_name: &str, api_key: &Option<String>, user_id: i32, folder_id: i32) -> Result<bool, Error> {
+    let url = format!("{}/api/data/delete_folder", server_name);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+    let request_body = serde_json::to_string(&DeleteFolderRequest { user_id, folder_id })
+        .map_err(|e| anyhow::Error::msg(format!("Serialization Error: {}", e)))?;
+
+    let response = Request::post(&url)
+        .header("Api-Key", api_key_ref)
+        .header("Content-Type", "application/json")
+        .body(request_body)?
+        .send()
+        .await?;
+
+    if response.ok() {
+        Ok(true)
+    } else {
+        Err(anyhow::Error::msg(format!("Failed to delete folder: {}", response.status_text())))
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct PodcastFoldersRequest<'a> {
+    user_id: i32,
+    podcast_id: i32,
+    folder_ids: &'a [i32],
+}
+
+// Replaces the full set of folders a podcast belongs to
+pub async fn call_set_podcast_folders(server_name: &str, api_key: &Option<String>, user_id: i32, podcast_id: i32, folder_ids: &[i32]) -> Result<bool, Error> {
+    let url = format!("{}/api/data/set_podcast_folders", server_name);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+    let request_body = serde_json::to_string(&PodcastFoldersRequest { user_id, podcast_id, folder_ids })
+        .map_err(|e| anyhow::Error::msg(format!("Serialization Error: {}", e)))?;
+
+    let response = Request::post(&url)
+        .header("Api-Key", api_key_ref)
+        .header("Content-Type", "application/json")
+        .body(request_body)?
+        .send()
+        .await?;
+
+    if response.ok() {
+        Ok(true)
+    } else {
+        Err(anyhow::Error::msg(format!("Failed to update podcast folders: {}", response.status_text())))
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct EpisodePlayedRequest {
+    episode_id: i32,
+    user_id: i32,
+    played: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct EpisodePlayedResponse {
+    listen_duration: i32,
+}
+
+// Marks an episode as listened to the end, or resets it to unplayed. Returns the recorded listen duration.
+pub async fn call_mark_episode_played(server_name: &str, api_key: &Option<String>, user_id: i32, episode_id: i32, played: bool) -> Result<i32, Error> {
+    let url = format!("{}/api/data/mark_episode_played", server_name);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+    let request_body = serde_json::to_string(&EpisodePlayedRequest { episode_id, user_id, played })
+        .map_err(|e| anyhow::Error::msg(format!("Serialization Error: {}", e)))?;
+
+    let response = Request::post(&url)
+        .header("Api-Key", api_key_ref)
+        .header("Content-Type", "application/json")
+        .body(request_body)?
+        .send()
+        .await?;
+
+    if response.ok() {
+        let response_body: EpisodePlayedResponse = response.json().await?;
+        Ok(response_body.listen_duration)
+    } else {
+        Err(anyhow::Error::msg(format!("Failed to mark episode: {}", response.status_text())))
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct HistoryEpisodeRemoveRequest {
+    episode_id: i32,
+    user_id: i32,
+}
+
+pub async fn call_remove_history_episode(server_name: &str, api_key: &Option<String>, user_id: i32, episode_id: i32) -> Result<bool, Error> {
+    let url = format!("{}/api/data/remove_history_episode", server_name);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+    let request_body = serde_json::to_string(&HistoryEpisodeRemoveRequest { episode_id, user_id })
+        .map_err(|e| anyhow::Error::msg(format!("Serialization Error: {}", e)))?;
+
+    let response = Request::post(&url)
+        .header("Api-Key", api_key_ref)
+        .header("Content-Type", "application/json")
+        .body(request_body)?
+        .send()
+        .await?;
+
+    if response.ok() {
+        Ok(true)
+    } else {
+        Err(anyhow::Error::msg(format!("Failed to remove episode from history: {}", response.status_text())))
+    }
+}
+
+#[derive(Serialize)]
+struct ClearHistoryRequest {
+    user_id: i32,
+}
+
+#[derive(Deserialize)]
+struct ClearHistoryResponse {
+    removed: i64,
+}
+
+// Returns how many entries were removed
+pub async fn call_clear_history(server_name: &str, api_key: &Option<String>, user_id: i32) -> Result<i64, Error> {
+    let url = format!("{}/api/data/clear_history", server_name);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+    let request_body = serde_json::to_string(&ClearHistoryRequest { user_id })
+        .map_err(|e| anyhow::Error::msg(format!("Serialization Error: {}", e)))?;
+
+    let response = Request::post(&url)
+        .header("Api-Key", api_key_ref)
+        .header("Content-Type", "application/json")
+        .body(request_body)?
+        .send()
+        .await?;
+
+    if !response.ok() {
+        return Err(anyhow::Error::msg(format!("Failed to clear history: {}", response.status_text())));
+    }
+    let response_data: ClearHistoryResponse = response.json().await?;
+    Ok(response_data.removed)
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CatchUpRequest {
+    pub user_id: i32,
+    pub podcast_id: i32,
+    // Only episodes published before this one are marked
+    pub older_than_episode_id: Option<i32>,
+    // The newest unfinished episodes to leave alone
+    pub keep_latest: Option<usize>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[allow(non_snake_case)]
+pub struct MarkedEpisode {
+    pub EpisodeID: i32,
+    pub ListenDuration: i32,
+}
+
+#[derive(Deserialize, Debug)]
+struct CatchUpResponse {
+    episodes: Vec<MarkedEpisode>,
+}
+
+// Marks a podcast's back catalog as played and returns the episodes that changed
+pub async fn call_catch_up_podcast(server_name: &str, api_key: &Option<String>, request_data: &CatchUpRequest) -> Result<Vec<MarkedEpisode>, Error> {
+    let url = format!("{}/api/data/catch_up_podcast", server_name);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+    let request_body = serde_json::to_string(request_data).map_err(|e| anyhow::Error::msg(format!("Serialization Error: {}", e)))?;
+
+    let response = Request::post(&url)
+        .header("Api-Key", api_key_ref)
+        .header("Content-Type", "application/json")
+        .body(request_body)?
+        .send()
+        .await?;
+
+    if response.ok() {
+        let response_body: CatchUpResponse = response.json().await?;
+        Ok(response_body.episodes)
+    } else {
+        Err(anyhow::Error::msg(format!("Failed to catch up podcast: {}", response.status_text())))
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct QueueReorderRequest<'a> {
+    user_id: i32,
+    episode_ids: &'a [i32],
+}
+
+// Saves a new order for the whole queue. Fails if the server's queue no longer has the same episodes.
+pub async fn call_reorder_queue(server_name: &str, api_key: &Option<String>, user_id: i32, episode_ids: &[i32]) -> Result<bool, Error> {
+    let url = format!("{}/api/data/reorder_queue", server_name);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+    let request_body = serde_json::to_string(&QueueReorderRequest { user_id, episode_ids })
+        .map_err(|e| anyhow::Error::msg(format!("Serialization Error: {}", e)))?;
+
+    let response = Request::post(&url)
+        .header("Api-Key", api_key_ref)
+        .header("Content-Type", "application/json")
+        .body(request_body)?
+        .send()
+        .await?;
+
+    if response.ok() {
+        Ok(true)
+    } else {
+        Err(anyhow::Error::msg(format!("Failed to reorder queue: {}", response.status_text())))
+    }
+}
+
+// Playlists
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[allow(non_snake_case)]
+pub struct Playlist {
+    pub PlaylistID: i32,
+    pub Name: String,
+    // JSON rules for smart playlists, None for manual ones
+    #[serde(default)]
+    pub Rules: Option<String>,
+    // Manual playlists only, in play order
+    #[serde(default)]
+    pub EpisodeIDs: Vec<i32>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PlaylistsResponse {
+    playlists: Vec<Playlist>,
+}
+
+pub async fn call_get_playlists(server_name: &str, api_key: &Option<String>, user_id: i32) -> Result<Vec<Playlist>, Error> {
+    let url = format!("{}/api/data/playlists/{}", server_name, user_id);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+
+    let response = Request::get(&url)
+        .header("Api-Key", api_key_ref)
+        .send()
+        .await?;
+
+    if response.ok() {
+        let response_body = response.json::<PlaylistsResponse>().await?;
+        Ok(response_body.playlists)
+    } else {
+        Err(anyhow::Error::msg(format!("Failed to get playlists: {}", response.status_text())))
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct PlaylistEpisodesResponse {
+    episodes: Vec<Episode>,
+}
+
+// Full episode details for a manual playlist, in play order
+pub async fn call_get_playlist_episodes(server_name: &str, api_key: &Option<String>, user_id: i32, playlist_id: i32) -> Result<Vec<Episode>, Error> {
+    let url = format!("{}/api/data/playlist_episodes/{}/{}", server_name, user_id, playlist_id);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+
+    let response = Request::get(&url)
+        .header("Api-Key", api_key_ref)
+        .send()
+        .await?;
+
+    if response.ok() {
+        let response_body = response.json::<PlaylistEpisodesResponse>().await?;
+        Ok(response_body.episodes)
+    } else {
+        Err(anyhow::Error::msg(format!("Failed to get playlist episodes: {}", response.status_text())))
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct CreatePlaylistRequest<'a> {
+    user_id: i32,
+    name: &'a str,
+    rules: Option<&'a str>,
+}
+
+#[derive(Deserialize, Debug)]
+struct CreatePlaylistResponse {
+    playlist_id: i32,
+    name: String,
+}
+
+pub async fn call_create_playlist(server_name: &str, api_key: &Option<String>, user_id: i32, name: &str, rules: Option<&str>) -> Result<Playlist, Error> {
+    let url = format!("{}/api/data/create_playlist", server_name);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+    let request_body = serde_json::to_string(&CreatePlaylistRequest { user_id, name, rules })
+        .map_err(|e| anyhow::Error::msg(format!("Serialization Error: {}", e)))?;
+
+    let response = Request::post(&url)
+        .header("Api-Key", api_key_ref)
+        .header("Content-Type", "application/json")
+        .body(request_body)?
+        .send()
+        .await?;
+
+    if response.ok() {
+        let response_body = response.json::<CreatePlaylistResponse>().await?;
+        Ok(Playlist {
+            PlaylistID: response_body.playlist_id,
+            Name: response_body.name,
+            Rules: rules.map(str::to_string),
+            EpisodeIDs: Vec::new(),
+        })
+    } else {
+        let error_text = response.text().await.unwrap_or_else(|_| String::from("Failed to read error message"));
+        Err(anyhow::Error::msg(format!("Failed to create playlist: {} - {}", response.status_text(), error_text)))
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct UpdatePlaylistRequest<'a> {
+    user_id: i32,
+    playlist_id: i32,
+    name: &'a str,
+    rules: Option<&'a str>,
+}
+
+// Renames a playlist and, for smart playlists, replaces its rules
+pub async fn call_update_playlist(server_name: &str, api_key: &Option<String>, user_id: i32, playlist_id: i32, name: &str, rules: Option<&str>) -> Result<bool, Error> {
+    let url = format!("{}/api/data/update_playlist", server_name);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+    let request_body = serde_json::to_string(&UpdatePlaylistRequest { user_id, playlist_id, name, rules })
+        .map_err(|e| anyhow::Error::msg(format!("Serialization Error: {}", e)))?;
+
+    let response = Request::post(&url)
+        .header("Api-Key", api_key_ref)
+        .header("Content-Type", "application/json")
+        .body(request_body)?
+        .send()
+        .await?;
+
+    if response.ok() {
+        Ok(true)
+    } else {
+        let error_text = response.text().await.unwrap_or_else(|_| String::from("Failed to read error message"));
+        Err(anyhow::Error::msg(format!("Failed to update playlist: {} - {}", response.status_text(), error_text)))
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct DeletePlaylistRequest {
+    user_id: i32,
+    playlist_id: i32,
+}
+
+pub async fn call_delete_playlist(server_name: &str, api_key: &Option<String>, user_id: i32, playlist_id: i32) -> Result<bool, Error> {
+    let url = format!("{}/api/data/delete_playlist", server_name);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+    let request_body = serde_json::to_string(&DeletePlaylistRequest { user_id, playlist_id })
+        .map_err(|e| anyhow::Error::msg(format!("Serialization Error: {}", e)))?;
+
+    let response = Request::post(&url)
+        .header("Api-Key", api_key_ref)
+        .header("Content-Type", "application/json")
+        .body(request_body)?
+        .send()
+        .await?;
+
+    if response.ok() {
+        Ok(true)
+    } else {
+        Err(anyhow::Error::msg(format!("Failed to delete playlist: {}", response.status_text())))
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct PlaylistEpisodesRequest<'a> {
+    user_id: i32,
+    playlist_id: i32,
+    episode_ids: &'a [i32],
+}
+
+#[derive(Deserialize, Debug)]
+struct PlaylistEpisodeIdsResponse {
+    episode_ids: Vec<i32>,
+}
+
+async fn post_playlist_episodes(server_name: &str, api_key: &Option<String>, endpoint: &str, user_id: i32, playlist_id: i32, episode_ids: &[i32]) -> Result<Vec<i32>, Error> {
+    let url = format!("{}/api/data/{}", server_name, endpoint);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+    let request_body = serde_json::to_string(&PlaylistEpisodesRequest { user_id, playlist_id, episode_ids })
+        .map_err(|e| anyhow::Error::msg(format!("Serialization Error: {}", e)))?;
+
+    let response = Request::post(&url)
+        .header("Api-Key", api_key_ref)
+        .header("Content-Type", "application/json")
+        .body(request_body)?
+        .send()
+        .await?;
+
+    if response.ok() {
+        let response_body = response.json::<PlaylistEpisodeIdsResponse>().await?;
+        Ok(response_body.episode_ids)
+    } else {
+        Err(anyhow::Error::msg(format!("Failed to update playlist episodes: {}", response.status_text())))
+    }
+}
+
+// Replaces a manual playlist's episodes and order. Returns what the server kept.
+pub async fn call_set_playlist_episodes(server_name: &str, api_key: &Option<String>, user_id: i32, playlist_id: i32, episode_ids: &[i32]) -> Result<Vec<i32>, Error> {
+    post_playlist_episodes(server_name, api_key, "set_playlist_episodes", user_id, playlist_id, episode_ids).await
+}
+
+// Appends episodes that aren't already in the playlist. Returns the playlist's new contents.
+pub async fn call_add_playlist_episodes(server_name: &str, api_key: &Option<String>, user_id: i32, playlist_id: i32, episode_ids: &[i32]) -> Result<Vec<i32>, Error> {
+    post_playlist_episodes(server_name, api_key, "add_playlist_episodes", user_id, playlist_id, episode_ids).await
+}
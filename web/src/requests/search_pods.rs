@@ -6,8 +6,10 @@ use std::fmt;
 use anyhow::Error;
 use rss::Channel;
 use wasm_bindgen::JsValue;
+use web_sys::AbortSignal;
 use chrono::{DateTime, Utc, TimeZone};
 use yew::Properties;
+use crate::requests::pod_req::bool_from_int;
 
 #[derive(Deserialize, Debug)]
 pub struct RecentEps {
@@ -22,7 +24,7 @@ pub struct PodcastSearchResult {
     pub results: Option<Vec<ITunesPodcast>>, // for iTunes
 }
 
-#[derive(Deserialize, Debug, PartialEq, Clone, Serialize)]
+#[derive(Deserialize, Debug, PartialEq, Clone, Serialize, Default)]
 pub struct UnifiedPodcast {
     pub(crate) id: i64,
     pub(crate) title: String,
@@ -42,8 +44,16 @@ pub struct UnifiedPodcast {
     pub(crate) explicit: bool,
     #[allow(non_snake_case)]
     pub(crate) episodeCount: i32,
+    // Search providers that listed this podcast
+    #[serde(default)]
+    pub(crate) sources: Vec<String>,
+    // Language tag as the directory gave it, when it gives one
+    #[serde(default)]
+    pub(crate) language: Option<String>,
 }
 
+pub const ITUNES_NO_DESCRIPTION: &str = "Descriptions not provided by iTunes";
+
 // Implement conversions from Podcast and ITunesPodcast to UnifiedPodcast
 impl From<Podcast> for UnifiedPodcast {
     fn from(podcast: Podcast) -> Self {
@@ -62,6 +72,8 @@ impl From<Podcast> for UnifiedPodcast {
             categories: podcast.categories,
             explicit: podcast.explicit,
             episodeCount: podcast.episodeCount,
+            sources: Vec::new(),
+            language: podcast.language,
         }
     }
 }
@@ -84,7 +96,7 @@ impl From<ITunesPodcast> for UnifiedPodcast {
             originalUrl: podcast.feedUrl,
             author: podcast.artistName.clone(),
             ownerName: podcast.artistName,
-            description: String::from("Descriptions not provided by iTunes"),
+            description: String::from(ITUNES_NO_DESCRIPTION),
             image: podcast.artworkUrl100.clone(),
             link: podcast.collectionViewUrl,
             artwork: podcast.artworkUrl100,
@@ -96,6 +108,9 @@ impl From<ITunesPodcast> for UnifiedPodcast {
                 _ => false,
             },
             episodeCount: podcast.trackCount.unwrap_or(0),
+            sources: Vec::new(),
+            // iTunes only tells the storefront country, not the language
+            language: None,
             // Map other fields as necessary
         }
     }
@@ -124,6 +139,8 @@ pub struct Podcast {
     pub(crate) explicit: bool,
     #[allow(non_snake_case)]
     pub(crate) episodeCount: i32,
+    #[serde(default)]
+    pub(crate) language: Option<String>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Clone, Serialize)]
@@ -234,6 +251,13 @@ pub struct Episode {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "EpisodeID")]
     pub episode_id: Option<i32>,
+    // Only sent for episodes of subscribed podcasts, which come from the database
+    #[serde(rename = "ListenDuration", default, skip_serializing_if = "Option::is_none")]
+    pub listen_duration: Option<i32>,
+    #[serde(rename = "Saved", default, deserialize_with = "bool_from_int")]
+    pub saved: bool,
+    #[serde(rename = "Downloaded", default, deserialize_with = "bool_from_int")]
+    pub downloaded: bool,
 }
 
 
@@ -243,14 +267,14 @@ pub struct PodcastFeedResult {
     pub(crate) episodes: Vec<Episode>,
 }
 
-pub async fn call_get_podcast_info(podcast_value: &String, search_api_url: &Option<String>, search_index: &str) -> Result<PodcastSearchResult, anyhow::Error> {
+pub async fn call_get_podcast_info(podcast_value: &String, search_api_url: &Option<String>, search_index: &str, signal: Option<&AbortSignal>) -> Result<PodcastSearchResult, anyhow::Error> {
     let url = if let Some(api_url) = search_api_url {
         format!("{}?query={}&index={}", api_url, podcast_value, search_index)
     } else {
         return Err(anyhow::Error::msg("API URL is not provided"));
     };
 
-    let response = Request::get(&url).send().await.map_err(|err| anyhow::Error::new(err))?;
+    let response = Request::get(&url).abort_signal(signal).send().await.map_err(|err| anyhow::Error::new(err))?;
 
     if response.ok() {
         let response_text = response.text().await.map_err(|err| anyhow::Error::new(err))?;
@@ -265,21 +289,6 @@ pub async fn call_get_podcast_info(podcast_value: &String, search_api_url: &Opti
 
 
 
-pub async fn test_connection(search_api_url: &Option<String>) -> Result<(), Error> {
-    let url = search_api_url.as_ref().ok_or_else(|| Error::msg("API URL is missing"))?;
-
-    match Request::get(url).send().await {
-        Ok(response) => {
-            if response.ok() {
-                Ok(())
-            } else {
-                Err(Error::msg(format!("HTTP error occurred: {}", response.status_text())))
-            }
-        }
-        Err(err) => Err(Error::new(err)),
-    }
-}
-
 #[derive(Debug, Deserialize, PartialEq, Clone)]
 pub struct PodcastEpisodesResponse {
     pub episodes: Vec<Episode>,
@@ -292,9 +301,26 @@ pub async fn call_get_podcast_episodes(
     podcast_id: &i32,
 ) -> Result<PodcastFeedResult, anyhow::Error> {
     let url = format!("{}/api/data/podcast_episodes?user_id={}&podcast_id={}", server_name, user_id, podcast_id);
+    fetch_podcast_episodes(&url, api_key).await
+}
+
+// One page of a subscribed podcast's episodes, newest first
+pub async fn call_get_podcast_episodes_page(
+    server_name: &str,
+    api_key: &Option<String>,
+    user_id: &i32,
+    podcast_id: &i32,
+    offset: usize,
+    limit: usize,
+) -> Result<PodcastFeedResult, anyhow::Error> {
+    let url = format!("{}/api/data/podcast_episodes?user_id={}&podcast_id={}&offset={}&limit={}", server_name, user_id, podcast_id, offset, limit);
+    fetch_podcast_episodes(&url, api_key).await
+}
+
+async fn fetch_podcast_episodes(url: &str, api_key: &Option<String>) -> Result<PodcastFeedResult, anyhow::Error> {
     let api_key_ref = api_key.as_deref().ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
 
-    let response = Request::get(&url)
+    let response = Request::get(url)
         .header("Api-Key", api_key_ref)
         .send()
         .await?;
@@ -350,6 +376,9 @@ pub async fn call_parse_podcast_url(server_name: String, api_key: &Option<String
                 guid: item.guid().map(|g| g.value().to_string()),
                 duration: item.itunes_ext().and_then(|ext| ext.duration()).map(|d| d.to_string()),
                 episode_id: None,
+                listen_duration: None,
+                saved: false,
+                downloaded: false,
             }
         }).collect();
 
@@ -407,10 +436,14 @@ pub async fn call_parse_podcast_channel_info(podcast_url: &str) -> Result<Podcas
 
 // In Databases
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SearchRequest {
     pub search_term: String,
     pub user_id: i32,
+    pub offset: usize,
+    // None returns every match in one response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Clone)]
@@ -443,10 +476,12 @@ pub struct SearchEpisode {
     pub ListenDuration: Option<i32>,
 }
 
+// `signal` lets search-as-you-type abandon a query the user has already typed past
 pub async fn call_search_database (
     server_name: &String, 
     api_key: &Option<String>, 
-    request_data: &SearchRequest
+    request_data: &SearchRequest,
+    signal: Option<&AbortSignal>,
 ) -> Result<Vec<SearchEpisode>, Error> {
     let url = format!("{}/api/data/search_data", server_name);
 
@@ -458,6 +493,7 @@ pub async fn call_search_database (
     let response = Request::post(&url)
         .header("Api-Key", api_key_ref)
         .header("Content-Type", "application/json")
+        .abort_signal(signal)
         .body(request_body)?
         .send()
         .await?;
@@ -0,0 +1,473 @@
+use std::collections::HashMap;
+use anyhow::Error;
+use chrono::DateTime;
+use futures_util::future::{join_all, FutureExt, LocalBoxFuture};
+use gloo_net::http::Request;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use web_sys::AbortSignal;
+use crate::components::persistence::{load_user_preference, save_user_preference};
+use crate::requests::search_pods::{call_get_podcast_info, UnifiedPodcast, ITUNES_NO_DESCRIPTION};
+
+// Podcast discovery across several directories at once. Each directory is a `SearchProvider`
+// that turns a query into `UnifiedPodcast`s; `search_all` asks every enabled one concurrently,
+// merges podcasts listed by more than one and ranks the lot.
+//
+// Adding a directory means implementing the trait, giving it an entry in `PROVIDERS` and
+// building it in `enabled_providers`. Directories that aren't reached through the search API
+// have a configurable base URL, so a local stand-in can answer for them while testing.
+
+pub trait SearchProvider {
+    fn id(&self) -> &'static str;
+    fn search<'a>(&'a self, query: &'a str, signal: Option<&'a AbortSignal>) -> LocalBoxFuture<'a, Result<Vec<UnifiedPodcast>, Error>>;
+}
+
+pub const PODCAST_INDEX: &str = "podcast_index";
+pub const ITUNES: &str = "itunes";
+pub const FYYD: &str = "fyyd";
+pub const GPODDER: &str = "gpodder";
+
+// Every known provider with its display name, in the order they are offered
+pub const PROVIDERS: [(&str, &str); 4] = [
+    (PODCAST_INDEX, "Podcast Index"),
+    (ITUNES, "iTunes"),
+    (FYYD, "fyyd"),
+    (GPODDER, "gpodder.net"),
+];
+
+pub fn provider_name(id: &str) -> &'static str {
+    PROVIDERS.iter().find(|(provider, _)| *provider == id).map_or("Unknown", |(_, name)| name)
+}
+
+const SEARCH_PROVIDER_PREFERENCE: &str = "search_providers";
+const DEFAULT_FYYD_URL: &str = "https://api.fyyd.de";
+const DEFAULT_GPODDER_URL: &str = "https://gpodder.net";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct SearchProviderPreferences {
+    pub enabled: Vec<String>,
+    pub fyyd_url: String,
+    pub gpodder_url: String,
+}
+
+impl Default for SearchProviderPreferences {
+    fn default() -> Self {
+        SearchProviderPreferences {
+            enabled: vec![PODCAST_INDEX.to_string(), ITUNES.to_string()],
+            fyyd_url: DEFAULT_FYYD_URL.to_string(),
+            gpodder_url: DEFAULT_GPODDER_URL.to_string(),
+        }
+    }
+}
+
+impl SearchProviderPreferences {
+    pub fn load(user_key: &str) -> Self {
+        load_user_preference(user_key, SEARCH_PROVIDER_PREFERENCE)
+    }
+
+    pub fn save(&self, user_key: &str) {
+        save_user_preference(user_key, SEARCH_PROVIDER_PREFERENCE, self);
+    }
+
+    pub fn is_enabled(&self, id: &str) -> bool {
+        self.enabled.iter().any(|enabled| enabled == id)
+    }
+
+    // The last enabled provider can't be switched off, a search needs somewhere to look
+    pub fn toggle(&mut self, id: &str) {
+        if self.is_enabled(id) {
+            if self.enabled.len() > 1 {
+                self.enabled.retain(|enabled| enabled != id);
+            }
+        } else {
+            self.enabled.push(id.to_string());
+        }
+    }
+}
+
+pub fn enabled_providers(preferences: &SearchProviderPreferences, search_api_url: &Option<String>) -> Vec<Box<dyn SearchProvider>> {
+    let mut providers: Vec<Box<dyn SearchProvider>> = Vec::new();
+    for (id, _) in PROVIDERS {
+        if !preferences.is_enabled(id) {
+            continue;
+        }
+        match id {
+            PODCAST_INDEX | ITUNES => providers.push(Box::new(SearchApiProvider { id, api_url: search_api_url.clone() })),
+            FYYD => providers.push(Box::new(FyydProvider { base_url: preferences.fyyd_url.trim_end_matches('/').to_string() })),
+            GPODDER => providers.push(Box::new(GpodderProvider { base_url: preferences.gpodder_url.trim_end_matches('/').to_string() })),
+            _ => {}
+        }
+    }
+    providers
+}
+
+// Podcast Index and iTunes are both reached through the search API configured on the server
+struct SearchApiProvider {
+    id: &'static str,
+    api_url: Option<String>,
+}
+
+impl SearchProvider for SearchApiProvider {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn search<'a>(&'a self, query: &'a str, signal: Option<&'a AbortSignal>) -> LocalBoxFuture<'a, Result<Vec<UnifiedPodcast>, Error>> {
+        async move {
+            let encoded = utf8_percent_encode(query, NON_ALPHANUMERIC).to_string();
+            let results = call_get_podcast_info(&encoded, &self.api_url, self.id, signal).await?;
+            let podcasts = match (results.feeds, results.results) {
+                (Some(feeds), _) => feeds.into_iter().map(UnifiedPodcast::from).collect(),
+                (None, Some(results)) => results.into_iter().map(UnifiedPodcast::from).collect(),
+                (None, None) => Vec::new(),
+            };
+            Ok(podcasts)
+        }
+        .boxed_local()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct FyydResponse {
+    #[serde(default)]
+    data: Vec<FyydPodcast>,
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(non_snake_case)]
+struct FyydPodcast {
+    id: i64,
+    title: String,
+    xmlURL: String,
+    #[serde(default)]
+    htmlURL: Option<String>,
+    #[serde(default)]
+    imgURL: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    lastpub: Option<String>,
+    #[serde(default)]
+    episode_count: Option<i32>,
+    #[serde(default)]
+    language: Option<String>,
+}
+
+impl From<FyydPodcast> for UnifiedPodcast {
+    fn from(podcast: FyydPodcast) -> Self {
+        let image = podcast.imgURL.unwrap_or_default();
+        let author = podcast.author.unwrap_or_default();
+        UnifiedPodcast {
+            id: podcast.id,
+            title: podcast.title,
+            url: podcast.xmlURL.clone(),
+            originalUrl: podcast.xmlURL,
+            link: podcast.htmlURL.unwrap_or_default(),
+            description: podcast.description.unwrap_or_default(),
+            author: author.clone(),
+            ownerName: author,
+            image: image.clone(),
+            artwork: image,
+            lastUpdateTime: podcast.lastpub.as_deref()
+                .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+                .map_or(0, |date| date.timestamp()),
+            // fyyd only sends category ids, which mean nothing on their own
+            categories: None,
+            explicit: false,
+            episodeCount: podcast.episode_count.unwrap_or(0),
+            sources: Vec::new(),
+            language: podcast.language.filter(|language| !language.is_empty()),
+        }
+    }
+}
+
+struct FyydProvider {
+    base_url: String,
+}
+
+impl SearchProvider for FyydProvider {
+    fn id(&self) -> &'static str {
+        FYYD
+    }
+
+    fn search<'a>(&'a self, query: &'a str, signal: Option<&'a AbortSignal>) -> LocalBoxFuture<'a, Result<Vec<UnifiedPodcast>, Error>> {
+        async move {
+            let url = format!("{}/0.2/search/podcast?title={}&count=25", self.base_url, utf8_percent_encode(query, NON_ALPHANUMERIC));
+            let response = Request::get(&url).abort_signal(signal).send().await?;
+            if !response.ok() {
+                return Err(Error::msg(format!("fyyd search failed: {}", response.status_text())));
+            }
+            let results = response.json::<FyydResponse>().await?;
+            Ok(results.data.into_iter().map(UnifiedPodcast::from).collect())
+        }
+        .boxed_local()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct GpodderPodcast {
+    url: String,
+    title: String,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    logo_url: Option<String>,
+    #[serde(default)]
+    website: Option<String>,
+}
+
+impl From<GpodderPodcast> for UnifiedPodcast {
+    fn from(podcast: GpodderPodcast) -> Self {
+        let image = podcast.logo_url.unwrap_or_default();
+        let author = podcast.author.unwrap_or_default();
+        UnifiedPodcast {
+            // gpodder has no ids, the feed URL identifies a podcast
+            id: 0,
+            title: podcast.title,
+            url: podcast.url.clone(),
+            originalUrl: podcast.url,
+            link: podcast.website.unwrap_or_default(),
+            description: podcast.description.unwrap_or_default(),
+            author: author.clone(),
+            ownerName: author,
+            image: image.clone(),
+            artwork: image,
+            lastUpdateTime: 0,
+            categories: None,
+            explicit: false,
+            episodeCount: 0,
+            sources: Vec::new(),
+            language: None,
+        }
+    }
+}
+
+// Any directory speaking the gpodder.net search API, including self hosted ones
+struct GpodderProvider {
+    base_url: String,
+}
+
+impl SearchProvider for GpodderProvider {
+    fn id(&self) -> &'static str {
+        GPODDER
+    }
+
+    fn search<'a>(&'a self, query: &'a str, signal: Option<&'a AbortSignal>) -> LocalBoxFuture<'a, Result<Vec<UnifiedPodcast>, Error>> {
+        async move {
+            let url = format!("{}/search.json?q={}", self.base_url, utf8_percent_encode(query, NON_ALPHANUMERIC));
+            let response = Request::get(&url).abort_signal(signal).send().await?;
+            if !response.ok() {
+                return Err(Error::msg(format!("gpodder search failed: {}", response.status_text())));
+            }
+            let results = response.json::<Vec<GpodderPodcast>>().await?;
+            Ok(results.into_iter().map(UnifiedPodcast::from).collect())
+        }
+        .boxed_local()
+    }
+}
+
+#[derive(Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct SearchOutcome {
+    pub podcasts: Vec<UnifiedPodcast>,
+    // Providers that failed, so the page can say which directories are missing
+    pub failed: Vec<String>,
+}
+
+// Passing a signal lets a newer search abort this one before every provider has answered
+pub async fn search_all(providers: &[Box<dyn SearchProvider>], query: &str, signal: Option<&AbortSignal>) -> SearchOutcome {
+    let responses = join_all(providers.iter().map(|provider| async move { (provider.id(), provider.search(query, signal).await) })).await;
+    let mut failed = Vec::new();
+    let mut listings = Vec::new();
+    for (id, response) in responses {
+        match response {
+            Ok(podcasts) => listings.push((id, podcasts)),
+            Err(e) => {
+                web_sys::console::log_1(&format!("{} search failed: {}", id, e).into());
+                failed.push(id.to_string());
+            }
+        }
+    }
+    SearchOutcome { podcasts: merge_results(query, listings), failed }
+}
+
+// Lower case without the scheme, a leading www. or a trailing slash, so the same feed listed
+// slightly differently by two directories is only shown once
+pub fn normalize_feed_url(url: &str) -> String {
+    let url = url.trim().to_lowercase();
+    let without_scheme = url.split_once("://").map_or(url.as_str(), |(_, rest)| rest);
+    let without_www = without_scheme.strip_prefix("www.").unwrap_or(without_scheme);
+    without_www.trim_end_matches('/').to_string()
+}
+
+// Keeps what the first listing had and fills its gaps from a duplicate
+fn fill_missing(podcast: &mut UnifiedPodcast, other: UnifiedPodcast) {
+    if podcast.description.is_empty() || podcast.description == ITUNES_NO_DESCRIPTION {
+        podcast.description = other.description;
+    }
+    for (field, value) in [
+        (&mut podcast.link, other.link),
+        (&mut podcast.author, other.author),
+        (&mut podcast.ownerName, other.ownerName),
+        (&mut podcast.image, other.image),
+        (&mut podcast.artwork, other.artwork),
+    ] {
+        if field.is_empty() {
+            *field = value;
+        }
+    }
+    if podcast.categories.as_ref().map_or(true, HashMap::is_empty) {
+        podcast.categories = other.categories;
+    }
+    podcast.explicit |= other.explicit;
+    podcast.episodeCount = podcast.episodeCount.max(other.episodeCount);
+    podcast.lastUpdateTime = podcast.lastUpdateTime.max(other.lastUpdateTime);
+    podcast.sources.extend(other.sources);
+    podcast.language = podcast.language.take().or(other.language);
+}
+
+// Reciprocal rank fusion: a podcast near the top of several directories beats one that a
+// single directory ranks first. Titles matching the query get a boost on top.
+const RANK_OFFSET: f64 = 10.0;
+
+fn title_boost(title: &str, query: &str) -> f64 {
+    let title = title.trim().to_lowercase();
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        0.0
+    } else if title == query {
+        0.2
+    } else if title.contains(&query) {
+        0.05
+    } else {
+        0.0
+    }
+}
+
+pub fn merge_results(query: &str, listings: Vec<(&'static str, Vec<UnifiedPodcast>)>) -> Vec<UnifiedPodcast> {
+    let mut merged: Vec<(UnifiedPodcast, f64)> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for (id, podcasts) in listings {
+        for (rank, mut podcast) in podcasts.into_iter().enumerate() {
+            let key = normalize_feed_url(&podcast.url);
+            if key.is_empty() {
+                continue;
+            }
+            podcast.sources = vec![id.to_string()];
+            let score = 1.0 / (RANK_OFFSET + rank as f64);
+            match positions.get(&key).copied() {
+                Some(position) => {
+                    let (existing, existing_score) = &mut merged[position];
+                    // A directory listing the same feed twice only counts once
+                    if !existing.sources.iter().any(|source| source == id) {
+                        *existing_score += score;
+                        fill_missing(existing, podcast);
+                    }
+                }
+                None => {
+                    positions.insert(key, merged.len());
+                    merged.push((podcast, score));
+                }
+            }
+        }
+    }
+    for (podcast, score) in merged.iter_mut() {
+        *score += title_boost(&podcast.title, query);
+    }
+    merged.sort_by(|(a, a_score), (b, b_score)| {
+        b_score.partial_cmp(a_score).unwrap_or(std::cmp::Ordering::Equal).then(b.episodeCount.cmp(&a.episodeCount))
+    });
+    merged.into_iter().map(|(podcast, _)| podcast).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn podcast(title: &str, url: &str) -> UnifiedPodcast {
+        UnifiedPodcast { title: title.to_string(), url: url.to_string(), originalUrl: url.to_string(), ..Default::default() }
+    }
+
+    fn titles(podcasts: &[UnifiedPodcast]) -> Vec<&str> {
+        podcasts.iter().map(|podcast| podcast.title.as_str()).collect()
+    }
+
+    #[test]
+    fn normalizes_scheme_www_case_and_trailing_slash() {
+        assert_eq!(normalize_feed_url("HTTPS://www.Example.com/Feed/"), "example.com/feed");
+        assert_eq!(normalize_feed_url("http://example.com/feed"), "example.com/feed");
+        assert_eq!(normalize_feed_url("  example.com/feed  "), "example.com/feed");
+        assert_eq!(normalize_feed_url("https://wwwexample.com/feed"), "wwwexample.com/feed");
+        assert_eq!(normalize_feed_url(""), "");
+    }
+
+    #[test]
+    fn listed_by_several_directories_ranks_first() {
+        let merged = merge_results("", vec![
+            (ITUNES, vec![podcast("Solo", "https://solo.example/feed"), podcast("Shared", "https://shared.example/feed")]),
+            (FYYD, vec![podcast("Shared", "http://www.shared.example/feed/")]),
+        ]);
+        assert_eq!(titles(&merged), vec!["Shared", "Solo"]);
+        assert_eq!(merged[0].sources, vec![ITUNES.to_string(), FYYD.to_string()]);
+    }
+
+    #[test]
+    fn matching_title_is_boosted() {
+        let merged = merge_results("Daily", vec![
+            (ITUNES, vec![podcast("Weekly", "https://weekly.example"), podcast("News", "https://news.example"), podcast("The Daily", "https://daily.example")]),
+        ]);
+        assert_eq!(titles(&merged), vec!["The Daily", "Weekly", "News"]);
+    }
+
+    #[test]
+    fn same_directory_listing_a_feed_twice_counts_once() {
+        let merged = merge_results("", vec![
+            (ITUNES, vec![podcast("Twice", "https://twice.example"), podcast("Twice again", "https://twice.example/")]),
+            (FYYD, vec![podcast("Other", "https://other.example")]),
+        ]);
+        assert_eq!(merged.len(), 2);
+        let twice = merged.iter().find(|podcast| podcast.title == "Twice").unwrap();
+        assert_eq!(twice.sources, vec![ITUNES.to_string()]);
+    }
+
+    #[test]
+    fn feeds_without_a_url_are_dropped() {
+        let merged = merge_results("", vec![(GPODDER, vec![podcast("No feed", ""), podcast("Feed", "https://feed.example")])]);
+        assert_eq!(titles(&merged), vec!["Feed"]);
+    }
+
+    #[test]
+    fn duplicates_fill_in_missing_details() {
+        let mut first = podcast("Show", "https://show.example");
+        first.description = ITUNES_NO_DESCRIPTION.to_string();
+        first.episodeCount = 10;
+        let mut second = podcast("Show", "https://show.example");
+        second.description = "About the show".to_string();
+        second.author = "Host".to_string();
+        second.explicit = true;
+        second.episodeCount = 12;
+        second.language = Some("en".to_string());
+
+        let merged = merge_results("", vec![(ITUNES, vec![first]), (PODCAST_INDEX, vec![second])]);
+        let show = &merged[0];
+        assert_eq!(show.description, "About the show");
+        assert_eq!(show.author, "Host");
+        assert!(show.explicit);
+        assert_eq!(show.episodeCount, 12);
+        assert_eq!(show.language.as_deref(), Some("en"));
+    }
+
+    #[test]
+    fn equal_scores_prefer_more_episodes() {
+        let mut small = podcast("Small", "https://small.example");
+        small.episodeCount = 5;
+        let mut large = podcast("Large", "https://large.example");
+        large.episodeCount = 50;
+        let merged = merge_results("", vec![(ITUNES, vec![small]), (FYYD, vec![large])]);
+        assert_eq!(titles(&merged), vec!["Large", "Small"]);
+    }
+}
@@ -58,6 +58,54 @@ pub async fn call_set_theme(server_name: &Option<String>, api_key: &Option<Strin
     }
 }
 
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct GetLanguageResponse {
+    language: Option<String>,
+}
+// None when the user hasn't picked a language on any device yet
+pub async fn call_get_language(server_name: &str, api_key: &str, user_id: &i32) -> Result<Option<String>, anyhow::Error> {
+    let url = format!("{}/api/data/get_language/{}", server_name, user_id);
+
+    let response = Request::get(&url)
+        .header("Api-Key", api_key)
+        .header("Content-Type", "application/json")
+        .send()
+        .await?;
+
+    if response.ok() {
+        let response_body = response.json::<GetLanguageResponse>().await?;
+        Ok(response_body.language)
+    } else {
+        Err(Error::msg(format!("Error getting language: {}", response.status_text())))
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub struct SetLanguageRequest {
+    pub(crate) user_id: i32,
+    pub(crate) new_language: String,
+}
+
+pub async fn call_set_language(server_name: &str, api_key: &Option<String>, set_language: &SetLanguageRequest) -> Result<(), Error> {
+    let url = format!("{}/api/data/user/set_language", server_name);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| Error::msg("API key is missing"))?;
+
+    let json_body = serde_json::to_string(set_language)?;
+
+    let response = Request::put(&url)
+        .header("Api-Key", api_key_ref)
+        .header("Content-Type", "application/json")
+        .body(json_body)?
+        .send()
+        .await?;
+
+    if response.ok() {
+        Ok(())
+    } else {
+        Err(Error::msg(format!("Error updating language: {}", response.status_text())))
+    }
+}
+
 // Admin Only API Calls
 
 #[derive(Deserialize, Debug, PartialEq, Clone)]
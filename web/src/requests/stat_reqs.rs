@@ -1,6 +1,6 @@
 use anyhow::Error;
 use gloo_net::http::Request;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Debug, PartialEq, Clone)]
 #[allow(non_snake_case)]
@@ -39,3 +39,234 @@ pub async fn call_get_stats(server_name: String, api_key: Option<String>, user_i
     }
 }
 
+
+// One listened episode, as the stats dashboard buckets it
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+#[allow(non_snake_case)]
+pub struct ListeningActivity {
+    pub ListenDate: String,
+    pub ListenDuration: i32,
+    pub EpisodeID: i32,
+    pub EpisodeDuration: i32,
+    pub PodcastID: i32,
+    pub PodcastName: String,
+    #[serde(default)]
+    pub Categories: Option<String>,
+}
+
+// Minutes listened on one of the user's local days
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+#[allow(non_snake_case)]
+pub struct ListenDay {
+    pub ListenDay: String,
+    pub Minutes: i64,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct ListeningActivityResponse {
+    pub activity: Vec<ListeningActivity>,
+    // Older servers only send the episodes
+    #[serde(default)]
+    pub days: Vec<ListenDay>,
+}
+
+// Bounds are UTC timestamps, the end is exclusive
+pub async fn call_get_listening_activity(
+    server_name: &str,
+    api_key: &Option<String>,
+    user_id: i32,
+    start: Option<&str>,
+    end: Option<&str>,
+) -> Result<ListeningActivityResponse, anyhow::Error> {
+    let mut url = format!("{}/api/data/listening_activity/{}", server_name, user_id);
+    let bounds: Vec<String> = [("start", start), ("end", end)]
+        .iter()
+        .filter_map(|(name, value)| value.map(|value| format!("{}={}", name, value)))
+        .collect();
+    if !bounds.is_empty() {
+        url.push('?');
+        url.push_str(&bounds.join("&"));
+    }
+    let api_key_ref = api_key.as_deref().ok_or_else(|| Error::msg("API key is missing"))?;
+
+    let response = Request::get(&url)
+        .header("Api-Key", api_key_ref)
+        .send()
+        .await?;
+    if !response.ok() {
+        return Err(anyhow::Error::msg(format!("Failed to get listening activity: {}", response.status_text())));
+    }
+
+    Ok(response.json::<ListeningActivityResponse>().await?)
+}
+
+// A personal target, with how far the current day, week or month has got
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+#[allow(non_snake_case)]
+pub struct ListeningGoal {
+    pub GoalID: i32,
+    // "minutes" or "episodes"
+    pub GoalType: String,
+    pub Target: i32,
+    // "day", "week" or "month"
+    pub Period: String,
+    pub Progress: i32,
+    pub PeriodStart: String,
+}
+
+// Consecutive days with any listening, counted in the user's timezone
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone, Default)]
+pub struct ListeningStreak {
+    pub current: i32,
+    pub longest: i32,
+    pub listened_today: bool,
+}
+
+impl ListeningStreak {
+    // A running streak that today hasn't extended yet
+    pub fn at_risk(&self) -> bool {
+        self.current > 0 && !self.listened_today
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone, Default)]
+pub struct ListeningGoalsResponse {
+    pub goals: Vec<ListeningGoal>,
+    pub streak: ListeningStreak,
+}
+
+pub async fn call_get_listening_goals(server_name: &str, api_key: &Option<String>, user_id: i32) -> Result<ListeningGoalsResponse, anyhow::Error> {
+    let url = format!("{}/api/data/listening_goals/{}", server_name, user_id);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| Error::msg("API key is missing"))?;
+
+    let response = Request::get(&url)
+        .header("Api-Key", api_key_ref)
+        .send()
+        .await?;
+    if !response.ok() {
+        return Err(anyhow::Error::msg(format!("Failed to get listening goals: {}", response.status_text())));
+    }
+
+    Ok(response.json::<ListeningGoalsResponse>().await?)
+}
+
+#[derive(Serialize, Debug)]
+struct CreateListeningGoalRequest<'a> {
+    user_id: i32,
+    goal_type: &'a str,
+    target: i32,
+    period: &'a str,
+}
+
+#[derive(Deserialize, Debug)]
+struct CreateListeningGoalResponse {
+    goal_id: i32,
+}
+
+pub async fn call_create_listening_goal(server_name: &str, api_key: &Option<String>, user_id: i32, goal_type: &str, target: i32, period: &str) -> Result<i32, anyhow::Error> {
+    let url = format!("{}/api/data/create_listening_goal", server_name);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| Error::msg("API key is missing"))?;
+    let request_body = serde_json::to_string(&CreateListeningGoalRequest { user_id, goal_type, target, period })
+        .map_err(|e| anyhow::Error::msg(format!("Serialization Error: {}", e)))?;
+
+    let response = Request::post(&url)
+        .header("Api-Key", api_key_ref)
+        .header("Content-Type", "application/json")
+        .body(request_body)?
+        .send()
+        .await?;
+    if !response.ok() {
+        let error_text = response.text().await.unwrap_or_else(|_| String::from("Failed to read error message"));
+        return Err(anyhow::Error::msg(format!("Failed to create listening goal: {} - {}", response.status_text(), error_text)));
+    }
+
+    Ok(response.json::<CreateListeningGoalResponse>().await?.goal_id)
+}
+
+#[derive(Serialize, Debug)]
+struct DeleteListeningGoalRequest {
+    user_id: i32,
+    goal_id: i32,
+}
+
+pub async fn call_delete_listening_goal(server_name: &str, api_key: &Option<String>, user_id: i32, goal_id: i32) -> Result<(), anyhow::Error> {
+    let url = format!("{}/api/data/delete_listening_goal", server_name);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| Error::msg("API key is missing"))?;
+    let request_body = serde_json::to_string(&DeleteListeningGoalRequest { user_id, goal_id })
+        .map_err(|e| anyhow::Error::msg(format!("Serialization Error: {}", e)))?;
+
+    let response = Request::post(&url)
+        .header("Api-Key", api_key_ref)
+        .header("Content-Type", "application/json")
+        .body(request_body)?
+        .send()
+        .await?;
+    if !response.ok() {
+        return Err(anyhow::Error::msg(format!("Failed to delete listening goal: {}", response.status_text())));
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+#[allow(non_snake_case)]
+pub struct YearReviewPodcast {
+    pub PodcastID: i32,
+    pub PodcastName: String,
+    #[serde(default)]
+    pub ArtworkURL: Option<String>,
+    pub Seconds: i64,
+    pub Episodes: i32,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+#[allow(non_snake_case)]
+pub struct YearReviewEpisode {
+    pub EpisodeID: i32,
+    pub EpisodeTitle: String,
+    #[serde(default)]
+    pub EpisodeArtwork: Option<String>,
+    pub PodcastName: String,
+    pub Plays: i32,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+#[allow(non_snake_case)]
+pub struct YearReviewSubscription {
+    pub PodcastID: i32,
+    pub PodcastName: String,
+    #[serde(default)]
+    pub ArtworkURL: Option<String>,
+    pub DateAdded: String,
+}
+
+// One calendar year of listening, in the user's timezone
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub struct YearReview {
+    pub year: i32,
+    pub total_seconds: i64,
+    pub episodes_listened: i32,
+    pub episodes_finished: i32,
+    pub podcasts_listened: i32,
+    pub top_podcasts: Vec<YearReviewPodcast>,
+    // Seconds listened in each month, January first
+    pub by_month: Vec<i64>,
+    pub longest_streak: i32,
+    pub most_replayed: Option<YearReviewEpisode>,
+    pub new_subscriptions: Vec<YearReviewSubscription>,
+}
+
+pub async fn call_get_year_review(server_name: &str, api_key: &Option<String>, user_id: i32, year: i32) -> Result<YearReview, anyhow::Error> {
+    let url = format!("{}/api/data/year_review/{}?year={}", server_name, user_id, year);
+    let api_key_ref = api_key.as_deref().ok_or_else(|| Error::msg("API key is missing"))?;
+
+    let response = Request::get(&url)
+        .header("Api-Key", api_key_ref)
+        .send()
+        .await?;
+    if !response.ok() {
+        return Err(anyhow::Error::msg(format!("Failed to get year in review: {}", response.status_text())));
+    }
+
+    Ok(response.json::<YearReview>().await?)
+}